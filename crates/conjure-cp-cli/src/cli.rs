@@ -5,11 +5,11 @@ use clap::{Args, Parser, Subcommand};
 use clap_complete::Shell;
 use conjure_cp::settings::{
     DEFAULT_MINION_DISCRETE_THRESHOLD, Parser as InputParser, QuantifiedExpander, Rewriter,
-    SolverFamily,
+    SatCardinalityEncoding, SatPbEncoding, SolverFamily,
 };
 use conjure_cp::solver::adaptors::MinionValueOrder;
 
-use crate::{pretty, solve, test_solve};
+use crate::{format, pretty, solve, test_solve};
 
 pub(crate) const DEBUG_HELP_HEADING: Option<&str> = Some("Debug");
 pub(crate) const LOGGING_HELP_HEADING: Option<&str> = Some("Logging & Output");
@@ -31,6 +31,8 @@ pub enum Command {
     /// Generate a completion script for the shell provided
     Completion(CompletionArgs),
     Pretty(pretty::Args),
+    /// Format Essence files in place
+    Fmt(format::Args),
     // Run the language server
     ServerLSP,
 }
@@ -176,14 +178,56 @@ pub struct GlobalArgs {
     )]
     pub minion_valorder: Option<MinionValueOrder>,
 
+    /// Which encoding the SAT backend uses for sums of Booleans, such as at-most-k constraints.
+    ///
+    /// Possible values: `totalizer`, `sequential-counter`.
+    #[arg(
+        long,
+        value_name = "ENCODING",
+        default_value_t = SatCardinalityEncoding::Totalizer,
+        value_parser = parse_sat_cardinality_encoding,
+        global = true,
+        help_heading = CONFIGURATION_HELP_HEADING
+    )]
+    pub sat_cardinality_encoding: SatCardinalityEncoding,
+
+    /// Which encoding the SAT backend uses for weighted sums of Booleans.
+    ///
+    /// Possible values: `gte` (generalized totalizer), `dpw` (dynamic polynomial watchdog),
+    /// `adder`.
+    #[arg(
+        long,
+        value_name = "ENCODING",
+        default_value_t = SatPbEncoding::Gte,
+        value_parser = parse_sat_pb_encoding,
+        global = true,
+        help_heading = CONFIGURATION_HELP_HEADING
+    )]
+    pub sat_pb_encoding: SatPbEncoding,
+
+    /// Solve SAT models with an external SAT solver instead of the built-in CaDiCaL.
+    ///
+    /// The command is split on whitespace, and ran with the path of a DIMACS file as its last
+    /// argument for each solution. The solver should print its result in the SAT competition
+    /// format (`s SATISFIABLE` / `v ...`). For example: `--sat-solver-command "kissat -q"`.
+    #[arg(
+        long,
+        value_name = "COMMAND",
+        global = true,
+        help_heading = CONFIGURATION_HELP_HEADING
+    )]
+    pub sat_solver_command: Option<String>,
+
     /// Save a solver input file to <filename>.
     ///
     /// This input file will be in a format compatible by the command-line
     /// interface of the selected solver. For example, when the solver is Minion,
     /// a valid .minion file will be output.
     ///
-    /// This file is for informational purposes only; the results of running
-    /// this file cannot be used by Conjure Oxide in any way.
+    /// For SAT, the variables of the DIMACS file are named in comments, and a
+    /// map from them back to the variables of the model is saved to
+    /// <filename>.map.json. For other solvers, this file is for informational
+    /// purposes only.
     #[arg(long,global=true, value_names=["filename"], next_line_help=true, help_heading=LOGGING_HELP_HEADING)]
     pub save_solver_input_file: Option<PathBuf>,
 
@@ -238,6 +282,14 @@ fn parse_parser(input: &str) -> Result<InputParser, String> {
     input.parse()
 }
 
+fn parse_sat_cardinality_encoding(input: &str) -> Result<SatCardinalityEncoding, String> {
+    input.parse()
+}
+
+fn parse_sat_pb_encoding(input: &str) -> Result<SatPbEncoding, String> {
+    input.parse()
+}
+
 fn parse_minion_value_order(input: &str) -> Result<MinionValueOrder, String> {
     match input {
         "ascend" => Ok(MinionValueOrder::Ascend),
@@ -0,0 +1,69 @@
+use std::fs;
+use std::io::{self, Read as _, Write as _};
+use std::path::{Path, PathBuf};
+use std::process::exit;
+
+use anyhow::{Context as _, anyhow};
+use clap::ValueHint;
+use conjure_cp::parse::tree_sitter::format_essence;
+
+#[derive(Clone, Debug, clap::Args)]
+pub struct Args {
+    /// The Essence files to format, or `-` to format standard input to standard output
+    #[arg(value_name = "INPUT_ESSENCE", value_hint = ValueHint::FilePath, required = true)]
+    pub input_files: Vec<PathBuf>,
+
+    /// Check that the files are formatted, without changing them.
+    ///
+    /// Return-code will be 0 if they are all formatted, and 1 if any are not.
+    #[arg(long)]
+    pub check: bool,
+}
+
+pub fn run_fmt_command(fmt_args: Args) -> anyhow::Result<()> {
+    let mut unformatted = vec![];
+
+    for input_file in &fmt_args.input_files {
+        let stdin = input_file.as_os_str() == "-";
+        let source = if stdin {
+            let mut source = String::new();
+            io::stdin()
+                .read_to_string(&mut source)
+                .context("Could not read standard input")?;
+            source
+        } else {
+            fs::read_to_string(input_file)
+                .with_context(|| format!("Could not read {}", input_file.display()))?
+        };
+
+        let formatted = format_essence(&source)
+            .map_err(|err| anyhow!("Could not format {}: {err}", display_name(input_file)))?;
+
+        if fmt_args.check {
+            if formatted != source {
+                unformatted.push(display_name(input_file));
+            }
+        } else if stdin {
+            io::stdout().write_all(formatted.as_bytes())?;
+        } else if formatted != source {
+            fs::write(input_file, formatted)
+                .with_context(|| format!("Could not write {}", input_file.display()))?;
+        }
+    }
+
+    if !unformatted.is_empty() {
+        for file in unformatted {
+            eprintln!("Not formatted: {file}");
+        }
+        exit(1);
+    }
+    Ok(())
+}
+
+fn display_name(input_file: &Path) -> String {
+    if input_file.as_os_str() == "-" {
+        "<stdin>".to_string()
+    } else {
+        input_file.display().to_string()
+    }
+}
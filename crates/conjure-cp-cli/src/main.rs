@@ -4,6 +4,7 @@
 static GLOBAL: mimalloc::MiMalloc = mimalloc::MiMalloc;
 
 mod cli;
+mod format;
 mod pretty;
 mod print_info_schema;
 mod rule_trace_aggregates;
@@ -12,6 +13,7 @@ mod test_solve;
 use clap::{CommandFactory, Parser};
 use clap_complete::generate;
 use cli::{Cli, GlobalArgs};
+use format::run_fmt_command;
 use pretty::run_pretty_command;
 use print_info_schema::run_print_info_schema_command;
 use rule_trace_aggregates::RuleTraceAggregatesHandle;
@@ -227,6 +229,7 @@ fn run_subcommand(cli: Cli) -> anyhow::Result<()> {
         cli::Command::PrintJsonSchema => run_print_info_schema_command(),
         cli::Command::Completion(completion_args) => run_completion_command(completion_args),
         cli::Command::Pretty(pretty_args) => run_pretty_command(global_args, pretty_args),
+        cli::Command::Fmt(fmt_args) => run_fmt_command(fmt_args),
         cli::Command::ServerLSP => run_lsp_server(),
     }
 }
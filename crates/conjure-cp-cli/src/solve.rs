@@ -2,16 +2,17 @@
 #![allow(clippy::unwrap_used)]
 use std::time::Duration;
 use std::{
-    fs::File,
+    fs::{self, File},
     io::Write as _,
-    path::PathBuf,
+    path::{Path, PathBuf},
     process::exit,
     sync::{Arc, RwLock},
 };
 
 use anyhow::anyhow;
 use clap::ValueHint;
-use conjure_cp::instantiate::instantiate_model;
+use conjure_cp::instantiate::{instantiate_model, instantiate_model_from_json};
+use conjure_cp::solver::adaptors::rustsat::ExternalSatSolver;
 use conjure_cp::{
     Model,
     context::Context,
@@ -21,6 +22,7 @@ use conjure_cp::{
         Rewriter, set_comprehension_expander, set_current_parser, set_current_rewriter,
         set_current_solver_family, set_default_rule_trace_enabled, set_minion_discrete_threshold,
         set_rule_trace_aggregates_enabled, set_rule_trace_enabled, set_rule_trace_verbose_enabled,
+        set_sat_cardinality_encoding, set_sat_pb_encoding,
     },
     solver::Solver,
 };
@@ -29,7 +31,7 @@ use conjure_cp::{
 };
 use conjure_cp::{parse::tree_sitter::parse_essence_file_native, solver::adaptors::*};
 use conjure_cp_cli::find_conjure::conjure_executable;
-use conjure_cp_cli::utils::conjure::{get_solutions, solutions_to_json};
+use conjure_cp_cli::utils::conjure::{get_solutions, save_solver_input_file, solutions_to_json};
 use serde_json::to_string_pretty;
 
 use crate::cli::{GlobalArgs, LOGGING_HELP_HEADING};
@@ -71,7 +73,7 @@ pub struct Args {
     #[arg(value_name = "INPUT_ESSENCE", value_hint = ValueHint::FilePath)]
     pub essence_file: PathBuf,
 
-    /// The input Essence parameter file
+    /// The input Essence parameter file, or a JSON parameter file ending in `.json`
     #[arg(value_name = "PARAM_ESSENCE", value_hint = ValueHint::FilePath)]
     pub param_file: Option<PathBuf>,
 
@@ -122,10 +124,12 @@ pub fn run_solve_command(global_args: GlobalArgs, solve_args: Args) -> anyhow::R
 
     // unify models
     let unified_model = match param_file_name {
-        Some(param_file_name) => {
-            let param_model = parse(&global_args, Arc::clone(&context), param_file_name)?;
-            instantiate_model(problem_model, param_model)?
-        }
+        Some(param_file_name) => instantiate(
+            &global_args,
+            Arc::clone(&context),
+            problem_model,
+            param_file_name,
+        )?,
         None => problem_model,
     };
     drop(ctx_lock);
@@ -139,9 +143,7 @@ pub fn run_solve_command(global_args: GlobalArgs, solve_args: Args) -> anyhow::R
 
         if let Some(path) = global_args.save_solver_input_file {
             let solver = solver.load_model(rewritten_model)?;
-            eprintln!("Writing solver input file to {}", path.display());
-            let mut file: Box<dyn std::io::Write> = Box::new(File::create(path)?);
-            solver.write_solver_input_file(&mut file)?;
+            save_solver_input_file(&solver, &path)?;
         }
     } else {
         run_solver(solver, &global_args, &solve_args, rewritten_model)?
@@ -174,6 +176,8 @@ pub(crate) fn init_context(
     set_comprehension_expander(global_args.comprehension_expander);
     set_current_solver_family(global_args.solver);
     set_minion_discrete_threshold(global_args.minion_discrete_threshold);
+    set_sat_cardinality_encoding(global_args.sat_cardinality_encoding);
+    set_sat_pb_encoding(global_args.sat_pb_encoding);
     set_rule_trace_enabled(rule_trace_enabled);
     set_default_rule_trace_enabled(default_rule_trace_enabled);
     set_rule_trace_verbose_enabled(verbose_rule_trace_enabled);
@@ -240,7 +244,14 @@ pub(crate) fn init_solver(global_args: &GlobalArgs) -> Solver {
 
     match family {
         SolverFamily::Minion => Solver::new(Minion::with_value_order(global_args.minion_valorder)),
-        SolverFamily::Sat(_) => Solver::new(Sat::default()),
+        SolverFamily::Sat(_) => match global_args
+            .sat_solver_command
+            .as_deref()
+            .and_then(ExternalSatSolver::from_command_line)
+        {
+            Some(external_solver) => Solver::new(Sat::with_external_solver(external_solver)),
+            None => Solver::new(Sat::default()),
+        },
         SolverFamily::Smt(theory_cfg) => Solver::new(Smt::new(timeout_ms, theory_cfg)),
     }
 }
@@ -260,6 +271,29 @@ pub(crate) fn parse(
     }
 }
 
+/// Instantiates `problem_model` with the values in the parameter file `param_file`.
+///
+/// Parameter files ending in `.json` are read as JSON, in the same format as solutions. Other
+/// parameter files are parsed as Essence.
+pub(crate) fn instantiate(
+    global_args: &GlobalArgs,
+    context: Arc<RwLock<Context<'static>>>,
+    problem_model: Model,
+    param_file: &str,
+) -> anyhow::Result<Model> {
+    if Path::new(param_file)
+        .extension()
+        .is_some_and(|ext| ext == "json")
+    {
+        tracing::info!(target: "file", "Input file: {}", param_file);
+        let params = fs::read_to_string(param_file)?;
+        return instantiate_model_from_json(problem_model, &params);
+    }
+
+    let param_model = parse(global_args, context, param_file)?;
+    instantiate_model(problem_model, param_model)
+}
+
 pub(crate) fn parse_with_conjure(
     input_file: &str,
     context: Arc<RwLock<Context<'static>>>,
@@ -312,7 +346,7 @@ pub(crate) fn rewrite(
                 &rule_sets,
                 global_args.check_equally_applicable_rules,
                 config,
-            )
+            )?
         }
         Rewriter::Naive => {
             tracing::info!("Rewriting the model using the default / naive rewriter");
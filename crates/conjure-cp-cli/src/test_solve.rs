@@ -5,7 +5,6 @@ use std::sync::Arc;
 use crate::cli::GlobalArgs;
 use crate::solve::{self, init_solver};
 use clap::ValueHint;
-use conjure_cp::instantiate::instantiate_model;
 use conjure_cp_cli::utils::conjure::{
     get_solutions, get_solutions_from_conjure, solutions_to_json,
 };
@@ -17,7 +16,7 @@ pub struct Args {
     #[arg(value_name = "INPUT_ESSENCE",value_hint=ValueHint::FilePath)]
     pub input_file: PathBuf,
 
-    /// The input Essence parameter file
+    /// The input Essence parameter file, or a JSON parameter file ending in `.json`
     #[arg(value_name = "PARAM_ESSENCE", value_hint=ValueHint::FilePath)]
     pub param_file: Option<PathBuf>,
 }
@@ -44,10 +43,12 @@ pub fn run_test_solve_command(global_args: GlobalArgs, local_args: Args) -> anyh
     let problem_model = solve::parse(&global_args, Arc::clone(&context), essence_file_name)?;
 
     let unified_model = match param_file_name {
-        Some(param_file_name) => {
-            let param_model = solve::parse(&global_args, Arc::clone(&context), param_file_name)?;
-            instantiate_model(problem_model, param_model)?
-        }
+        Some(param_file_name) => solve::instantiate(
+            &global_args,
+            Arc::clone(&context),
+            problem_model,
+            param_file_name,
+        )?,
         None => problem_model,
     };
 
@@ -1,5 +1,5 @@
 use std::collections::BTreeMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::string::ToString;
 use std::sync::{Arc, Mutex, RwLock};
 
@@ -17,6 +17,7 @@ use crate::utils::json::sort_json_object;
 use conjure_cp::Model;
 use conjure_cp::parse::tree_sitter::parse_essence_file;
 use conjure_cp::solver::Solver;
+use conjure_cp::solver::states::ModelLoaded;
 
 use glob::glob;
 
@@ -122,6 +123,27 @@ fn retroactively_prune_dominated(
         .collect()
 }
 
+/// Writes the solver input file of `solver` to `path`.
+///
+/// If the solver gives a map from the variables of this file back to the model, it is written
+/// next to it, to `<path>.map.json`.
+pub fn save_solver_input_file(
+    solver: &Solver<ModelLoaded>,
+    path: &Path,
+) -> Result<(), anyhow::Error> {
+    eprintln!("Writing solver input file to {}", path.display());
+    let mut file: Box<dyn std::io::Write> = Box::new(std::fs::File::create(path)?);
+    solver.write_solver_input_file(&mut file)?;
+
+    if let Some(map) = solver.solver_input_map() {
+        let mut map_path = path.as_os_str().to_owned();
+        map_path.push(".map.json");
+        std::fs::write(map_path, serde_json::to_string_pretty(&map)?)?;
+    }
+
+    Ok(())
+}
+
 pub fn get_solutions(
     solver: Solver,
     model: Model,
@@ -146,12 +168,7 @@ pub fn get_solutions(
     let solver = solver.load_model(model)?;
 
     if let Some(solver_input_file) = solver_input_file {
-        eprintln!(
-            "Writing solver input file to {}",
-            solver_input_file.display()
-        );
-        let file = Box::new(std::fs::File::create(solver_input_file)?);
-        solver.write_solver_input_file(&mut (file as Box<dyn std::io::Write>))?;
+        save_solver_input_file(&solver, solver_input_file)?;
     }
 
     eprintln!("Running {adaptor_name}...");
@@ -219,7 +236,10 @@ pub fn get_solutions(
 
     for sol in sols.iter_mut() {
         // Get the value of complex variables using their auxiliary variables
-        for (name, representation) in representations.iter() {
+        //
+        // Auxiliary variables are declared after the variable they represent, and can have
+        // representations of their own, so work backwards to find their values first
+        for (name, representation) in representations.iter().rev() {
             let value = representation.value_up(sol).map_err(|err| {
                 anyhow::anyhow!(
                     "failed to reconstruct value for variable {name} from solver solution: {err}"
@@ -326,6 +346,7 @@ pub fn solutions_to_json(solutions: &Vec<BTreeMap<Name, Literal>>) -> JsonValue
         let mut json_solution = Map::new();
         for (var_name, constant) in solution {
             let serialized_constant = serde_json::to_value(constant).unwrap();
+            let serialized_constant = enum_values_to_names(serialized_constant);
             json_solution.insert(var_name.to_string(), serialized_constant);
         }
         json_solutions.push(JsonValue::Object(json_solution));
@@ -334,10 +355,41 @@ pub fn solutions_to_json(solutions: &Vec<BTreeMap<Name, Literal>>) -> JsonValue
     sort_json_object(&ans, true)
 }
 
+/// Replaces the serialized values of enumerated types in `value` with the names of the values.
+///
+/// e.g. `{"Enum": [{"User": "Colour"}, {"User": "red"}]}` becomes `"red"`, as in Conjure's JSON
+/// solutions.
+fn enum_values_to_names(value: JsonValue) -> JsonValue {
+    match value {
+        JsonValue::Object(map) => {
+            if let Some(JsonValue::Array(fields)) = map.get("Enum")
+                && map.len() == 1
+                && let [_, value_name] = fields.as_slice()
+                && let Ok(value_name) = serde_json::from_value::<Name>(value_name.clone())
+            {
+                return JsonValue::String(value_name.to_string());
+            }
+
+            JsonValue::Object(
+                map.into_iter()
+                    .map(|(k, v)| (k, enum_values_to_names(v)))
+                    .collect(),
+            )
+        }
+        JsonValue::Array(values) => {
+            JsonValue::Array(values.into_iter().map(enum_values_to_names).collect())
+        }
+        value => value,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use conjure_cp::ast::{DeclarationPtr, Domain, Moo, Reference};
+    use conjure_cp::ast::{
+        AbstractLiteral, DeclarationPtr, Domain, GroundDomain, Moo, Reference, SetAttr,
+    };
+    use conjure_cp::parse::conjure_json::literal_from_json;
 
     #[test]
     fn retroactive_pruning_removes_dominated_prior_solution() {
@@ -371,4 +423,27 @@ mod tests {
 
         assert_eq!(pruned, vec![sol_false]);
     }
+
+    #[test]
+    fn solutions_to_json_values_read_back_as_literals() {
+        let colour = Name::user("Colour");
+        let values = vec![Name::user("red"), Name::user("green")];
+        let domain = GroundDomain::Set(
+            SetAttr::default(),
+            Moo::new(GroundDomain::Enum(colour.clone(), values)),
+        );
+        let literal = Literal::AbstractLiteral(AbstractLiteral::Set(vec![
+            Literal::Enum(Box::new((colour.clone(), Name::user("red")))),
+            Literal::Enum(Box::new((colour, Name::user("green")))),
+        ]));
+
+        let x = Name::user("x");
+        let solution = BTreeMap::from([(x.clone(), literal.clone())]);
+        let json = solutions_to_json(&vec![solution]);
+
+        assert_eq!(
+            literal_from_json(&json[0][x.to_string()], &domain).unwrap(),
+            literal
+        );
+    }
 }
@@ -413,6 +413,93 @@ pub fn normalize_solutions_for_comparison(
                         });
                         updates.push((k, Literal::AbstractLiteral(set)));
                     }
+                    Literal::AbstractLiteral(AbstractLiteral::Function(mappings)) => {
+                        // only conversion needed is to convert bools to ints
+                        let to_int = |x| match x {
+                            Literal::Bool(false) => Literal::Int(0),
+                            Literal::Bool(true) => Literal::Int(1),
+                            x => x,
+                        };
+                        let mappings = mappings
+                            .into_iter()
+                            .map(|(x, y)| (to_int(x), to_int(y)))
+                            .collect_vec();
+
+                        updates.push((
+                            k,
+                            Literal::AbstractLiteral(AbstractLiteral::Function(mappings)),
+                        ));
+                    }
+                    Literal::AbstractLiteral(AbstractLiteral::Sequence(elems)) => {
+                        // only conversion needed is to convert bools to ints
+                        let elems = elems
+                            .into_iter()
+                            .map(|x| match x {
+                                Literal::Bool(false) => Literal::Int(0),
+                                Literal::Bool(true) => Literal::Int(1),
+                                x => x,
+                            })
+                            .collect_vec();
+
+                        updates.push((
+                            k,
+                            Literal::AbstractLiteral(AbstractLiteral::Sequence(elems)),
+                        ));
+                    }
+                    Literal::AbstractLiteral(AbstractLiteral::MSet(elems)) => {
+                        // only conversion needed is to convert bools to ints
+                        let elems = elems
+                            .into_iter()
+                            .map(|x| match x {
+                                Literal::Bool(false) => Literal::Int(0),
+                                Literal::Bool(true) => Literal::Int(1),
+                                x => x,
+                            })
+                            .collect_vec();
+
+                        updates.push((k, Literal::AbstractLiteral(AbstractLiteral::MSet(elems))));
+                    }
+                    Literal::AbstractLiteral(AbstractLiteral::Relation(tuples)) => {
+                        // only conversion needed is to convert bools to ints
+                        let tuples = tuples
+                            .into_iter()
+                            .map(|tuple| {
+                                tuple
+                                    .into_iter()
+                                    .map(|x| match x {
+                                        Literal::Bool(false) => Literal::Int(0),
+                                        Literal::Bool(true) => Literal::Int(1),
+                                        x => x,
+                                    })
+                                    .collect_vec()
+                            })
+                            .collect_vec();
+
+                        updates.push((
+                            k,
+                            Literal::AbstractLiteral(AbstractLiteral::Relation(tuples)),
+                        ));
+                    }
+                    Literal::AbstractLiteral(AbstractLiteral::Partition(parts)) => {
+                        // only conversion needed is to convert bools to ints
+                        let parts = parts
+                            .into_iter()
+                            .map(|part| {
+                                part.into_iter()
+                                    .map(|x| match x {
+                                        Literal::Bool(false) => Literal::Int(0),
+                                        Literal::Bool(true) => Literal::Int(1),
+                                        x => x,
+                                    })
+                                    .collect_vec()
+                            })
+                            .collect_vec();
+
+                        updates.push((
+                            k,
+                            Literal::AbstractLiteral(AbstractLiteral::Partition(parts)),
+                        ));
+                    }
                     e => bug!("unexpected literal type: {e:?}"),
                 }
             }
@@ -6,8 +6,8 @@ use crate::ast::domains::{
     unresolved::{FieldUnresolved, UnresolvedDomain},
 };
 use crate::ast::{
-    DeclarationPtr, DomainOpError, Expression, Field, FuncAttr, Literal, Moo, Reference, RelAttr,
-    ReturnType, SequenceAttr, Typeable,
+    DeclarationPtr, DomainOpError, Expression, Field, FuncAttr, Literal, Moo, Name, Reference,
+    RelAttr, ReturnType, SequenceAttr, Typeable,
 };
 use itertools::Itertools;
 use polyquine::Quine;
@@ -277,6 +277,12 @@ impl Domain {
         ))))
     }
 
+    /// Create a new enumerated or unnamed type domain with the given name and values.
+    /// Enumerated domains are always ground (see [GroundDomain::Enum]).
+    pub fn enumerated(name: Name, values: Vec<Name>) -> DomainPtr {
+        Moo::new(Domain::Ground(Moo::new(GroundDomain::Enum(name, values))))
+    }
+
     /// Create a new Sequence domain
     pub fn sequence<T>(attr: T, inner_dom: DomainPtr) -> DomainPtr
     where
@@ -855,6 +861,14 @@ impl Domain {
         None
     }
 
+    /// If this is a [GroundDomain::Enum], get its (name, [values])
+    pub fn as_enum_ground(&self) -> Option<(&Name, &Vec<Name>)> {
+        if let Some(GroundDomain::Enum(name, values)) = self.as_ground() {
+            return Some((name, values));
+        }
+        None
+    }
+
     /// Compute the intersection of two domains
     pub fn union(&self, other: &Domain) -> Result<Domain, DomainOpError> {
         match (self, other) {
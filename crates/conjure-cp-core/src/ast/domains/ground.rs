@@ -53,6 +53,8 @@ pub enum GroundDomain {
     Relation(RelAttr, Vec<Moo<GroundDomain>>),
     /// A variant domain with its domain options (reusing field entries)
     Variant(Vec<FieldGround>),
+    /// An enumerated or unnamed type with the given name, containing the given values in order
+    Enum(Name, Vec<Name>),
 }
 
 impl GroundDomain {
@@ -134,6 +136,13 @@ impl GroundDomain {
             (GroundDomain::Relation(..), _) | (_, GroundDomain::Relation(..)) => {
                 Err(DomainOpError::WrongType)
             }
+            (GroundDomain::Enum(n1, vs1), GroundDomain::Enum(n2, vs2)) if n1 == n2 => {
+                let values = vs1.iter().chain(vs2).unique().cloned().collect();
+                Ok(GroundDomain::Enum(n1.clone(), values))
+            }
+            (GroundDomain::Enum(..), _) | (_, GroundDomain::Enum(..)) => {
+                Err(DomainOpError::WrongType)
+            }
             #[allow(unreachable_patterns)]
             (GroundDomain::Sequence(_, _), _) | (_, GroundDomain::Sequence(_, _)) => {
                 todo!("union sequence domains")
@@ -201,6 +210,10 @@ impl GroundDomain {
                 SetAttr::default(),
                 Moo::new((*x).intersect(y)?),
             )),
+            (GroundDomain::MSet(_, x), GroundDomain::MSet(_, y)) => Ok(GroundDomain::MSet(
+                MSetAttr::default(),
+                Moo::new((*x).intersect(y)?),
+            )),
 
             (GroundDomain::Int(_), GroundDomain::Int(_)) => {
                 let mut v: BTreeSet<i32> = BTreeSet::new();
@@ -214,6 +227,10 @@ impl GroundDomain {
                 }
                 Ok(GroundDomain::from_set_i32(&v))
             }
+            (GroundDomain::Enum(n1, vs1), GroundDomain::Enum(n2, vs2)) if n1 == n2 => {
+                let values = vs1.iter().filter(|v| vs2.contains(v)).cloned().collect();
+                Ok(GroundDomain::Enum(n1.clone(), values))
+            }
             (GroundDomain::Relation(_, _), GroundDomain::Relation(_, _)) => {
                 todo!("Relation union not yet supported")
             }
@@ -324,6 +341,12 @@ impl GroundDomain {
             GroundDomain::Variant(..) => {
                 todo!("Enumerating variant domains is not yet supported")
             }
+            GroundDomain::Enum(name, values) => {
+                let name = name.clone();
+                Ok(Box::new(values.clone().into_iter().map(move |value| {
+                    Literal::Enum(Box::new((name.clone(), value)))
+                })))
+            }
         }
     }
 
@@ -438,6 +461,7 @@ impl GroundDomain {
             GroundDomain::Partition(_, _) => {
                 todo!("Length bound of Partitions is not yet supported")
             }
+            GroundDomain::Enum(_, values) => Ok(values.len() as u64),
         }
     }
 
@@ -684,6 +708,10 @@ impl GroundDomain {
                 }
                 _ => Ok(false),
             },
+            GroundDomain::Enum(name, values) => match lit {
+                Literal::Enum(value) => Ok(value.0 == *name && values.contains(&value.1)),
+                _ => Ok(false),
+            },
         }
     }
 
@@ -975,6 +1003,25 @@ impl GroundDomain {
                     Ok(GroundDomain::Bool)
                 }
             }
+            Literal::Enum(first_value) => {
+                // check all literals are values of the same enumerated type
+                let name = &first_value.0;
+                let mut values = vec![];
+                for lit in literals {
+                    let Literal::Enum(value) = lit else {
+                        return Err(DomainOpError::WrongType);
+                    };
+                    let (lit_name, value) = value.as_ref();
+                    if lit_name != name {
+                        return Err(DomainOpError::WrongType);
+                    }
+                    if !values.contains(value) {
+                        values.push(value.clone());
+                    }
+                }
+
+                Ok(GroundDomain::Enum(name.clone(), values))
+            }
             Literal::AbstractLiteral(AbstractLiteral::Set(_)) => {
                 let mut all_elems = vec![];
 
@@ -1233,7 +1280,10 @@ impl Typeable for GroundDomain {
                 }
                 ReturnType::Relation(inner_types)
             }
-            GroundDomain::Partition(_, inner) => ReturnType::Set(Box::new(inner.return_type())),
+            GroundDomain::Partition(_, inner) => {
+                ReturnType::Partition(Box::new(inner.return_type()))
+            }
+            GroundDomain::Enum(name, _) => ReturnType::Enum(name.clone()),
         }
     }
 }
@@ -1289,6 +1339,7 @@ impl Display for GroundDomain {
             GroundDomain::Partition(attrs, inner) => {
                 write!(f, "partition {attrs} from {inner}")
             }
+            GroundDomain::Enum(name, _) => write!(f, "{name}"),
         }
     }
 }
@@ -496,7 +496,7 @@ pub fn eval_constant(expr: &Expr) -> Option<Lit> {
                     return None;
                 };
                 match x {
-                    Lit::Int(_) | Lit::Bool(_) => {
+                    Lit::Int(_) | Lit::Bool(_) | Lit::Enum(_) => {
                         if lits.contains(&x) {
                             return Some(Lit::Bool(false));
                         } else {
@@ -516,7 +516,7 @@ pub fn eval_constant(expr: &Expr) -> Option<Lit> {
                 };
 
                 match x {
-                    Lit::Int(_) | Lit::Bool(_) => {
+                    Lit::Int(_) | Lit::Bool(_) | Lit::Enum(_) => {
                         if lits.contains(x) {
                             return Some(Lit::Bool(false));
                         } else {
@@ -631,22 +631,142 @@ pub fn eval_constant(expr: &Expr) -> Option<Lit> {
         }
         Expr::Defined(_, _) => todo!(),
         Expr::Range(_, _) => todo!(),
-        Expr::Image(_, _, _) => todo!(),
-        Expr::ImageSet(_, _, _) => todo!(),
-        Expr::PreImage(_, _, _) => todo!(),
-        Expr::Inverse(_, _, _) => todo!(),
-        Expr::Restrict(_, _, _) => todo!(),
+        Expr::Image(_, f, x) => {
+            let mappings = eval_function(f)?;
+            let x = eval_constant(x)?;
+
+            // undefined if f is not defined on x
+            mappings
+                .into_iter()
+                .find(|(y, _)| *y == x)
+                .map(|(_, image)| image)
+        }
+        Expr::ImageSet(_, f, x) => {
+            let mappings = eval_function(f)?;
+            let x = eval_constant(x)?;
+
+            let images = mappings
+                .into_iter()
+                .filter(|(y, _)| *y == x)
+                .map(|(_, image)| image)
+                .collect();
+            Some(Lit::AbstractLiteral(AbstractLiteral::Set(images)))
+        }
+        Expr::PreImage(_, f, y) => {
+            let mappings = eval_function(f)?;
+            let y = eval_constant(y)?;
+
+            let xs = mappings
+                .into_iter()
+                .filter(|(_, image)| *image == y)
+                .map(|(x, _)| x)
+                .collect();
+            Some(Lit::AbstractLiteral(AbstractLiteral::Set(xs)))
+        }
+        Expr::Inverse(_, f, g) => {
+            let f: HashSet<(Lit, Lit)> = eval_function(f)?.into_iter().collect();
+            let g: HashSet<(Lit, Lit)> =
+                eval_function(g)?.into_iter().map(|(x, y)| (y, x)).collect();
+            Some(Lit::Bool(f == g))
+        }
+        Expr::Restrict(_, _, _) => None,
         Expr::Active(_, _, _) => todo!(),
         Expr::ToSet(_, _) => todo!(),
         Expr::ToMSet(_, _) => todo!(),
-        Expr::ToRelation(_, _) => todo!(),
-        Expr::RelationProj(_, _, _) => todo!(),
-        Expr::Apart(_, _, _) => todo!(),
-        Expr::Together(_, _, _) => todo!(),
-        Expr::Participants(_, _) => todo!(),
-        Expr::Party(_, _, _) => todo!(),
-        Expr::Parts(_, _) => todo!(),
-        Expr::Card(_, _) => todo!(),
+        Expr::Freq(_, m, x) => {
+            let Lit::AbstractLiteral(AbstractLiteral::MSet(elems)) = eval_constant(m)? else {
+                return None;
+            };
+            let x = eval_constant(x)?;
+            let count = elems.iter().filter(|y| **y == x).count();
+            Some(Lit::Int(count.try_into().ok()?))
+        }
+        Expr::ToRelation(_, f) => {
+            let tuples = eval_function(f)?
+                .into_iter()
+                .map(|(x, y)| vec![x, y])
+                .collect();
+            Some(Lit::AbstractLiteral(AbstractLiteral::Relation(tuples)))
+        }
+        Expr::RelationProj(_, r, projections) => {
+            let Lit::AbstractLiteral(AbstractLiteral::Relation(tuples)) = eval_constant(r)? else {
+                return None;
+            };
+            let projections = projections
+                .iter()
+                .map(|x| match x {
+                    Some(x) => eval_constant(x).map(Some),
+                    None => Some(None),
+                })
+                .collect::<Option<Vec<_>>>()?;
+
+            // the tuples matching the given values, without the given positions
+            let mut matching = tuples
+                .into_iter()
+                .filter(|tuple| {
+                    izip!(tuple, &projections).all(|(x, y)| y.as_ref().is_none_or(|y| x == y))
+                })
+                .map(|tuple| {
+                    izip!(tuple, &projections)
+                        .filter(|(_, y)| y.is_none())
+                        .map(|(x, _)| x)
+                        .collect_vec()
+                });
+
+            if projections.iter().all(Option::is_some) {
+                Some(Lit::Bool(matching.next().is_some()))
+            } else {
+                Some(Lit::AbstractLiteral(AbstractLiteral::Relation(
+                    matching.collect(),
+                )))
+            }
+        }
+        Expr::Apart(_, xs, p) => {
+            let xs = eval_collection(xs)?;
+            let together = eval_partition(p)?
+                .iter()
+                .any(|part| xs.iter().all(|x| part.contains(x)));
+            Some(Lit::Bool(!together))
+        }
+        Expr::Together(_, xs, p) => {
+            let xs = eval_collection(xs)?;
+            let together = eval_partition(p)?
+                .iter()
+                .any(|part| xs.iter().all(|x| part.contains(x)));
+            Some(Lit::Bool(together))
+        }
+        Expr::Participants(_, p) => {
+            let elems = eval_partition(p)?.into_iter().flatten().collect();
+            Some(Lit::AbstractLiteral(AbstractLiteral::Set(elems)))
+        }
+        Expr::Party(_, x, p) => {
+            let x = eval_constant(x)?;
+            let part = eval_partition(p)?
+                .into_iter()
+                .find(|part| part.contains(&x))
+                .unwrap_or_default();
+            Some(Lit::AbstractLiteral(AbstractLiteral::Set(part)))
+        }
+        Expr::Parts(_, p) => {
+            let parts = eval_partition(p)?
+                .into_iter()
+                .map(|part| Lit::AbstractLiteral(AbstractLiteral::Set(part)))
+                .collect();
+            Some(Lit::AbstractLiteral(AbstractLiteral::Set(parts)))
+        }
+        Expr::Card(_, x) => {
+            let size = match eval_constant(x)? {
+                Lit::AbstractLiteral(
+                    AbstractLiteral::Set(elems)
+                    | AbstractLiteral::MSet(elems)
+                    | AbstractLiteral::Sequence(elems),
+                ) => elems.len(),
+                Lit::AbstractLiteral(AbstractLiteral::Function(mappings)) => mappings.len(),
+                Lit::AbstractLiteral(AbstractLiteral::Relation(tuples)) => tuples.len(),
+                _ => return None,
+            };
+            Some(Lit::Int(size.try_into().ok()?))
+        }
         Expr::LexLt(_, a, b) => {
             let lt = vec_expr_pairs_op::<i32, _>(a, b, |pairs, (a_len, b_len)| {
                 pairs
@@ -706,6 +826,42 @@ pub fn eval_constant(expr: &Expr) -> Option<Lit> {
     }
 }
 
+/// Evaluates `expr` to the mappings of a constant function, if possible.
+///
+/// Sequences are functions from their indices, starting from 1, to their elements.
+fn eval_function(expr: &Expr) -> Option<Vec<(Lit, Lit)>> {
+    match eval_constant(expr)? {
+        Lit::AbstractLiteral(AbstractLiteral::Function(mappings)) => Some(mappings),
+        Lit::AbstractLiteral(AbstractLiteral::Sequence(elems)) => Some(
+            (1..)
+                .zip(elems)
+                .map(|(i, elem)| (Lit::Int(i), elem))
+                .collect(),
+        ),
+        _ => None,
+    }
+}
+
+/// Evaluates `expr` to the parts of a constant partition, if possible.
+fn eval_partition(expr: &Expr) -> Option<Vec<Vec<Lit>>> {
+    match eval_constant(expr)? {
+        Lit::AbstractLiteral(AbstractLiteral::Partition(parts)) => Some(parts),
+        _ => None,
+    }
+}
+
+/// Evaluates `expr` to the elements of a constant set, multi-set, or matrix, if possible.
+fn eval_collection(expr: &Expr) -> Option<Vec<Lit>> {
+    match eval_constant(expr)? {
+        Lit::AbstractLiteral(
+            AbstractLiteral::Set(elems)
+            | AbstractLiteral::MSet(elems)
+            | AbstractLiteral::Matrix(elems, _),
+        ) => Some(elems),
+        _ => None,
+    }
+}
+
 pub fn un_op<T, A>(f: fn(T) -> A, a: &Expr) -> Option<A>
 where
     T: TryFrom<Lit>,
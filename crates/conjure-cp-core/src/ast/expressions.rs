@@ -308,6 +308,12 @@ pub enum Expression {
     #[compatible(JsonInput)]
     ToMSet(Metadata, Moo<Expression>),
 
+    /// Number of occurrences of an element in a multi-set: `freq(M, x)`
+    /// First Expr Arg is the multi-set
+    /// Second Expr Arg is the element to count
+    #[compatible(JsonInput)]
+    Freq(Metadata, Moo<Expression>, Moo<Expression>),
+
     #[compatible(JsonInput)]
     ToRelation(Metadata, Moo<Expression>),
 
@@ -763,14 +769,8 @@ impl Expression {
     /// Returns the possible values of the expression, recursing to leaf expressions
     pub fn domain_of(&self) -> Option<DomainPtr> {
         match self {
-            Expression::Union(_, a, b) => Some(Domain::set(
-                SetAttr::<IntVal>::default(),
-                a.domain_of()?.union(&b.domain_of()?).ok()?,
-            )),
-            Expression::Intersect(_, a, b) => Some(Domain::set(
-                SetAttr::<IntVal>::default(),
-                a.domain_of()?.intersect(&b.domain_of()?).ok()?,
-            )),
+            Expression::Union(_, a, b) => a.domain_of()?.union(&b.domain_of()?).ok(),
+            Expression::Intersect(_, a, b) => a.domain_of()?.intersect(&b.domain_of()?).ok(),
             Expression::In(_, _, _) => Some(Domain::bool()),
             Expression::Supset(_, _, _) => Some(Domain::bool()),
             Expression::SupsetEq(_, _, _) => Some(Domain::bool()),
@@ -1367,6 +1367,11 @@ impl Expression {
                 Some(Domain::relation(rel_attrs, vec![domain, codomain]))
             }
             Expression::RelationProj(_, relation, projections) => {
+                // projecting every position tests whether the tuple is in the relation
+                if projections.iter().all(Option::is_some) {
+                    return Some(Domain::bool());
+                }
+
                 let (_, domains) = relation.domain_of()?.as_relation()?;
                 let new_doms = domains
                     .iter()
@@ -1430,6 +1435,20 @@ impl Expression {
                     Domain::int(inner.as_int()?),
                 ))
             }
+            Expression::Freq(_, mset, _) => {
+                // An element can occur at most maxOccur times, and at most maxSize times
+                let (attr, _) = mset.domain_of()?.as_mset()?;
+                let attr = attr.resolve().ok()?;
+                let high = [attr.occurrence.high(), attr.size.high()]
+                    .into_iter()
+                    .flatten()
+                    .min()
+                    .copied();
+                match high {
+                    Some(high) => Some(Domain::int(vec![Range::Bounded(0, high)])),
+                    None => Some(Domain::int(vec![Range::UnboundedR(0)])),
+                }
+            }
             Expression::Party(_, _, p) => {
                 // Will pick a part, so set will share same attrs
                 let (attr, inner) = p.domain_of()?.as_partition()?;
@@ -1512,9 +1531,15 @@ impl Expression {
                 } else if let Some((attrs, dom, codom)) = domain.as_function() {
                     let size = Self::function_elements_size(attrs, &dom, &codom);
                     size.map(|size| Domain::int(vec![size]))
+                } else if let Some((attrs, _)) = domain.as_sequence() {
+                    let size = match attrs.resolve().ok()?.size {
+                        Range::UnboundedL(max) => Range::Bounded(0, max),
+                        size => size,
+                    };
+                    Some(Domain::int(vec![size]))
                 } else {
                     bug!(
-                        "Domain of {self} needed to be a matrix, set, mset, relation, or function for cardinality"
+                        "Domain of {self} needed to be a matrix, set, mset, relation, function, or sequence for cardinality"
                     )
                 }
             }
@@ -1670,6 +1695,7 @@ impl Expression {
             Active,
             ToSet,
             ToMSet,
+            Freq,
             ToRelation,
             RelationProj,
             Card,
@@ -1687,6 +1713,15 @@ impl Expression {
         self.transform_bi(&|_| meta.clone());
     }
 
+    /// Returns this expression with the given source span id in its metadata.
+    ///
+    /// The metadata of its sub-expressions is left unchanged.
+    pub fn with_span_id(self, span_id: u32) -> Expression {
+        let mut metas: VecDeque<Metadata> = self.children_bi();
+        metas[0].span_id = Some(span_id);
+        self.with_children_bi(metas)
+    }
+
     /// Checks whether this expression is safe.
     ///
     /// An expression is unsafe if can be undefined, or if any of its children can be undefined.
@@ -1850,6 +1885,8 @@ pub fn get_function_codomain(function: &Moo<Expression>) -> Option<DomainPtr> {
         Ok(d) => {
             match d.as_ref() {
                 GroundDomain::Function(_, _, codomain) => Some(codomain.clone().into()),
+                // Sequences are functions from their indices to their elements
+                GroundDomain::Sequence(_, inner) => Some(inner.clone().into()),
                 // Not defined for anything other than a function
                 _ => None,
             }
@@ -1857,6 +1894,7 @@ pub fn get_function_codomain(function: &Moo<Expression>) -> Option<DomainPtr> {
         Err(_) => {
             match function_domain.as_unresolved()? {
                 UnresolvedDomain::Function(_, _, codomain) => Some(codomain.clone()),
+                UnresolvedDomain::Sequence(_, inner) => Some(inner.clone()),
                 // Not defined for anything other than a function
                 _ => None,
             }
@@ -2289,6 +2327,7 @@ impl Display for Expression {
             }
             Expression::ToSet(_, other) => write!(f, "toSet({other})"),
             Expression::ToMSet(_, other) => write!(f, "toMSet({other})"),
+            Expression::Freq(_, mset, element) => write!(f, "freq({mset}, {element})"),
             Expression::ToRelation(_, function) => write!(f, "toRelation({function})"),
             Expression::RelationProj(_, relation, projections) => {
                 let projections_str = projections
@@ -2329,10 +2368,8 @@ fn minus_operand_return_type(expr: &Expression) -> ReturnType {
 impl Typeable for Expression {
     fn return_type(&self) -> ReturnType {
         match self {
-            Expression::Union(_, subject, _) => ReturnType::Set(Box::new(subject.return_type())),
-            Expression::Intersect(_, subject, _) => {
-                ReturnType::Set(Box::new(subject.return_type()))
-            }
+            Expression::Union(_, subject, _) => subject.return_type(),
+            Expression::Intersect(_, subject, _) => subject.return_type(),
             Expression::In(_, _, _) => ReturnType::Bool,
             Expression::Supset(_, _, _) => ReturnType::Bool,
             Expression::SupsetEq(_, _, _) => ReturnType::Bool,
@@ -2403,12 +2440,26 @@ impl Typeable for Expression {
             Expression::Lt(_, _, _) => ReturnType::Bool,
             Expression::Apart(_, _, _) => ReturnType::Bool,
             Expression::Together(_, _, _) => ReturnType::Bool,
-            Expression::Party(_, _, subject) => ReturnType::Set(Box::new(subject.return_type())),
-            Expression::Participants(_, subject) => {
-                ReturnType::Set(Box::new(subject.return_type()))
+            Expression::Freq(_, _, _) => ReturnType::Int,
+            Expression::Party(_, _, subject) | Expression::Participants(_, subject) => {
+                let subject = subject.return_type();
+                match subject {
+                    ReturnType::Partition(inner) => ReturnType::Set(inner),
+                    _ => bug!(
+                        "Invalid partition operation: expected the operand to be a partition, got {self}: {subject}"
+                    ),
+                }
             }
             Expression::Parts(_, subject) => {
-                ReturnType::Set(Box::new(ReturnType::Set(Box::new(subject.return_type()))))
+                let subject = subject.return_type();
+                match subject {
+                    ReturnType::Partition(inner) => {
+                        ReturnType::Set(Box::new(ReturnType::Set(inner)))
+                    }
+                    _ => bug!(
+                        "Invalid parts operation: expected the operand to be a partition, got {self}: {subject}"
+                    ),
+                }
             }
             Expression::SafeDiv(_, _, _) => ReturnType::Int,
             Expression::UnsafeDiv(_, _, _) => ReturnType::Int,
@@ -2501,7 +2552,7 @@ impl Typeable for Expression {
             Expression::Image(_, function, _) => {
                 let subject = function.return_type();
                 match subject {
-                    ReturnType::Function(_, codomain) => *codomain,
+                    ReturnType::Function(_, codomain) | ReturnType::Sequence(codomain) => *codomain,
                     _ => bug!(
                         "Invalid image operation: expected the operand to be a function, got {self}: {subject}"
                     ),
@@ -2587,6 +2638,11 @@ impl Typeable for Expression {
                 }
             }
             Expression::RelationProj(_, relation, projections) => {
+                // projecting every position tests whether the tuple is in the relation
+                if projections.iter().all(Option::is_some) {
+                    return ReturnType::Bool;
+                }
+
                 let subject = relation.return_type();
                 match subject {
                     ReturnType::Relation(domains) => {
@@ -2735,6 +2791,7 @@ impl Expression {
             | Expression::Apart(_, m1, m2)
             | Expression::Together(_, m1, m2)
             | Expression::Party(_, m1, m2)
+            | Expression::Freq(_, m1, m2)
             | Expression::LexLt(_, m1, m2)
             | Expression::LexLeq(_, m1, m2)
             | Expression::LexGt(_, m1, m2)
@@ -2946,6 +3003,7 @@ impl CacheHashable for Expression {
             | Expression::Apart(_, m1, m2)
             | Expression::Together(_, m1, m2)
             | Expression::Party(_, m1, m2)
+            | Expression::Freq(_, m1, m2)
             | Expression::SafeDiv(_, m1, m2)
             | Expression::UnsafeDiv(_, m1, m2)
             | Expression::SafeMod(_, m1, m2)
@@ -3155,4 +3213,100 @@ mod tests {
         let sum = Expression::Sum(Metadata::new(), Moo::new(matrix_expr![]));
         assert_eq!(sum.domain_of(), None);
     }
+
+    fn find(name: &str, domain: DomainPtr) -> Moo<Expression> {
+        let decl = DeclarationPtr::new_find(Name::user(name), domain);
+        Moo::new(Expression::Atomic(
+            Metadata::new(),
+            Atom::Reference(Reference::new(decl)),
+        ))
+    }
+
+    #[test]
+    fn test_domain_of_set_union_and_intersect() {
+        let a = find(
+            "a",
+            Domain::set(
+                SetAttr::<IntVal>::default(),
+                Domain::int(vec![Range::Bounded(1, 3)]),
+            ),
+        );
+        let b = find(
+            "b",
+            Domain::set(
+                SetAttr::<IntVal>::default(),
+                Domain::int(vec![Range::Bounded(2, 5)]),
+            ),
+        );
+
+        let union = Expression::Union(Metadata::new(), a.clone(), b.clone());
+        assert_eq!(
+            union.domain_of(),
+            Some(Domain::set(
+                SetAttr::<IntVal>::default(),
+                Domain::int(vec![Range::Bounded(1, 5)])
+            ))
+        );
+        assert_eq!(
+            union.return_type(),
+            ReturnType::Set(Box::new(ReturnType::Int))
+        );
+
+        let intersect = Expression::Intersect(Metadata::new(), a, b);
+        assert_eq!(
+            intersect.domain_of(),
+            Some(Domain::set(
+                SetAttr::<IntVal>::default(),
+                Domain::int(vec![Range::Bounded(2, 3)])
+            ))
+        );
+        assert_eq!(
+            intersect.return_type(),
+            ReturnType::Set(Box::new(ReturnType::Int))
+        );
+    }
+
+    #[test]
+    fn test_domain_of_mset_union_and_intersect() {
+        let a = find(
+            "a",
+            Domain::mset(
+                MSetAttr::<IntVal>::default(),
+                Domain::int(vec![Range::Bounded(1, 3)]),
+            ),
+        );
+        let b = find(
+            "b",
+            Domain::mset(
+                MSetAttr::<IntVal>::default(),
+                Domain::int(vec![Range::Bounded(2, 5)]),
+            ),
+        );
+
+        let union = Expression::Union(Metadata::new(), a.clone(), b.clone());
+        assert_eq!(
+            union.domain_of(),
+            Some(Domain::mset(
+                MSetAttr::<IntVal>::default(),
+                Domain::int(vec![Range::Bounded(1, 5)])
+            ))
+        );
+        assert_eq!(
+            union.return_type(),
+            ReturnType::MSet(Box::new(ReturnType::Int))
+        );
+
+        let intersect = Expression::Intersect(Metadata::new(), a, b);
+        assert_eq!(
+            intersect.domain_of(),
+            Some(Domain::mset(
+                MSetAttr::<IntVal>::default(),
+                Domain::int(vec![Range::Bounded(2, 3)])
+            ))
+        );
+        assert_eq!(
+            intersect.return_type(),
+            ReturnType::MSet(Box::new(ReturnType::Int))
+        );
+    }
 }
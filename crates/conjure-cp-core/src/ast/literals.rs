@@ -6,7 +6,7 @@ use std::hash::Hash;
 use ustr::Ustr;
 
 use super::{
-    Atom, Domain, DomainPtr, Expression, GroundDomain, Metadata, Moo, PartitionAttr, Range,
+    Atom, Domain, DomainPtr, Expression, GroundDomain, Metadata, Moo, Name, PartitionAttr, Range,
     ReturnType, SetAttr, Typeable, domains::HasDomain, domains::Int, records::Field,
 };
 use crate::ast::domains::{MSetAttr, SequenceAttr};
@@ -31,6 +31,16 @@ pub enum Literal {
     //abstract literal variant ends in Literal, but that's ok
     #[allow(clippy::enum_variant_names)]
     AbstractLiteral(AbstractLiteral<Literal>),
+    /// A value of an enumerated or unnamed type
+    Enum(
+        // box these fields to make the size of literal smaller
+        Box<(
+            // The name of the enumerated type
+            Name,
+            // The name of the value
+            Name,
+        )>,
+    ),
 }
 
 impl HasDomain for Literal {
@@ -39,6 +49,10 @@ impl HasDomain for Literal {
             Literal::Int(i) => Domain::int(vec![Range::Single(*i)]),
             Literal::Bool(_) => Domain::bool(),
             Literal::AbstractLiteral(abstract_literal) => abstract_literal.domain_of(),
+            Literal::Enum(value) => {
+                let (name, value) = value.as_ref();
+                Domain::enumerated(name.clone(), vec![value.clone()])
+            }
         }
     }
 }
@@ -936,6 +950,7 @@ impl Display for Literal {
             Literal::Int(i) => write!(f, "{i}"),
             Literal::Bool(b) => write!(f, "{b}"),
             Literal::AbstractLiteral(l) => write!(f, "{l}"),
+            Literal::Enum(value) => write!(f, "{}", value.1),
         }
     }
 }
@@ -14,14 +14,17 @@ pub mod matrix;
 mod metadata;
 mod model;
 mod name;
+mod objective;
 pub mod pretty;
 pub mod records;
 mod reference;
 mod sat_encoding;
+mod search_heuristic;
 pub mod serde;
 mod symbol_table;
 mod types;
 mod variables;
+mod where_condition;
 
 mod moo;
 mod partial_eval;
@@ -42,13 +45,16 @@ pub use metadata::Metadata;
 pub use model::*;
 pub use moo::Moo;
 pub use name::Name;
+pub use objective::{Objective, ObjectiveDirection, ObjectiveDomainError};
 pub use partial_eval::run_partial_evaluator;
 pub use records::Field;
 pub use reference::Reference;
 pub use sat_encoding::SATIntEncoding;
+pub use search_heuristic::{SearchHeuristic, ValueHeuristic, VariableHeuristic};
 pub use symbol_table::{SymbolTable, SymbolTablePtr};
 pub use types::*;
 pub use variables::DecisionVariable;
+pub use where_condition::{SourceLocation, WhereCondition};
 
 /// Helper to build a matrix `AbstractLiteral` with given domains
 #[doc(hidden)]
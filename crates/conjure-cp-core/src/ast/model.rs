@@ -16,8 +16,9 @@ use uniplate::{Biplate, Tree, Uniplate};
 
 use super::serde::{HasId, ObjId, PtrAsInner};
 use super::{
-    Atom, CnfClause, DeclarationPtr, Expression, Literal, Metadata, Moo, Name, ReturnType,
-    SymbolTable, SymbolTablePtr, Typeable,
+    Atom, CnfClause, DeclarationPtr, Expression, Literal, Metadata, Moo, Name, Objective,
+    ObjectiveDomainError, Reference, ReturnType, SearchHeuristic, SymbolTable, SymbolTablePtr,
+    Typeable, WhereCondition,
     comprehension::Comprehension,
     declaration::DeclarationKind,
     pretty::{
@@ -38,6 +39,15 @@ pub struct Model {
 
     pub search_order: Option<Vec<Name>>,
     pub dominance: Option<Expression>,
+    pub objective: Option<Objective>,
+
+    /// Conditions on the parameters of the model, checked when it is instantiated.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub where_conditions: Vec<WhereCondition>,
+
+    /// The search heuristics set by the `heuristic` statements of the model.
+    #[serde(default, skip_serializing_if = "SearchHeuristic::is_empty")]
+    pub search_heuristic: SearchHeuristic,
 
     #[serde(skip, default = "default_context")]
     #[derivative(PartialEq = "ignore")]
@@ -56,6 +66,9 @@ impl Model {
             cnf_clauses: Vec::new(),
             search_order: None,
             dominance: None,
+            objective: None,
+            where_conditions: Vec::new(),
+            search_heuristic: SearchHeuristic::default(),
             context,
         }
     }
@@ -174,6 +187,73 @@ impl Model {
         self.symbols_mut().insert(decl)
     }
 
+    /// The search order in terms of the variables given to the solver.
+    ///
+    /// Each variable in [`search_order`](Model::search_order) that has been given a
+    /// representation is replaced by its representation variables, recursively. Variables are
+    /// only listed once.
+    pub fn solver_search_order(&self) -> Option<Vec<Name>> {
+        fn push_solver_vars(symbols: &SymbolTable, name: &Name, vars: &mut IndexSet<Name>) {
+            let reprs = symbols.representations_for(name).unwrap_or_default();
+            if reprs.is_empty() {
+                vars.insert(name.clone());
+                return;
+            }
+
+            for repr in reprs.iter().flatten() {
+                let Ok(decls) = repr.declaration_down() else {
+                    continue;
+                };
+                for decl in decls {
+                    push_solver_vars(symbols, &decl.name(), vars);
+                }
+            }
+        }
+
+        let search_order = self.search_order.as_ref()?;
+        let symbols = self.symbols();
+        let mut vars = IndexSet::new();
+        for name in search_order {
+            push_solver_vars(&symbols, name, &mut vars);
+        }
+
+        Some(vars.into_iter().collect())
+    }
+
+    /// Replaces a compound objective expression with a fresh decision variable constrained to be
+    /// equal to it.
+    ///
+    /// This lets the objective be rewritten alongside the other constraints, and leaves solvers
+    /// with a single variable to optimise.
+    ///
+    /// Returns an error if the domain of the objective expression cannot be found.
+    pub fn flatten_objective(&mut self) -> Result<(), ObjectiveDomainError> {
+        let Some(objective) = &self.objective else {
+            return Ok(());
+        };
+
+        if objective.variable_name().is_some() {
+            return Ok(());
+        }
+
+        let Some(domain) = objective.expression.domain_of() else {
+            return Err(ObjectiveDomainError(objective.expression.clone()));
+        };
+
+        let decl = self.symbols_mut().gen_find(&domain);
+        let reference = Expression::Atomic(Metadata::new(), Atom::Reference(Reference::new(decl)));
+
+        #[allow(clippy::unwrap_used)]
+        let objective = self.objective.as_mut().unwrap();
+        let expr = std::mem::replace(&mut objective.expression, reference.clone());
+        self.add_constraint(Expression::Eq(
+            Metadata::new(),
+            Moo::new(reference),
+            Moo::new(expr),
+        ));
+        Ok(())
+    }
+
     /// Converts the constraints in this model to a single expression suitable for use inside
     /// another expression tree.
     pub fn into_single_expression(self) -> Expression {
@@ -206,6 +286,12 @@ impl Model {
         if let Some(dominance) = &self.dominance {
             exprs.push_back(dominance.clone());
         }
+        if let Some(objective) = &self.objective {
+            exprs.push_back(objective.expression.clone());
+        }
+        for condition in &self.where_conditions {
+            exprs.push_back(condition.expression.clone());
+        }
 
         for symbol_table in Biplate::<SymbolTablePtr>::universe_bi(&exprs) {
             visit_symbol_table(symbol_table, &mut id_list);
@@ -249,6 +335,9 @@ impl Hash for Model {
         self.cnf_clauses.hash(state);
         self.search_order.hash(state);
         self.dominance.hash(state);
+        self.objective.hash(state);
+        self.where_conditions.hash(state);
+        self.search_heuristic.hash(state);
     }
 }
 
@@ -271,19 +360,25 @@ impl Biplate<Expression> for Model {
             None => Tree::Zero,
         };
 
+        let objective_tree = match &self.objective {
+            Some(objective) => Tree::One(objective.expression.clone()),
+            None => Tree::Zero,
+        };
+
         let tree = Tree::Many(VecDeque::from([
             Tree::One(self.root().clone()),
             symtab_tree,
             dom_tree,
+            objective_tree,
         ]));
 
         let self2 = self.clone();
         let ctx = Box::new(move |x| {
             let Tree::Many(xs) = x else {
-                panic!("Expected a tree with three children");
+                panic!("Expected a tree with four children");
             };
-            if xs.len() != 3 {
-                panic!("Expected a tree with three children");
+            if xs.len() != 4 {
+                panic!("Expected a tree with four children");
             }
 
             let Tree::One(root) = xs[0].clone() else {
@@ -296,6 +391,11 @@ impl Biplate<Expression> for Model {
                 Tree::Zero => None,
                 _ => panic!("Expected dominance tree"),
             };
+            let objective_expr = match xs[3].clone() {
+                Tree::One(expr) => Some(expr),
+                Tree::Zero => None,
+                _ => panic!("Expected objective tree"),
+            };
 
             let mut self3 = self2.clone();
 
@@ -306,6 +406,9 @@ impl Biplate<Expression> for Model {
             *self3.root_mut_unchecked() = root;
             *self3.symbols_mut() = symtab;
             self3.dominance = dominance;
+            if let (Some(objective), Some(expr)) = (self3.objective.as_mut(), objective_expr) {
+                objective.expression = expr;
+            }
 
             self3
         });
@@ -422,6 +525,12 @@ impl Display for Model {
             }
         }
 
+        for condition in &self.where_conditions {
+            writeln!(f, "{condition}")?;
+        }
+
+        write!(f, "{}", self.search_heuristic)?;
+
         if !self.constraints().is_empty() {
             writeln!(f, "\nsuch that\n")?;
             writeln!(f, "{}", pretty_expressions_as_top_level(self.constraints()))?;
@@ -431,6 +540,10 @@ impl Display for Model {
             writeln!(f, "\nclauses:\n")?;
             writeln!(f, "{}", pretty_clauses(self.clauses()))?;
         }
+
+        if let Some(objective) = &self.objective {
+            writeln!(f, "\n{objective}")?;
+        }
         Ok(())
     }
 }
@@ -448,6 +561,12 @@ pub struct SerdeModel {
     cnf_clauses: Vec<CnfClause>,
     search_order: Option<Vec<Name>>,
     dominance: Option<Expression>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    objective: Option<Objective>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    where_conditions: Vec<WhereCondition>,
+    #[serde(default, skip_serializing_if = "SearchHeuristic::is_empty")]
+    search_heuristic: SearchHeuristic,
 }
 
 impl SerdeModel {
@@ -462,6 +581,12 @@ impl SerdeModel {
         if let Some(dominance) = &self.dominance {
             exprs.push_back(dominance.clone());
         }
+        if let Some(objective) = &self.objective {
+            exprs.push_back(objective.expression.clone());
+        }
+        for condition in &self.where_conditions {
+            exprs.push_back(condition.expression.clone());
+        }
 
         // Some expressions (e.g. abstract comprehensions) contain additional symbol tables.
         for table in Biplate::<SymbolTablePtr>::universe_bi(&exprs) {
@@ -487,7 +612,7 @@ impl SerdeModel {
             }
         }
 
-        self.constraints = self.constraints.transform_bi(&move |decl: DeclarationPtr| {
+        let restore_declaration = move |decl: DeclarationPtr| {
             let id = decl.id();
             all_declarations
                 .get(&id)
@@ -497,7 +622,15 @@ impl SerdeModel {
                     )
                 })
                 .clone()
-        });
+        };
+
+        self.constraints = self.constraints.transform_bi(&restore_declaration);
+        if let Some(objective) = self.objective.as_mut() {
+            objective.expression = objective.expression.transform_bi(&restore_declaration);
+        }
+        for condition in self.where_conditions.iter_mut() {
+            condition.expression = condition.expression.transform_bi(&restore_declaration);
+        }
 
         Some(Model {
             constraints: self.constraints,
@@ -505,6 +638,9 @@ impl SerdeModel {
             cnf_clauses: self.cnf_clauses,
             search_order: self.search_order,
             dominance: self.dominance,
+            objective: self.objective,
+            where_conditions: self.where_conditions,
+            search_heuristic: self.search_heuristic,
             context,
         })
     }
@@ -518,6 +654,9 @@ impl From<Model> for SerdeModel {
             cnf_clauses: val.cnf_clauses,
             search_order: val.search_order,
             dominance: val.dominance,
+            objective: val.objective,
+            where_conditions: val.where_conditions,
+            search_heuristic: val.search_heuristic,
         }
     }
 }
@@ -530,6 +669,9 @@ impl Display for SerdeModel {
             cnf_clauses: self.cnf_clauses.clone(),
             search_order: self.search_order.clone(),
             dominance: self.dominance.clone(),
+            objective: self.objective.clone(),
+            where_conditions: self.where_conditions.clone(),
+            search_heuristic: self.search_heuristic,
             context: default_context(),
         };
         std::fmt::Display::fmt(&model, f)
@@ -545,6 +687,9 @@ impl SerdeModel {
             cnf_clauses: self.cnf_clauses.clone(),
             search_order: self.search_order.clone(),
             dominance: self.dominance.clone(),
+            objective: self.objective.clone(),
+            where_conditions: self.where_conditions.clone(),
+            search_heuristic: self.search_heuristic,
             context: default_context(),
         };
         model.collect_stable_id_mapping()
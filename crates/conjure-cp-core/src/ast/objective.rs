@@ -0,0 +1,62 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::{Atom, Expression, Name};
+
+/// Whether an [`Objective`] should be minimised or maximised.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ObjectiveDirection {
+    Minimising,
+    Maximising,
+}
+
+/// An optimisation objective, e.g. `minimising x + y`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Objective {
+    pub direction: ObjectiveDirection,
+    pub expression: Expression,
+}
+
+impl Objective {
+    pub fn new(direction: ObjectiveDirection, expression: Expression) -> Self {
+        Objective {
+            direction,
+            expression,
+        }
+    }
+
+    /// The name of the variable being optimised, if the objective expression is a single
+    /// reference.
+    ///
+    /// This is always the case after rewriting (see [`Model::flatten_objective`]).
+    ///
+    /// [`Model::flatten_objective`]: super::Model::flatten_objective
+    pub fn variable_name(&self) -> Option<Name> {
+        match &self.expression {
+            Expression::Atomic(_, Atom::Reference(reference)) => Some(reference.name().clone()),
+            _ => None,
+        }
+    }
+}
+
+/// An objective that cannot be replaced by a decision variable, as its domain is not known.
+#[derive(Clone, Debug, Error)]
+#[error("Cannot find the domain of the objective {0}")]
+pub struct ObjectiveDomainError(pub Expression);
+
+impl fmt::Display for ObjectiveDirection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ObjectiveDirection::Minimising => write!(f, "minimising"),
+            ObjectiveDirection::Maximising => write!(f, "maximising"),
+        }
+    }
+}
+
+impl fmt::Display for Objective {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.direction, self.expression)
+    }
+}
@@ -165,6 +165,14 @@ fn simplify_comparison_with_literal(expr: &Expr, lit: &Lit) -> Option<(bool, boo
 
     let expr_domain = resolved_ground_domain_of_for_partial_eval(expr)?;
 
+    // enumerated constants can be lowered to integers before the variables they are compared with
+    if matches!(
+        (expr_domain.as_ref(), lit),
+        (GroundDomain::Enum(_, _), Lit::Int(_))
+    ) {
+        return None;
+    }
+
     if !expr_domain.contains(lit).ok()? {
         return Some((false, true));
     }
@@ -791,7 +799,7 @@ pub fn run_partial_evaluator(expr: &Expr) -> ApplicationResult {
         Expr::UnsafePow(_, _, _) => Err(RuleNotApplicable),
         Expr::SafePow(_, _, _) => Err(RuleNotApplicable),
         Expr::Minus(_, _, _) => Err(RuleNotApplicable),
-        Expr::Card(_, _) => todo!(),
+        Expr::Card(_, _) => Err(RuleNotApplicable),
 
         // As these are in a low level solver form, I'm assuming that these have already been
         // simplified and partially evaluated.
@@ -819,22 +827,23 @@ pub fn run_partial_evaluator(expr: &Expr) -> ApplicationResult {
         Expr::Active(_, _, _) => todo!(),
         Expr::Defined(_, _) => todo!(),
         Expr::Range(_, _) => todo!(),
-        Expr::Image(_, _, _) => todo!(),
-        Expr::ImageSet(_, _, _) => todo!(),
-        Expr::PreImage(_, _, _) => todo!(),
-        Expr::Inverse(_, _, _) => todo!(),
-        Expr::Restrict(_, _, _) => todo!(),
+        Expr::Image(_, _, _) => Err(RuleNotApplicable),
+        Expr::ImageSet(_, _, _) => Err(RuleNotApplicable),
+        Expr::PreImage(_, _, _) => Err(RuleNotApplicable),
+        Expr::Inverse(_, _, _) => Err(RuleNotApplicable),
+        Expr::Restrict(_, _, _) => Err(RuleNotApplicable),
         Expr::ToSet(_, _) => todo!(),
         Expr::ToMSet(_, _) => todo!(),
-        Expr::ToRelation(_, _) => todo!(),
-        Expr::RelationProj(_, _, _) => todo!(),
-        Expr::Apart(_, _, _) => todo!(),
-        Expr::Together(_, _, _) => todo!(),
-        Expr::Participants(_, _) => todo!(),
-        Expr::Party(_, _, _) => todo!(),
-        Expr::Parts(_, _) => todo!(),
-        Expr::Subsequence(_, _, _) => todo!(),
-        Expr::Substring(_, _, _) => todo!(),
+        Expr::Freq(_, _, _) => Err(RuleNotApplicable),
+        Expr::ToRelation(_, _) => Err(RuleNotApplicable),
+        Expr::RelationProj(_, _, _) => Err(RuleNotApplicable),
+        Expr::Apart(_, _, _) => Err(RuleNotApplicable),
+        Expr::Together(_, _, _) => Err(RuleNotApplicable),
+        Expr::Participants(_, _) => Err(RuleNotApplicable),
+        Expr::Party(_, _, _) => Err(RuleNotApplicable),
+        Expr::Parts(_, _) => Err(RuleNotApplicable),
+        Expr::Subsequence(_, _, _) => Err(RuleNotApplicable),
+        Expr::Substring(_, _, _) => Err(RuleNotApplicable),
         Expr::LexLt(_, _, _) => Err(RuleNotApplicable),
         Expr::LexLeq(_, _, _) => Err(RuleNotApplicable),
         Expr::LexGt(_, _, _) => Err(RuleNotApplicable),
@@ -0,0 +1,112 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+/// How a solver picks the next variable to branch on, e.g. `heuristic sdf`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum VariableHeuristic {
+    /// Branch on the variables in the search order.
+    Static,
+    /// Smallest domain first.
+    Sdf,
+    /// Smallest ratio of current to initial domain size first.
+    Srf,
+    /// Largest domain first.
+    Ldf,
+    /// Weighted degree.
+    Wdeg,
+    /// Smallest ratio of domain size to weighted degree first.
+    DomOverWdeg,
+    /// Conflict ordering.
+    Conflict,
+}
+
+/// Which value a solver tries first for the variable it branches on, e.g. `heuristic descend`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ValueHeuristic {
+    Ascend,
+    Descend,
+    Random,
+}
+
+/// The search heuristics set by the `heuristic` statements of a model.
+///
+/// Unset heuristics are left to the solver.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SearchHeuristic {
+    pub variable: Option<VariableHeuristic>,
+    pub value: Option<ValueHeuristic>,
+}
+
+impl SearchHeuristic {
+    pub fn is_empty(&self) -> bool {
+        self.variable.is_none() && self.value.is_none()
+    }
+}
+
+impl FromStr for VariableHeuristic {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "static" => Ok(VariableHeuristic::Static),
+            "sdf" => Ok(VariableHeuristic::Sdf),
+            "srf" => Ok(VariableHeuristic::Srf),
+            "ldf" => Ok(VariableHeuristic::Ldf),
+            "wdeg" => Ok(VariableHeuristic::Wdeg),
+            "domoverwdeg" => Ok(VariableHeuristic::DomOverWdeg),
+            "conflict" => Ok(VariableHeuristic::Conflict),
+            _ => Err(format!("unknown variable heuristic '{s}'")),
+        }
+    }
+}
+
+impl FromStr for ValueHeuristic {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ascend" => Ok(ValueHeuristic::Ascend),
+            "descend" => Ok(ValueHeuristic::Descend),
+            "random" => Ok(ValueHeuristic::Random),
+            _ => Err(format!("unknown value heuristic '{s}'")),
+        }
+    }
+}
+
+impl fmt::Display for VariableHeuristic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VariableHeuristic::Static => write!(f, "static"),
+            VariableHeuristic::Sdf => write!(f, "sdf"),
+            VariableHeuristic::Srf => write!(f, "srf"),
+            VariableHeuristic::Ldf => write!(f, "ldf"),
+            VariableHeuristic::Wdeg => write!(f, "wdeg"),
+            VariableHeuristic::DomOverWdeg => write!(f, "domoverwdeg"),
+            VariableHeuristic::Conflict => write!(f, "conflict"),
+        }
+    }
+}
+
+impl fmt::Display for ValueHeuristic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValueHeuristic::Ascend => write!(f, "ascend"),
+            ValueHeuristic::Descend => write!(f, "descend"),
+            ValueHeuristic::Random => write!(f, "random"),
+        }
+    }
+}
+
+impl fmt::Display for SearchHeuristic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(variable) = self.variable {
+            writeln!(f, "heuristic {variable}")?;
+        }
+        if let Some(value) = self.value {
+            writeln!(f, "heuristic {value}")?;
+        }
+        Ok(())
+    }
+}
@@ -1,4 +1,4 @@
-use crate::ast::Field;
+use crate::ast::{Field, Name};
 use itertools::Itertools;
 use polyquine::Quine;
 use serde::{Deserialize, Serialize};
@@ -18,6 +18,8 @@ pub enum ReturnType {
     Function(Box<ReturnType>, Box<ReturnType>),
     Relation(Vec<ReturnType>),
     Partition(Box<ReturnType>),
+    /// An enumerated or unnamed type, identified by its name
+    Enum(Name),
 
     /// An unknown type
     ///
@@ -83,6 +85,7 @@ impl Display for ReturnType {
                 write!(f, "relation of ({inners})")
             }
             ReturnType::Partition(inner) => write!(f, "partition of {inner}"),
+            ReturnType::Enum(name) => write!(f, "{name}"),
             ReturnType::Unknown => write!(f, "?"),
         }
     }
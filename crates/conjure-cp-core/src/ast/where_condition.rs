@@ -0,0 +1,48 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use super::Expression;
+
+/// A position in an Essence source file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SourceLocation {
+    /// The line number, counting from 1.
+    pub line: usize,
+    /// The column number, counting from 1.
+    pub column: usize,
+}
+
+impl fmt::Display for SourceLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, column {}", self.line, self.column)
+    }
+}
+
+/// A condition on the parameters of a problem, e.g. `where n > 0`.
+///
+/// These are checked once the `given`s of the problem have been replaced by their values (see
+/// [`instantiate_model`](crate::instantiate::instantiate_model)).
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct WhereCondition {
+    pub expression: Expression,
+
+    /// Where the condition is written in the source file, if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<SourceLocation>,
+}
+
+impl WhereCondition {
+    pub fn new(expression: Expression, location: Option<SourceLocation>) -> Self {
+        WhereCondition {
+            expression,
+            location,
+        }
+    }
+}
+
+impl fmt::Display for WhereCondition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "where {}", self.expression)
+    }
+}
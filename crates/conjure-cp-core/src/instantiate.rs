@@ -1,17 +1,99 @@
 use crate::{
     Model,
-    ast::{DeclarationKind, DeclarationPtr, declaration::Declaration, eval_constant},
+    ast::{
+        DeclarationKind, DeclarationPtr, Expression, GroundDomain, Literal, Name, WhereCondition,
+        declaration::Declaration, eval_constant,
+    },
+    parse::literal_from_json,
 };
 use anyhow::anyhow;
+use serde_json::Value as JsonValue;
+use thiserror::Error;
+
+/// A `where` condition of a problem that does not hold for the given parameter values.
+#[derive(Debug, Clone, Error)]
+#[error(
+    "Where condition `{}`{} {}",
+    .condition.expression,
+    match &.condition.location {
+        Some(location) => format!(" ({location})"),
+        None => String::new(),
+    },
+    .reason
+)]
+pub struct WhereConditionError {
+    pub condition: WhereCondition,
+    pub reason: WhereConditionFailure,
+}
+
+/// Why a `where` condition failed.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum WhereConditionFailure {
+    /// The condition evaluated to false.
+    #[error("is not satisfied by the parameter values")]
+    False,
+
+    /// The condition could not be evaluated to a boolean constant.
+    #[error("cannot be evaluated to a boolean constant")]
+    NotConstant,
+}
 
 /// Instantiate a problem model with values from a parameter model.
 ///
 /// For each `given` declaration in `problem_model`, this looks for a corresponding value `letting`
 /// in `param_model`, checks it is a constant and within the given domain, and replaces the `given`
 /// with a value-letting in the returned model.
+///
+/// Once all `given`s have values, the `where` conditions of `problem_model` are checked. If one
+/// does not hold, a [`WhereConditionError`] is returned.
 pub fn instantiate_model(problem_model: Model, param_model: Model) -> anyhow::Result<Model> {
+    let param_table = param_model.symbols_ptr_unchecked().read();
+
+    instantiate_givens(problem_model, |name, _| {
+        let param_decl = param_table.lookup(name);
+        let expr = param_decl
+            .as_ref()
+            .and_then(DeclarationPtr::as_value_letting)
+            .ok_or_else(|| {
+                anyhow!(
+                    "Given declaration `{name}` does not have corresponding letting in parameter file"
+                )
+            })?;
+        Ok(expr.clone())
+    })
+}
+
+/// Instantiate a problem model with values from a JSON parameter file.
+///
+/// `params` should be a JSON object mapping the name of each `given` to its value, in the format
+/// used for solutions (see [`literal_from_json`]). Otherwise, this behaves like
+/// [`instantiate_model`].
+pub fn instantiate_model_from_json(problem_model: Model, params: &str) -> anyhow::Result<Model> {
+    let params: JsonValue = serde_json::from_str(params)?;
+    let params = params
+        .as_object()
+        .ok_or_else(|| anyhow!("JSON parameter file should contain an object"))?;
+
+    instantiate_givens(problem_model, |name, domain| {
+        let value = params.get(&name.to_string()).ok_or_else(|| {
+            anyhow!("Given declaration `{name}` does not have a value in parameter file")
+        })?;
+        let value = literal_from_json(value, domain)
+            .map_err(|e| anyhow!("Value of given `{name}` cannot be read: {e}"))?;
+        Ok(Expression::from(value))
+    })
+}
+
+/// Replaces each `given` in `problem_model` with a value-letting, then checks the `where`
+/// conditions of the model.
+///
+/// `value_of` gets the value of a given from its name and its resolved domain. As domains can
+/// refer to other givens, this is called once the domain of each given can be resolved.
+fn instantiate_givens(
+    problem_model: Model,
+    mut value_of: impl FnMut(&Name, &GroundDomain) -> anyhow::Result<Expression>,
+) -> anyhow::Result<Model> {
     let symbol_table = problem_model.symbols_ptr_unchecked().write();
-    let param_table = param_model.symbols_ptr_unchecked().write();
     let mut pending_givens = symbol_table
         .iter_local()
         .filter_map(|(name, decl)| decl.as_given().map(|_| name.clone()))
@@ -30,24 +112,15 @@ pub fn instantiate_model(problem_model: Model, param_model: Model) -> anyhow::Re
                 continue;
             };
 
-            let param_decl = param_table.lookup(&name);
-            let expr = param_decl
-                .as_ref()
-                .and_then(DeclarationPtr::as_value_letting)
-                .ok_or_else(|| {
-                    anyhow!(
-                        "Given declaration `{name}` does not have corresponding letting in parameter file"
-                    )
-                })?;
-
-            let expr_value = eval_constant(&expr)
-                .ok_or_else(|| anyhow!("Letting expression `{expr}` cannot be evaluated"))?;
-
             let Ok(ground_domain) = domain.resolve() else {
                 next_pending.push(name);
                 continue;
             };
 
+            let expr = value_of(&name, &ground_domain)?;
+            let expr_value = eval_constant(&expr)
+                .ok_or_else(|| anyhow!("Letting expression `{expr}` cannot be evaluated"))?;
+
             if !ground_domain.contains(&expr_value)? {
                 return Err(anyhow!(
                     "Domain of given statement `{name}` does not contain letting value"
@@ -56,7 +129,7 @@ pub fn instantiate_model(problem_model: Model, param_model: Model) -> anyhow::Re
 
             let new_decl = Declaration::new(
                 name.clone(),
-                DeclarationKind::ValueLetting(expr.clone(), Some(domain.clone())),
+                DeclarationKind::ValueLetting(expr, Some(domain.clone())),
             );
             drop(domain);
             decl.replace(new_decl);
@@ -80,5 +153,25 @@ pub fn instantiate_model(problem_model: Model, param_model: Model) -> anyhow::Re
     }
 
     drop(symbol_table);
+
+    for condition in &problem_model.where_conditions {
+        if let Some(reason) = where_condition_failure(condition) {
+            return Err(WhereConditionError {
+                condition: condition.clone(),
+                reason,
+            }
+            .into());
+        }
+    }
+
     Ok(problem_model)
 }
+
+/// Returns why `condition` does not hold, or `None` if it does.
+fn where_condition_failure(condition: &WhereCondition) -> Option<WhereConditionFailure> {
+    match eval_constant(&condition.expression) {
+        Some(Literal::Bool(true)) => None,
+        Some(Literal::Bool(false)) => Some(WhereConditionFailure::False),
+        _ => Some(WhereConditionFailure::NotConstant),
+    }
+}
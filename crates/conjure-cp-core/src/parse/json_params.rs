@@ -0,0 +1,368 @@
+use itertools::Itertools;
+use serde_json::{Map as JsonMap, Value as JsonValue};
+use uniplate::Uniplate;
+
+use crate::ast::{AbstractLiteral, GroundDomain, Literal, Name};
+use crate::error::{Error, Result};
+
+/// Reads a value of the domain `domain` from JSON.
+///
+/// Values are in the same format as solutions (see `solutions_to_json` in `conjure-cp-cli`): the
+/// serialized form of [`Literal`], e.g. `{"Int": 3}`, except that values of enumerated types are
+/// written as just their name, e.g. `"red"`. Enumerated values may also be given as
+/// `{"Enum": "red"}`.
+///
+/// As solvers do not distinguish between booleans and integers, `{"Int": 0}` and `{"Int": 1}` are
+/// also read as booleans where `domain` expects them. Matrices take their index domains from
+/// `domain`.
+pub fn literal_from_json(value: &JsonValue, domain: &GroundDomain) -> Result<Literal> {
+    let enum_types = domain
+        .universe()
+        .into_iter()
+        .filter_map(|d| match d {
+            GroundDomain::Enum(name, values) => Some((name, values)),
+            _ => None,
+        })
+        .collect_vec();
+
+    let value = enum_strings_to_values(value.clone(), domain)?;
+    let value = enum_names_to_values(value, &enum_types)?;
+    let literal: Literal = serde_json::from_value(value)?;
+    Ok(fit_to_domain(literal, domain))
+}
+
+/// Replaces the strings in `value` that are at enumerated types in `domain` with the serialized
+/// form of the enumerated value they name.
+fn enum_strings_to_values(value: JsonValue, domain: &GroundDomain) -> Result<JsonValue> {
+    let map_all = |elems: JsonValue, domain: &GroundDomain| match elems {
+        JsonValue::Array(elems) => Ok(JsonValue::Array(
+            elems
+                .into_iter()
+                .map(|x| enum_strings_to_values(x, domain))
+                .collect::<Result<_>>()?,
+        )),
+        elems => Ok(elems),
+    };
+
+    match (value, domain) {
+        (JsonValue::String(value_name), GroundDomain::Enum(enum_name, values)) => {
+            let value_name = Name::user(&value_name);
+            if !values.contains(&value_name) {
+                return Err(Error::Parse(format!(
+                    "'{value_name}' is not a value of the enumerated type '{enum_name}'"
+                )));
+            }
+
+            let literal = Literal::Enum(Box::new((enum_name.clone(), value_name)));
+            Ok(serde_json::to_value(literal)?)
+        }
+        (JsonValue::Object(mut map), domain) if map.len() == 1 => {
+            let Some(JsonValue::Object(literal)) = map.get_mut("AbstractLiteral") else {
+                return Ok(JsonValue::Object(map));
+            };
+            if literal.len() != 1 {
+                return Ok(JsonValue::Object(map));
+            }
+            let Some((kind, elems)) = literal.iter_mut().next() else {
+                return Ok(JsonValue::Object(map));
+            };
+
+            let old_elems = elems.take();
+            *elems = match (kind.as_str(), domain, old_elems) {
+                ("Set", GroundDomain::Set(_, elem_domain), elems)
+                | ("MSet", GroundDomain::MSet(_, elem_domain), elems)
+                | ("Sequence", GroundDomain::Sequence(_, elem_domain), elems) => {
+                    map_all(elems, elem_domain)?
+                }
+                ("Partition", GroundDomain::Partition(_, elem_domain), JsonValue::Array(parts)) => {
+                    JsonValue::Array(
+                        parts
+                            .into_iter()
+                            .map(|part| map_all(part, elem_domain))
+                            .collect::<Result<_>>()?,
+                    )
+                }
+                (
+                    "Matrix",
+                    GroundDomain::Matrix(elem_domain, index_domains),
+                    JsonValue::Array(mut parts),
+                ) if !index_domains.is_empty() && !parts.is_empty() => {
+                    let inner_domain = if index_domains.len() == 1 {
+                        elem_domain.as_ref().clone()
+                    } else {
+                        GroundDomain::Matrix(elem_domain.clone(), index_domains[1..].to_vec())
+                    };
+                    parts[0] = map_all(parts[0].take(), &inner_domain)?;
+                    JsonValue::Array(parts)
+                }
+                ("Tuple", GroundDomain::Tuple(elem_domains), JsonValue::Array(elems))
+                    if elems.len() == elem_domains.len() =>
+                {
+                    JsonValue::Array(
+                        elems
+                            .into_iter()
+                            .zip(elem_domains)
+                            .map(|(x, d)| enum_strings_to_values(x, d))
+                            .collect::<Result<_>>()?,
+                    )
+                }
+                ("Relation", GroundDomain::Relation(_, elem_domains), JsonValue::Array(tuples)) => {
+                    JsonValue::Array(
+                        tuples
+                            .into_iter()
+                            .map(|tuple| match tuple {
+                                JsonValue::Array(elems) if elems.len() == elem_domains.len() => {
+                                    Ok(JsonValue::Array(
+                                        elems
+                                            .into_iter()
+                                            .zip(elem_domains)
+                                            .map(|(x, d)| enum_strings_to_values(x, d))
+                                            .collect::<Result<_>>()?,
+                                    ))
+                                }
+                                tuple => Ok(tuple),
+                            })
+                            .collect::<Result<_>>()?,
+                    )
+                }
+                ("Function", GroundDomain::Function(_, dom, codom), JsonValue::Array(pairs)) => {
+                    JsonValue::Array(
+                        pairs
+                            .into_iter()
+                            .map(|pair| match pair {
+                                JsonValue::Array(mut pair) if pair.len() == 2 => {
+                                    pair[0] = enum_strings_to_values(pair[0].take(), dom)?;
+                                    pair[1] = enum_strings_to_values(pair[1].take(), codom)?;
+                                    Ok(JsonValue::Array(pair))
+                                }
+                                pair => Ok(pair),
+                            })
+                            .collect::<Result<_>>()?,
+                    )
+                }
+                (_, _, elems) => elems,
+            };
+
+            Ok(JsonValue::Object(map))
+        }
+        (value, _) => Ok(value),
+    }
+}
+
+/// Replaces the enumerated values in `value`, written as `{"Enum": <name>}`, with their serialized
+/// form, which includes the name of their type.
+fn enum_names_to_values(value: JsonValue, enum_types: &[(Name, Vec<Name>)]) -> Result<JsonValue> {
+    match value {
+        JsonValue::Object(map) => {
+            if let Some(JsonValue::String(value_name)) = map.get("Enum")
+                && map.len() == 1
+            {
+                let value_name = Name::user(value_name);
+                let Some((enum_name, _)) = enum_types
+                    .iter()
+                    .find(|(_, values)| values.contains(&value_name))
+                else {
+                    return Err(Error::Parse(format!(
+                        "'{value_name}' is not a value of an enumerated type in the domain"
+                    )));
+                };
+
+                let literal = Literal::Enum(Box::new((enum_name.clone(), value_name)));
+                return Ok(serde_json::to_value(literal)?);
+            }
+
+            let map = map
+                .into_iter()
+                .map(|(k, v)| Ok((k, enum_names_to_values(v, enum_types)?)))
+                .collect::<Result<JsonMap<_, _>>>()?;
+            Ok(JsonValue::Object(map))
+        }
+        JsonValue::Array(values) => Ok(JsonValue::Array(
+            values
+                .into_iter()
+                .map(|v| enum_names_to_values(v, enum_types))
+                .collect::<Result<_>>()?,
+        )),
+        value => Ok(value),
+    }
+}
+
+/// Converts the parts of `literal` that have a different representation in solutions to the form
+/// expected by `domain`.
+fn fit_to_domain(literal: Literal, domain: &GroundDomain) -> Literal {
+    let fit_all = |elems: Vec<Literal>, domain: &GroundDomain| {
+        elems
+            .into_iter()
+            .map(|x| fit_to_domain(x, domain))
+            .collect_vec()
+    };
+
+    match (literal, domain) {
+        (Literal::Int(0), GroundDomain::Bool) => Literal::Bool(false),
+        (Literal::Int(1), GroundDomain::Bool) => Literal::Bool(true),
+        (Literal::AbstractLiteral(literal), domain) => {
+            Literal::AbstractLiteral(match (literal, domain) {
+                (
+                    AbstractLiteral::Matrix(elems, index_domain),
+                    GroundDomain::Matrix(elem_domain, index_domains),
+                ) if !index_domains.is_empty() => {
+                    let (first_index_domain, rest) = index_domains.split_at(1);
+                    let inner_domain = if rest.is_empty() {
+                        elem_domain.as_ref().clone()
+                    } else {
+                        GroundDomain::Matrix(elem_domain.clone(), rest.to_vec())
+                    };
+
+                    // solutions only give the start of the index domain, so use the full index
+                    // domain of the given if the number of elements matches
+                    let index_domain = if first_index_domain[0]
+                        .length()
+                        .is_ok_and(|n| n == elems.len() as u64)
+                    {
+                        first_index_domain[0].clone()
+                    } else {
+                        index_domain
+                    };
+
+                    AbstractLiteral::Matrix(fit_all(elems, &inner_domain), index_domain)
+                }
+                (AbstractLiteral::Set(elems), GroundDomain::Set(_, elem_domain)) => {
+                    AbstractLiteral::Set(fit_all(elems, elem_domain))
+                }
+                (AbstractLiteral::MSet(elems), GroundDomain::MSet(_, elem_domain)) => {
+                    AbstractLiteral::MSet(fit_all(elems, elem_domain))
+                }
+                (AbstractLiteral::Sequence(elems), GroundDomain::Sequence(_, elem_domain)) => {
+                    AbstractLiteral::Sequence(fit_all(elems, elem_domain))
+                }
+                (AbstractLiteral::Tuple(elems), GroundDomain::Tuple(elem_domains))
+                    if elems.len() == elem_domains.len() =>
+                {
+                    AbstractLiteral::Tuple(
+                        elems
+                            .into_iter()
+                            .zip(elem_domains)
+                            .map(|(x, d)| fit_to_domain(x, d))
+                            .collect(),
+                    )
+                }
+                (AbstractLiteral::Function(pairs), GroundDomain::Function(_, dom, codom)) => {
+                    AbstractLiteral::Function(
+                        pairs
+                            .into_iter()
+                            .map(|(x, y)| (fit_to_domain(x, dom), fit_to_domain(y, codom)))
+                            .collect(),
+                    )
+                }
+                (literal, _) => literal,
+            })
+        }
+        (literal, _) => literal,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::{Moo, Range, SetAttr};
+    use serde_json::json;
+
+    #[test]
+    fn reads_ints_and_bools() {
+        let int_domain = GroundDomain::Int(vec![Range::Bounded(1, 5)]);
+        assert_eq!(
+            literal_from_json(&json!({"Int": 3}), &int_domain).unwrap(),
+            Literal::Int(3)
+        );
+        assert_eq!(
+            literal_from_json(&json!({"Int": 1}), &GroundDomain::Bool).unwrap(),
+            Literal::Bool(true)
+        );
+    }
+
+    #[test]
+    fn reads_enum_values_in_sets() {
+        let colour = Name::user("Colour");
+        let values = vec![Name::user("red"), Name::user("green")];
+        let domain = GroundDomain::Set(
+            SetAttr::default(),
+            Moo::new(GroundDomain::Enum(colour.clone(), values)),
+        );
+
+        let literal = literal_from_json(
+            &json!({"AbstractLiteral": {"Set": [{"Enum": "green"}]}}),
+            &domain,
+        )
+        .unwrap();
+        assert_eq!(
+            literal,
+            Literal::AbstractLiteral(AbstractLiteral::Set(vec![Literal::Enum(Box::new((
+                colour,
+                Name::user("green")
+            )))]))
+        );
+
+        assert!(
+            literal_from_json(
+                &json!({"AbstractLiteral": {"Set": [{"Enum": "blue"}]}}),
+                &domain
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn reads_enum_values_written_as_their_name() {
+        let colour = Name::user("Colour");
+        let values = vec![Name::user("red"), Name::user("green")];
+        let domain = GroundDomain::Tuple(vec![
+            Moo::new(GroundDomain::Enum(colour.clone(), values)),
+            Moo::new(GroundDomain::Int(vec![Range::Bounded(1, 5)])),
+        ]);
+
+        let literal = literal_from_json(
+            &json!({"AbstractLiteral": {"Tuple": ["red", {"Int": 2}]}}),
+            &domain,
+        )
+        .unwrap();
+        assert_eq!(
+            literal,
+            Literal::AbstractLiteral(AbstractLiteral::Tuple(vec![
+                Literal::Enum(Box::new((colour, Name::user("red")))),
+                Literal::Int(2)
+            ]))
+        );
+
+        assert!(
+            literal_from_json(
+                &json!({"AbstractLiteral": {"Tuple": ["blue", {"Int": 2}]}}),
+                &domain
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn takes_matrix_index_domain_from_domain() {
+        let index_domain = Moo::new(GroundDomain::Int(vec![Range::Bounded(1, 2)]));
+        let domain = GroundDomain::Matrix(Moo::new(GroundDomain::Bool), vec![index_domain.clone()]);
+
+        let literal = literal_from_json(
+            &json!({"AbstractLiteral": {"Matrix": [
+                [{"Int": 0}, {"Int": 1}],
+                {"Int": [{"UnboundedR": 1}]}
+            ]}}),
+            &domain,
+        )
+        .unwrap();
+
+        assert_eq!(
+            literal,
+            Literal::AbstractLiteral(AbstractLiteral::Matrix(
+                vec![Literal::Bool(false), Literal::Bool(true)],
+                index_domain
+            ))
+        );
+        assert!(domain.contains(&literal).unwrap());
+    }
+}
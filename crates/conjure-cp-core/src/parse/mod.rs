@@ -1,4 +1,5 @@
 pub use example_models::{get_example_model, get_example_model_by_path};
+pub use json_params::literal_from_json;
 pub use parse_model::model_from_json;
 
 #[doc(hidden)]
@@ -6,4 +7,6 @@ pub use parse_model::parse_expression;
 
 mod example_models;
 
+mod json_params;
+
 mod parse_model;
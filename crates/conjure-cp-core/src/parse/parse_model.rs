@@ -15,10 +15,10 @@ use crate::ast::comprehension::ComprehensionBuilder;
 use crate::ast::records::Field;
 use crate::ast::{
     AbstractLiteral, Atom, BinaryAttr, DeclarationPtr, Domain, Expression, FuncAttr, IntVal,
-    JectivityAttr, Literal, MSetAttr, Name, PartialityAttr, Range, RelAttr, ReturnType,
-    SequenceAttr, SetAttr, SymbolTable, SymbolTablePtr,
+    JectivityAttr, Literal, MSetAttr, Name, Objective, ObjectiveDirection, PartialityAttr, Range,
+    RelAttr, ReturnType, SequenceAttr, SetAttr, SymbolTable, SymbolTablePtr, WhereCondition,
 };
-use crate::ast::{DomainPtr, Metadata};
+use crate::ast::{DomainPtr, Metadata, eval_constant};
 use crate::context::Context;
 use crate::error::{Error, Result};
 use crate::{Model, bug, error, into_matrix_expr, throw_error};
@@ -80,6 +80,16 @@ pub fn model_from_json(str: &str, context: Arc<RwLock<Context<'static>>>) -> Res
                             valid_decl = true;
                             break;
                         }
+                        "LettingDomainDefnEnum" => {
+                            parse_enum_letting(value, &mut model.symbols_mut())?;
+                            valid_decl = true;
+                            break;
+                        }
+                        "LettingDomainDefnUnnamed" => {
+                            parse_unnamed_letting(value, &scope)?;
+                            valid_decl = true;
+                            break;
+                        }
                         _ => continue,
                     }
                 }
@@ -100,12 +110,73 @@ pub fn model_from_json(str: &str, context: Arc<RwLock<Context<'static>>>) -> Res
                     .collect::<Result<Vec<_>>>()?;
                 m.add_constraints(constraints);
             }
+            "Where" => {
+                let conditions_arr = entry.1.as_array().ok_or(error!("Where is not an array"))?;
+
+                // conjure's JSON output does not include source locations
+                let conditions = conditions_arr
+                    .iter()
+                    .map(|x| {
+                        parse_expression(x, m.symbols_ptr_unchecked())
+                            .map(|expr| WhereCondition::new(expr, None))
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                m.where_conditions.extend(conditions);
+            }
+            "Objective" => {
+                if m.objective.is_some() {
+                    throw_error!("A model can only have one objective")?;
+                }
+                m.objective = Some(parse_objective(entry.1, m.symbols_ptr_unchecked())?);
+            }
+            "SearchOrder" => {
+                let orders = entry
+                    .1
+                    .as_array()
+                    .ok_or(error!("SearchOrder is not an array"))?;
+                let names = orders
+                    .iter()
+                    .map(|x| {
+                        x["BranchingOn"]["Name"]
+                            .as_str()
+                            .map(Name::user)
+                            .ok_or(error!("SearchOrder is not a list of BranchingOn names"))
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                m.search_order.get_or_insert_default().extend(names);
+            }
+            "SearchHeuristic" => {
+                let heuristic = entry.1["Name"]
+                    .as_str()
+                    .ok_or(error!("SearchHeuristic is not a name"))?;
+                if let Ok(variable) = heuristic.parse() {
+                    m.search_heuristic.variable = Some(variable);
+                } else if let Ok(value) = heuristic.parse() {
+                    m.search_heuristic.value = Some(value);
+                } else {
+                    throw_error!(format!("Unknown search heuristic '{heuristic}'"))?;
+                }
+            }
             otherwise => bug!("Unhandled Statement {:#?}", otherwise),
         }
     }
     Ok(m)
 }
 
+fn parse_objective(v: &JsonValue, scope: &SymbolTablePtr) -> Result<Objective> {
+    let arr = v.as_array().ok_or(error!("Objective is not an array"))?;
+    let direction = match arr.first().and_then(|x| x.as_str()) {
+        Some("Minimising") => ObjectiveDirection::Minimising,
+        Some("Maximising") => ObjectiveDirection::Maximising,
+        _ => throw_error!("Objective direction is not Minimising or Maximising")?,
+    };
+    let expression = parse_expression(
+        arr.get(1).ok_or(error!("Objective has no expression"))?,
+        scope,
+    )?;
+    Ok(Objective::new(direction, expression))
+}
+
 fn parse_variable(v: &JsonValue, symtab: &mut SymbolTable) -> Result<()> {
     let arr = v.as_array().ok_or(error!("FindOrGiven is not an array"))?;
 
@@ -181,6 +252,85 @@ fn parse_letting(v: &JsonValue, scope: &SymbolTablePtr) -> Result<()> {
     }
 }
 
+/// Parses `letting name be new type enum {values}`, declaring the type and each of its values.
+fn parse_enum_letting(v: &JsonValue, symtab: &mut SymbolTable) -> Result<()> {
+    let arr = v
+        .as_array()
+        .ok_or(error!("LettingDomainDefnEnum is not an array"))?;
+    let name = arr[0]
+        .as_object()
+        .ok_or(error!("LettingDomainDefnEnum[0] is not an object"))?["Name"]
+        .as_str()
+        .ok_or(error!("LettingDomainDefnEnum[0].Name is not a string"))?;
+    let name = Name::User(Ustr::from(name));
+
+    let values = arr[1]
+        .as_array()
+        .ok_or(error!("LettingDomainDefnEnum[1] is not an array"))?
+        .iter()
+        .map(|value| {
+            value["Name"].as_str().map(Name::user).ok_or(error!(
+                "LettingDomainDefnEnum[1] contains a value with no name"
+            ))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut decls = vec![DeclarationPtr::new_domain_letting(
+        name.clone(),
+        Domain::enumerated(name.clone(), values.clone()),
+    )];
+    for value in values {
+        // as in the tree-sitter parser, each value has an explicit domain
+        let literal = Literal::Enum(Box::new((name.clone(), value.clone())));
+        let value_domain = Domain::enumerated(name.clone(), vec![value.clone()]);
+        decls.push(DeclarationPtr::new_value_letting_with_domain(
+            value,
+            Expression::from(literal),
+            value_domain,
+        ));
+    }
+
+    for decl in decls {
+        let decl_name = decl.name().clone();
+        symtab.insert(decl).ok_or(Error::Parse(format!(
+            "Could not add {decl_name} to symbol table as it already exists"
+        )))?;
+    }
+    Ok(())
+}
+
+/// Parses `letting name be new type of size n`.
+///
+/// The values of an unnamed type cannot be referred to, so are only given names for printing
+/// solutions.
+fn parse_unnamed_letting(v: &JsonValue, scope: &SymbolTablePtr) -> Result<()> {
+    let arr = v
+        .as_array()
+        .ok_or(error!("LettingDomainDefnUnnamed is not an array"))?;
+    let name = arr[0]
+        .as_object()
+        .ok_or(error!("LettingDomainDefnUnnamed[0] is not an object"))?["Name"]
+        .as_str()
+        .ok_or(error!("LettingDomainDefnUnnamed[0].Name is not a string"))?;
+    let name = Name::User(Ustr::from(name));
+
+    let size = parse_expression(&arr[1], scope)?;
+    let Some(Literal::Int(size)) = eval_constant(&size) else {
+        return throw_error!("The size of an unnamed type must be a constant integer");
+    };
+
+    let values = (1..=size)
+        .map(|i| Name::user(&format!("{name}_{i}")))
+        .collect();
+    let domain = Domain::enumerated(name.clone(), values);
+    scope
+        .write()
+        .insert(DeclarationPtr::new_domain_letting(name.clone(), domain))
+        .ok_or(Error::Parse(format!(
+            "Could not add {name} to symbol table as it already exists"
+        )))
+}
+
 fn parse_domain(
     domain_name: &str,
     domain_value: &JsonValue,
@@ -783,6 +933,7 @@ fn binary_operator(op_name: &str) -> Option<BinOp> {
         "MkOpApart" => Some(Expression::Apart),
         "MkOpTogether" => Some(Expression::Together),
         "MkOpParty" => Some(Expression::Party),
+        "MkOpFreq" => Some(Expression::Freq),
         "MkOpSubstring" => Some(Expression::Substring),
         "MkOpSubsequence" => Some(Expression::Subsequence),
         _ => None,
@@ -801,7 +952,8 @@ fn unary_operator(op_name: &str, inner: Option<&Expression>) -> Option<UnaryOp>
                     | ReturnType::Set(_)
                     | ReturnType::MSet(_)
                     | ReturnType::Relation(_)
-                    | ReturnType::Function(_, _) => Some(Expression::Card),
+                    | ReturnType::Function(_, _)
+                    | ReturnType::Sequence(_) => Some(Expression::Card),
                     _ => None,
                 }
             } else {
@@ -937,10 +1089,14 @@ pub fn parse_expression(obj: &JsonValue, scope: &SymbolTablePtr) -> Result<Expre
                 parse_abs_variant(&abslit["AbstractLiteral"]["AbsLitVariant"], scope)
             } else if abstract_literal.contains_key("AbsLitRelation") {
                 parse_abs_relation(&abslit["AbstractLiteral"]["AbsLitRelation"], scope)
-            } else if abstract_literal.contains_key("AbstractLiteralPartition") {
+            } else if abstract_literal.contains_key("AbsLitPartition") {
                 parse_abs_partition(&abslit["AbstractLiteral"]["AbsLitPartition"], scope)
             } else if abstract_literal.contains_key("AbsLitSequence") {
                 parse_abs_sequence(&abslit["AbstractLiteral"]["AbsLitSequence"], scope)
+            } else if abstract_literal.contains_key("AbsLitTuple") {
+                parse_abs_tuple(&abslit["AbstractLiteral"]["AbsLitTuple"], scope)
+            } else if abstract_literal.contains_key("AbsLitRecord") {
+                parse_abs_record(&abslit["AbstractLiteral"]["AbsLitRecord"], scope)
             } else {
                 parse_abstract_matrix_as_expr(obj, scope)
             }
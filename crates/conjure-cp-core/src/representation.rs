@@ -87,6 +87,18 @@ pub trait Representation: Send + Sync + Debug {
     /// Creates declarations for the representation variables of `self`.
     fn declaration_down(&self) -> Result<Vec<DeclarationPtr>, ApplicationError>;
 
+    /// Returns the constraints the representation variables must satisfy to encode a valid value
+    /// of `self`.
+    ///
+    /// These are added to the model by the rule that selects this representation. For example, a
+    /// set represented as a matrix of its elements needs these elements to be distinct.
+    fn structural_constraints(
+        &self,
+        _symtab: &SymbolTable,
+    ) -> Result<Vec<Expression>, ApplicationError> {
+        Ok(vec![])
+    }
+
     /// The rule name for this representaion.
     fn repr_name(&self) -> &str;
 
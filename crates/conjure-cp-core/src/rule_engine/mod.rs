@@ -215,7 +215,7 @@ pub fn rewrite_model_with_configured_rewriter<'a>(
     configured_rewriter: Rewriter,
 ) -> Result<Model, RewriteError> {
     match configured_rewriter {
-        Rewriter::Morph(config) => Ok(rewrite_morph(model, rule_sets, false, config)),
+        Rewriter::Morph(config) => rewrite_morph(model, rule_sets, false, config),
         Rewriter::Naive => rewrite_naive(&model, rule_sets, false),
     }
 }
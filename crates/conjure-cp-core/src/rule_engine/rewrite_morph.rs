@@ -15,7 +15,7 @@ use tree_morph::{
 };
 
 use super::{
-    MorphState, RuleData, RuleSet, get_rules_grouped,
+    MorphState, RewriteError, RuleData, RuleSet, get_rules_grouped,
     rewriter_common::try_rewrite_value_letting_once,
 };
 
@@ -41,7 +41,8 @@ use super::{
 ///
 /// # Returns
 ///
-/// The rewritten `Model` after all applicable rules have been applied.
+/// The rewritten `Model` after all applicable rules have been applied, or an error if the objective
+/// of the model cannot be replaced by a decision variable (see [`Model::flatten_objective`]).
 ///
 /// # Panics
 ///
@@ -53,8 +54,9 @@ pub fn rewrite_morph<'a>(
     rule_sets: &Vec<&'a RuleSet<'a>>,
     prop_multiple_equally_applicable: bool,
     config: MorphConfig,
-) -> Model {
+) -> Result<Model, RewriteError> {
     set_current_rewriter(Rewriter::Morph(config));
+    model.flatten_objective()?;
 
     if rule_trace_enabled() {
         trace!(
@@ -116,7 +118,7 @@ pub fn rewrite_morph<'a>(
         );
     }
 
-    model
+    Ok(model)
 }
 
 fn build_engine<'a>(
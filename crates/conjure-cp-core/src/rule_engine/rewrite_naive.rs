@@ -47,6 +47,7 @@ pub fn rewrite_naive<'a>(
         .collect_vec();
 
     let mut model = model.clone();
+    model.flatten_objective()?;
     let mut done_something = true;
 
     let mut rewriter_stats = RewriterStats::new();
@@ -5,7 +5,7 @@ use super::{
     submodel_zipper::expression_ctx,
 };
 use crate::ast::{
-    DeclarationPtr, Expression, Model, Name, SymbolTable,
+    DeclarationPtr, Expression, Model, Name, ObjectiveDomainError, SymbolTable,
     pretty::{pretty_variable_declaration, pretty_vec},
 };
 use crate::settings::{
@@ -254,6 +254,9 @@ pub(crate) fn try_rewrite_value_letting_once(
 pub enum RewriteError {
     #[error("Error resolving rules {0}")]
     ResolveRulesError(ResolveRulesError),
+
+    #[error("{0}")]
+    ObjectiveDomainError(ObjectiveDomainError),
 }
 
 impl From<ResolveRulesError> for RewriteError {
@@ -261,3 +264,9 @@ impl From<ResolveRulesError> for RewriteError {
         RewriteError::ResolveRulesError(error)
     }
 }
+
+impl From<ObjectiveDomainError> for RewriteError {
+    fn from(error: ObjectiveDomainError) -> Self {
+        RewriteError::ObjectiveDomainError(error)
+    }
+}
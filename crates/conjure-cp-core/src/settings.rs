@@ -361,6 +361,75 @@ impl FromStr for SatEncoding {
     }
 }
 
+/// The encoding used by the SAT backend for sums of Booleans, such as at-most-k constraints.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+pub enum SatCardinalityEncoding {
+    #[default]
+    Totalizer,
+    SequentialCounter,
+}
+
+impl Display for SatCardinalityEncoding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SatCardinalityEncoding::Totalizer => write!(f, "totalizer"),
+            SatCardinalityEncoding::SequentialCounter => write!(f, "sequential-counter"),
+        }
+    }
+}
+
+impl FromStr for SatCardinalityEncoding {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "totalizer" => Ok(SatCardinalityEncoding::Totalizer),
+            "sequential-counter" => Ok(SatCardinalityEncoding::SequentialCounter),
+            other => Err(format!(
+                "unknown sat-cardinality-encoding: {other}; expected one of: totalizer, sequential-counter"
+            )),
+        }
+    }
+}
+
+/// The encoding used by the SAT backend for weighted sums of Booleans (pseudo-Boolean
+/// constraints).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+pub enum SatPbEncoding {
+    /// Generalized totalizer.
+    #[default]
+    Gte,
+    /// Dynamic polynomial watchdog.
+    Dpw,
+    /// Binary adder.
+    Adder,
+}
+
+impl Display for SatPbEncoding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SatPbEncoding::Gte => write!(f, "gte"),
+            SatPbEncoding::Dpw => write!(f, "dpw"),
+            SatPbEncoding::Adder => write!(f, "adder"),
+        }
+    }
+}
+
+impl FromStr for SatPbEncoding {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "gte" => Ok(SatPbEncoding::Gte),
+            "dpw" => Ok(SatPbEncoding::Dpw),
+            "adder" => Ok(SatPbEncoding::Adder),
+            other => Err(format!(
+                "unknown sat-pb-encoding: {other}; expected one of: gte, dpw, adder"
+            )),
+        }
+    }
+}
+
 #[derive(
     Debug,
     EnumIter,
@@ -397,6 +466,13 @@ thread_local! {
     static MINION_DISCRETE_THRESHOLD: Cell<usize> =
         const { Cell::new(DEFAULT_MINION_DISCRETE_THRESHOLD) };
 
+    /// Thread-local setting for how the SAT backend encodes sums of Booleans.
+    static SAT_CARDINALITY_ENCODING: Cell<SatCardinalityEncoding> =
+        const { Cell::new(SatCardinalityEncoding::Totalizer) };
+
+    /// Thread-local setting for how the SAT backend encodes weighted sums of Booleans.
+    static SAT_PB_ENCODING: Cell<SatPbEncoding> = const { Cell::new(SatPbEncoding::Gte) };
+
     /// Thread-local setting controlling whether rule-trace outputs are active in this phase.
     ///
     /// This is intentionally off by default and can be disabled before solver-time rewrites so
@@ -436,6 +512,22 @@ pub fn minion_discrete_threshold() -> usize {
     MINION_DISCRETE_THRESHOLD.with(|current| current.get())
 }
 
+pub fn set_sat_cardinality_encoding(encoding: SatCardinalityEncoding) {
+    SAT_CARDINALITY_ENCODING.with(|current| current.set(encoding));
+}
+
+pub fn sat_cardinality_encoding() -> SatCardinalityEncoding {
+    SAT_CARDINALITY_ENCODING.with(|current| current.get())
+}
+
+pub fn set_sat_pb_encoding(encoding: SatPbEncoding) {
+    SAT_PB_ENCODING.with(|current| current.set(encoding));
+}
+
+pub fn sat_pb_encoding() -> SatPbEncoding {
+    SAT_PB_ENCODING.with(|current| current.get())
+}
+
 pub fn set_rule_trace_enabled(enabled: bool) {
     RULE_TRACE_ENABLED.with(|current| current.set(enabled));
 }
@@ -1,5 +1,7 @@
 use regex::Regex;
+use std::cell::RefCell;
 use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
 use std::sync::LazyLock;
 use ustr::Ustr;
 
@@ -20,13 +22,13 @@ use crate::solver::SearchStatus::{Complete, Incomplete};
 use crate::solver::SolveSuccess;
 use crate::solver::SolverAdaptor;
 use crate::solver::SolverError;
-use crate::solver::SolverError::OpNotImplemented;
 use crate::solver::private;
 
 use super::dominance_injection::{
     add_dominance_constraints_for_solution, add_represented_decision_values,
     minion_error_to_solver_error,
 };
+use super::model_modifier::{MidsearchState, MinionModelModifier};
 use super::parse_model::model_to_minion;
 
 /// A [SolverAdaptor] for interacting with Minion.
@@ -37,7 +39,7 @@ pub struct Minion {
     model: Option<MinionModel>,
     value_order: Option<MinionValueOrder>,
     dominance_expression: Option<Expression>,
-    dominance_model_template: Option<ConjureModel>,
+    model_template: Option<ConjureModel>,
 }
 
 /// Value-order override for Minion search.
@@ -103,7 +105,7 @@ impl Minion {
             model: None,
             value_order: None,
             dominance_expression: None,
-            dominance_model_template: None,
+            model_template: None,
         }
     }
 
@@ -114,7 +116,7 @@ impl Minion {
             model: None,
             value_order,
             dominance_expression: None,
-            dominance_model_template: None,
+            model_template: None,
         }
     }
 }
@@ -134,7 +136,10 @@ impl SolverAdaptor for Minion {
         let mut any_solutions = false;
         let mut user_terminated = false;
         let dominance_expression = self.dominance_expression.clone();
-        let dominance_model_template = self.dominance_model_template.clone();
+        let dominance_model_template = self
+            .dominance_expression
+            .as_ref()
+            .and_then(|_| self.model_template.clone());
         let mut midsearch_error: Option<SolverError> = None;
         let base_model = self.model.as_ref().expect("STATE MACHINE ERR");
         let mut known_var_names = base_model
@@ -145,6 +150,11 @@ impl SolverAdaptor for Minion {
         let mut next_midsearch_aux_var_id = 0usize;
         let mut solution_ordinal = 0usize;
 
+        // When optimising, Minion finds strictly improving solutions, so only the last one is
+        // reported.
+        let optimising = base_model.objective.is_some();
+        let mut best_solution: Option<HashMap<Name, conjure_ast::Literal>> = None;
+
         let solver_ctx = run_minion_with_options(
             self.model.clone().expect("STATE MACHINE ERR"),
             Box::new(|solutions| {
@@ -155,8 +165,9 @@ impl SolverAdaptor for Minion {
                     add_represented_decision_values(&mut conjure_solutions, model_template);
                 }
 
-                let continue_search = callback(conjure_solutions.clone());
-                if !continue_search {
+                if optimising {
+                    best_solution = Some(conjure_solutions.clone());
+                } else if !callback(conjure_solutions.clone()) {
                     user_terminated = true;
                     return false;
                 }
@@ -185,6 +196,10 @@ impl SolverAdaptor for Minion {
             return Err(err);
         }
 
+        if let Some(solution) = best_solution {
+            callback(solution);
+        }
+
         let status = if user_terminated {
             Incomplete(UserTerminated)
         } else if any_solutions {
@@ -198,12 +213,88 @@ impl SolverAdaptor for Minion {
         })
     }
 
+    /// Runs Minion, passing a [`MinionModelModifier`] to the callback with each solution.
+    ///
+    /// If the model has an objective, every strictly improving solution is passed to the
+    /// callback, as the modifier can only be used while Minion is running.
     fn solve_mut(
         &mut self,
         callback: SolverMutCallback,
         _: private::Internal,
     ) -> Result<SolveSuccess, SolverError> {
-        Err(OpNotImplemented("solve_mut".into()))
+        let mut any_solutions = false;
+        let mut user_terminated = false;
+        let dominance_expression = self.dominance_expression.clone();
+        let mut midsearch_error: Option<SolverError> = None;
+        let base_model = self.model.as_ref().expect("STATE MACHINE ERR");
+        let known_var_names = base_model
+            .named_variables
+            .get_variable_order()
+            .into_iter()
+            .collect::<HashSet<_>>();
+        let model_template = self.model_template.clone().expect("STATE MACHINE ERR");
+        let state = Rc::new(RefCell::new(MidsearchState::new(
+            model_template,
+            known_var_names,
+        )));
+        let mut solution_ordinal = 0usize;
+
+        let solver_ctx = run_minion_with_options(
+            self.model.clone().expect("STATE MACHINE ERR"),
+            Box::new(|solutions| {
+                any_solutions = true;
+                solution_ordinal += 1;
+                let mut conjure_solutions = translate_solution(solutions);
+                if dominance_expression.is_some() {
+                    add_represented_decision_values(
+                        &mut conjure_solutions,
+                        &state.borrow().model_template,
+                    );
+                }
+
+                let modifier = Box::new(MinionModelModifier::new(Rc::clone(&state)));
+                if !callback(conjure_solutions.clone(), modifier) {
+                    user_terminated = true;
+                    return false;
+                }
+
+                let mut state = state.borrow_mut();
+                let state = &mut *state;
+                if let Err(err) = add_dominance_constraints_for_solution(
+                    dominance_expression.as_ref(),
+                    Some(&state.model_template),
+                    &conjure_solutions,
+                    &mut state.known_var_names,
+                    &mut state.next_midsearch_aux_var_id,
+                    solution_ordinal,
+                ) {
+                    midsearch_error = Some(err);
+                    return false;
+                }
+
+                true
+            }),
+            RunOptions {
+                value_order: self.value_order.map(Into::into),
+            },
+        )
+        .map_err(minion_error_to_solver_error)?;
+
+        if let Some(err) = midsearch_error {
+            return Err(err);
+        }
+
+        let status = if user_terminated {
+            Incomplete(UserTerminated)
+        } else if any_solutions {
+            Complete(HasSolutions)
+        } else {
+            Complete(NoSolutions)
+        };
+        Ok(SolveSuccess {
+            stats: get_solver_stats(&solver_ctx),
+            status,
+        })
     }
 
     fn load_model(&mut self, model: ConjureModel, _: private::Internal) -> Result<(), SolverError> {
@@ -211,7 +302,7 @@ impl SolverAdaptor for Minion {
             Expression::DominanceRelation(_, inner) => inner.as_ref().clone(),
             _ => expr.clone(),
         });
-        self.dominance_model_template = self.dominance_expression.as_ref().map(|_| model.clone());
+        self.model_template = Some(model.clone());
         self.model = Some(model_to_minion(model)?);
         Ok(())
     }
@@ -415,11 +415,11 @@ fn remap_var_names_in_constraint(
                 .collect(),
             tuples,
         ),
-        C::Str2Plus(vars, out) => C::Str2Plus(
+        C::Str2Plus(vars, tuples) => C::Str2Plus(
             vars.into_iter()
                 .map(|v| remap_var_names_in_var(&v, remap))
                 .collect(),
-            remap_var_names_in_var(&out, remap),
+            tuples,
         ),
         C::Max(vars, out) => C::Max(
             vars.into_iter()
@@ -623,32 +623,52 @@ pub(super) fn add_dominance_constraints_for_solution(
         "[minion-inject] rewritten_dominance(solution#{solution_ordinal}) = {rewritten_dominance:#?}"
     ));
 
-    let mut dominance_model = model_template.clone();
-    dominance_model.replace_constraints(vec![]);
-    dominance_model.replace_clauses(vec![]);
-    dominance_model.dominance = None;
-    dominance_model.add_constraint(rewritten_dominance);
+    inject_constraint_during_search(
+        rewritten_dominance,
+        model_template,
+        known_var_names,
+        next_midsearch_aux_var_id,
+        &format!("solution#{solution_ordinal}"),
+    )
+}
+
+/// Rewrites a high-level constraint using the Minion rule sets, and adds the result to the
+/// currently-running Minion instance.
+///
+/// Auxiliary variables introduced by rewriting are added to Minion under fresh names. Any search
+/// variables referenced by `constraint` must already be in `known_var_names`.
+///
+/// `label` is used to identify this injection in the injection log.
+pub(super) fn inject_constraint_during_search(
+    constraint: Expression,
+    model_template: &ConjureModel,
+    known_var_names: &mut HashSet<minion_ast::VarName>,
+    next_midsearch_aux_var_id: &mut usize,
+    label: &str,
+) -> Result<(), SolverError> {
+    let mut injection_model = model_template.clone();
+    injection_model.replace_constraints(vec![]);
+    injection_model.replace_clauses(vec![]);
+    injection_model.dominance = None;
+    injection_model.objective = None;
+    injection_model.add_constraint(constraint);
 
     let rewritten = rewrite_model_with_configured_rewriter(
-        dominance_model,
+        injection_model,
         &get_rule_sets_for_solver_family(SolverFamily::Minion),
         current_rewriter(),
     )
     .map_err(|e| {
         Runtime(format!(
-            "failed to rewrite dominance constraint for Minion injection: {e}"
+            "failed to rewrite constraint for Minion injection: {e}"
         ))
     })?;
 
-    let dominance_minion_model = model_to_minion(rewritten)?;
-    append_minion_injection_log(&format!(
-        "[minion-inject] minion_model(solution#{solution_ordinal}) START"
-    ));
-    append_minion_injection_log(&dump_minion_model(&dominance_minion_model));
-    append_minion_injection_log(&format!(
-        "[minion-inject] minion_model(solution#{solution_ordinal}) END"
-    ));
-    let search_var_names = dominance_minion_model
+    let injection_minion_model = model_to_minion(rewritten)?;
+    append_minion_injection_log(&format!("[minion-inject] minion_model({label}) START"));
+    append_minion_injection_log(&dump_minion_model(&injection_minion_model));
+    append_minion_injection_log(&format!("[minion-inject] minion_model({label}) END"));
+    let search_var_names = injection_minion_model
         .named_variables
         .get_search_variable_order()
         .into_iter()
@@ -656,22 +676,22 @@ pub(super) fn add_dominance_constraints_for_solution(
 
     let mut remap = HashMap::<minion_ast::VarName, minion_ast::VarName>::new();
 
-    for var_name in dominance_minion_model.named_variables.get_variable_order() {
+    for var_name in injection_minion_model.named_variables.get_variable_order() {
         if search_var_names.contains(&var_name) {
             if !known_var_names.contains(&var_name) {
                 return Err(Runtime(format!(
-                    "dominance injection references unknown search variable '{var_name}'"
+                    "Minion injection references unknown search variable '{var_name}'"
                 )));
             }
             continue;
         }
 
-        let domain = dominance_minion_model
+        let domain = injection_minion_model
             .named_variables
             .get_vartype(var_name.clone())
             .ok_or_else(|| {
                 Runtime(format!(
-                    "dominance injection variable '{var_name}' is missing a Minion domain"
+                    "Minion injection variable '{var_name}' is missing a Minion domain"
                 ))
             })?;
 
@@ -683,46 +703,44 @@ pub(super) fn add_dominance_constraints_for_solution(
 
         add_aux_var_during_search(fresh_name.clone(), domain).map_err(|e| {
             Runtime(format!(
-                "failed to add Minion dominance aux variable '{fresh_name}' (from '{var_name}', domain={domain:?}): {e:#?}"
+                "failed to add Minion aux variable '{fresh_name}' (from '{var_name}', domain={domain:?}): {e:#?}"
             ))
         })?;
         append_minion_injection_log(&format!(
-            "[minion-inject] add_aux(solution#{solution_ordinal}): {var_name} -> {fresh_name}, domain={domain:?}"
+            "[minion-inject] add_aux({label}): {var_name} -> {fresh_name}, domain={domain:?}"
         ));
         known_var_names.insert(fresh_name.clone());
         remap.insert(var_name, fresh_name);
     }
 
-    for (constraint_idx, constraint) in dominance_minion_model.constraints.into_iter().enumerate() {
+    for (constraint_idx, constraint) in injection_minion_model.constraints.into_iter().enumerate() {
         let remapped_constraint = remap_var_names_in_constraint(constraint.clone(), &remap);
         append_minion_injection_log(&format!(
-            "[minion-inject] add_constraint(solution#{solution_ordinal}, idx={constraint_idx}) original={constraint:?} remapped={remapped_constraint:?}"
+            "[minion-inject] add_constraint({label}, idx={constraint_idx}) original={constraint:?} remapped={remapped_constraint:?}"
         ));
         match add_constraint_during_search(remapped_constraint.clone()) {
             Ok(()) => append_minion_injection_log(&format!(
-                "[minion-inject] add_constraint(solution#{solution_ordinal}, idx={constraint_idx}) => OK"
+                "[minion-inject] add_constraint({label}, idx={constraint_idx}) => OK"
             )),
-            Err(MinionError::RuntimeError(RuntimeError::InvalidInstance(msg)))
-                if msg.contains("propagation failure when adding constraint midsearch") =>
-            {
+            Err(MinionError::RuntimeError(RuntimeError::PropagationFailure)) => {
                 append_minion_injection_log(&format!(
-                    "[minion-inject] add_constraint(solution#{solution_ordinal}, idx={constraint_idx}) => PROPAGATION_FAILURE (treated as ok): {msg}"
+                    "[minion-inject] add_constraint({label}, idx={constraint_idx}) => PROPAGATION_FAILURE (treated as ok)"
                 ));
             }
             Err(other) => {
                 let solver_err = minion_error_to_solver_error(other);
                 append_minion_injection_log(&format!(
-                    "[minion-inject] add_constraint(solution#{solution_ordinal}, idx={constraint_idx}) => ERROR: {:#?}",
+                    "[minion-inject] add_constraint({label}, idx={constraint_idx}) => ERROR: {:#?}",
                     solver_err
                 ));
                 return Err(Runtime(format!(
-                    "failed to inject Minion dominance constraint #{constraint_idx}: original={constraint:?}; remapped={remapped_constraint:?}; error={:#?}",
+                    "failed to inject Minion constraint #{constraint_idx}: original={constraint:?}; remapped={remapped_constraint:?}; error={:#?}",
                     solver_err
                 )));
             }
         }
     }
 
-    append_minion_injection_log(&format!("[minion-inject] END solution#{solution_ordinal}"));
+    append_minion_injection_log(&format!("[minion-inject] END {label}"));
     Ok(())
 }
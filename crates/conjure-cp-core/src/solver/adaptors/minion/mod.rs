@@ -1,6 +1,8 @@
 /// Minion solver adaptor
 mod adaptor;
 mod dominance_injection;
+mod model_modifier;
 mod parse_model;
 
 pub use adaptor::{Minion, MinionValueOrder};
+pub use model_modifier::MinionModelModifier;
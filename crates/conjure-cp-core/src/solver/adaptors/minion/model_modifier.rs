@@ -0,0 +1,130 @@
+//! Incremental solving for Minion.
+//!
+//! Minion supports adding variables and constraints from inside its solution callback. These are
+//! exposed through [`MinionModelModifier`], which is passed to the user callback by
+//! [`Solver::solve_mut`](crate::solver::Solver::solve_mut).
+
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::rc::Rc;
+
+use anyhow::anyhow;
+use minion_sys::ast as minion_ast;
+use minion_sys::{add_aux_var_during_search, add_constraint_during_search};
+
+use crate::Model as ConjureModel;
+use crate::ast::{DeclarationPtr, Domain, Expression, Moo, Name};
+use crate::solver::SolverError;
+use crate::solver::model_modifier::{ModelModifier, ModificationFailure};
+use crate::solver::private;
+
+use super::dominance_injection::{inject_constraint_during_search, minion_error_to_solver_error};
+use super::parse_model::{name_to_string, var_to_minion};
+
+/// State needed to add things to a running Minion search.
+///
+/// This is shared between the solve loop and every [`MinionModelModifier`] it hands out.
+pub(super) struct MidsearchState {
+    /// The loaded model, used to rewrite added constraints.
+    ///
+    /// Variables added during search are declared in its symbol table.
+    pub(super) model_template: ConjureModel,
+
+    /// The names of all Minion variables in the running search.
+    pub(super) known_var_names: HashSet<minion_ast::VarName>,
+
+    pub(super) next_midsearch_aux_var_id: usize,
+    next_injection_id: usize,
+}
+
+impl MidsearchState {
+    pub(super) fn new(
+        model_template: ConjureModel,
+        known_var_names: HashSet<minion_ast::VarName>,
+    ) -> Self {
+        MidsearchState {
+            model_template,
+            known_var_names,
+            next_midsearch_aux_var_id: 0,
+            next_injection_id: 0,
+        }
+    }
+}
+
+/// A [`ModelModifier`] for Minion.
+///
+/// Constraints are given as high level expressions, and are rewritten using the Minion rule sets
+/// before being added to the search. Modifications only affect the remainder of the current
+/// search, and must be made from inside the solution callback.
+///
+/// Variables added during search are auxiliary variables in Minion: they are not branched on,
+/// and do not appear in solutions.
+pub struct MinionModelModifier {
+    state: Rc<RefCell<MidsearchState>>,
+}
+
+impl MinionModelModifier {
+    pub(super) fn new(state: Rc<RefCell<MidsearchState>>) -> Self {
+        MinionModelModifier { state }
+    }
+}
+
+impl private::Sealed for MinionModelModifier {}
+
+impl ModelModifier for MinionModelModifier {
+    fn add_constraint(&self, constraint: Expression) -> Result<(), ModificationFailure> {
+        let mut state = self.state.borrow_mut();
+        let state = &mut *state;
+
+        let label = format!("modification#{}", state.next_injection_id);
+        state.next_injection_id += 1;
+
+        inject_constraint_during_search(
+            constraint,
+            &state.model_template,
+            &mut state.known_var_names,
+            &mut state.next_midsearch_aux_var_id,
+            &label,
+        )
+        .map_err(|e| ModificationFailure::Error(e.into()))
+    }
+
+    fn add_variable(&self, name: Name, domain: Domain) -> Result<(), ModificationFailure> {
+        let mut state = self.state.borrow_mut();
+
+        if state.model_template.symbols().lookup(&name).is_some() {
+            return Err(ModificationFailure::ArgsInvalid(anyhow!(
+                "variable {name} is already defined"
+            )));
+        }
+
+        let declaration = DeclarationPtr::new_find(name.clone(), Moo::new(domain));
+        let var = declaration
+            .as_find()
+            .expect("a newly created find declaration should be a decision variable")
+            .clone();
+        let var_model = var_to_minion(&name, &var).map_err(|e| match e {
+            SolverError::ModelFeatureNotSupported(_) | SolverError::ModelInvalid(_) => {
+                ModificationFailure::ArgsInvalid(e.into())
+            }
+            _ => ModificationFailure::Error(e.into()),
+        })?;
+
+        let var_name = name_to_string(name);
+        let domain = var_model
+            .named_variables
+            .get_vartype(var_name.clone())
+            .expect("var_to_minion should define the given variable");
+
+        add_aux_var_during_search(var_name.clone(), domain)
+            .map_err(|e| ModificationFailure::Error(minion_error_to_solver_error(e).into()))?;
+        for constraint in var_model.constraints {
+            add_constraint_during_search(constraint)
+                .map_err(|e| ModificationFailure::Error(minion_error_to_solver_error(e).into()))?;
+        }
+
+        state.model_template.symbols_mut().insert(declaration);
+        state.known_var_names.insert(var_name);
+        Ok(())
+    }
+}
@@ -27,16 +27,79 @@ pub fn model_to_minion(model: ConjureModel) -> Result<MinionModel, SolverError>
     let table_vars = collect_table_variables(&model);
     load_symbol_table(&model, &table_vars, &mut minion_model)?;
     load_constraints(&model, &mut minion_model)?;
+    load_objective(&model, &mut minion_model)?;
+    load_search_heuristic(&model, &mut minion_model);
     Ok(minion_model)
 }
 
+/// Converts a single decision variable to a `minion_sys` model containing only that variable,
+/// and any constraints needed to restrict it to its domain.
+pub(super) fn var_to_minion(
+    name: &conjure_ast::Name,
+    var: &conjure_ast::DecisionVariable,
+) -> Result<MinionModel, SolverError> {
+    let mut minion_model = MinionModel::new();
+    load_var(name, var, true, &HashSet::new(), &mut minion_model)?;
+    Ok(minion_model)
+}
+
+/// Loads the objective, if any, into `minion_model`.
+fn load_objective(
+    conjure_model: &ConjureModel,
+    minion_model: &mut MinionModel,
+) -> Result<(), SolverError> {
+    let Some(objective) = &conjure_model.objective else {
+        return Ok(());
+    };
+
+    let name = objective.variable_name().ok_or_else(|| {
+        ModelFeatureNotSupported(format!(
+            "objective should be a single decision variable, but got `{}`",
+            objective.expression
+        ))
+    })?;
+
+    let name = name_to_string(name);
+    minion_model.objective = Some(match objective.direction {
+        conjure_ast::ObjectiveDirection::Minimising => minion_ast::Objective::Minimising(name),
+        conjure_ast::ObjectiveDirection::Maximising => minion_ast::Objective::Maximising(name),
+    });
+    Ok(())
+}
+
+/// Loads the variable and value ordering heuristics into `minion_model`.
+fn load_search_heuristic(conjure_model: &ConjureModel, minion_model: &mut MinionModel) {
+    use conjure_ast::{ValueHeuristic, VariableHeuristic};
+
+    if let Some(variable) = conjure_model.search_heuristic.variable {
+        minion_model.var_order = match variable {
+            VariableHeuristic::Static => minion_ast::VarOrder::Static,
+            VariableHeuristic::Sdf => minion_ast::VarOrder::Sdf,
+            VariableHeuristic::Srf => minion_ast::VarOrder::Srf,
+            VariableHeuristic::Ldf => minion_ast::VarOrder::Ldf,
+            VariableHeuristic::Wdeg => minion_ast::VarOrder::Wdeg,
+            VariableHeuristic::DomOverWdeg => minion_ast::VarOrder::DomOverWdeg,
+            VariableHeuristic::Conflict => minion_ast::VarOrder::Conflict,
+        };
+    }
+
+    minion_model.value_order = conjure_model
+        .search_heuristic
+        .value
+        .map(|value| match value {
+            ValueHeuristic::Ascend => minion_ast::ValueOrder::Ascend,
+            ValueHeuristic::Descend => minion_ast::ValueOrder::Descend,
+            ValueHeuristic::Random => minion_ast::ValueOrder::Random,
+        });
+}
+
 /// Loads the symbol table into `minion_model`.
 fn load_symbol_table(
     conjure_model: &ConjureModel,
     table_vars: &HashSet<conjure_ast::Name>,
     minion_model: &mut MinionModel,
 ) -> Result<(), SolverError> {
-    if let Some(ref vars) = conjure_model.search_order {
+    if let Some(ref vars) = conjure_model.solver_search_order() {
         let search_vars: HashSet<_> = vars.iter().cloned().collect();
 
         // add search vars in order first
@@ -204,7 +267,7 @@ fn try_add_var(
     added.ok_or(ModelInvalid(format!("variable {name:?} is defined twice")))
 }
 
-fn name_to_string(name: conjure_ast::Name) -> String {
+pub(super) fn name_to_string(name: conjure_ast::Name) -> String {
     match name {
         // print machine names in a custom, easier to regex, way.
         conjure_ast::Name::Machine(x) => format!("__conjure_machine_name_{x}"),
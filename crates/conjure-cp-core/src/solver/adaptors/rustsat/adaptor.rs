@@ -1,15 +1,17 @@
 use std::any::type_name;
+use std::cell::RefCell;
 use std::fmt::format;
 use std::hash::Hash;
 use std::iter::Inspect;
 use std::ops::Deref;
 use std::ptr::null;
+use std::rc::Rc;
 use std::vec;
 
 use clap::error;
 use minion_sys::ast::{Model, Tuple};
 use rustsat::encodings::am1::Def;
-use rustsat::solvers::{Solve, SolverResult};
+use rustsat::solvers::{PhaseLit, Solve, SolveIncremental, SolverResult};
 use rustsat::types::{Assignment, Clause, Lit, TernaryVal, Var as satVar};
 use std::collections::{BTreeMap, HashMap};
 use std::result::Result::Ok;
@@ -19,11 +21,17 @@ use ustr::Ustr;
 use rustsat_cadical::CaDiCaL;
 
 use crate::ast::pretty::pretty_vec;
-use crate::ast::{Atom, Expression, GroundDomain, Literal, Metadata, Moo, Name};
+use crate::ast::{
+    Atom, CnfClause, Expression, GroundDomain, Literal, Metadata, Moo, Name, Objective,
+    ObjectiveDirection, ValueHeuristic,
+};
 use crate::rule_engine::rewrite_model_with_configured_rewriter;
 use crate::settings::current_rewriter;
 use crate::solver::SearchComplete::NoSolutions;
-use crate::solver::adaptors::rustsat::convs::{cnf_clause_to_sat_clause, handle_cnf};
+use crate::solver::adaptors::rustsat::convs::{cnf_clause_to_sat_clause, handle_cnf, handle_disjn};
+use crate::solver::adaptors::rustsat::dimacs::{DimacsVarMap, write_dimacs};
+use crate::solver::adaptors::rustsat::external::ExternalSatSolver;
+use crate::solver::adaptors::rustsat::model_modifier::{SatModelModifier, SatSearchState};
 use crate::solver::{
     self, SearchStatus, SolveSuccess, SolverAdaptor, SolverCallback, SolverError, SolverFamily,
     SolverMutCallback, private,
@@ -46,7 +54,10 @@ pub struct Sat {
     solver_inst: CaDiCaL<'static, 'static>,
     decision_refs: Option<Vec<Name>>,
     dominance_expression: Option<Expression>,
-    dominance_model_template: Option<ConjureModel>,
+    objective: Option<Objective>,
+    model_template: Option<ConjureModel>,
+    phase_hints: Vec<Lit>,
+    external_solver: Option<ExternalSatSolver>,
 }
 
 impl private::Sealed for Sat {}
@@ -60,7 +71,10 @@ impl Default for Sat {
             model_inst: None,
             decision_refs: None,
             dominance_expression: None,
-            dominance_model_template: None,
+            objective: None,
+            model_template: None,
+            phase_hints: Vec::new(),
+            external_solver: None,
         }
     }
 }
@@ -179,7 +193,10 @@ fn add_represented_decision_values(solution: &mut HashMap<Name, Literal>, model:
         .clone()
         .into_iter()
         .collect::<BTreeMap<Name, Literal>>();
-    for (name, representation) in representations {
+    // representation variables are declared after the variable they represent, and can be
+    // represented themselves (e.g. the elements of a set are integers, represented by bits), so
+    // work backwards to find their values first
+    for (name, representation) in representations.into_iter().rev() {
         let Ok(value) = representation.value_up(&solution_btree) else {
             continue;
         };
@@ -217,6 +234,30 @@ fn get_ref_sols(
     solution
 }
 
+/// Reads the value of the objective variable from a solution.
+fn objective_value(
+    objective: &Objective,
+    solution: &HashMap<Name, Literal>,
+) -> Result<i32, SolverError> {
+    let name = objective.variable_name().ok_or_else(|| {
+        SolverError::ModelFeatureNotSupported(format!(
+            "objective should be a single decision variable, but got `{}`",
+            objective.expression
+        ))
+    })?;
+
+    match solution.get(&name) {
+        Some(Literal::Int(value)) => Ok(*value),
+        Some(Literal::Bool(value)) => Ok(*value as i32),
+        Some(other) => Err(SolverError::Runtime(format!(
+            "objective variable {name} has non-integer value {other}"
+        ))),
+        None => Err(SolverError::Runtime(format!(
+            "objective variable {name} is missing from the solution"
+        ))),
+    }
+}
+
 fn is_user_visible_solution_var(name: &Name) -> bool {
     !matches!(name, Name::Machine(_))
 }
@@ -255,13 +296,141 @@ fn blocking_clause_for_solution(
     Ok(clause)
 }
 
+/// Gets the phase each SAT variable in the search order should be tried with first.
+///
+/// CaDiCaL does not take a variable order, so the search order of the model is only used to pick
+/// which variables get a phase: those in the search order if there is one, otherwise all decision
+/// variables. Trying `false` first follows ascending value order, and `true` first descending
+/// value order. The random value order and the default ascending order without a search order
+/// are left to the solver.
+fn phase_hints(model: &ConjureModel, finds: &[Name], var_map: &HashMap<Name, Lit>) -> Vec<Lit> {
+    let value = model.search_heuristic.value;
+    let (vars, value) = match (model.solver_search_order(), value) {
+        (_, Some(ValueHeuristic::Random)) | (None, None) => return vec![],
+        (Some(vars), value) => (vars, value.unwrap_or(ValueHeuristic::Ascend)),
+        (None, Some(value)) => (finds.to_vec(), value),
+    };
+
+    vars.iter()
+        .filter_map(|name| var_map.get(name))
+        .map(|lit| match value {
+            ValueHeuristic::Descend => *lit,
+            _ => !*lit,
+        })
+        .collect()
+}
+
+/// Gives `solver` the phase of each literal in `phase_hints`.
+///
+/// This must be called before any clauses are added. CaDiCaL's lucky phase search ignores the
+/// phases we give it, so it is turned off when there are any.
+fn add_phase_hints(solver: &mut CaDiCaL, phase_hints: &[Lit]) -> Result<(), SolverError> {
+    if phase_hints.is_empty() {
+        return Ok(());
+    }
+
+    solver.set_option("lucky", 0).map_err(|e| {
+        SolverError::Runtime(format!(
+            "Failed turning off lucky phases in SAT solver: {e}"
+        ))
+    })?;
+    for lit in phase_hints {
+        solver.phase_lit(*lit).map_err(|e| {
+            SolverError::Runtime(format!("Failed setting phase of {lit} in SAT solver: {e}"))
+        })?;
+    }
+    Ok(())
+}
+
 impl Sat {
+    /// Creates a SAT adaptor that solves using `solver`, a SAT solver binary, instead of the
+    /// CaDiCaL library.
+    ///
+    /// The solver is ran once for each solution, with the solutions found so far blocked.
+    /// Optimisation, dominance and [`solve_mut`](SolverAdaptor::solve_mut) are not supported.
+    pub fn with_external_solver(solver: ExternalSatSolver) -> Self {
+        Sat {
+            external_solver: Some(solver),
+            ..Default::default()
+        }
+    }
+
+    /// Finds solutions by running `external_solver` on the CNF, adding a clause blocking each
+    /// solution found before running it again.
+    fn solve_external(
+        &mut self,
+        external_solver: &ExternalSatSolver,
+        callback: SolverCallback,
+    ) -> Result<SolveSuccess, SolverError> {
+        if self.objective.is_some() || self.dominance_expression.is_some() {
+            return Err(SolverError::OpNotSupported(format!(
+                "optimisation and dominance with external SAT solver {}",
+                external_solver.program()
+            )));
+        }
+
+        let var_map = self.var_map.clone().ok_or_else(|| {
+            SolverError::Runtime("Variable map is missing when retrieving solution".to_string())
+        })?;
+        let find_refs = self.decision_refs.clone().ok_or_else(|| {
+            SolverError::Runtime(
+                "Decision references are missing when retrieving solution".to_string(),
+            )
+        })?;
+        let (mut cnf, var_manager): (Cnf, BasicVarManager) = self
+            .model_inst
+            .clone()
+            .ok_or_else(|| SolverError::Runtime("Model instance is missing".to_string()))?
+            .into_cnf();
+        let n_vars = var_manager.n_used();
+
+        let mut has_sol = false;
+        let status = 'search: loop {
+            let Some(mut sol) = external_solver.solve(&cnf, n_vars, &var_map)? else {
+                break if has_sol {
+                    SearchStatus::Complete(solver::SearchComplete::HasSolutions)
+                } else {
+                    SearchStatus::Complete(NoSolutions)
+                };
+            };
+
+            // variables the solver leaves out of its solution are don't-cares
+            for lit in var_map.values() {
+                let inserter = sol.var_value(lit.var());
+                sol.assign_var(lit.var(), inserter);
+            }
+
+            has_sol = true;
+            let sol_old = get_ref_sols(find_refs.clone(), sol, var_map.clone());
+
+            for solution in enumerate_all_solutions(sol_old) {
+                if !callback(solution.clone()) {
+                    break 'search SearchStatus::Incomplete(
+                        solver::SearchIncomplete::UserTerminated,
+                    );
+                }
+                cnf.add_clause(blocking_clause_for_solution(&solution, &var_map)?);
+            }
+        };
+
+        Ok(SolveSuccess {
+            stats: SolverStats {
+                conjure_solver_wall_time_s: -1.0,
+                solver_family: Some(self.get_family()),
+                solver_adaptor: Some("SAT".to_string()),
+                ..Default::default()
+            },
+            status,
+        })
+    }
+
     fn add_dominance_constraints_for_solution(
         dominance_expression: Option<&Expression>,
         dominance_model_template: Option<&ConjureModel>,
         solver: &mut CaDiCaL<'static, 'static>,
         solution: &HashMap<Name, Literal>,
         var_map: &mut HashMap<Name, Lit>,
+        var_manager: &mut BasicVarManager,
     ) -> Result<(), SolverError> {
         let Some(dominance_expression) = dominance_expression else {
             return Ok(());
@@ -274,20 +443,84 @@ impl Sat {
         let rewritten_dominance =
             rewrite_dominance_to_block_dominated_futures(dominance_expression, solution);
 
-        let mut dominance_model = model_template.clone();
-        dominance_model.replace_constraints(vec![]);
-        dominance_model.replace_clauses(vec![]);
-        dominance_model.dominance = None;
-        dominance_model.add_constraint(rewritten_dominance);
+        Sat::add_constraint_as_clauses(
+            rewritten_dominance,
+            model_template,
+            solver,
+            var_map,
+            var_manager,
+            None,
+            "dominance",
+        )
+    }
 
-        let rule_sets = dominance_model.context.read().unwrap().rule_sets.clone();
-        let rewritten =
-            rewrite_model_with_configured_rewriter(dominance_model, &rule_sets, current_rewriter())
-                .map_err(|e| {
-                    SolverError::Runtime(format!(
-                        "Failed to rewrite dominance constraint into CNF clauses: {e}"
-                    ))
-                })?;
+    /// Restricts the rest of the search to solutions strictly better than `value`.
+    fn add_objective_bound(
+        objective: &Objective,
+        value: i32,
+        model_template: &ConjureModel,
+        solver: &mut CaDiCaL<'static, 'static>,
+        var_map: &mut HashMap<Name, Lit>,
+        var_manager: &mut BasicVarManager,
+    ) -> Result<(), SolverError> {
+        let objective_expr = Moo::new(objective.expression.clone());
+        let value_expr = Moo::new(Expression::Atomic(
+            Metadata::new(),
+            Atom::Literal(Literal::Int(value)),
+        ));
+        let bound = match objective.direction {
+            ObjectiveDirection::Minimising => {
+                Expression::Lt(Metadata::new(), objective_expr, value_expr)
+            }
+            ObjectiveDirection::Maximising => {
+                Expression::Gt(Metadata::new(), objective_expr, value_expr)
+            }
+        };
+
+        Sat::add_constraint_as_clauses(
+            bound,
+            model_template,
+            solver,
+            var_map,
+            var_manager,
+            None,
+            "objective bound",
+        )
+    }
+
+    /// Rewrites `constraint` to CNF in the context of `model_template`, and adds the resulting
+    /// clauses to the running solver, allocating SAT variables for any new auxiliaries from
+    /// `var_manager`.
+    ///
+    /// If `guard` is given, `!guard` is added to every clause, so the constraint is only enforced
+    /// when solving under the assumption `guard`.
+    pub(super) fn add_constraint_as_clauses(
+        constraint: Expression,
+        model_template: &ConjureModel,
+        solver: &mut CaDiCaL<'static, 'static>,
+        var_map: &mut HashMap<Name, Lit>,
+        var_manager: &mut BasicVarManager,
+        guard: Option<Lit>,
+        purpose: &str,
+    ) -> Result<(), SolverError> {
+        let mut constraint_model = model_template.clone();
+        constraint_model.replace_constraints(vec![]);
+        constraint_model.replace_clauses(vec![]);
+        constraint_model.dominance = None;
+        constraint_model.objective = None;
+        constraint_model.add_constraint(constraint);
+
+        let rule_sets = constraint_model.context.read().unwrap().rule_sets.clone();
+        let rewritten = rewrite_model_with_configured_rewriter(
+            constraint_model,
+            &rule_sets,
+            current_rewriter(),
+        )
+        .map_err(|e| {
+            SolverError::Runtime(format!(
+                "Failed to rewrite {purpose} constraint into CNF clauses: {e}"
+            ))
+        })?;
 
         for clause in rewritten.clauses() {
             let mut missing_refs: Vec<Name> = Vec::new();
@@ -322,13 +555,7 @@ impl Sat {
                     if var_map.contains_key(name) {
                         continue;
                     }
-                    let next_idx = var_map
-                        .values()
-                        .map(|lit| lit.var().idx32())
-                        .max()
-                        .map(|idx| idx + 1)
-                        .unwrap_or(0);
-                    let new_var = satVar::new(next_idx);
+                    let new_var = var_manager.new_var();
                     let new_lit = new_var.pos_lit();
                     var_map.insert(name.clone(), new_lit);
                     largest_new_var = Some(new_var);
@@ -338,19 +565,22 @@ impl Sat {
             if let Some(max_var) = largest_new_var {
                 solver.reserve(max_var).map_err(|e| {
                     SolverError::Runtime(format!(
-                        "Failed reserving SAT variable capacity up to {max_var} for dominance clauses: {e}"
+                        "Failed reserving SAT variable capacity up to {max_var} for {purpose} clauses: {e}"
                     ))
                 })?;
             }
 
-            if let Some(sat_clause) = cnf_clause_to_sat_clause(clause, var_map).map_err(|e| {
+            if let Some(mut sat_clause) = cnf_clause_to_sat_clause(clause, var_map).map_err(|e| {
                 SolverError::Runtime(format!(
-                    "Failed converting dominance CNF clause to SAT clause. clause={clause:?}; error={e}"
+                    "Failed converting {purpose} CNF clause to SAT clause. clause={clause:?}; error={e}"
                 ))
             })? {
+                if let Some(guard) = guard {
+                    sat_clause.add(!guard);
+                }
                 solver.add_clause(sat_clause).map_err(|e| {
                     SolverError::Runtime(format!(
-                        "Failed adding dominance clause to SAT solver: {e}"
+                        "Failed adding {purpose} clause to SAT solver: {e}"
                     ))
                 })?;
             }
@@ -358,6 +588,26 @@ impl Sat {
 
         Ok(())
     }
+
+    /// Allocates a new SAT variable for `name`.
+    ///
+    /// Variables are allocated by `var_manager`, which also knows about the auxiliary variables
+    /// added when the instance was converted to CNF.
+    pub(super) fn add_fresh_sat_var(
+        name: Name,
+        solver: &mut CaDiCaL<'static, 'static>,
+        var_map: &mut HashMap<Name, Lit>,
+        var_manager: &mut BasicVarManager,
+    ) -> Result<Lit, SolverError> {
+        let new_var = var_manager.new_var();
+        solver.reserve(new_var).map_err(|e| {
+            SolverError::Runtime(format!("Failed reserving SAT variable {new_var}: {e}"))
+        })?;
+
+        let new_lit = new_var.pos_lit();
+        var_map.insert(name, new_lit);
+        Ok(new_lit)
+    }
 }
 
 impl SolverAdaptor for Sat {
@@ -366,24 +616,34 @@ impl SolverAdaptor for Sat {
         callback: SolverCallback,
         _: private::Internal,
     ) -> Result<SolveSuccess, SolverError> {
+        if let Some(external_solver) = self.external_solver.clone() {
+            return self.solve_external(&external_solver, callback);
+        }
+
         let dominance_expression = self.dominance_expression.clone();
-        let dominance_model_template = self.dominance_model_template.clone();
+        let objective = self.objective.clone();
+        let model_template = self.model_template.clone();
         let mut solver = &mut self.solver_inst;
         let mut var_map = self.var_map.clone().ok_or_else(|| {
             SolverError::Runtime("Variable map is missing when retrieving solution".to_string())
         })?;
 
-        let cnf: (Cnf, BasicVarManager) = self
+        let (cnf, mut var_manager): (Cnf, BasicVarManager) = self
             .model_inst
             .clone()
             .ok_or_else(|| SolverError::Runtime("Model instance is missing".to_string()))?
             .into_cnf();
 
-        solver.add_cnf(cnf.0).map_err(|e| {
+        add_phase_hints(solver, &self.phase_hints)?;
+        solver.add_cnf(cnf).map_err(|e| {
             SolverError::Runtime(format!("Failed adding CNF to SAT solver before solve: {e}"))
         })?;
 
         let mut has_sol = false;
+
+        // When optimising, each solution found tightens the objective bound, so the last one
+        // found before the problem becomes unsatisfiable is optimal.
+        let mut best_solution: Option<HashMap<Name, Literal>> = None;
         loop {
             let res = match solver.solve() {
                 Ok(r) => r,
@@ -398,6 +658,9 @@ impl SolverAdaptor for Sat {
             match res {
                 SolverResult::Sat => {}
                 SolverResult::Unsat => {
+                    if let Some(solution) = best_solution {
+                        callback(solution);
+                    }
                     return Ok(SolveSuccess {
                         stats: SolverStats {
                             conjure_solver_wall_time_s: -1.0,
@@ -448,6 +711,27 @@ impl SolverAdaptor for Sat {
 
             tracing::info!("old solution {:#?}", sol_old);
 
+            if let Some(objective) = objective.as_ref() {
+                let model_template = model_template.as_ref().ok_or_else(|| {
+                    SolverError::Runtime("Model template is missing when optimising".to_string())
+                })?;
+
+                let mut objective_solution = full_assignment_solution.clone();
+                add_represented_decision_values(&mut objective_solution, model_template);
+                let value = objective_value(objective, &objective_solution)?;
+
+                best_solution = enumerate_all_solutions(sol_old).into_iter().next();
+                Sat::add_objective_bound(
+                    objective,
+                    value,
+                    model_template,
+                    solver,
+                    &mut var_map,
+                    &mut var_manager,
+                )?;
+                continue;
+            }
+
             let solutions = enumerate_all_solutions(sol_old);
 
             tracing::info!("final solutions for run");
@@ -469,16 +753,19 @@ impl SolverAdaptor for Sat {
 
                 let mut dominance_solution = full_assignment_solution.clone();
                 dominance_solution.extend(solution.clone());
-                if let Some(model_template) = dominance_model_template.as_ref() {
+                if let Some(model_template) = model_template.as_ref()
+                    && dominance_expression.is_some()
+                {
                     add_represented_decision_values(&mut dominance_solution, model_template);
                 }
 
                 Sat::add_dominance_constraints_for_solution(
                     dominance_expression.as_ref(),
-                    dominance_model_template.as_ref(),
+                    model_template.as_ref(),
                     solver,
                     &dominance_solution,
                     &mut var_map,
+                    &mut var_manager,
                 )?;
 
                 let blocking_cl = blocking_clause_for_solution(&solution, &var_map)?;
@@ -492,12 +779,153 @@ impl SolverAdaptor for Sat {
         }
     }
 
+    /// Finds solutions one at a time, passing a [`SatModelModifier`] to the callback with each.
+    ///
+    /// Modifications are added to the running SAT solver, so the search continues from its
+    /// current state rather than restarting.
     fn solve_mut(
         &mut self,
         callback: SolverMutCallback,
         _: private::Internal,
     ) -> Result<SolveSuccess, SolverError> {
-        Err(SolverError::OpNotSupported("solve_mut".to_owned()))
+        if self.objective.is_some() {
+            return Err(SolverError::OpNotSupported(
+                "solve_mut for models with an objective".to_owned(),
+            ));
+        }
+        if let Some(external_solver) = &self.external_solver {
+            return Err(SolverError::OpNotSupported(format!(
+                "solve_mut with external SAT solver {}",
+                external_solver.program()
+            )));
+        }
+
+        let dominance_expression = self.dominance_expression.clone();
+        let model_template = self.model_template.clone().ok_or_else(|| {
+            SolverError::Runtime("Model template is missing when solving".to_string())
+        })?;
+        let var_map = self.var_map.clone().ok_or_else(|| {
+            SolverError::Runtime("Variable map is missing when retrieving solution".to_string())
+        })?;
+        let find_refs = self.decision_refs.clone().ok_or_else(|| {
+            SolverError::Runtime(
+                "Decision references are missing when retrieving solution".to_string(),
+            )
+        })?;
+
+        let (cnf, var_manager): (Cnf, BasicVarManager) = self
+            .model_inst
+            .clone()
+            .ok_or_else(|| SolverError::Runtime("Model instance is missing".to_string()))?
+            .into_cnf();
+
+        let mut solver = std::mem::take(&mut self.solver_inst);
+        add_phase_hints(&mut solver, &self.phase_hints)?;
+        solver.add_cnf(cnf).map_err(|e| {
+            SolverError::Runtime(format!("Failed adding CNF to SAT solver before solve: {e}"))
+        })?;
+
+        let state = Rc::new(RefCell::new(SatSearchState {
+            solver,
+            var_map,
+            var_manager,
+            model_template,
+            assumptions: vec![],
+        }));
+        let mut has_sol = false;
+
+        let status = 'search: loop {
+            let mut guard = state.borrow_mut();
+            let current = &mut *guard;
+
+            // Assumptions only apply to the next call to the SAT solver.
+            let assumptions = std::mem::take(&mut current.assumptions);
+            let res = current.solver.solve_assumps(&assumptions).map_err(|e| {
+                SolverError::Runtime(format!("Solver encountered an error during solving: {}", e))
+            })?;
+
+            match res {
+                SolverResult::Sat => {}
+                SolverResult::Unsat => {
+                    break if has_sol {
+                        SearchStatus::Complete(solver::SearchComplete::HasSolutions)
+                    } else {
+                        SearchStatus::Complete(NoSolutions)
+                    };
+                }
+                SolverResult::Interrupted => {
+                    return Err(SolverError::Runtime("!!Interrupted Solution!!".to_string()));
+                }
+            };
+
+            let mut sol: Assignment = current.solver.full_solution().map_err(|e| {
+                SolverError::Runtime(format!(
+                    "Solver encountered an error when retrieving solution: {}",
+                    e
+                ))
+            })?;
+
+            for (name, lit) in &current.var_map {
+                let inserter = sol.var_value(lit.var());
+                sol.assign_var(lit.var(), inserter);
+            }
+
+            has_sol = true;
+            let sol_old = get_ref_sols(find_refs.clone(), sol.clone(), current.var_map.clone());
+            let full_assignment_solution = get_ref_sols(
+                current.var_map.keys().cloned().collect(),
+                sol,
+                current.var_map.clone(),
+            );
+            drop(guard);
+
+            for solution in enumerate_all_solutions(sol_old) {
+                let modifier = Box::new(SatModelModifier::new(Rc::clone(&state)));
+                if !callback(solution.clone(), modifier) {
+                    break 'search SearchStatus::Incomplete(
+                        solver::SearchIncomplete::UserTerminated,
+                    );
+                }
+
+                let mut guard = state.borrow_mut();
+                let current = &mut *guard;
+
+                if dominance_expression.is_some() {
+                    let mut dominance_solution = full_assignment_solution.clone();
+                    dominance_solution.extend(solution.clone());
+                    add_represented_decision_values(
+                        &mut dominance_solution,
+                        &current.model_template,
+                    );
+
+                    Sat::add_dominance_constraints_for_solution(
+                        dominance_expression.as_ref(),
+                        Some(&current.model_template),
+                        &mut current.solver,
+                        &dominance_solution,
+                        &mut current.var_map,
+                        &mut current.var_manager,
+                    )?;
+                }
+
+                let blocking_cl = blocking_clause_for_solution(&solution, &current.var_map)?;
+                current.solver.add_clause(blocking_cl).map_err(|e| {
+                    SolverError::Runtime(format!(
+                        "Failed adding solution blocking clause to SAT solver: {e}"
+                    ))
+                })?;
+            }
+        };
+
+        Ok(SolveSuccess {
+            stats: SolverStats {
+                conjure_solver_wall_time_s: -1.0,
+                solver_family: Some(self.get_family()),
+                solver_adaptor: Some("SAT".to_string()),
+                ..Default::default()
+            },
+            status,
+        })
     }
 
     fn load_model(&mut self, model: ConjureModel, _: private::Internal) -> Result<(), SolverError> {
@@ -505,7 +933,8 @@ impl SolverAdaptor for Sat {
             Expression::DominanceRelation(_, inner) => inner.as_ref().clone(),
             _ => expr.clone(),
         });
-        self.dominance_model_template = self.dominance_expression.as_ref().map(|_| model.clone());
+        self.objective.clone_from(&model.objective);
+        self.model_template = Some(model.clone());
 
         let sym_tab = model.symbols().deref().clone();
 
@@ -520,20 +949,15 @@ impl SolverAdaptor for Sat {
             let domain = decl
                 .domain()
                 .expect("Decision variable should have a domain");
-            let domain = domain.as_ground().expect("Domain should be ground");
-
-            // only decision variables with boolean domains or representations using booleans are supported at this time
-            if (domain != &GroundDomain::Bool
-                && sym_tab
-                    .get_representation(&name, &["sat_log_int"])
-                    .is_none()
-                && sym_tab
-                    .get_representation(&name, &["sat_direct_int"])
-                    .is_none()
-                && sym_tab
-                    .get_representation(&name, &["sat_order_int"])
-                    .is_none())
-            {
+            let domain = domain.resolve().expect("Domain should be ground");
+            let domain = domain.as_ref();
+
+            // only decision variables with boolean domains or representations are supported at
+            // this time; represented variables are encoded through their representation variables
+            let has_representation = sym_tab
+                .representations_for(&name)
+                .is_some_and(|reprs| !reprs.is_empty());
+            if domain != &GroundDomain::Bool && !has_representation {
                 Err(SolverError::ModelInvalid(
                     "Only Boolean Decision Variables supported".to_string(),
                 ))?;
@@ -563,8 +987,18 @@ impl SolverAdaptor for Sat {
 
         let clauses = m_clone.clauses();
 
-        let inst: SatInstance = handle_cnf(clauses, &mut var_map, finds.clone());
+        let mut inst: SatInstance = handle_cnf(clauses, &mut var_map, finds.clone());
+
+        // the remaining constraint is loaded as a clause of its own
+        for constraint in constraints {
+            handle_disjn(
+                &CnfClause::new(vec![constraint.clone()]),
+                &mut var_map,
+                &mut inst,
+            );
+        }
 
+        self.phase_hints = phase_hints(&m_clone, &finds, &var_map);
         self.var_map = Some(var_map);
         let cnf: (Cnf, BasicVarManager) = inst.clone().into_cnf();
         tracing::info!("CNF: {:?}", cnf.0);
@@ -587,20 +1021,26 @@ impl SolverAdaptor for Sat {
         &self,
         writer: &mut Box<dyn std::io::Write>,
     ) -> Result<(), std::io::Error> {
-        // TODO: add comments saying what conjure oxide variables each clause has
-        // e.g.
-        //      c y x z
-        //        1 2 3
-        //      c x -y
-        //        1 -1
-        // This will require handwriting a dimacs writer, but that should be easy. For now, just
-        // let rustsat write the dimacs.
-
         let model = self.model_inst.clone().unwrap_or_else(|| {
             bug!("model should exist when we write the solver input file, as we should be in the LoadedModel state");
         });
+        let var_map = self.var_map.as_ref().unwrap_or_else(|| {
+            bug!("variable map should exist when we write the solver input file, as we should be in the LoadedModel state");
+        });
         let (cnf, var_manager): (Cnf, BasicVarManager) = model.into_cnf();
-        cnf.write_dimacs(writer, var_manager.n_used())
+        write_dimacs(
+            writer,
+            &cnf,
+            var_manager.n_used(),
+            var_map,
+            self.objective.as_ref(),
+        )
+    }
+
+    fn solver_input_map(&self) -> Option<serde_json::Value> {
+        let model = self.model_template.as_ref()?;
+        let var_map = self.var_map.as_ref()?;
+        serde_json::to_value(DimacsVarMap::new(model, var_map)).ok()
     }
 }
 
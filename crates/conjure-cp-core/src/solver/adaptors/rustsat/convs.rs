@@ -106,8 +106,17 @@ pub fn handle_disjn(
     let mut lits = Clause::new();
 
     for literal in disjn.iter() {
-        let lit: Lit = handle_lit(literal, vars_added, inst_in_use);
-        lits.add(lit);
+        match literal {
+            // the clause is always satisfied
+            Expression::Atomic(_, Atom::Literal(Literal::Bool(true))) => return,
+            // false literals are left out, so a clause of only false literals is empty and
+            // cannot be satisfied
+            Expression::Atomic(_, Atom::Literal(Literal::Bool(false))) => {}
+            _ => {
+                let lit: Lit = handle_lit(literal, vars_added, inst_in_use);
+                lits.add(lit);
+            }
+        }
     }
 
     inst_in_use.add_clause(lits);
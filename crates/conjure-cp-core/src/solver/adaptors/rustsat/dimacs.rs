@@ -0,0 +1,439 @@
+//! Writing the SAT encoding of a model as DIMACS, and reading solutions of it back.
+//!
+//! The CNF is written with a `c` comment line for each named SAT variable, giving the [`Name`] of
+//! the variable and, for the bits of integers, the [`SATIntEncoding`] and bit or value it stands
+//! for:
+//!
+//! ```text
+//! c 1 x#sat_log_int_00 (log encoding of x, bit 0)
+//! c 4 y#sat_order_int_2 (order encoding of y, y >= 2)
+//! c 7 z#sat_direct_int_3 (direct encoding of z, z = 3)
+//! ```
+//!
+//! When the model has an objective over a single variable, the file is written in the WCNF format
+//! of the MaxSAT evaluations instead: hard clauses are prefixed with `h`, and soft clauses weight
+//! the bits of the objective variable so that an optimal MaxSAT solution is an optimal solution.
+//!
+//! A [`DimacsVarMap`] holds the same information in a machine-readable form, so that the solutions
+//! of an external SAT solver can be turned back into values of the decision variables of the
+//! model with [`DimacsVarMap::decode`].
+
+use std::collections::{BTreeMap, HashMap};
+use std::io::{self, Write};
+
+use itertools::Itertools;
+use rustsat::instances::Cnf;
+use rustsat::types::Lit;
+use serde::{Deserialize, Serialize};
+
+use crate::Model;
+use crate::ast::{
+    DeclarationPtr, GroundDomain, Literal, Name, Objective, ObjectiveDirection, SATIntEncoding,
+    SymbolTable,
+};
+use crate::solver::SolverError;
+
+/// A map from the variables of a DIMACS file written by the SAT adaptor to the variables of the
+/// model it was written from.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DimacsVarMap {
+    /// The named SAT variables.
+    pub variables: Vec<DimacsVar>,
+
+    /// The decision variables that are encoded by other variables, in declaration order.
+    pub represented: Vec<RepresentedVar>,
+}
+
+/// A named SAT variable in a DIMACS file.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DimacsVar {
+    /// The number of the variable in the DIMACS file, counting from 1.
+    pub index: u32,
+    pub name: Name,
+
+    /// The encoding this variable is part of, if it is part of an integer.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<SATIntEncoding>,
+
+    /// The bit (log encoding) or value (order and direct encodings) this variable stands for.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub position: Option<i32>,
+}
+
+/// A decision variable that is encoded by other variables through a representation.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RepresentedVar {
+    pub name: Name,
+    pub domain: GroundDomain,
+
+    /// The name of the representation rule used.
+    pub representation: String,
+}
+
+/// Gets the integer encoding `name` is part of, and the bit or value it stands for.
+fn sat_int_encoding(name: &Name) -> Option<(&Name, SATIntEncoding, i32)> {
+    let Name::Represented(fields) = name else {
+        return None;
+    };
+    let (src, repr_name, suffix) = fields.as_ref();
+    let encoding = match repr_name.as_str() {
+        "sat_log_int" => SATIntEncoding::Log,
+        "sat_order_int" => SATIntEncoding::Order,
+        "sat_direct_int" => SATIntEncoding::Direct,
+        _ => return None,
+    };
+    Some((src, encoding, suffix.parse().ok()?))
+}
+
+/// Describes what the SAT variable `name` stands for.
+fn describe(name: &Name) -> Option<String> {
+    let (src, encoding, position) = sat_int_encoding(name)?;
+    Some(match encoding {
+        SATIntEncoding::Log => format!("log encoding of {src}, bit {position}"),
+        SATIntEncoding::Order => format!("order encoding of {src}, {src} >= {position}"),
+        SATIntEncoding::Direct => format!("direct encoding of {src}, {src} = {position}"),
+    })
+}
+
+/// Gets the soft clauses that make minimising their total weight optimise `objective`.
+///
+/// Returns `None` if the objective is not a single variable encoded as a boolean or by one of the
+/// SAT integer encodings.
+fn soft_clauses(objective: &Objective, var_map: &HashMap<Name, Lit>) -> Option<Vec<(u64, Lit)>> {
+    let objective_name = objective.variable_name()?;
+
+    // literals that each add their weight to the value of the objective when true
+    let mut additions: Vec<(u64, Lit)> = vec![];
+    if let Some(lit) = var_map.get(&objective_name) {
+        additions.push((1, *lit));
+    } else {
+        let bits = var_map
+            .iter()
+            .filter_map(|(name, lit)| {
+                let (src, encoding, position) = sat_int_encoding(name)?;
+                (src == &objective_name).then_some((encoding, position, *lit))
+            })
+            .sorted_by_key(|(_, position, _)| *position)
+            .collect_vec();
+        let (encoding, _, _) = bits.first()?;
+
+        match encoding {
+            SATIntEncoding::Log => {
+                // two's complement: the last bit is the sign bit
+                let sign = bits.len() - 1;
+                for (i, (_, _, lit)) in bits.iter().enumerate() {
+                    let weight = 1u64 << i;
+                    additions.push(if i == sign {
+                        (weight, !*lit)
+                    } else {
+                        (weight, *lit)
+                    });
+                }
+            }
+            // each level that holds adds one to the value
+            SATIntEncoding::Order => additions.extend(bits.iter().map(|(_, _, lit)| (1, *lit))),
+            SATIntEncoding::Direct => {
+                let min = bits.first()?.1;
+                additions.extend(
+                    bits.iter()
+                        .map(|(_, value, lit)| ((value - min) as u64, *lit)),
+                );
+            }
+        }
+    }
+
+    // a soft clause costs its weight when violated, so when minimising each literal should be
+    // false, and when maximising true
+    Some(
+        additions
+            .into_iter()
+            .filter(|(weight, _)| *weight > 0)
+            .map(|(weight, lit)| match objective.direction {
+                ObjectiveDirection::Maximising => (weight, lit),
+                ObjectiveDirection::Minimising => (weight, !lit),
+            })
+            .collect(),
+    )
+}
+
+/// Writes a clause as a line of DIMACS, after `prefix`.
+fn write_clause(
+    writer: &mut dyn Write,
+    prefix: &str,
+    lits: impl IntoIterator<Item = Lit>,
+) -> Result<(), io::Error> {
+    write!(writer, "{prefix}")?;
+    for lit in lits {
+        write!(writer, "{} ", lit.to_ipasir())?;
+    }
+    writeln!(writer, "0")
+}
+
+/// Writes `cnf` in the DIMACS format, with comments giving the names of the variables in
+/// `var_map`.
+///
+/// If `objective` can be written as soft clauses, the WCNF format is used instead.
+pub fn write_dimacs(
+    writer: &mut dyn Write,
+    cnf: &Cnf,
+    n_vars: u32,
+    var_map: &HashMap<Name, Lit>,
+    objective: Option<&Objective>,
+) -> Result<(), io::Error> {
+    let n_vars = var_map
+        .values()
+        .map(|lit| lit.var().idx32() + 1)
+        .fold(n_vars, u32::max);
+
+    for (name, lit) in var_map.iter().sorted_by_key(|(_, lit)| lit.var()) {
+        let index = lit.var().idx32() + 1;
+        match describe(name) {
+            Some(description) => writeln!(writer, "c {index} {name} ({description})")?,
+            None => writeln!(writer, "c {index} {name}")?,
+        }
+    }
+
+    let soft = objective.and_then(|objective| Some((objective, soft_clauses(objective, var_map)?)));
+    match soft {
+        Some((objective, soft)) => {
+            writeln!(writer, "c {objective}")?;
+            for clause in cnf.iter() {
+                write_clause(writer, "h ", clause.iter().copied())?;
+            }
+            for (weight, lit) in soft {
+                write_clause(writer, &format!("{weight} "), [lit])?;
+            }
+        }
+        None => {
+            if let Some(objective) = objective {
+                writeln!(
+                    writer,
+                    "c {objective} cannot be written as soft clauses, so it is left out"
+                )?;
+            }
+            writeln!(writer, "p cnf {n_vars} {}", cnf.len())?;
+            for clause in cnf.iter() {
+                write_clause(writer, "", clause.iter().copied())?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+impl DimacsVarMap {
+    /// Creates the map for the DIMACS file of `model`, whose variables were given SAT variables by
+    /// `var_map`.
+    pub fn new(model: &Model, var_map: &HashMap<Name, Lit>) -> DimacsVarMap {
+        let variables = var_map
+            .iter()
+            .sorted_by_key(|(_, lit)| lit.var())
+            .map(|(name, lit)| {
+                let encoding = sat_int_encoding(name);
+                DimacsVar {
+                    index: lit.var().idx32() + 1,
+                    name: name.clone(),
+                    encoding: encoding.as_ref().map(|(_, encoding, _)| encoding.clone()),
+                    position: encoding.map(|(_, _, position)| position),
+                }
+            })
+            .collect();
+
+        let symbols = model.symbols();
+        let represented = symbols
+            .clone()
+            .into_iter_local()
+            .filter_map(|(name, decl)| {
+                let domain = decl.as_find()?.domain.resolve().ok()?;
+                let reprs = symbols.representations_for(&name)?;
+                let [repr] = reprs.as_slice() else {
+                    return None;
+                };
+                let [repr] = repr.as_slice() else {
+                    return None;
+                };
+                Some(RepresentedVar {
+                    name,
+                    domain: domain.as_ref().clone(),
+                    representation: repr.repr_name().to_owned(),
+                })
+            })
+            .collect();
+
+        DimacsVarMap {
+            variables,
+            represented,
+        }
+    }
+
+    /// Gets the values of the decision variables of the model from a solution of its DIMACS file,
+    /// given as the literals that are true.
+    ///
+    /// As in the solutions of the SAT adaptor, booleans are given as the integers 0 and 1.
+    /// Variables that are encoded by other variables are given the value their representation
+    /// gives them, and the encoding variables themselves are left out.
+    pub fn decode(&self, solution: &[Lit]) -> Result<BTreeMap<Name, Literal>, SolverError> {
+        let assignment: HashMap<u32, bool> = solution
+            .iter()
+            .map(|lit| (lit.var().idx32() + 1, lit.is_pos()))
+            .collect();
+
+        let mut values = self
+            .variables
+            .iter()
+            .map(|var| {
+                let value = assignment.get(&var.index).ok_or_else(|| {
+                    SolverError::Runtime(format!(
+                        "solution has no value for SAT variable {} ({})",
+                        var.index, var.name
+                    ))
+                })?;
+                Ok((var.name.clone(), Literal::Int(*value as i32)))
+            })
+            .collect::<Result<BTreeMap<_, _>, SolverError>>()?;
+
+        // rebuild the representations of the model, declaring each variable unless an earlier
+        // representation already has
+        let mut symbols = SymbolTable::new();
+        let mut representations = vec![];
+        for var in &self.represented {
+            if symbols.lookup(&var.name).is_none() {
+                symbols.insert(DeclarationPtr::new_find(
+                    var.name.clone(),
+                    var.domain.clone().into(),
+                ));
+            }
+            let repr = symbols
+                .get_or_add_representation(&var.name, &[var.representation.as_str()])
+                .and_then(|reprs| reprs.into_iter().next())
+                .ok_or_else(|| {
+                    SolverError::Runtime(format!(
+                        "could not apply representation {} to {}",
+                        var.representation, var.name
+                    ))
+                })?;
+            representations.push((var.name.clone(), repr));
+        }
+
+        // representation variables can be represented themselves, so work backwards
+        for (name, representation) in representations.into_iter().rev() {
+            let value = representation.value_up(&values).map_err(|e| {
+                SolverError::Runtime(format!(
+                    "failed to reconstruct value for variable {name} from SAT solution: {e}"
+                ))
+            })?;
+            values.insert(name, value);
+        }
+
+        Ok(values
+            .into_iter()
+            .filter(|(name, _)| !matches!(name, Name::Represented(_) | Name::Machine(_)))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::{Atom, Expression, Metadata, Reference};
+    use rustsat::clause;
+    use rustsat::types::Var;
+    use ustr::Ustr;
+
+    fn bit(src: &str, encoding: &str, suffix: &str) -> Name {
+        Name::Represented(Box::new((
+            Name::user(src),
+            Ustr::from(encoding),
+            Ustr::from(suffix),
+        )))
+    }
+
+    fn write_to_string(
+        cnf: &Cnf,
+        var_map: &HashMap<Name, Lit>,
+        objective: Option<&Objective>,
+    ) -> String {
+        let mut out = vec![];
+        write_dimacs(&mut out, cnf, 0, var_map, objective).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    fn minimising(name: &str) -> Objective {
+        let decl = DeclarationPtr::new_find(Name::user(name), GroundDomain::Bool.into());
+        Objective::new(
+            ObjectiveDirection::Minimising,
+            Expression::Atomic(Metadata::new(), Atom::Reference(Reference::new(decl))),
+        )
+    }
+
+    #[test]
+    fn writes_variable_names_as_comments() {
+        let var_map = HashMap::from([
+            (Name::user("b"), Var::new(0).pos_lit()),
+            (bit("x", "sat_log_int", "00"), Var::new(1).pos_lit()),
+            (bit("y", "sat_order_int", "-1"), Var::new(2).pos_lit()),
+            (Name::Machine(0), Var::new(3).pos_lit()),
+        ]);
+        let mut cnf = Cnf::new();
+        cnf.add_clause(clause![Var::new(0).pos_lit(), Var::new(1).neg_lit()]);
+        cnf.add_clause(clause![Var::new(3).pos_lit()]);
+
+        assert_eq!(
+            write_to_string(&cnf, &var_map, None),
+            "c 1 b\n\
+             c 2 x#sat_log_int_00 (log encoding of x, bit 0)\n\
+             c 3 y#sat_order_int_-1 (order encoding of y, y >= -1)\n\
+             c 4 __0\n\
+             p cnf 4 2\n\
+             1 -2 0\n\
+             4 0\n"
+        );
+    }
+
+    #[test]
+    fn writes_objective_as_soft_clauses() {
+        // x: int(-2..1) in log encoding
+        let var_map = HashMap::from([
+            (bit("x", "sat_log_int", "00"), Var::new(0).pos_lit()),
+            (bit("x", "sat_log_int", "01"), Var::new(1).pos_lit()),
+        ]);
+        let mut cnf = Cnf::new();
+        cnf.add_clause(clause![Var::new(0).pos_lit(), Var::new(1).pos_lit()]);
+
+        let dimacs = write_to_string(&cnf, &var_map, Some(&minimising("x")));
+        assert!(dimacs.ends_with("c minimising x\nh 1 2 0\n1 -1 0\n2 2 0\n"));
+    }
+
+    #[test]
+    fn decodes_solutions() {
+        let var_map = HashMap::from([
+            (Name::user("a"), Var::new(0).pos_lit()),
+            (Name::user("b"), Var::new(1).pos_lit()),
+            (Name::Machine(0), Var::new(2).pos_lit()),
+        ]);
+        let map = DimacsVarMap {
+            variables: DimacsVarMap::new(&Model::default(), &var_map).variables,
+            represented: vec![],
+        };
+
+        let json = serde_json::to_string(&map).unwrap();
+        let map: DimacsVarMap = serde_json::from_str(&json).unwrap();
+
+        let solution = map
+            .decode(&[
+                Var::new(0).neg_lit(),
+                Var::new(1).pos_lit(),
+                Var::new(2).pos_lit(),
+            ])
+            .unwrap();
+        assert_eq!(
+            solution,
+            BTreeMap::from([
+                (Name::user("a"), Literal::Int(0)),
+                (Name::user("b"), Literal::Int(1)),
+            ])
+        );
+
+        assert!(map.decode(&[Var::new(0).pos_lit()]).is_err());
+    }
+}
@@ -0,0 +1,192 @@
+//! Running an external SAT solver on the DIMACS encoding of a model.
+//!
+//! Any solver that takes the path of a DIMACS file as its last argument and prints its result in
+//! the format of the SAT competitions can be used:
+//!
+//! ```text
+//! s SATISFIABLE
+//! v 1 -2 3 0
+//! ```
+
+use std::collections::HashMap;
+use std::process::Command;
+
+use rustsat::instances::Cnf;
+use rustsat::types::{Assignment, Lit};
+
+use crate::ast::Name;
+use crate::solver::SolverError;
+use crate::solver::adaptors::rustsat::dimacs::write_dimacs;
+
+/// A SAT solver binary, ran on a DIMACS file for each call to the solver.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExternalSatSolver {
+    program: String,
+    args: Vec<String>,
+}
+
+impl ExternalSatSolver {
+    /// Creates a solver that runs `program` with `args`, followed by the path of the DIMACS file.
+    ///
+    /// `program` is looked up on the `PATH` if it is not a path.
+    pub fn new(program: impl Into<String>, args: Vec<String>) -> Self {
+        ExternalSatSolver {
+            program: program.into(),
+            args,
+        }
+    }
+
+    /// Creates a solver from a command line, such as `kissat -q`, split on whitespace.
+    ///
+    /// Returns `None` if the command line is empty.
+    pub fn from_command_line(command_line: &str) -> Option<Self> {
+        let mut words = command_line.split_whitespace().map(str::to_owned);
+        let program = words.next()?;
+        Some(ExternalSatSolver::new(program, words.collect()))
+    }
+
+    pub fn program(&self) -> &str {
+        &self.program
+    }
+
+    /// Solves `cnf`, returning a satisfying assignment, or `None` if it is unsatisfiable.
+    ///
+    /// The variables in `var_map` are named in the DIMACS file, to make it easier to debug.
+    pub(super) fn solve(
+        &self,
+        cnf: &Cnf,
+        n_vars: u32,
+        var_map: &HashMap<Name, Lit>,
+    ) -> Result<Option<Assignment>, SolverError> {
+        let io_error = |e: std::io::Error| {
+            SolverError::Runtime(format!(
+                "failed to run external SAT solver {}: {e}",
+                self.program
+            ))
+        };
+
+        let dir = tempfile::tempdir().map_err(io_error)?;
+        let path = dir.path().join("model.cnf");
+        let mut file = std::fs::File::create(&path).map_err(io_error)?;
+        write_dimacs(&mut file, cnf, n_vars, var_map, None).map_err(io_error)?;
+        drop(file);
+
+        let output = Command::new(&self.program)
+            .args(&self.args)
+            .arg(&path)
+            .output()
+            .map_err(io_error)?;
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        parse_solver_output(&stdout).map_err(|e| {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            SolverError::Runtime(format!(
+                "external SAT solver {} ({}): {e}\n{stderr}",
+                self.program, output.status
+            ))
+        })
+    }
+}
+
+/// Reads the result of a SAT solver from its output, in the format of the SAT competitions.
+///
+/// Returns the satisfying assignment given in the `v` lines, or `None` if the solver found the
+/// problem unsatisfiable.
+fn parse_solver_output(output: &str) -> Result<Option<Assignment>, String> {
+    let mut status = None;
+    let mut lits = vec![];
+
+    for line in output.lines() {
+        let mut words = line.split_whitespace();
+        match words.next() {
+            Some("s") => status = Some(words.collect::<Vec<_>>().join(" ")),
+            Some("v") => {
+                for word in words {
+                    let value: i32 = word
+                        .parse()
+                        .map_err(|_| format!("unexpected value '{word}' in solution"))?;
+                    if value == 0 {
+                        break;
+                    }
+                    lits.push(
+                        Lit::from_ipasir(value)
+                            .map_err(|e| format!("unexpected value '{word}' in solution: {e}"))?,
+                    );
+                }
+            }
+            _ => {}
+        }
+    }
+
+    match status.as_deref() {
+        Some("SATISFIABLE") => Ok(Some(lits.into_iter().collect())),
+        Some("UNSATISFIABLE") => Ok(None),
+        Some(other) => Err(format!("solver gave up with status '{other}'")),
+        None => Err("no status line ('s ...') in solver output".to_owned()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustsat::types::{TernaryVal, Var};
+
+    #[test]
+    fn parses_satisfiable_output() {
+        let output = "c some comment\ns SATISFIABLE\nv 1 -2\nv 3 0\n";
+        let assignment = parse_solver_output(output).unwrap().unwrap();
+
+        assert_eq!(assignment[Var::new(0)], TernaryVal::True);
+        assert_eq!(assignment[Var::new(1)], TernaryVal::False);
+        assert_eq!(assignment[Var::new(2)], TernaryVal::True);
+    }
+
+    #[test]
+    fn parses_unsatisfiable_output() {
+        assert_eq!(parse_solver_output("s UNSATISFIABLE\n").unwrap(), None);
+    }
+
+    #[test]
+    fn rejects_unknown_and_missing_status() {
+        assert!(parse_solver_output("s UNKNOWN\n").is_err());
+        assert!(parse_solver_output("c crashed\n").is_err());
+        assert!(parse_solver_output("s SATISFIABLE\nv 1 x 0\n").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn runs_solver_on_dimacs_file() {
+        use rustsat::clause;
+        use std::os::unix::fs::PermissionsExt;
+
+        // a mock solver, which gives the first clause of the file as the solution
+        let dir = tempfile::tempdir().unwrap();
+        let script = dir.path().join("mock-sat");
+        std::fs::write(
+            &script,
+            "#!/bin/sh\necho 's SATISFIABLE'\necho \"v $(grep -v '^[cp]' \"$1\" | head -n 1)\"\n",
+        )
+        .unwrap();
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let mut cnf = Cnf::new();
+        cnf.add_clause(clause![Var::new(0).pos_lit(), Var::new(1).neg_lit()]);
+        let solver = ExternalSatSolver::new(script.to_string_lossy(), vec![]);
+        let assignment = solver.solve(&cnf, 2, &HashMap::new()).unwrap().unwrap();
+
+        assert_eq!(assignment[Var::new(0)], TernaryVal::True);
+        assert_eq!(assignment[Var::new(1)], TernaryVal::False);
+    }
+
+    #[test]
+    fn splits_command_lines() {
+        assert_eq!(
+            ExternalSatSolver::from_command_line("kissat -q --sat"),
+            Some(ExternalSatSolver::new(
+                "kissat",
+                vec!["-q".to_owned(), "--sat".to_owned()]
+            ))
+        );
+        assert_eq!(ExternalSatSolver::from_command_line("  "), None);
+    }
+}
@@ -1,4 +1,10 @@
 mod adaptor;
 mod convs;
+mod dimacs;
+mod external;
+mod model_modifier;
 
 pub use adaptor::Sat;
+pub use dimacs::{DimacsVar, DimacsVarMap, RepresentedVar};
+pub use external::ExternalSatSolver;
+pub use model_modifier::SatModelModifier;
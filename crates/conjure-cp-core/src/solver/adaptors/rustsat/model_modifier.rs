@@ -0,0 +1,142 @@
+//! Incremental solving for SAT.
+//!
+//! CaDiCaL allows clauses to be added between calls to `solve`, and supports solving under
+//! assumptions. These are exposed through [`SatModelModifier`], which is passed to the user
+//! callback by [`Solver::solve_mut`](crate::solver::Solver::solve_mut).
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use anyhow::anyhow;
+use rustsat::instances::BasicVarManager;
+use rustsat::types::Lit;
+use rustsat_cadical::CaDiCaL;
+
+use super::adaptor::Sat;
+
+use crate::Model as ConjureModel;
+use crate::ast::{DeclarationPtr, Domain, Expression, GroundDomain, Moo, Name};
+use crate::solver::model_modifier::{ModelModifier, ModificationFailure};
+use crate::solver::private;
+
+/// The SAT solver and variable mapping of a running search.
+///
+/// This is shared between the solve loop and every [`SatModelModifier`] it hands out.
+pub(super) struct SatSearchState {
+    pub(super) solver: CaDiCaL<'static, 'static>,
+    pub(super) var_map: HashMap<Name, Lit>,
+
+    /// Allocates the SAT variables added during search.
+    pub(super) var_manager: BasicVarManager,
+
+    /// The loaded model, used to encode added constraints.
+    ///
+    /// Variables added during search are declared in its symbol table.
+    pub(super) model_template: ConjureModel,
+
+    /// Literals to assume during the next call to the SAT solver.
+    pub(super) assumptions: Vec<Lit>,
+}
+
+/// A [`ModelModifier`] for the SAT adaptor.
+///
+/// Constraints are given as high level expressions, and are encoded into clauses using the
+/// model's rule sets (and therefore its integer encoding) before being added to the solver.
+///
+/// Only boolean variables can be added during search. These do not appear in solutions.
+///
+/// Assumed constraints are guarded by a fresh selector literal, which is assumed during the next
+/// call to the SAT solver only. A single call may produce several solutions when some variables
+/// are unconstrained; an assumed constraint does not apply to the remaining solutions from the
+/// current call.
+pub struct SatModelModifier {
+    state: Rc<RefCell<SatSearchState>>,
+}
+
+impl SatModelModifier {
+    pub(super) fn new(state: Rc<RefCell<SatSearchState>>) -> Self {
+        SatModelModifier { state }
+    }
+}
+
+impl private::Sealed for SatModelModifier {}
+
+impl ModelModifier for SatModelModifier {
+    fn add_constraint(&self, constraint: Expression) -> Result<(), ModificationFailure> {
+        let mut state = self.state.borrow_mut();
+        let state = &mut *state;
+
+        Sat::add_constraint_as_clauses(
+            constraint,
+            &state.model_template,
+            &mut state.solver,
+            &mut state.var_map,
+            &mut state.var_manager,
+            None,
+            "added",
+        )
+        .map_err(|e| ModificationFailure::Error(e.into()))
+    }
+
+    fn add_assumed_constraint(&self, constraint: Expression) -> Result<(), ModificationFailure> {
+        let mut state = self.state.borrow_mut();
+        let state = &mut *state;
+
+        let selector_name = state
+            .model_template
+            .symbols_mut()
+            .gen_find(&Domain::bool())
+            .name()
+            .clone();
+        let selector = Sat::add_fresh_sat_var(
+            selector_name,
+            &mut state.solver,
+            &mut state.var_map,
+            &mut state.var_manager,
+        )
+        .map_err(|e| ModificationFailure::Error(e.into()))?;
+
+        Sat::add_constraint_as_clauses(
+            constraint,
+            &state.model_template,
+            &mut state.solver,
+            &mut state.var_map,
+            &mut state.var_manager,
+            Some(selector),
+            "assumed",
+        )
+        .map_err(|e| ModificationFailure::Error(e.into()))?;
+
+        state.assumptions.push(selector);
+        Ok(())
+    }
+
+    fn add_variable(&self, name: Name, domain: Domain) -> Result<(), ModificationFailure> {
+        let mut state = self.state.borrow_mut();
+        let state = &mut *state;
+
+        if state.model_template.symbols().lookup(&name).is_some() {
+            return Err(ModificationFailure::ArgsInvalid(anyhow!(
+                "variable {name} is already defined"
+            )));
+        }
+
+        if domain.as_ground() != Some(&GroundDomain::Bool) {
+            return Err(ModificationFailure::ArgsInvalid(anyhow!(
+                "only boolean variables can be added during SAT search, but {name} has domain {domain}"
+            )));
+        }
+
+        let declaration = DeclarationPtr::new_find(name.clone(), Moo::new(domain));
+        Sat::add_fresh_sat_var(
+            name,
+            &mut state.solver,
+            &mut state.var_map,
+            &mut state.var_manager,
+        )
+        .map_err(|e| ModificationFailure::Error(e.into()))?;
+        state.model_template.symbols_mut().insert(declaration);
+        Ok(())
+    }
+}
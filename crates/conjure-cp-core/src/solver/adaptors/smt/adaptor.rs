@@ -1,18 +1,24 @@
+use std::cell::RefCell;
 use std::collections::{BTreeMap, HashMap};
 use std::iter::FusedIterator;
+use std::rc::Rc;
 
 use itertools::Itertools;
 use uniplate::Uniplate;
 use versions::Versioning;
 use z3::{
-    Config, PrepareSynchronized, SatResult, Solvable, Solver, Statistics, Translate, with_z3_config,
+    Config, Optimize, PrepareSynchronized, SatResult, Solvable, Solver, Statistics, Translate,
+    with_z3_config,
 };
 
 use super::convert_model::*;
+use super::model_modifier::{SmtModelModifier, SmtSearchState};
 use super::store::*;
 use super::theories::*;
 
-use crate::ast::{Atom, Expression, GroundDomain, Literal, Metadata, Moo, Name};
+use crate::ast::{
+    Atom, Expression, GroundDomain, Literal, Metadata, Moo, Name, Objective, ObjectiveDirection,
+};
 use crate::rule_engine::rewrite_model_with_configured_rewriter;
 use crate::settings::{Rewriter, current_rewriter, set_current_rewriter};
 use crate::{Model, solver::*};
@@ -35,7 +41,11 @@ pub struct Smt {
     theory_config: TheoryConfig,
 
     dominance_expression: Option<Expression>,
-    dominance_model_template: Option<Model>,
+
+    /// The loaded model, used to rewrite constraints added after loading.
+    model_template: Option<Model>,
+
+    objective: Option<Objective>,
 }
 
 impl private::Sealed for Smt {}
@@ -49,7 +59,8 @@ impl Default for Smt {
             solver_cfg: Config::new(),
             theory_config: TheoryConfig::default(),
             dominance_expression: None,
-            dominance_model_template: None,
+            model_template: None,
+            objective: None,
         }
     }
 }
@@ -214,7 +225,7 @@ fn ensure_supported_z3_runtime() -> Result<(), SolverError> {
 }
 
 impl Smt {
-    fn add_dominance_constraints_for_solution(
+    pub(super) fn add_dominance_constraints_for_solution(
         dominance_expression: Option<&Expression>,
         dominance_model_template: Option<&Model>,
         solver: &mut Solver,
@@ -241,20 +252,44 @@ impl Smt {
             ),
         );
 
-        let mut dominance_model = model_template.clone();
-        dominance_model.replace_constraints(vec![]);
-        dominance_model.replace_clauses(vec![]);
-        dominance_model.dominance = None;
-        dominance_model.add_constraint(rewritten_dominance);
+        Smt::add_rewritten_constraint(
+            rewritten_dominance,
+            model_template,
+            solver,
+            store,
+            theory_config,
+            "dominance constraint",
+        )
+    }
 
-        let rule_sets = dominance_model.context.read().unwrap().rule_sets.clone();
-        let rewritten =
-            rewrite_model_with_configured_rewriter(dominance_model, &rule_sets, current_rewriter())
-                .map_err(|e| {
-                    SolverError::Runtime(format!(
-                        "Failed to rewrite dominance constraint for SMT solving: {e}"
-                    ))
-                })?;
+    /// Rewrites a high-level constraint using the rule sets of `model_template`, and asserts the
+    /// result in `solver`.
+    ///
+    /// `purpose` describes the constraint in error messages.
+    pub(super) fn add_rewritten_constraint(
+        constraint: Expression,
+        model_template: &Model,
+        solver: &mut Solver,
+        store: &mut SymbolStore,
+        theory_config: TheoryConfig,
+        purpose: &str,
+    ) -> Result<(), SolverError> {
+        let mut constraint_model = model_template.clone();
+        constraint_model.replace_constraints(vec![]);
+        constraint_model.replace_clauses(vec![]);
+        constraint_model.dominance = None;
+        constraint_model.objective = None;
+        constraint_model.add_constraint(constraint);
+
+        let rule_sets = constraint_model.context.read().unwrap().rule_sets.clone();
+        let rewritten = rewrite_model_with_configured_rewriter(
+            constraint_model,
+            &rule_sets,
+            current_rewriter(),
+        )
+        .map_err(|e| {
+            SolverError::Runtime(format!("Failed to rewrite {purpose} for SMT solving: {e}"))
+        })?;
 
         load_model_impl(
             store,
@@ -266,16 +301,105 @@ impl Smt {
     }
 }
 
+impl Smt {
+    /// Finds a single optimal solution for the given objective using Z3's optimisation engine.
+    fn solve_optimising(
+        &mut self,
+        callback: SolverCallback,
+        objective: &Objective,
+    ) -> Result<SolveSuccess, SolverError> {
+        let name = objective.variable_name().ok_or_else(|| {
+            SolverError::ModelFeatureNotSupported(format!(
+                "objective should be a single decision variable, but got `{}`",
+                objective.expression
+            ))
+        })?;
+        let direction = objective.direction;
+        let solver_send = self.solver_inst.synchronized();
+        let store_send = self.store.synchronized();
+        let mut stats: SolverStats = Default::default();
+
+        let (solution, z3_time) =
+            with_z3_config(&self.solver_cfg, move || -> Result<_, SolverError> {
+                let solver = solver_send.recover();
+                let store = store_send.recover();
+                let (_, objective_ast, _) = store.get(&name).ok_or_else(|| {
+                    SolverError::Runtime(format!(
+                        "objective variable {name} does not exist in the SMT model"
+                    ))
+                })?;
+
+                let optimize = Optimize::new();
+                for assertion in solver.get_assertions() {
+                    optimize.assert(&assertion);
+                }
+                match direction {
+                    ObjectiveDirection::Minimising => optimize.minimize(objective_ast),
+                    ObjectiveDirection::Maximising => optimize.maximize(objective_ast),
+                }
+
+                let solution = match optimize.check(&[]) {
+                    SatResult::Sat => {
+                        let model = optimize.get_model().ok_or_else(|| {
+                            SolverError::Runtime("Z3 reported sat but gave no model".into())
+                        })?;
+                        let instance = store.read_from_model(&model, true).ok_or_else(|| {
+                            SolverError::Runtime("could not read solution from Z3 model".into())
+                        })?;
+                        Some(instance.as_literals_map()?)
+                    }
+                    SatResult::Unsat => None,
+                    SatResult::Unknown => {
+                        return Err(SolverError::Runtime(format!(
+                            "Z3 could not find an optimal solution: {}",
+                            optimize.get_reason_unknown().unwrap_or_default()
+                        )));
+                    }
+                };
+
+                let z3_time = match optimize.get_statistics().value("time") {
+                    Some(z3::StatisticsValue::Double(time)) => Some(time),
+                    _ => None,
+                };
+                Ok((solution, z3_time))
+            })?;
+
+        if let Some(time) = z3_time {
+            stats.solver_time_s = time;
+        }
+
+        let search_complete = match solution {
+            Some(solution) => {
+                callback(solution);
+                SearchComplete::HasSolutions
+            }
+            None => SearchComplete::NoSolutions,
+        };
+
+        Ok(SolveSuccess {
+            stats,
+            status: SearchStatus::Complete(search_complete),
+        })
+    }
+}
+
 impl SolverAdaptor for Smt {
     fn solve(
         &mut self,
         callback: SolverCallback,
         _: private::Internal,
     ) -> Result<SolveSuccess, SolverError> {
+        if let Some(objective) = self.objective.clone() {
+            return self.solve_optimising(callback, &objective);
+        }
+
         let solver_send = self.solver_inst.synchronized();
         let store_send = self.store.synchronized();
         let dominance_expression = self.dominance_expression.clone();
-        let dominance_model_template = self.dominance_model_template.clone();
+        let dominance_model_template = self
+            .dominance_expression
+            .as_ref()
+            .and_then(|_| self.model_template.clone());
         let theory_config = self.theory_config;
         let mut stats: SolverStats = Default::default();
 
@@ -358,12 +482,104 @@ impl SolverAdaptor for Smt {
         })
     }
 
+    /// Finds solutions one at a time, passing an [`SmtModelModifier`] to the callback with each.
+    ///
+    /// Modifications are asserted in the running Z3 solver, and apply to all later solutions.
+    /// Scopes pushed by the callback map to Z3 scopes, so popping one retracts the modifications
+    /// made inside it.
     fn solve_mut(
         &mut self,
         callback: SolverMutCallback,
         _: private::Internal,
     ) -> Result<SolveSuccess, SolverError> {
-        Err(SolverError::OpNotImplemented("solve_mut".into()))
+        if self.objective.is_some() {
+            return Err(SolverError::OpNotSupported(
+                "solve_mut for models with an objective".into(),
+            ));
+        }
+
+        let solver_send = self.solver_inst.synchronized();
+        let store_send = self.store.synchronized();
+        let dominance_expression = self.dominance_expression.clone();
+        let model_template = self.model_template.clone().expect("STATE MACHINE ERR");
+        let theory_config = self.theory_config;
+        let mut stats: SolverStats = Default::default();
+
+        let (search_status, final_z3_time) =
+            with_z3_config(&self.solver_cfg, move || -> Result<_, SolverError> {
+                let state = Rc::new(RefCell::new(SmtSearchState::new(
+                    solver_send.recover(),
+                    store_send.recover(),
+                    theory_config,
+                    model_template,
+                    dominance_expression,
+                )));
+                let mut final_z3_time: Option<f64> = None;
+                let mut found_solution = false;
+
+                loop {
+                    let mut guard = state.borrow_mut();
+                    let current = &mut *guard;
+                    if current.solver.check() != SatResult::Sat {
+                        break;
+                    }
+                    found_solution = true;
+
+                    if let Some(z3::StatisticsValue::Double(time)) =
+                        current.solver.get_statistics().value("time")
+                    {
+                        final_z3_time = Some(time);
+                    }
+
+                    let model = current.solver.get_model().ok_or_else(|| {
+                        SolverError::Runtime("Z3 reported sat but gave no model".into())
+                    })?;
+                    let instance =
+                        current.store.read_from_model(&model, true).ok_or_else(|| {
+                            SolverError::Runtime("could not read solution from Z3 model".into())
+                        })?;
+
+                    // Computed before the callback, so variables it adds are not blocked on.
+                    let counterexample = current.blocking_store().generate_constraint(&instance);
+                    let solution = instance.as_literals_map()?;
+
+                    if current.dominance_expression.is_some() {
+                        let mut dominance_solution = solution.clone();
+                        add_represented_decision_values(
+                            &mut dominance_solution,
+                            &current.model_template,
+                        );
+                        current.add_dominance_constraints(dominance_solution)?;
+                    }
+                    drop(guard);
+
+                    let modifier = Box::new(SmtModelModifier::new(Rc::clone(&state)));
+                    if !callback(solution, modifier) {
+                        return Ok((
+                            SearchStatus::Incomplete(SearchIncomplete::UserTerminated),
+                            final_z3_time,
+                        ));
+                    }
+
+                    state.borrow_mut().block(counterexample);
+                }
+
+                let search_complete = if found_solution {
+                    SearchComplete::HasSolutions
+                } else {
+                    SearchComplete::NoSolutions
+                };
+                Ok((SearchStatus::Complete(search_complete), final_z3_time))
+            })?;
+
+        if let Some(time) = final_z3_time {
+            stats.solver_time_s = time;
+        }
+
+        Ok(SolveSuccess {
+            stats,
+            status: search_status,
+        })
     }
 
     fn load_model(&mut self, model: Model, _: private::Internal) -> Result<(), SolverError> {
@@ -373,7 +589,8 @@ impl SolverAdaptor for Smt {
             Expression::DominanceRelation(_, inner) => inner.as_ref().clone(),
             _ => expr.clone(),
         });
-        self.dominance_model_template = self.dominance_expression.as_ref().map(|_| model.clone());
+        self.model_template = Some(model.clone());
+        self.objective.clone_from(&model.objective);
         load_model_impl(
             &mut self.store,
             &mut self.solver_inst,
@@ -489,6 +706,7 @@ mod tests {
     use super::*;
     use crate::ast::{DeclarationPtr, Domain, Moo, Reference};
     use crate::context::Context;
+    use crate::solver::model_modifier::ModificationFailure;
 
     #[test]
     fn from_solution_substitution_replaces_reference_with_literal() {
@@ -642,7 +860,7 @@ mod tests {
 
         Smt::add_dominance_constraints_for_solution(
             smt.dominance_expression.as_ref(),
-            smt.dominance_model_template.as_ref(),
+            smt.model_template.as_ref(),
             &mut smt.solver_inst,
             &mut smt.store,
             theory_config,
@@ -689,4 +907,179 @@ mod tests {
         assert_eq!(smt.solver_inst.check(), SatResult::Sat);
         smt.solver_inst.pop(1);
     }
+
+    #[test]
+    fn constraints_added_by_model_modifier_restrict_later_solutions() {
+        let theory_config = TheoryConfig::default();
+        let context = Context::new_ptr_empty(SolverFamily::Smt(theory_config));
+        set_current_rewriter(Rewriter::Naive);
+
+        let x = Name::User("x".into());
+        let y = Name::User("y".into());
+        let x_decl = DeclarationPtr::new_find(x.clone(), Domain::bool());
+        let y_decl = DeclarationPtr::new_find(y, Domain::bool());
+        let x_ref = Expression::Atomic(
+            Metadata::new(),
+            Atom::Reference(Reference::new(x_decl.clone())),
+        );
+
+        let mut model = Model::new(context);
+        model.add_symbol(x_decl);
+        model.add_symbol(y_decl);
+
+        let mut smt = Smt::new(None, theory_config);
+        smt.load_model(model, private::Internal)
+            .expect("SMT model should load");
+
+        let solutions = std::sync::Arc::new(std::sync::Mutex::new(vec![]));
+        let solutions_in_callback = std::sync::Arc::clone(&solutions);
+        let result = smt
+            .solve_mut(
+                Box::new(move |solution, modifier| {
+                    let mut solutions = solutions_in_callback.lock().unwrap();
+                    if solutions.is_empty() {
+                        assert!(modifier.add_constraint(x_ref.clone()).is_ok());
+                    }
+                    solutions.push(solution);
+                    true
+                }),
+                private::Internal,
+            )
+            .expect("solve_mut should succeed");
+
+        assert!(matches!(
+            result.status,
+            SearchStatus::Complete(SearchComplete::HasSolutions)
+        ));
+
+        let solutions = solutions.lock().unwrap();
+        let expected_count = if solutions[0][&x] == Literal::Bool(true) {
+            2
+        } else {
+            3
+        };
+        assert_eq!(solutions.len(), expected_count);
+        assert!(
+            solutions[1..]
+                .iter()
+                .all(|solution| solution[&x] == Literal::Bool(true))
+        );
+    }
+
+    // loads a model with the boolean decision variables x and y, returning the declaration of x
+    fn load_x_and_y(smt: &mut Smt) -> DeclarationPtr {
+        let theory_config = TheoryConfig::default();
+        let context = Context::new_ptr_empty(SolverFamily::Smt(theory_config));
+        set_current_rewriter(Rewriter::Naive);
+
+        let x_decl = DeclarationPtr::new_find(Name::User("x".into()), Domain::bool());
+        let y_decl = DeclarationPtr::new_find(Name::User("y".into()), Domain::bool());
+
+        let mut model = Model::new(context);
+        model.add_symbol(x_decl.clone());
+        model.add_symbol(y_decl);
+
+        smt.load_model(model, private::Internal)
+            .expect("SMT model should load");
+        x_decl
+    }
+
+    #[test]
+    fn constraints_added_in_a_scope_are_removed_by_pop() {
+        let mut smt = Smt::new(None, TheoryConfig::default());
+        let x_decl = load_x_and_y(&mut smt);
+        let x = Name::User("x".into());
+        let x_in_callback = x.clone();
+
+        let solutions = std::sync::Arc::new(std::sync::Mutex::new(vec![]));
+        let solutions_in_callback = std::sync::Arc::clone(&solutions);
+        smt.solve_mut(
+            Box::new(move |solution, modifier| {
+                let mut solutions = solutions_in_callback.lock().unwrap();
+                match solutions.len() {
+                    // keep x as it is, until the next solution
+                    0 => {
+                        let x_value = Expression::Atomic(
+                            Metadata::new(),
+                            Atom::Literal(solution[&x_in_callback].clone()),
+                        );
+                        let x_ref = Expression::Atomic(
+                            Metadata::new(),
+                            Atom::Reference(Reference::new(x_decl.clone())),
+                        );
+                        assert!(modifier.push().is_ok());
+                        assert!(
+                            modifier
+                                .add_constraint(Expression::Eq(
+                                    Metadata::new(),
+                                    Moo::new(x_ref),
+                                    Moo::new(x_value),
+                                ))
+                                .is_ok()
+                        );
+                    }
+                    1 => assert!(modifier.pop().is_ok()),
+                    _ => {}
+                }
+                solutions.push(solution);
+                true
+            }),
+            private::Internal,
+        )
+        .expect("solve_mut should succeed");
+
+        // all four solutions are found once the constraint is popped, and no solution is repeated
+        let solutions = solutions.lock().unwrap();
+        assert_eq!(solutions.len(), 4);
+        assert_eq!(solutions[0][&x], solutions[1][&x]);
+        for (i, solution) in solutions.iter().enumerate() {
+            assert!(!solutions[i + 1..].contains(solution));
+        }
+    }
+
+    #[test]
+    fn variables_added_in_a_scope_are_removed_by_pop() {
+        let mut smt = Smt::new(None, TheoryConfig::default());
+        load_x_and_y(&mut smt);
+        let z = Name::User("z".into());
+        let z_in_callback = z.clone();
+
+        let solutions = std::sync::Arc::new(std::sync::Mutex::new(vec![]));
+        let solutions_in_callback = std::sync::Arc::clone(&solutions);
+        smt.solve_mut(
+            Box::new(move |solution, modifier| {
+                let mut solutions = solutions_in_callback.lock().unwrap();
+                match solutions.len() {
+                    0 => {
+                        assert!(matches!(
+                            modifier.pop(),
+                            Err(ModificationFailure::ArgsInvalid(_))
+                        ));
+                        assert!(modifier.push().is_ok());
+                        assert!(
+                            modifier
+                                .add_variable(
+                                    z_in_callback.clone(),
+                                    Moo::unwrap_or_clone(Domain::bool())
+                                )
+                                .is_ok()
+                        );
+                    }
+                    1 => assert!(modifier.pop().is_ok()),
+                    _ => {}
+                }
+                solutions.push(solution);
+                true
+            }),
+            private::Internal,
+        )
+        .expect("solve_mut should succeed");
+
+        let solutions = solutions.lock().unwrap();
+        let has_z = solutions
+            .iter()
+            .map(|solution| solution.contains_key(&z))
+            .collect_vec();
+        assert_eq!(has_z, vec![false, true, false, false]);
+    }
 }
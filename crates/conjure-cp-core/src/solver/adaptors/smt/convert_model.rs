@@ -45,9 +45,7 @@ pub fn load_model_impl(
             /// This variable has representations; ignore it
             continue;
         }
-        let (sym, ast, restriction) = var_to_ast(&name, &var, theory_config)?;
-        store.insert(name, (decl.resolved_domain().unwrap(), ast, sym));
-        solver.assert(restriction);
+        load_var_impl(store, solver, theory_config, name, &var)?;
     }
     for expr in model.iter() {
         let bool: Bool = expr_to_ast(store, expr, theory_config)?;
@@ -56,6 +54,24 @@ pub fn load_model_impl(
     Ok(())
 }
 
+/// Adds a single decision variable to the store, asserting that it stays within its domain.
+pub fn load_var_impl(
+    store: &mut SymbolStore,
+    solver: &mut Solver,
+    theory_config: &TheoryConfig,
+    name: Name,
+    var: &DecisionVariable,
+) -> SolverResult<()> {
+    let (sym, ast, restriction) = var_to_ast(&name, var, theory_config)?;
+    let domain = var
+        .domain_of()
+        .resolve()
+        .unwrap_or_else(|e| bug!("could not resolve domain for {name}: {e}"));
+    store.insert(name, (domain, ast, sym));
+    solver.assert(restriction);
+    Ok(())
+}
+
 /// Returns the AST representation of the variable as well as a boolean assertion which restricts
 /// it to the input variable's domain since most Z3 sorts are unbounded.
 fn var_to_ast(
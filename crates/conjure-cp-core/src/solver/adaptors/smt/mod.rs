@@ -1,8 +1,10 @@
 mod adaptor;
 mod convert_model;
 mod helpers;
+mod model_modifier;
 mod store;
 mod theories;
 
 pub use adaptor::Smt;
+pub use model_modifier::SmtModelModifier;
 pub use theories::*;
@@ -0,0 +1,214 @@
+//! Incremental solving for SMT.
+//!
+//! Z3 allows assertions to be added between calls to `check()`, and to be retracted again using
+//! `push()` and `pop()`. These are exposed through [`SmtModelModifier`], which is passed to the
+//! user callback by [`Solver::solve_mut`](crate::solver::Solver::solve_mut).
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use anyhow::anyhow;
+use z3::Solver;
+use z3::ast::Bool;
+
+use super::adaptor::Smt;
+use super::convert_model::load_var_impl;
+use super::store::SymbolStore;
+use super::theories::TheoryConfig;
+
+use crate::Model;
+use crate::ast::{DeclarationPtr, Domain, Expression, Literal, Moo, Name, SymbolTable};
+use crate::solver::model_modifier::{ModelModifier, ModificationFailure};
+use crate::solver::{SolverError, private};
+
+/// The Z3 solver and symbol store of a running search.
+///
+/// This is shared between the solve loop and every [`SmtModelModifier`] it hands out.
+pub(super) struct SmtSearchState {
+    pub(super) solver: Solver,
+    pub(super) store: SymbolStore,
+    pub(super) theory_config: TheoryConfig,
+
+    /// The loaded model, used to rewrite added constraints.
+    ///
+    /// Variables added during search are declared in its symbol table.
+    pub(super) model_template: Model,
+
+    pub(super) dominance_expression: Option<Expression>,
+
+    /// The scopes pushed by the user, innermost last.
+    scopes: Vec<SmtScope>,
+}
+
+/// A scope pushed by [`SmtModelModifier::push`], with a Z3 scope of its own.
+///
+/// Constraints added by the search itself while a scope is open (blocking previous solutions, and
+/// dominance) must outlive it, so are recorded here and added again when it is popped.
+struct SmtScope {
+    /// The symbols of the model when the scope was pushed.
+    symbols: SymbolTable,
+
+    /// The Z3 constants when the scope was pushed.
+    store: SymbolStore,
+
+    blocking_constraints: Vec<Bool>,
+    dominance_solutions: Vec<HashMap<Name, Literal>>,
+}
+
+impl SmtSearchState {
+    pub(super) fn new(
+        solver: Solver,
+        store: SymbolStore,
+        theory_config: TheoryConfig,
+        model_template: Model,
+        dominance_expression: Option<Expression>,
+    ) -> Self {
+        SmtSearchState {
+            solver,
+            store,
+            theory_config,
+            model_template,
+            dominance_expression,
+            scopes: vec![],
+        }
+    }
+
+    /// The Z3 constants that solutions are blocked on.
+    ///
+    /// Variables added inside a scope are left out, as the blocking constraint outlives them.
+    pub(super) fn blocking_store(&self) -> &SymbolStore {
+        self.scopes
+            .first()
+            .map(|scope| &scope.store)
+            .unwrap_or(&self.store)
+    }
+
+    /// Asserts a constraint that blocks a previous solution, in every scope.
+    pub(super) fn block(&mut self, constraint: Bool) {
+        self.solver.assert(&constraint);
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.blocking_constraints.push(constraint);
+        }
+    }
+
+    /// Asserts the dominance constraints for a previous solution, in every scope.
+    pub(super) fn add_dominance_constraints(
+        &mut self,
+        solution: HashMap<Name, Literal>,
+    ) -> Result<(), SolverError> {
+        Smt::add_dominance_constraints_for_solution(
+            self.dominance_expression.as_ref(),
+            Some(&self.model_template),
+            &mut self.solver,
+            &mut self.store,
+            self.theory_config,
+            &solution,
+        )?;
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.dominance_solutions.push(solution);
+        }
+        Ok(())
+    }
+}
+
+/// A [`ModelModifier`] for the SMT adaptor.
+///
+/// Constraints are given as high level expressions, and are rewritten using the model's rule sets
+/// before being asserted. Modifications apply to all solutions found after the current one, until
+/// the scope they were made in is popped.
+///
+/// Variables added during search appear in later solutions, but are not used to block repeated
+/// solutions.
+pub struct SmtModelModifier {
+    state: Rc<RefCell<SmtSearchState>>,
+}
+
+impl SmtModelModifier {
+    pub(super) fn new(state: Rc<RefCell<SmtSearchState>>) -> Self {
+        SmtModelModifier { state }
+    }
+}
+
+impl private::Sealed for SmtModelModifier {}
+
+impl ModelModifier for SmtModelModifier {
+    fn add_constraint(&self, constraint: Expression) -> Result<(), ModificationFailure> {
+        let mut state = self.state.borrow_mut();
+        let state = &mut *state;
+
+        Smt::add_rewritten_constraint(
+            constraint,
+            &state.model_template,
+            &mut state.solver,
+            &mut state.store,
+            state.theory_config,
+            "added constraint",
+        )
+        .map_err(|e| ModificationFailure::Error(e.into()))
+    }
+
+    fn add_variable(&self, name: Name, domain: Domain) -> Result<(), ModificationFailure> {
+        let mut state = self.state.borrow_mut();
+        let state = &mut *state;
+
+        if state.model_template.symbols().lookup(&name).is_some() {
+            return Err(ModificationFailure::ArgsInvalid(anyhow!(
+                "variable {name} is already defined"
+            )));
+        }
+
+        let declaration = DeclarationPtr::new_find(name.clone(), Moo::new(domain));
+        let var = declaration
+            .as_find()
+            .expect("a newly created find declaration should be a decision variable")
+            .clone();
+        load_var_impl(
+            &mut state.store,
+            &mut state.solver,
+            &state.theory_config,
+            name,
+            &var,
+        )
+        .map_err(|e| ModificationFailure::ArgsInvalid(e.into()))?;
+
+        state.model_template.symbols_mut().insert(declaration);
+        Ok(())
+    }
+
+    fn push(&self) -> Result<(), ModificationFailure> {
+        let mut state = self.state.borrow_mut();
+        let scope = SmtScope {
+            symbols: state.model_template.symbols().clone(),
+            store: state.store.clone(),
+            blocking_constraints: vec![],
+            dominance_solutions: vec![],
+        };
+        state.scopes.push(scope);
+        state.solver.push();
+        Ok(())
+    }
+
+    fn pop(&self) -> Result<(), ModificationFailure> {
+        let mut state = self.state.borrow_mut();
+        let Some(scope) = state.scopes.pop() else {
+            return Err(ModificationFailure::ArgsInvalid(anyhow!(
+                "there is no scope to pop"
+            )));
+        };
+
+        state.solver.pop(1);
+        *state.model_template.symbols_mut() = scope.symbols;
+        state.store = scope.store;
+
+        for constraint in scope.blocking_constraints {
+            state.block(constraint);
+        }
+        for solution in scope.dominance_solutions {
+            state
+                .add_dominance_constraints(solution)
+                .map_err(|e| ModificationFailure::Error(e.into()))?;
+        }
+        Ok(())
+    }
+}
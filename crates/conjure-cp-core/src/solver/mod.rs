@@ -229,8 +229,9 @@ pub trait SolverAdaptor: private::Sealed + Any {
 
     /// Writes a solver input file to the given writer.
     ///
-    /// This method is for debugging use only, and there are no plans to make the solutions
-    /// obtained by running this file through the solver translatable back into high-level Essence.
+    /// This method is mainly for debugging use. Unless the adaptor gives a
+    /// [`solver_input_map`](SolverAdaptor::solver_input_map), solutions obtained by running this
+    /// file through the solver cannot be translated back into high-level Essence.
     ///
     /// This file is runnable using the solvers command line interface. E.g. for Minion, this
     /// outputs a valid .minion file.
@@ -243,6 +244,17 @@ pub trait SolverAdaptor: private::Sealed + Any {
     /// + This function is ran after model loading but before solving - therefore, it is safe for
     ///   solving to mutate the model object.
     fn write_solver_input_file(&self, writer: &mut Box<dyn Write>) -> Result<(), std::io::Error>;
+
+    /// Gets a map from the variables of the solver input file back to the variables of the model,
+    /// for translating solutions of the file found outside of Conjure Oxide.
+    ///
+    /// The format of the map is adaptor specific. By default, no map is given.
+    ///
+    /// Like [`write_solver_input_file`](SolverAdaptor::write_solver_input_file), this is ran after
+    /// model loading but before solving.
+    fn solver_input_map(&self) -> Option<serde_json::Value> {
+        None
+    }
 }
 
 /// An abstract representation of a constraints solver.
@@ -365,8 +377,9 @@ impl Solver<ModelLoaded> {
 
     /// Writes a solver input file to the given writer.
     ///
-    /// This method is for debugging use only, and there are no plans to make the solutions
-    /// obtained by running this file through the solver translatable back into high-level Essence.
+    /// This method is mainly for debugging use. Unless the adaptor gives a
+    /// [`solver_input_map`](Solver::solver_input_map), solutions obtained by running this file
+    /// through the solver cannot be translated back into high-level Essence.
     ///
     /// This file is runnable using the solvers command line interface. E.g. for Minion, this
     /// outputs a valid .minion file.
@@ -379,6 +392,14 @@ impl Solver<ModelLoaded> {
     ) -> Result<(), std::io::Error> {
         self.adaptor.write_solver_input_file(writer)
     }
+
+    /// Gets a map from the variables of the solver input file back to the variables of the model,
+    /// if the adaptor supports it.
+    ///
+    /// For SAT, this is a [`DimacsVarMap`](adaptors::rustsat::DimacsVarMap).
+    pub fn solver_input_map(&self) -> Option<serde_json::Value> {
+        self.adaptor.solver_input_map()
+    }
 }
 
 impl Solver<ExecutionSuccess> {
@@ -27,6 +27,28 @@ pub trait ModelModifier: private::Sealed {
     fn add_variable(&self, name: Name, domain: Domain) -> Result<(), ModificationFailure> {
         Err(ModificationFailure::OpNotSupported)
     }
+
+    /// Adds a constraint that only applies while searching for the next solution.
+    ///
+    /// Solvers that support solving under assumptions can do this without retracting anything
+    /// else that was added to the model.
+    fn add_assumed_constraint(&self, constraint: Expression) -> Result<(), ModificationFailure> {
+        Err(ModificationFailure::OpNotSupported)
+    }
+
+    /// Starts a new scope. Constraints and variables added after this are removed again by the
+    /// matching [`pop`](ModelModifier::pop).
+    ///
+    /// Scopes are kept across solutions, so a scope pushed in one call of the callback can be
+    /// popped in a later one.
+    fn push(&self) -> Result<(), ModificationFailure> {
+        Err(ModificationFailure::OpNotSupported)
+    }
+
+    /// Removes the constraints and variables added since the matching [`push`](ModelModifier::push).
+    fn pop(&self) -> Result<(), ModificationFailure> {
+        Err(ModificationFailure::OpNotSupported)
+    }
 }
 
 /// A [`ModelModifier`] for a solver that does not support incremental solving. Returns
@@ -0,0 +1,299 @@
+/**
+ * Works out what can be written at a position in an Essence file, for completion in the LSP.
+ * This only looks at the CST, so that it still works while the file is being edited and does
+ * not parse.
+ */
+use tree_sitter::{Node, Tree};
+
+use crate::parser::traversal::WalkDFS;
+
+// what kind of completion makes sense at a position
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CompletionContext {
+    // the start of a new top-level statement
+    Statement,
+    // a domain, e.g. after `find x :`
+    Domain,
+    // the attributes of a domain constructor, e.g. `set (|) of int`
+    DomainAttribute(String),
+    // an expression
+    Expression,
+    // nothing useful can be suggested, e.g. a new name in a declaration, or a comment
+    None,
+}
+
+// a domain constructor, offered as a snippet (in LSP snippet syntax) where a domain is expected
+#[derive(Debug, Clone)]
+pub struct DomainSnippet {
+    pub label: &'static str,
+    pub snippet: String,
+}
+
+// the size attributes of SetAttr
+const SIZE_ATTRIBUTES: [&str; 3] = ["size", "minSize", "maxSize"];
+// the attributes of FuncAttr, other than its size
+const PARTIALITY_ATTRIBUTES: [&str; 2] = ["total", "partial"];
+const JECTIVITY_ATTRIBUTES: [&str; 3] = ["injective", "surjective", "bijective"];
+const OCCURRENCE_ATTRIBUTES: [&str; 2] = ["minOccur", "maxOccur"];
+const RELATION_ATTRIBUTES: [&str; 13] = [
+    "reflexive",
+    "irreflexive",
+    "coreflexive",
+    "symmetric",
+    "antiSymmetric",
+    "aSymmetric",
+    "transitive",
+    "total",
+    "connex",
+    "Euclidean",
+    "serial",
+    "equivalence",
+    "partialOrder",
+];
+const PARTITION_ATTRIBUTES: [&str; 7] = [
+    "numParts",
+    "minNumParts",
+    "maxNumParts",
+    "partSize",
+    "minPartSize",
+    "maxPartSize",
+    "regular",
+];
+
+// domain constructors that take attributes in brackets after their keyword
+const ATTRIBUTED_DOMAINS: [&str; 6] = [
+    "set",
+    "mset",
+    "sequence",
+    "function",
+    "relation",
+    "partition",
+];
+
+// the attributes that can be given to a domain constructor
+pub fn domain_attributes(constructor: &str) -> Vec<&'static str> {
+    let with_size = |others: &[&'static str]| [&SIZE_ATTRIBUTES[..], others].concat();
+    match constructor {
+        "set" => SIZE_ATTRIBUTES.to_vec(),
+        "mset" => with_size(&OCCURRENCE_ATTRIBUTES),
+        "sequence" => with_size(&JECTIVITY_ATTRIBUTES),
+        "function" => with_size(&[&PARTIALITY_ATTRIBUTES[..], &JECTIVITY_ATTRIBUTES].concat()),
+        "relation" => with_size(&RELATION_ATTRIBUTES),
+        "partition" => PARTITION_ATTRIBUTES.to_vec(),
+        _ => vec![],
+    }
+}
+
+// snippets for each domain constructor, with placeholders for their attributes and inner domains
+pub fn domain_snippets() -> Vec<DomainSnippet> {
+    let choice = |n: u32, options: &[&str]| format!("${{{n}|{}|}}", options.join(","));
+    let sizes = choice(1, &SIZE_ATTRIBUTES);
+
+    vec![
+        DomainSnippet {
+            label: "bool",
+            snippet: "bool".to_string(),
+        },
+        DomainSnippet {
+            label: "int",
+            snippet: "int(${1:1}..${2:10})".to_string(),
+        },
+        DomainSnippet {
+            label: "matrix",
+            snippet: "matrix indexed by [${1:int(1..10)}] of ${2:int}".to_string(),
+        },
+        DomainSnippet {
+            label: "set",
+            snippet: format!("set ({sizes} ${{2:n}}) of ${{3:int}}"),
+        },
+        DomainSnippet {
+            label: "mset",
+            snippet: format!(
+                "mset ({} ${{2:n}}) of ${{3:int}}",
+                choice(1, &[&SIZE_ATTRIBUTES[..], &OCCURRENCE_ATTRIBUTES].concat())
+            ),
+        },
+        DomainSnippet {
+            label: "sequence",
+            snippet: format!(
+                "sequence ({sizes} ${{2:n}}, {}) of ${{4:int}}",
+                choice(3, &JECTIVITY_ATTRIBUTES)
+            ),
+        },
+        DomainSnippet {
+            label: "function",
+            snippet: format!(
+                "function ({}, {}) ${{3:int}} --> ${{4:int}}",
+                choice(1, &PARTIALITY_ATTRIBUTES),
+                choice(2, &JECTIVITY_ATTRIBUTES)
+            ),
+        },
+        DomainSnippet {
+            label: "relation",
+            snippet: format!(
+                "relation ({}) of (${{2:int}} * ${{3:int}})",
+                choice(1, &RELATION_ATTRIBUTES)
+            ),
+        },
+        DomainSnippet {
+            label: "partition",
+            snippet: format!(
+                "partition ({} ${{2:n}}) from ${{3:int}}",
+                choice(1, &PARTITION_ATTRIBUTES[..6])
+            ),
+        },
+        DomainSnippet {
+            label: "tuple",
+            snippet: "tuple (${1:int}, ${2:int})".to_string(),
+        },
+        DomainSnippet {
+            label: "record",
+            snippet: "record {${1:name} : ${2:int}}".to_string(),
+        },
+        DomainSnippet {
+            label: "variant",
+            snippet: "variant {${1:name} : ${2:int}}".to_string(),
+        },
+    ]
+}
+
+// work out what can be written at the given byte offset (e.g. cursor position)
+pub fn completion_context(tree: &Tree, source: &str, byte: usize) -> CompletionContext {
+    let root = tree.root_node();
+    let word_start = word_start(source, byte);
+
+    let mut tokens = vec![];
+    for node in WalkDFS::new(&root) {
+        if node.kind() == "single_line_comment" {
+            // comments run to the end of the line
+            if node.start_byte() < word_start && word_start <= node.end_byte() {
+                return CompletionContext::None;
+            }
+            continue;
+        }
+        if node.child_count() == 0
+            && !node.is_missing()
+            && node.start_byte() < node.end_byte()
+            && node.end_byte() <= word_start
+        {
+            tokens.push(node);
+        }
+    }
+
+    let text = |node: &Node| &source[node.start_byte()..node.end_byte()];
+    let Some(prev) = tokens.last() else {
+        return CompletionContext::Statement;
+    };
+    let before_prev = tokens.len().checked_sub(2).map(|idx| text(&tokens[idx]));
+
+    match text(prev) {
+        ":" | "of" | "-->" | "from" | "domain" => CompletionContext::Domain,
+        // new names are being declared
+        "find" | "given" | "letting" | "forAll" | "exists" if prev.kind() != "identifier" => {
+            CompletionContext::None
+        }
+        "sum" | "min" | "max" if prev.kind() != "identifier" => CompletionContext::None,
+        "(" => match before_prev {
+            Some(keyword) if ATTRIBUTED_DOMAINS.contains(&keyword) => {
+                CompletionContext::DomainAttribute(keyword.to_string())
+            }
+            Some("tuple") => CompletionContext::Domain,
+            _ => CompletionContext::Expression,
+        },
+        "[" if matches!(before_prev, Some("by" | "matrix")) => CompletionContext::Domain,
+        "," => comma_context(&tokens, source),
+        _ if is_line_start(source, word_start) && ends_statement(prev) => {
+            CompletionContext::Statement
+        }
+        _ => CompletionContext::Expression,
+    }
+}
+
+// the names of the quantified variables in scope at the given byte offset
+pub fn quantified_names_at(tree: &Tree, source: &str, byte: usize) -> Vec<String> {
+    let mut names = vec![];
+    let mut node = tree.root_node().descendant_for_byte_range(byte, byte);
+    while let Some(current) = node {
+        let mut cursor = current.walk();
+        let variables: Vec<Node> = match current.kind() {
+            "comprehension" => current
+                .named_children(&mut cursor)
+                .filter(|child| child.kind() == "generator")
+                .filter_map(|generator| generator.child_by_field_name("variable"))
+                .collect(),
+            "quantifier_expr" | "aggregate_expr" => current
+                .children_by_field_name("variables", &mut cursor)
+                .collect(),
+            _ => vec![],
+        };
+        names.extend(
+            variables
+                .iter()
+                .filter(|var| var.kind() == "identifier")
+                .map(|var| source[var.start_byte()..var.end_byte()].to_string()),
+        );
+        node = current.parent();
+    }
+    names
+}
+
+// a comma either separates the items in some brackets, or the declarations/constraints of a
+// statement
+fn comma_context(tokens: &[Node], source: &str) -> CompletionContext {
+    let text = |node: &Node| &source[node.start_byte()..node.end_byte()];
+
+    let mut depth = 0;
+    for (idx, token) in tokens.iter().enumerate().rev().skip(1) {
+        match text(token) {
+            ")" | "]" | "}" => depth += 1,
+            "(" | "[" | "{" if depth > 0 => depth -= 1,
+            opener @ ("(" | "[" | "{") => {
+                let before = idx.checked_sub(1).map(|idx| text(&tokens[idx]));
+                return match (opener, before) {
+                    ("(", Some(keyword)) if ATTRIBUTED_DOMAINS.contains(&keyword) => {
+                        CompletionContext::DomainAttribute(keyword.to_string())
+                    }
+                    ("(", Some("tuple")) | ("[", Some("by" | "matrix")) => {
+                        CompletionContext::Domain
+                    }
+                    _ => CompletionContext::Expression,
+                };
+            }
+            "find" | "given" | "letting" | "forAll" | "exists" | "sum" | "min" | "max"
+                if token.kind() != "identifier" =>
+            {
+                return CompletionContext::None;
+            }
+            "such that" | "where" | "." => return CompletionContext::Expression,
+            _ => {}
+        }
+    }
+    CompletionContext::Expression
+}
+
+// whether the token is the last one of a complete top-level statement
+fn ends_statement(token: &Node) -> bool {
+    let mut node = *token;
+    while let Some(parent) = node.parent() {
+        if parent.kind() == "program" {
+            return !node.has_error() && node.end_byte() == token.end_byte();
+        }
+        node = parent;
+    }
+    false
+}
+
+fn word_start(source: &str, byte: usize) -> usize {
+    let byte = byte.min(source.len());
+    source[..byte]
+        .rfind(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+        .map(|idx| idx + 1)
+        .unwrap_or(0)
+}
+
+fn is_line_start(source: &str, byte: usize) -> bool {
+    source[..byte]
+        .rsplit('\n')
+        .next()
+        .is_some_and(|line| line.trim().is_empty())
+}
@@ -41,7 +41,7 @@ pub struct Diagnostic {
 
 // document symbol struct is used to denote a single token / node
 // this will be used for syntax highlighting and hovering
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub enum SymbolKind {
     Integer = 0,
@@ -56,10 +56,11 @@ pub enum SymbolKind {
     LettingVar = 9,
     Given = 10,
     GivenVar = 11,
+    Constraint = 12,
 } // to be extended
 
-// each type of token / symbol in the essence grammar will be
-// assigned an integer, which would be mapped to a colour
+// a declaration or block of constraints in the outline of a file (see diagnostics::outline)
+// range covers the whole declaration, and selection_range the name (or keyword) in it
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct DocumentSymbol {
@@ -67,6 +68,7 @@ pub struct DocumentSymbol {
     pub detail: Option<String>,
     pub kind: SymbolKind,
     pub range: Range,
+    pub selection_range: Range,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub children: Option<Vec<DocumentSymbol>>,
 }
@@ -1,8 +1,9 @@
 // Basic syntactic error detection helpers for the LSP API.
 
 use crate::diagnostics::diagnostics_api::{Diagnostic, Position, Range, Severity};
+use crate::diagnostics::error_detection::semantic_checks::check_model;
 use crate::errors::RecoverableParseError;
-use crate::parse_essence_with_context_and_map;
+use crate::parse_essence_for_diagnostics;
 use conjure_cp_core::context::Context;
 use std::sync::{Arc, RwLock};
 use tree_sitter::Tree;
@@ -12,7 +13,12 @@ pub fn detect_errors(source: &str, cst: &Tree) -> Vec<Diagnostic> {
     let mut errors: Vec<RecoverableParseError> = vec![];
     let context = Arc::new(RwLock::new(Context::default()));
 
-    let _ = parse_essence_with_context_and_map(source, context, &mut errors, Some(cst));
+    let parsed = parse_essence_for_diagnostics(source, context, &mut errors, Some(cst));
+
+    // problems that need the whole model can only be found once it has parsed without errors
+    if let Ok((Some(model), source_map)) = parsed {
+        errors.extend(check_model(source, &model, &source_map));
+    }
 
     diagnostics.extend(errors.into_iter().map(|e| error_to_diagnostic(&e)));
 
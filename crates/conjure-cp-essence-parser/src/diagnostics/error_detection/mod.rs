@@ -1 +1,2 @@
 pub mod collect_errors;
+pub mod semantic_checks;
@@ -0,0 +1,379 @@
+/**
+ * Checks a parsed model for problems that the parser itself does not detect: expressions with
+ * operands of the wrong type, references to domains that cannot be resolved, and value lettings
+ * outside their declared domain.
+ * Problems are located in the source code through the span ids the parser saves in the metadata
+ * of each expression.
+ */
+use std::collections::BTreeSet;
+
+use conjure_cp_core::ast::serde::HasId;
+use conjure_cp_core::ast::{
+    Atom, DeclarationKind, DomainOpError, DomainPtr, Expression, Model, ReturnType, Typeable,
+    UnresolvedDomain, eval_constant,
+};
+use tree_sitter::{Point, Range};
+use uniplate::{Biplate, Uniplate};
+
+use crate::diagnostics::source_map::{OccurrenceKind, SourceMap, SpanId};
+use crate::errors::RecoverableParseError;
+
+// check a model that parsed without errors, given the source code and source map it was parsed
+// from
+pub fn check_model(
+    source: &str,
+    model: &Model,
+    source_map: &SourceMap,
+) -> Vec<RecoverableParseError> {
+    let mut checker = Checker {
+        source,
+        source_map,
+        errors: vec![],
+    };
+
+    for constraint in model.constraints() {
+        checker.expect_type(constraint, &ReturnType::Bool, None);
+    }
+    for condition in &model.where_conditions {
+        checker.expect_type(&condition.expression, &ReturnType::Bool, None);
+    }
+    if let Some(objective) = &model.objective {
+        checker.expect_type(&objective.expression, &ReturnType::Int, None);
+    }
+
+    let symbols = model.symbols();
+    let mut checked_references = BTreeSet::new();
+    for (name, decl) in symbols.iter_local() {
+        let domains = match &*decl.kind() {
+            DeclarationKind::Find(var) => vec![var.domain.clone()],
+            DeclarationKind::Given(domain) | DeclarationKind::DomainLetting(domain) => {
+                vec![domain.clone()]
+            }
+            DeclarationKind::ValueLetting(expr, domain) => {
+                // lettings made by the parser (e.g. the values of enumerated types) have no
+                // expression span, so are reported at their name
+                let span = expr.get_meta().span_id.or_else(|| {
+                    checker
+                        .source_map
+                        .occurrences_of(&decl.id())
+                        .into_iter()
+                        .find(|occ| occ.kind == OccurrenceKind::Declaration)
+                        .map(|occ| occ.span)
+                });
+                if checker.check(expr, span)
+                    && let Some(domain) = domain
+                {
+                    checker.check_letting_domain(&name.to_string(), expr, domain, span);
+                }
+                domain.iter().cloned().collect()
+            }
+            _ => vec![],
+        };
+        for domain in domains {
+            checker.check_domain_references(&domain, &mut checked_references);
+        }
+    }
+
+    checker.errors
+}
+
+struct Checker<'a> {
+    source: &'a str,
+    source_map: &'a SourceMap,
+    errors: Vec<RecoverableParseError>,
+}
+
+impl Checker<'_> {
+    // check an expression and its operands, and that it has the expected type
+    fn expect_type(&mut self, expr: &Expression, expected: &ReturnType, span: Option<SpanId>) {
+        if self.check(expr, span) {
+            self.expect_operand(expr, expected, span);
+        }
+    }
+
+    // check the operands of an expression and its sub-expressions, returning whether it is well
+    // typed. the operands of an ill-typed expression are not checked, as its type (and that of
+    // anything containing it) is not known
+    //
+    // only expressions written in the source code (i.e. with a span) are checked, as those made by
+    // the parser (e.g. from the guards of a comprehension) can only be wrong if their parts are
+    fn check(&mut self, expr: &Expression, span: Option<SpanId>) -> bool {
+        let own_span = expr.get_meta().span_id;
+        let span = own_span.or(span);
+        let mut well_typed = true;
+        for child in expr.children() {
+            well_typed &= self.check(&child, span);
+        }
+        well_typed && (own_span.is_none() || self.check_operands(expr, span))
+    }
+
+    fn check_operands(&mut self, expr: &Expression, span: Option<SpanId>) -> bool {
+        match expr {
+            Expression::Neg(_, a) | Expression::Factorial(_, a) => {
+                self.expect_operand(a, &ReturnType::Int, span)
+            }
+            Expression::SafeDiv(_, a, b)
+            | Expression::UnsafeDiv(_, a, b)
+            | Expression::SafeMod(_, a, b)
+            | Expression::UnsafeMod(_, a, b)
+            | Expression::SafePow(_, a, b)
+            | Expression::UnsafePow(_, a, b) => {
+                self.expect_operand(a, &ReturnType::Int, span)
+                    & self.expect_operand(b, &ReturnType::Int, span)
+            }
+            Expression::Minus(_, a, b) => match type_of(a) {
+                // minus is also the difference of two sets
+                set @ ReturnType::Set(_) => self.expect_operand(b, &set, span),
+                _ => {
+                    self.expect_operand(a, &ReturnType::Int, span)
+                        & self.expect_operand(b, &ReturnType::Int, span)
+                }
+            },
+            Expression::Sum(_, list) | Expression::Product(_, list) => {
+                self.expect_elements(list, &ReturnType::Int, span)
+            }
+            Expression::Not(_, a) => self.expect_operand(a, &ReturnType::Bool, span),
+            Expression::Imply(_, a, b) | Expression::Iff(_, a, b) => {
+                self.expect_operand(a, &ReturnType::Bool, span)
+                    & self.expect_operand(b, &ReturnType::Bool, span)
+            }
+            Expression::And(_, list) | Expression::Or(_, list) => {
+                self.expect_elements(list, &ReturnType::Bool, span)
+            }
+            Expression::Eq(_, a, b)
+            | Expression::Neq(_, a, b)
+            | Expression::Lt(_, a, b)
+            | Expression::Leq(_, a, b)
+            | Expression::Gt(_, a, b)
+            | Expression::Geq(_, a, b) => self.expect_operand(b, &type_of(a), span),
+            Expression::In(_, a, b) => match type_of(b).elem_type() {
+                Some(elem_type) => self.expect_operand(a, &elem_type, span),
+                None => true,
+            },
+            Expression::UnsafeIndex(_, subject, _)
+            | Expression::SafeIndex(_, subject, _)
+            | Expression::UnsafeSlice(_, subject, _)
+            | Expression::SafeSlice(_, subject, _) => match type_of(subject) {
+                ReturnType::Matrix(_)
+                | ReturnType::Tuple(_)
+                | ReturnType::Record(_)
+                | ReturnType::Variant(_)
+                | ReturnType::Unknown => true,
+                got => {
+                    let message =
+                        format!("Cannot index {} of type '{got}'", self.describe(subject));
+                    self.report(message, subject, span);
+                    false
+                }
+            },
+            _ => true,
+        }
+    }
+
+    // check that each element of a list (e.g. the operand of sum) has the expected type
+    fn expect_elements(
+        &mut self,
+        list: &Expression,
+        expected: &ReturnType,
+        span: Option<SpanId>,
+    ) -> bool {
+        if let Expression::Comprehension(_, comprehension) = list {
+            // a return expression without a span was made by the parser from the guards of the
+            // comprehension, as `guards -> expression`
+            let ret = match &comprehension.return_expression {
+                Expression::Imply(meta, _, ret) if meta.span_id.is_none() => ret,
+                ret => ret,
+            };
+            return self.expect_operand(ret, expected, span);
+        }
+        let Some(elements) = list.unwrap_list() else {
+            // e.g. a set
+            let got = type_of(list);
+            return match got.elem_type() {
+                Some(elem_type) if compatible(&elem_type, expected) => true,
+                None if got == ReturnType::Unknown => true,
+                _ => {
+                    let message = format!(
+                        "Incorrect type '{got}' for {}, expected a collection of '{expected}'",
+                        self.describe(list)
+                    );
+                    self.report(message, list, span);
+                    false
+                }
+            };
+        };
+        let mut well_typed = true;
+        for element in elements {
+            well_typed &= self.expect_operand(&element, expected, span);
+        }
+        well_typed
+    }
+
+    fn expect_operand(
+        &mut self,
+        operand: &Expression,
+        expected: &ReturnType,
+        span: Option<SpanId>,
+    ) -> bool {
+        let got = type_of(operand);
+        if compatible(&got, expected) {
+            return true;
+        }
+        let message = format!(
+            "Incorrect type '{got}' for {}, expected '{expected}'",
+            self.describe(operand)
+        );
+        self.report(message, operand, span);
+        false
+    }
+
+    // check that the value of a letting with a declared domain is in that domain
+    fn check_letting_domain(
+        &mut self,
+        name: &str,
+        expr: &Expression,
+        domain: &DomainPtr,
+        span: Option<SpanId>,
+    ) {
+        if !self.expect_operand(expr, &domain.return_type(), span) {
+            return;
+        }
+        let Some(value) = eval_constant(expr) else {
+            return;
+        };
+        if let Ok(false) = domain.contains(&value) {
+            let message = format!("The value {value} of '{name}' is not in its domain {domain}");
+            self.report(message, expr, span);
+        }
+    }
+
+    // check that the domain lettings referred to in a domain can be resolved, reporting each
+    // domain letting once, at the places it is referred to
+    fn check_domain_references(&mut self, domain: &DomainPtr, checked: &mut BTreeSet<String>) {
+        for inner in Biplate::<UnresolvedDomain>::universe_bi(&**domain) {
+            let UnresolvedDomain::Reference(reference) = inner else {
+                continue;
+            };
+            let name = reference.ptr.name().to_string();
+            if !checked.insert(name.clone()) {
+                continue;
+            }
+
+            let message = match reference.ptr.as_domain_letting() {
+                None => format!("'{name}' is not a domain"),
+                Some(letting) => match letting.resolve() {
+                    // domains that depend on givens are resolved when they are instantiated
+                    Ok(_) | Err(DomainOpError::NotGround) => continue,
+                    Err(err) => format!("The domain '{name}' cannot be resolved: {err}"),
+                },
+            };
+
+            let mut ranges: Vec<_> = self
+                .source_map
+                .occurrences_of(&reference.ptr.id())
+                .into_iter()
+                .filter(|occ| occ.kind == OccurrenceKind::Reference)
+                .map(|occ| self.span_range(occ.span))
+                .collect();
+            if ranges.is_empty() {
+                ranges.push(None);
+            }
+            for range in ranges {
+                self.errors
+                    .push(RecoverableParseError::new(message.clone(), range));
+            }
+        }
+    }
+
+    fn report(&mut self, message: String, expr: &Expression, span: Option<SpanId>) {
+        let span = expr.get_meta().span_id.or(span);
+        let range = span.and_then(|span| self.span_range(span));
+        self.errors.push(RecoverableParseError::new(message, range));
+    }
+
+    // how an expression is named in messages: by its source code if it is known
+    fn describe(&self, expr: &Expression) -> String {
+        if let Expression::Atomic(_, Atom::Reference(reference)) = expr {
+            return format!("variable '{}'", reference.name());
+        }
+        let text = expr
+            .get_meta()
+            .span_id
+            .and_then(|span| self.source_map.spans.get(span as usize))
+            .and_then(|span| self.source.get(span.start_byte..span.end_byte));
+        match text {
+            Some(text) => format!("'{text}'"),
+            None => format!("'{expr}'"),
+        }
+    }
+
+    fn span_range(&self, span: SpanId) -> Option<Range> {
+        let span = self.source_map.spans.get(span as usize)?;
+        Some(Range {
+            start_byte: span.start_byte,
+            end_byte: span.end_byte,
+            start_point: Point::new(
+                span.start_point.line as usize,
+                span.start_point.character as usize,
+            ),
+            end_point: Point::new(
+                span.end_point.line as usize,
+                span.end_point.character as usize,
+            ),
+        })
+    }
+}
+
+fn type_of(expr: &Expression) -> ReturnType {
+    // the type of flatten does not take its depth into account
+    let flattens_with_depth = expr
+        .universe()
+        .iter()
+        .any(|expr| matches!(expr, Expression::Flatten(_, Some(_), _)));
+    if flattens_with_depth {
+        return ReturnType::Unknown;
+    }
+    match expr {
+        // references are typed by their declaration, as the value of a letting does not always
+        // have a domain (e.g. a function literal)
+        Expression::Atomic(_, Atom::Reference(reference)) => reference.ptr().return_type(),
+        _ => expr.return_type(),
+    }
+}
+
+// whether a value of type `got` can be used where `expected` is, treating unknown types (e.g. of
+// empty literals) as compatible with anything
+fn compatible(got: &ReturnType, expected: &ReturnType) -> bool {
+    let all = |gots: &[ReturnType], expecteds: &[ReturnType]| {
+        gots.len() == expecteds.len()
+            && gots
+                .iter()
+                .zip(expecteds)
+                .all(|(got, expected)| compatible(got, expected))
+    };
+    match (got, expected) {
+        (ReturnType::Unknown, _) | (_, ReturnType::Unknown) => true,
+        (ReturnType::Int, ReturnType::Int) | (ReturnType::Bool, ReturnType::Bool) => true,
+        (ReturnType::Matrix(got), ReturnType::Matrix(expected))
+        | (ReturnType::Set(got), ReturnType::Set(expected))
+        | (ReturnType::MSet(got), ReturnType::MSet(expected))
+        | (ReturnType::Sequence(got), ReturnType::Sequence(expected))
+        | (ReturnType::Partition(got), ReturnType::Partition(expected)) => {
+            compatible(got, expected)
+        }
+        (ReturnType::Tuple(gots), ReturnType::Tuple(expecteds))
+        | (ReturnType::Relation(gots), ReturnType::Relation(expecteds)) => all(gots, expecteds),
+        (ReturnType::Record(gots), ReturnType::Record(expecteds))
+        | (ReturnType::Variant(gots), ReturnType::Variant(expecteds)) => {
+            gots.len() == expecteds.len()
+                && gots.iter().zip(expecteds).all(|(got, expected)| {
+                    got.name == expected.name && compatible(&got.value, &expected.value)
+                })
+        }
+        (ReturnType::Function(got_from, got_to), ReturnType::Function(from, to)) => {
+            compatible(got_from, from) && compatible(got_to, to)
+        }
+        (ReturnType::Enum(got), ReturnType::Enum(expected)) => got == expected,
+        _ => false,
+    }
+}
@@ -1,4 +1,6 @@
+pub mod completion;
 pub mod diagnostics_api;
 pub mod error_detection;
+pub mod outline;
 pub mod semantic_tokens;
 pub mod source_map;
@@ -0,0 +1,254 @@
+/**
+ * The outline of an Essence file (its declarations and constraints) and the parts of it that can
+ * be folded, for the LSP.
+ * Like completion, this only looks at the CST, so that it still works while the file is being
+ * edited and does not parse.
+ */
+use tree_sitter::{Node, Tree};
+
+use crate::diagnostics::diagnostics_api::{DocumentSymbol, Position, Range, SymbolKind};
+use crate::formatter::starts_statement;
+use crate::parser::traversal::WalkDFS;
+
+// nodes that can be folded when they span several lines, other than top-level statements
+const FOLDABLE_KINDS: [&str; 2] = ["comprehension", "matrix"];
+
+// the longest text shown for a symbol, e.g. the value of a letting or a constraint
+const MAX_TEXT_LENGTH: usize = 60;
+
+// the declarations and constraints of a file, in the order they are written:
+// - each name declared by a find, given or letting, with its domain (or value) as detail
+// - each `such that` and `where`, with its constraints as children
+pub fn document_symbols(source: &str, tree: &Tree) -> Vec<DocumentSymbol> {
+    let mut symbols = vec![];
+    for statement in statements(tree) {
+        let node = statement.node;
+        match node.kind() {
+            "find_statement" => {
+                symbols.extend(variable_declarations(source, &node, SymbolKind::FindVar))
+            }
+            "given_statement" => {
+                symbols.extend(variable_declarations(source, &node, SymbolKind::GivenVar))
+            }
+            "letting_statement" => symbols.extend(letting_declarations(source, &node)),
+            "where_statement" => {
+                let keyword = node.child_by_field_name("where_keyword").unwrap_or(node);
+                let mut cursor = node.walk();
+                let conditions = node.children_by_field_name("expression", &mut cursor);
+                symbols.push(constraint_block(
+                    source,
+                    "where",
+                    &keyword,
+                    statement.range,
+                    conditions,
+                ));
+            }
+            "such that" => symbols.push(constraint_block(
+                source,
+                "such that",
+                &node,
+                statement.range,
+                statement.constraints.into_iter(),
+            )),
+            _ => {}
+        }
+    }
+    symbols
+}
+
+// the line ranges that can be folded: top-level statements, and comprehensions and matrix
+// literals, that span several lines
+pub fn folding_ranges(tree: &Tree) -> Vec<Range> {
+    let mut ranges: Vec<Range> = statements(tree)
+        .into_iter()
+        .map(|statement| statement.range)
+        .collect();
+    ranges.extend(
+        WalkDFS::new(&tree.root_node())
+            .filter(|node| FOLDABLE_KINDS.contains(&node.kind()))
+            .map(|node| node_range(&node)),
+    );
+    ranges.retain(|range| range.end.line > range.start.line);
+
+    // only the largest range starting on each line can be folded
+    ranges.sort_by_key(|range| (range.start.line, u32::MAX - range.end.line));
+    ranges.dedup_by_key(|range| range.start.line);
+    ranges
+}
+
+// a top-level statement. a `such that` is not a node in the CST, so is represented by its
+// keyword, and its constraints are the nodes after it
+struct Statement<'a> {
+    node: Node<'a>,
+    range: Range,
+    constraints: Vec<Node<'a>>,
+}
+
+fn statements(tree: &Tree) -> Vec<Statement<'_>> {
+    let root = tree.root_node();
+    let mut statements: Vec<Statement> = vec![];
+    let mut in_such_that = false;
+    let mut cursor = root.walk();
+    for child in root.children(&mut cursor) {
+        if matches!(child.kind(), "single_line_comment" | "language_declaration") {
+            continue;
+        }
+        if in_such_that
+            && !starts_statement(&child)
+            && let Some(such_that) = statements.last_mut()
+        {
+            if child.is_named() {
+                such_that.range.end = node_range(&child).end;
+                such_that.constraints.push(child);
+            }
+            continue;
+        }
+        in_such_that = child.kind() == "such that";
+        if child.is_named() || in_such_that {
+            statements.push(Statement {
+                node: child,
+                range: node_range(&child),
+                constraints: vec![],
+            });
+        }
+    }
+    statements
+}
+
+// the names declared by a find or given, with their domains
+fn variable_declarations(source: &str, statement: &Node, kind: SymbolKind) -> Vec<DocumentSymbol> {
+    let mut symbols = vec![];
+    let mut cursor = statement.walk();
+    for declaration in statement.named_children(&mut cursor) {
+        let (kind, detail) = match declaration.kind() {
+            "variable_declaration" => (
+                kind,
+                declaration
+                    .child_by_field_name("domain")
+                    .map(|domain| node_text(source, &domain)),
+            ),
+            // given E new type enum
+            "given_enum_declaration" => (SymbolKind::Domain, Some("new type enum".to_string())),
+            _ => continue,
+        };
+        let Some(variables) = declaration.child_by_field_name("variables") else {
+            continue;
+        };
+        symbols.extend(declared_names(
+            source,
+            &declaration,
+            &variables,
+            kind,
+            detail,
+        ));
+    }
+    symbols
+}
+
+// the names declared by a letting, with their domains or values
+fn letting_declarations(source: &str, statement: &Node) -> Vec<DocumentSymbol> {
+    let mut symbols = vec![];
+    let mut cursor = statement.walk();
+    for declaration in statement.named_children(&mut cursor) {
+        let Some(variables) = declaration.child_by_field_name("variable_list") else {
+            continue;
+        };
+        let (kind, detail) = if let Some(new_type) = declaration.child_by_field_name("new_type") {
+            (SymbolKind::Domain, Some(node_text(source, &new_type)))
+        } else {
+            let kind = match declaration.child_by_field_name("domain") {
+                Some(_) => SymbolKind::Domain,
+                None => SymbolKind::LettingVar,
+            };
+            let detail = declaration
+                .child_by_field_name("expr_or_domain")
+                .map(|value| node_text(source, &value));
+            (kind, detail)
+        };
+        symbols.extend(declared_names(
+            source,
+            &declaration,
+            &variables,
+            kind,
+            detail,
+        ));
+    }
+    symbols
+}
+
+// a symbol for each name in a variable list, spanning the declaration it is in
+fn declared_names(
+    source: &str,
+    declaration: &Node,
+    variables: &Node,
+    kind: SymbolKind,
+    detail: Option<String>,
+) -> Vec<DocumentSymbol> {
+    let mut cursor = variables.walk();
+    variables
+        .named_children(&mut cursor)
+        .filter(|name| name.kind() == "identifier")
+        .map(|name| DocumentSymbol {
+            name: source[name.byte_range()].to_string(),
+            detail: detail.clone(),
+            kind,
+            range: node_range(declaration),
+            selection_range: node_range(&name),
+            children: None,
+        })
+        .collect()
+}
+
+// a `such that` or `where`, with a child for each of its constraints
+fn constraint_block<'a>(
+    source: &str,
+    name: &str,
+    keyword: &Node,
+    range: Range,
+    constraints: impl Iterator<Item = Node<'a>>,
+) -> DocumentSymbol {
+    let children = constraints
+        .map(|constraint| DocumentSymbol {
+            name: node_text(source, &constraint),
+            detail: None,
+            kind: SymbolKind::Constraint,
+            range: node_range(&constraint),
+            selection_range: node_range(&constraint),
+            children: None,
+        })
+        .collect();
+    DocumentSymbol {
+        name: name.to_string(),
+        detail: None,
+        kind: SymbolKind::Constraint,
+        range,
+        selection_range: node_range(keyword),
+        children: Some(children),
+    }
+}
+
+// the source text of a node on one line, shortened if it is long
+fn node_text(source: &str, node: &Node) -> String {
+    let text = source[node.byte_range()]
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    if text.chars().count() <= MAX_TEXT_LENGTH {
+        return text;
+    }
+    let shortened: String = text.chars().take(MAX_TEXT_LENGTH - 3).collect();
+    format!("{}...", shortened.trim_end())
+}
+
+fn node_range(node: &Node) -> Range {
+    Range {
+        start: Position {
+            line: node.start_position().row as u32,
+            character: node.start_position().column as u32,
+        },
+        end: Position {
+            line: node.end_position().row as u32,
+            character: node.end_position().column as u32,
+        },
+    }
+}
@@ -93,6 +93,8 @@ pub fn token_encoding(kind: &SymbolKind) -> Option<TokenEncoding> {
             ty: TOKEN_TYPE_GIVENVAR,
             modifiers: (1 << MODIFIER_DECLARATION),
         }),
+        // constraints are not tokens
+        SymbolKind::Constraint => None,
     }
 }
 
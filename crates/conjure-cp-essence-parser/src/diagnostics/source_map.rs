@@ -3,6 +3,8 @@
  * This is used for error reporting and diagnostics.
  */
 use crate::diagnostics::diagnostics_api::{Position, SymbolKind};
+use conjure_cp_core::ast::DeclarationPtr;
+use conjure_cp_core::ast::serde::{HasId, ObjId};
 use rangemap::RangeMap;
 pub type SpanId = u32;
 
@@ -25,11 +27,29 @@ pub struct SourceSpan {
     pub hover_info: Option<HoverInfo>,
 }
 
+// whether an identifier declares a symbol, or refers to one declared elsewhere
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OccurrenceKind {
+    Declaration,
+    Reference,
+}
+
+// an identifier in the source code, linked to the declaration it resolves to.
+// declarations are identified by the id of their DeclarationPtr rather than by name,
+// so that shadowed names (e.g. comprehension variables) resolve to the right symbol
+#[derive(Debug, Clone)]
+pub struct SymbolOccurrence {
+    pub span: SpanId,
+    pub declaration: ObjId,
+    pub kind: OccurrenceKind,
+}
+
 // can add more metadata for hovering and stuff
 #[derive(Debug, Default, Clone)]
 pub struct SourceMap {
     pub spans: Vec<SourceSpan>,
     pub by_byte: RangeMap<usize, SpanId>,
+    pub occurrences: Vec<SymbolOccurrence>,
 }
 
 // allocate a new span and return span id
@@ -39,6 +59,23 @@ pub fn alloc_span(
     source_map: &mut SourceMap,
     hover_info: Option<HoverInfo>,
 ) -> SpanId {
+    let span_id = alloc_node_span(range, source_map);
+    source_map.spans[span_id as usize].hover_info = hover_info;
+    // map byte offsets to span id (RangeMap handles lookup)
+    // tree-sitter can generate zero-length ranges for missing tokens;
+    // avoid inserting empty ranges, which RangeMap rejects.
+    if range.start_byte < range.end_byte {
+        source_map
+            .by_byte
+            .insert(range.start_byte..range.end_byte, span_id);
+    }
+    span_id
+}
+
+// allocate a span for a node of the AST (e.g. an expression, through Metadata.span_id), so that
+// diagnostics about it can point to the source code.
+// these are not looked up by byte offset, so that they don't hide the identifiers inside them
+pub fn alloc_node_span(range: tree_sitter::Range, source_map: &mut SourceMap) -> SpanId {
     let span_id = source_map.spans.len() as SpanId;
     source_map.spans.push(SourceSpan {
         start_byte: range.start_byte,
@@ -51,16 +88,8 @@ pub fn alloc_span(
             line: range.end_point.row as u32,
             character: range.end_point.column as u32,
         },
-        hover_info,
+        hover_info: None,
     });
-    // map byte offsets to span id (RangeMap handles lookup)
-    // tree-sitter can generate zero-length ranges for missing tokens;
-    // avoid inserting empty ranges, which RangeMap rejects.
-    if range.start_byte < range.end_byte {
-        source_map
-            .by_byte
-            .insert(range.start_byte..range.end_byte, span_id);
-    }
     span_id
 }
 
@@ -70,6 +99,49 @@ impl SourceMap {
         self.by_byte.get(&byte).copied()
     }
 
+    // record that the identifier at span_id declares decl
+    pub fn record_declaration(&mut self, span_id: SpanId, decl: &DeclarationPtr) {
+        self.record_occurrence(span_id, decl, OccurrenceKind::Declaration);
+    }
+
+    // record that the identifier at span_id refers to decl
+    pub fn record_reference(&mut self, span_id: SpanId, decl: &DeclarationPtr) {
+        self.record_occurrence(span_id, decl, OccurrenceKind::Reference);
+    }
+
+    fn record_occurrence(&mut self, span_id: SpanId, decl: &DeclarationPtr, kind: OccurrenceKind) {
+        self.occurrences.push(SymbolOccurrence {
+            span: span_id,
+            declaration: decl.id(),
+            kind,
+        });
+    }
+
+    // helper to get the identifier at a given byte offset, if it is linked to a declaration
+    pub fn occurrence_at_byte(&self, byte: usize) -> Option<&SymbolOccurrence> {
+        let span_id = self.span_id_at_byte(byte)?;
+        self.occurrences.iter().find(|occ| occ.span == span_id)
+    }
+
+    // all identifiers (declaration and references) linked to the given declaration, in source order
+    pub fn occurrences_of(&self, declaration: &ObjId) -> Vec<&SymbolOccurrence> {
+        let mut occurrences: Vec<_> = self
+            .occurrences
+            .iter()
+            .filter(|occ| &occ.declaration == declaration)
+            .collect();
+        occurrences.sort_by_key(|occ| self.spans[occ.span as usize].start_byte);
+        occurrences
+    }
+
+    // the span of the identifier that declares the given declaration
+    pub fn declaration_span(&self, declaration: &ObjId) -> Option<&SourceSpan> {
+        self.occurrences
+            .iter()
+            .find(|occ| &occ.declaration == declaration && occ.kind == OccurrenceKind::Declaration)
+            .and_then(|occ| self.spans.get(occ.span as usize))
+    }
+
     // helper to get hover info for a given byte offset (e.g. cursor position)
     pub fn hover_info_at_byte(&self, byte: usize) -> Option<&HoverInfo> {
         self.span_id_at_byte(byte)
@@ -0,0 +1,533 @@
+//! A formatter for Essence source code.
+//!
+//! The formatter works on the tree-sitter CST rather than on the parsed [`Model`], so that
+//! comments are kept and the output is the same model as written, not as rewritten. It only
+//! changes the whitespace between tokens:
+//!
+//! - each top-level statement starts on a new line, with at most one blank line between them;
+//! - a `such that` with more than one constraint puts each constraint on its own, indented line;
+//! - tokens are separated by a single space, except inside brackets, before commas, around `..`,
+//!   and between a built-in or function and its arguments (e.g. `int(1..3)`, `f(x)`, `M[i]`);
+//! - comments stay on the line they were on, or on a line of their own;
+//! - lines longer than 100 characters are broken after top-level commas (or, if there are none,
+//!   after `/\`, `\/`, `->` and `<->`), and brackets that do not fit on the line put each of their
+//!   items on a line of its own.
+//!
+//! Formatting is idempotent, and the output is checked to parse to the same tokens as the input.
+//!
+//! [`Model`]: conjure_cp_core::ast::Model
+
+use tree_sitter::Node;
+
+use crate::errors::FatalParseError;
+use crate::parser::traversal::WalkDFS;
+use crate::util::get_tree;
+
+const INDENT: usize = 4;
+
+// the width that lines are wrapped at
+const MAX_WIDTH: usize = 100;
+
+// operators that a long expression without top-level commas is broken after
+const BREAKING_OPERATORS: [&str; 4] = ["/\\", "\\/", "->", "<->"];
+
+// top-level nodes that begin a statement of their own
+const STATEMENT_KINDS: [&str; 8] = [
+    "find_statement",
+    "given_statement",
+    "letting_statement",
+    "where_statement",
+    "branching_statement",
+    "heuristic_statement",
+    "dominance_relation",
+    "objective_statement",
+];
+
+// pairs of characters that would lex differently if the tokens they end and start were joined
+const JOINING_PAIRS: [&str; 12] = [
+    "/\\", "\\/", "->", "<-", "--", "!=", "<=", ">=", "**", "..", "<l", ">l",
+];
+
+/// Formats Essence source code.
+///
+/// Returns an error if the source has syntax errors, as it cannot then be formatted without
+/// risking changing its meaning.
+pub fn format_essence(source: &str) -> Result<String, FatalParseError> {
+    let tokens = tokens_of(source)?;
+    let formatted = Formatter::new(source, &tokens).format();
+
+    // formatting must only change whitespace
+    let reformatted = tokens_of(&formatted)?;
+    let same_tokens = tokens.len() == reformatted.len()
+        && tokens
+            .iter()
+            .zip(&reformatted)
+            .all(|(a, b)| a.kind == b.kind && a.text.trim_end() == b.text.trim_end());
+    if !same_tokens {
+        return Err(FatalParseError::internal_error(
+            "formatting changed the tokens of the model".to_string(),
+            None,
+        ));
+    }
+
+    Ok(formatted)
+}
+
+// a leaf of the CST, with what the formatter needs to know about where it sits
+#[derive(Debug)]
+struct Token<'a> {
+    kind: &'a str,
+    text: &'a str,
+    start_byte: usize,
+    end_byte: usize,
+    position: TokenPosition,
+    // the kind of the parent node
+    parent: &'a str,
+    // whether this is the first or last child of its parent
+    first: bool,
+    last: bool,
+    // whether this has the same parent as the token before it
+    shares_parent: bool,
+}
+
+// where a token sits in the top-level structure of the file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TokenPosition {
+    // the first token of a statement
+    Statement,
+    // the first token of one of several constraints in a `such that`
+    Constraint,
+    Comment,
+    // the `language` line
+    Language,
+    Other,
+}
+
+fn tokens_of(source: &str) -> Result<Vec<Token<'_>>, FatalParseError> {
+    let (tree, _) = get_tree(source).ok_or_else(|| {
+        FatalParseError::TreeSitterError("syntax error: the source could not be parsed".to_string())
+    })?;
+    let root = tree.root_node();
+    if root.has_error() {
+        let error = WalkDFS::new(&root)
+            .find(|node| node.is_error() || node.is_missing())
+            .unwrap_or(root);
+        return Err(FatalParseError::TreeSitterError(format!(
+            "syntax error at line {}, column {}",
+            error.start_position().row + 1,
+            error.start_position().column + 1
+        )));
+    }
+
+    // the constraints of each `such that`, so that those with several can be split over lines
+    let mut multi_constraints = vec![];
+    let mut constraints: Vec<Node> = vec![];
+    let mut in_such_that = false;
+    let mut cursor = root.walk();
+    for child in root.children(&mut cursor) {
+        let kind = child.kind();
+        if kind == "single_line_comment" || kind == "language_declaration" {
+            continue;
+        }
+        if in_such_that && !starts_statement(&child) {
+            if child.is_named() {
+                constraints.push(child);
+            }
+            continue;
+        }
+        if constraints.len() > 1 {
+            multi_constraints.append(&mut constraints);
+        }
+        constraints.clear();
+        in_such_that = kind == "such that";
+    }
+    if constraints.len() > 1 {
+        multi_constraints.append(&mut constraints);
+    }
+
+    let mut tokens: Vec<Token> = vec![];
+    let mut prev_parent = None;
+    let mut prev_top_level = None;
+    for node in WalkDFS::new(&root) {
+        if node.child_count() > 0 || node == root {
+            continue;
+        }
+        let parent = node.parent();
+
+        let position = match node.kind() {
+            "single_line_comment" => TokenPosition::Comment,
+            "language_declaration" => TokenPosition::Language,
+            _ => {
+                let top_level = top_level_ancestor(node);
+                let starts_top_level = prev_top_level != Some(top_level);
+                prev_top_level = Some(top_level);
+                if !starts_top_level {
+                    TokenPosition::Other
+                } else if starts_statement(&top_level) {
+                    TokenPosition::Statement
+                } else if multi_constraints.contains(&top_level) {
+                    TokenPosition::Constraint
+                } else {
+                    TokenPosition::Other
+                }
+            }
+        };
+
+        tokens.push(Token {
+            kind: node.kind(),
+            text: &source[node.start_byte()..node.end_byte()],
+            start_byte: node.start_byte(),
+            end_byte: node.end_byte(),
+            position,
+            parent: parent.map(|parent| parent.kind()).unwrap_or_default(),
+            first: node.prev_sibling().is_none(),
+            last: node.next_sibling().is_none(),
+            shares_parent: parent.is_some() && parent == prev_parent,
+        });
+        prev_parent = parent;
+    }
+    Ok(tokens)
+}
+
+// whether a child of the root node begins a statement
+pub(crate) fn starts_statement(node: &Node) -> bool {
+    matches!(node.kind(), "such that" | "_FRAGMENT_EXPRESSION")
+        || STATEMENT_KINDS.contains(&node.kind())
+}
+
+// the child of the root node that contains this node
+fn top_level_ancestor(node: Node) -> Node {
+    let mut node = node;
+    while let Some(parent) = node.parent() {
+        if parent.parent().is_none() {
+            return node;
+        }
+        node = parent;
+    }
+    node
+}
+
+struct Formatter<'a> {
+    source: &'a str,
+    tokens: &'a [Token<'a>],
+    // the index of the matching bracket of each bracket
+    partners: Vec<Option<usize>>,
+    out: String,
+    // the indentation of the line being written, and the column it has reached
+    indent: usize,
+    column: usize,
+    line_start: bool,
+    // the next token must go on a new line, e.g. after a comment
+    pending_line: bool,
+}
+
+impl<'a> Formatter<'a> {
+    fn new(source: &'a str, tokens: &'a [Token<'a>]) -> Self {
+        let mut partners = vec![None; tokens.len()];
+        let mut open = vec![];
+        for (idx, token) in tokens.iter().enumerate() {
+            match token.kind {
+                "(" | "[" | "{" => open.push(idx),
+                ")" | "]" | "}" => {
+                    if let Some(opener) = open.pop() {
+                        partners[opener] = Some(idx);
+                        partners[idx] = Some(opener);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Formatter {
+            source,
+            tokens,
+            partners,
+            out: String::new(),
+            indent: 0,
+            column: 0,
+            line_start: true,
+            pending_line: false,
+        }
+    }
+
+    fn format(mut self) -> String {
+        let mut idx = 0;
+        while idx < self.tokens.len() {
+            let token = &self.tokens[idx];
+            match token.position {
+                TokenPosition::Language => {
+                    self.start_line(idx, 0);
+                    self.write_token(idx, 0);
+                    idx += 1;
+                }
+                TokenPosition::Comment => {
+                    if !self.is_trailing_comment(idx) {
+                        let indent = if self.next_is_statement(idx) {
+                            0
+                        } else {
+                            INDENT
+                        };
+                        self.start_line(idx, indent);
+                    }
+                    self.write_token(idx, self.indent);
+                    idx += 1;
+                }
+                TokenPosition::Statement | TokenPosition::Constraint | TokenPosition::Other => {
+                    let indent = match token.position {
+                        TokenPosition::Constraint => INDENT,
+                        _ => 0,
+                    };
+                    let end = self.segment_end(idx);
+                    self.start_line(idx, indent);
+                    self.write_items(idx, end, indent + INDENT, !self.fits(idx, end));
+                    idx = end;
+                }
+            }
+        }
+
+        if !self.out.is_empty() {
+            self.out.push('\n');
+        }
+        self.out
+    }
+
+    // writes tokens lo..hi, breaking the line after each top-level comma if `breaking`, or after
+    // each top-level boolean operator if there are no top-level commas
+    fn write_items(&mut self, lo: usize, hi: usize, indent: usize, breaking: bool) {
+        let at_commas = self.has_top_level_comma(lo, hi);
+
+        let mut idx = lo;
+        while idx < hi {
+            match self.partners[idx] {
+                Some(close) if idx < close && close < hi => {
+                    // a group is indented from the line it starts on
+                    if self.pending_line && !self.line_start {
+                        self.new_line(indent);
+                    }
+                    self.write_group(idx, close);
+                    idx = close + 1;
+                }
+                _ => {
+                    self.write_token(idx, indent);
+                    idx += 1;
+                }
+            }
+            let prev = &self.tokens[idx - 1];
+            let breaks_after = if at_commas {
+                is_breaking_comma(prev)
+            } else {
+                BREAKING_OPERATORS.contains(&prev.text)
+            };
+            if breaking && idx < hi && breaks_after {
+                self.pending_line = true;
+            }
+        }
+    }
+
+    // writes the brackets open and close and what is between them, on one line if it fits, or
+    // else with each item on a line of its own
+    fn write_group(&mut self, open: usize, close: usize) {
+        let outer = self.indent;
+        if self.fits(open, close + 1) {
+            self.write_token(open, outer);
+            self.write_items(open + 1, close, outer, false);
+            self.write_token(close, outer);
+        } else if open + 1 < close - 1 && self.partners[open + 1] == Some(close - 1) {
+            // a group that only contains another, e.g. `sum([...])`, is broken inside the inner one
+            self.write_token(open, outer);
+            self.write_group(open + 1, close - 1);
+            self.write_token(close, outer);
+        } else {
+            self.write_token(open, outer);
+            self.pending_line = true;
+            self.write_items(open + 1, close, outer + INDENT, true);
+            self.pending_line = true;
+            self.write_token(close, outer);
+        }
+    }
+
+    // writes a token, on a new line with the given indentation if the line has been ended
+    fn write_token(&mut self, idx: usize, indent: usize) {
+        let token = &self.tokens[idx];
+        if token.position == TokenPosition::Comment && self.is_trailing_comment(idx) {
+            self.push(" ");
+        } else if (self.pending_line || token.position == TokenPosition::Comment)
+            && !self.line_start
+        {
+            self.new_line(indent);
+        } else if !self.line_start && needs_space(&self.tokens[idx - 1], token) {
+            self.push(" ");
+        }
+
+        self.push(token.text.trim_end());
+        self.pending_line = matches!(
+            token.position,
+            TokenPosition::Comment | TokenPosition::Language
+        );
+    }
+
+    // starts the line of a statement, constraint or comment, keeping one blank line before it if
+    // there was one
+    fn start_line(&mut self, idx: usize, indent: usize) {
+        if !self.out.is_empty() {
+            let blank_line = self.gap_before(idx).matches('\n').count() > 1
+                && self.tokens[idx - 1].kind != "such that";
+            if blank_line {
+                self.out.push('\n');
+            }
+            self.new_line(indent);
+        }
+        self.indent = indent;
+        self.pending_line = false;
+    }
+
+    fn new_line(&mut self, indent: usize) {
+        self.out.push('\n');
+        self.out.push_str(&" ".repeat(indent));
+        self.indent = indent;
+        self.column = indent;
+        self.line_start = true;
+        self.pending_line = false;
+    }
+
+    fn push(&mut self, text: &str) {
+        self.out.push_str(text);
+        self.column += text.chars().count();
+        self.line_start = false;
+    }
+
+    // whether tokens lo..hi fit on the rest of the current line
+    fn fits(&self, lo: usize, hi: usize) -> bool {
+        let mut width = self.column;
+        for idx in lo..hi {
+            let token = &self.tokens[idx];
+            if matches!(
+                token.position,
+                TokenPosition::Comment | TokenPosition::Language
+            ) {
+                return false;
+            }
+            if idx > lo && needs_space(&self.tokens[idx - 1], token) {
+                width += 1;
+            }
+            width += token.text.chars().count();
+        }
+        width <= MAX_WIDTH
+    }
+
+    fn has_top_level_comma(&self, lo: usize, hi: usize) -> bool {
+        let mut idx = lo;
+        while idx < hi {
+            if is_breaking_comma(&self.tokens[idx]) {
+                return true;
+            }
+            idx = match self.partners[idx] {
+                Some(close) if idx < close => close + 1,
+                _ => idx + 1,
+            };
+        }
+        false
+    }
+
+    // the end of the statement or constraint starting at idx, before any comments that follow it
+    fn segment_end(&self, idx: usize) -> usize {
+        let mut end = idx + 1;
+        while end < self.tokens.len() {
+            match self.tokens[end].position {
+                TokenPosition::Statement | TokenPosition::Constraint | TokenPosition::Language => {
+                    break;
+                }
+                TokenPosition::Comment if self.next_starts_segment(end) => break,
+                _ => end += 1,
+            }
+        }
+        end
+    }
+
+    fn gap_before(&self, idx: usize) -> &'a str {
+        match idx.checked_sub(1) {
+            Some(prev) => &self.source[self.tokens[prev].end_byte..self.tokens[idx].start_byte],
+            None => "",
+        }
+    }
+
+    // whether the comment at idx is on the same line as the token before it
+    fn is_trailing_comment(&self, idx: usize) -> bool {
+        idx > 0 && !self.line_start && !self.gap_before(idx).contains('\n')
+    }
+
+    // the next token that is not a comment
+    fn next_non_comment(&self, idx: usize) -> Option<&Token<'a>> {
+        self.tokens[idx + 1..]
+            .iter()
+            .find(|token| token.position != TokenPosition::Comment)
+    }
+
+    // whether the next token that is not a comment starts a statement, or there are none left
+    fn next_is_statement(&self, idx: usize) -> bool {
+        self.next_non_comment(idx).is_none_or(|token| {
+            matches!(
+                token.position,
+                TokenPosition::Statement | TokenPosition::Language
+            )
+        })
+    }
+
+    // whether the next token that is not a comment starts a statement or constraint, or there are
+    // none left
+    fn next_starts_segment(&self, idx: usize) -> bool {
+        self.next_is_statement(idx)
+            || self
+                .next_non_comment(idx)
+                .is_some_and(|token| token.position == TokenPosition::Constraint)
+    }
+}
+
+// whether a long list can be broken after this comma; not those between the names of a
+// declaration or quantifier, e.g. `forAll i, j : int(1..3)`
+fn is_breaking_comma(token: &Token) -> bool {
+    token.kind == ","
+        && !matches!(
+            token.parent,
+            "variable_list" | "quantifier_expr" | "aggregate_expr"
+        )
+}
+
+// whether two tokens on the same line should be separated by a space
+fn needs_space(prev: &Token, next: &Token) -> bool {
+    let is_word = |text: &str| text.ends_with(|c: char| c.is_ascii_alphanumeric() || c == '_');
+
+    let space = match (prev.text, next.text) {
+        (_, "," | ")" | "]" | "}" | ";") => false,
+        ("(" | "[" | "{", _) => false,
+        ("..", _) | (_, "..") => false,
+        // calls of built-ins and functions, e.g. `int(1..3)`, `allDiff(x)` and `f(x)`, but not
+        // domains with attributes, e.g. `set (size 2) of int`
+        (callee, "(") if is_word(callee) && next.shares_parent => matches!(
+            next.parent,
+            "set_domain" | "tuple_domain" | "relation_domain" | "tuple"
+        ),
+        (_, "[") if next.parent == "index_or_slice" => false,
+        ("!", _) if prev.parent == "not_expr" => false,
+        (_, "!") if next.parent == "factorial_expr" => false,
+        ("-", _) if prev.parent == "negative_expr" => false,
+        ("&", _) if prev.parent == "metavar" => false,
+        ("|", _) if prev.parent == "abs_value" && prev.first => false,
+        (_, "|") if next.parent == "abs_value" && next.last => false,
+        _ => true,
+    };
+
+    space || would_join(prev.text, next.text)
+}
+
+// whether two tokens written next to each other would be read as different tokens
+fn would_join(prev: &str, next: &str) -> bool {
+    let (Some(last), Some(first)) = (prev.chars().last(), next.chars().next()) else {
+        return false;
+    };
+    let is_word_char = |c: char| c.is_ascii_alphanumeric() || c == '_';
+
+    (is_word_char(last) && is_word_char(first))
+        || (last == '-' && first.is_ascii_digit())
+        || JOINING_PAIRS.contains(&format!("{last}{first}").as_str())
+}
@@ -3,10 +3,12 @@ use defile as _;
 
 pub mod diagnostics;
 pub mod errors;
+pub mod formatter;
 pub mod parser;
 pub mod parser_legacy;
 
 pub use diagnostics::error_detection::collect_errors::detect_errors;
 pub use errors::{FatalParseError, RecoverableParseError};
+pub use formatter::format_essence;
 pub use parser::*;
 pub use parser_legacy::parse_essence_file;
@@ -4,7 +4,7 @@ use crate::field;
 use crate::parser::ParseContext;
 use crate::parser::domain::parse_domain;
 use crate::util::{TypecheckingContext, named_children};
-use conjure_cp_core::ast::{AbstractLiteral, DomainPtr, Expression};
+use conjure_cp_core::ast::{AbstractLiteral, DomainPtr, Expression, Field, Moo, Name};
 use conjure_cp_core::{domain_int, range};
 use tree_sitter::Node;
 
@@ -21,6 +21,12 @@ pub fn parse_abstract(
         "tuple" => parse_tuple(ctx, node),
         "matrix" => parse_matrix(ctx, node),
         "set_literal" => parse_set_literal(ctx, node),
+        "mset_literal" => parse_mset_literal(ctx, node),
+        "sequence_literal" => parse_sequence_literal(ctx, node),
+        "function_literal" => parse_function_literal(ctx, node),
+        "relation_literal" => parse_relation_literal(ctx, node),
+        "partition_literal" => parse_partition_literal(ctx, node),
+        "variant_literal" => parse_variant_literal(ctx, node),
         _ => {
             ctx.record_error(RecoverableParseError::new(
                 format!("Expected abstract literal, got: {}", node.kind()),
@@ -48,9 +54,15 @@ fn typecheck_abstract_literal(ctx: &mut ParseContext, node: &Node) -> bool {
 
     let got = match node.kind() {
         "set_literal" => "set",
+        "mset_literal" => "mset",
         "matrix" => "matrix",
         "tuple" => "tuple",
         "record" => "record",
+        "sequence_literal" => "sequence",
+        "function_literal" => "function",
+        "relation_literal" => "relation",
+        "partition_literal" => "partition",
+        "variant_literal" => "variant",
         _ => {
             ctx.record_error(RecoverableParseError::new(
                 format!("Expected abstract literal, got: {}", node.kind()),
@@ -235,3 +247,196 @@ fn parse_set_literal(
         Ok(Some(AbstractLiteral::Set(elements)))
     }
 }
+
+fn parse_mset_literal(
+    ctx: &mut ParseContext,
+    node: &Node,
+) -> Result<Option<AbstractLiteral<Expression>>, FatalParseError> {
+    // Save the typechecking contexts
+    let saved_ctx = ctx.typechecking_context;
+    let saved_inner_ctx = ctx.inner_typechecking_context;
+
+    let mut elements = Vec::new();
+    let mut had_error = false;
+    for child in named_children(node) {
+        // Parse elements with inner typechecking context
+        ctx.typechecking_context = saved_inner_ctx;
+        ctx.inner_typechecking_context = TypecheckingContext::Unknown;
+
+        let Some(expr) = parse_expression(ctx, child)? else {
+            had_error = true;
+            continue;
+        };
+        elements.push(expr);
+    }
+
+    ctx.typechecking_context = saved_ctx;
+    ctx.inner_typechecking_context = saved_inner_ctx;
+    if had_error {
+        Ok(None)
+    } else {
+        Ok(Some(AbstractLiteral::MSet(elements)))
+    }
+}
+
+fn parse_sequence_literal(
+    ctx: &mut ParseContext,
+    node: &Node,
+) -> Result<Option<AbstractLiteral<Expression>>, FatalParseError> {
+    let Some(elements) = parse_elements(ctx, named_children(node))? else {
+        return Ok(None);
+    };
+    Ok(Some(AbstractLiteral::Sequence(elements)))
+}
+
+fn parse_function_literal(
+    ctx: &mut ParseContext,
+    node: &Node,
+) -> Result<Option<AbstractLiteral<Expression>>, FatalParseError> {
+    // the domain and codomain of a function can have different types, so don't typecheck them
+    let saved_ctx = ctx.typechecking_context;
+    let saved_inner_ctx = ctx.inner_typechecking_context;
+
+    let mut mappings = Vec::new();
+    let mut had_error = false;
+    for mapping in node.children_by_field_name("mapping", &mut node.walk()) {
+        let mut sides = Vec::new();
+        for side in ["from", "to"] {
+            ctx.typechecking_context = TypecheckingContext::Unknown;
+            ctx.inner_typechecking_context = TypecheckingContext::Unknown;
+
+            let Some(side_node) = field!(recover, ctx, mapping, side) else {
+                had_error = true;
+                continue;
+            };
+            let Some(expr) = parse_expression(ctx, side_node)? else {
+                had_error = true;
+                continue;
+            };
+            sides.push(expr);
+        }
+        if let Ok([from, to]) = <[Expression; 2]>::try_from(sides) {
+            mappings.push((from, to));
+        }
+    }
+
+    ctx.typechecking_context = saved_ctx;
+    ctx.inner_typechecking_context = saved_inner_ctx;
+    if had_error {
+        Ok(None)
+    } else {
+        Ok(Some(AbstractLiteral::Function(mappings)))
+    }
+}
+
+fn parse_relation_literal(
+    ctx: &mut ParseContext,
+    node: &Node,
+) -> Result<Option<AbstractLiteral<Expression>>, FatalParseError> {
+    // the components of a relation can have different types, so don't typecheck them
+    let saved_inner_ctx = ctx.inner_typechecking_context;
+    ctx.inner_typechecking_context = TypecheckingContext::Unknown;
+
+    let mut tuples = Vec::new();
+    let mut had_error = false;
+    for tuple in node.children_by_field_name("element", &mut node.walk()) {
+        let Some(elements) = parse_elements(ctx, named_children(&tuple))? else {
+            had_error = true;
+            continue;
+        };
+        tuples.push(elements);
+    }
+
+    ctx.inner_typechecking_context = saved_inner_ctx;
+    if had_error {
+        Ok(None)
+    } else {
+        Ok(Some(AbstractLiteral::Relation(tuples)))
+    }
+}
+
+fn parse_partition_literal(
+    ctx: &mut ParseContext,
+    node: &Node,
+) -> Result<Option<AbstractLiteral<Expression>>, FatalParseError> {
+    let mut parts = Vec::new();
+    let mut had_error = false;
+    for part in node.children_by_field_name("part", &mut node.walk()) {
+        let Some(elements) = parse_elements(ctx, named_children(&part))? else {
+            had_error = true;
+            continue;
+        };
+        parts.push(elements);
+    }
+
+    if had_error {
+        Ok(None)
+    } else {
+        Ok(Some(AbstractLiteral::Partition(parts)))
+    }
+}
+
+fn parse_variant_literal(
+    ctx: &mut ParseContext,
+    node: &Node,
+) -> Result<Option<AbstractLiteral<Expression>>, FatalParseError> {
+    let Some(pair) = field!(recover, ctx, node, "name_value_pair") else {
+        return Ok(None);
+    };
+    let Some(name_node) = field!(recover, ctx, pair, "name") else {
+        return Ok(None);
+    };
+    let name = Name::user(&ctx.source_code[name_node.start_byte()..name_node.end_byte()]);
+    let Some(value_node) = field!(recover, ctx, pair, "value") else {
+        return Ok(None);
+    };
+
+    let saved_ctx = ctx.typechecking_context;
+    let saved_inner_ctx = ctx.inner_typechecking_context;
+    ctx.typechecking_context = TypecheckingContext::Unknown;
+    ctx.inner_typechecking_context = TypecheckingContext::Unknown;
+    let value = parse_expression(ctx, value_node)?;
+    ctx.typechecking_context = saved_ctx;
+    ctx.inner_typechecking_context = saved_inner_ctx;
+
+    let Some(value) = value else {
+        return Ok(None);
+    };
+    Ok(Some(AbstractLiteral::Variant(Moo::new(Field {
+        name,
+        value,
+    }))))
+}
+
+/// Parses the elements of an abstract literal, typechecking them with the inner typechecking
+/// context.
+fn parse_elements<'a>(
+    ctx: &mut ParseContext,
+    nodes: impl Iterator<Item = Node<'a>>,
+) -> Result<Option<Vec<Expression>>, FatalParseError> {
+    // Save the typechecking contexts
+    let saved_ctx = ctx.typechecking_context;
+    let saved_inner_ctx = ctx.inner_typechecking_context;
+
+    let mut elements = Vec::new();
+    let mut had_error = false;
+    for child in nodes {
+        // Parse elements with inner typechecking context
+        ctx.typechecking_context = saved_inner_ctx;
+        ctx.inner_typechecking_context = TypecheckingContext::Unknown;
+
+        let Some(expr) = parse_expression(ctx, child)? else {
+            had_error = true;
+            continue;
+        };
+        elements.push(expr);
+    }
+
+    ctx.typechecking_context = saved_ctx;
+    ctx.inner_typechecking_context = saved_inner_ctx;
+    if had_error {
+        Ok(None)
+    } else {
+        Ok(Some(elements))
+    }
+}
@@ -9,7 +9,7 @@ use crate::parser::abstract_literal::parse_abstract;
 use crate::parser::comprehension::parse_comprehension;
 use crate::parser::dominance::parse_pareto_expression;
 use crate::util::{TypecheckingContext, named_children};
-use crate::{field, named_child};
+use crate::{child, field, named_child};
 
 use conjure_cp_core::ast::{
     Atom, DeclarationKind, DeclarationPtr, Expression, GroundDomain, Literal, Metadata, Moo, Name,
@@ -22,6 +22,14 @@ use ustr::Ustr;
 pub fn parse_atom(
     ctx: &mut ParseContext,
     node: &Node,
+) -> Result<Option<Expression>, FatalParseError> {
+    let atom = parse_atom_kind(ctx, node)?;
+    Ok(atom.map(|atom| ctx.record_expression_span(atom, node)))
+}
+
+fn parse_atom_kind(
+    ctx: &mut ParseContext,
+    node: &Node,
 ) -> Result<Option<Expression>, FatalParseError> {
     match node.kind() {
         "atom" | "sub_atom_expr" => {
@@ -77,12 +85,17 @@ pub fn parse_atom(
                 Atom::Literal(lit),
             )))
         }
-        "matrix" | "record" | "tuple" | "set_literal" => {
+        "matrix" | "record" | "tuple" | "set_literal" | "mset_literal" | "sequence_literal"
+        | "function_literal" | "relation_literal" | "partition_literal" | "variant_literal" => {
             let Some(abs) = parse_abstract(ctx, node)? else {
                 return Ok(None);
             };
             Ok(Some(Expression::AbstractLiteral(Metadata::new(), abs)))
         }
+        "unary_operator" => parse_unary_operator(ctx, node),
+        "binary_operator" => parse_binary_operator(ctx, node),
+        "active" => parse_active(ctx, node),
+        "application" => parse_application(ctx, node),
         "flatten" => parse_flatten(ctx, node),
         "table" | "negative_table" => parse_table(ctx, node),
         "index_or_slice" => parse_index_or_slice(ctx, node),
@@ -144,6 +157,239 @@ fn parse_flatten(
     }
 }
 
+/// Parses a built-in operator with one argument, such as `defined(f)` or `parts(P)`.
+fn parse_unary_operator(
+    ctx: &mut ParseContext,
+    node: &Node,
+) -> Result<Option<Expression>, FatalParseError> {
+    let Some(op_node) = field!(recover, ctx, node, "operator") else {
+        return Ok(None);
+    };
+    let op_str = &ctx.source_code[op_node.start_byte()..op_node.end_byte()];
+    // dontCare has no expression in the AST yet
+    if op_str == "dontCare" {
+        ctx.record_error(RecoverableParseError::new(
+            format!("'{op_str}' is not supported yet"),
+            Some(op_node.range()),
+        ));
+        return Ok(None);
+    }
+
+    // the argument is a function, sequence, relation or partition, so don't typecheck it
+    let saved_context = ctx.typechecking_context;
+    ctx.typechecking_context = TypecheckingContext::Unknown;
+    ctx.inner_typechecking_context = TypecheckingContext::Unknown;
+
+    let Some(arg_node) = field!(recover, ctx, node, "argument") else {
+        return Ok(None);
+    };
+    let Some(arg) = parse_expression(ctx, arg_node)? else {
+        return Ok(None);
+    };
+
+    ctx.typechecking_context = saved_context;
+
+    let arg = Moo::new(arg);
+    let expr = match op_str {
+        "defined" => Expression::Defined(Metadata::new(), arg),
+        "range" => Expression::Range(Metadata::new(), arg),
+        "toSet" => Expression::ToSet(Metadata::new(), arg),
+        "toMSet" => Expression::ToMSet(Metadata::new(), arg),
+        "toRelation" => Expression::ToRelation(Metadata::new(), arg),
+        "participants" => Expression::Participants(Metadata::new(), arg),
+        "parts" => Expression::Parts(Metadata::new(), arg),
+        _ => {
+            ctx.record_error(RecoverableParseError::new(
+                format!("Invalid operator: '{op_str}'"),
+                Some(op_node.range()),
+            ));
+            return Ok(None);
+        }
+    };
+
+    ctx.add_span_and_doc_hover(&op_node, op_str, SymbolKind::Function, None, None);
+    Ok(Some(expr))
+}
+
+/// Parses a built-in operator with two arguments, such as `image(f, x)` or `party(x, P)`.
+fn parse_binary_operator(
+    ctx: &mut ParseContext,
+    node: &Node,
+) -> Result<Option<Expression>, FatalParseError> {
+    let Some(op_node) = field!(recover, ctx, node, "operator") else {
+        return Ok(None);
+    };
+    let op_str = &ctx.source_code[op_node.start_byte()..op_node.end_byte()];
+    // catchUndef has no expression in the AST yet
+    if op_str == "catchUndef" {
+        ctx.record_error(RecoverableParseError::new(
+            format!("'{op_str}' is not supported yet"),
+            Some(op_node.range()),
+        ));
+        return Ok(None);
+    }
+
+    // the arguments can be of different types, so don't typecheck them
+    let saved_context = ctx.typechecking_context;
+
+    let mut args = Vec::new();
+    for side in ["left", "right"] {
+        ctx.typechecking_context = TypecheckingContext::Unknown;
+        ctx.inner_typechecking_context = TypecheckingContext::Unknown;
+
+        let Some(arg_node) = field!(recover, ctx, node, side) else {
+            return Ok(None);
+        };
+        let Some(arg) = parse_expression(ctx, arg_node)? else {
+            return Ok(None);
+        };
+        args.push(Moo::new(arg));
+    }
+
+    ctx.typechecking_context = saved_context;
+
+    let Ok([left, right]) = <[Moo<Expression>; 2]>::try_from(args) else {
+        return Ok(None);
+    };
+    let expr = match op_str {
+        "image" => Expression::Image(Metadata::new(), left, right),
+        "imageSet" => Expression::ImageSet(Metadata::new(), left, right),
+        "preImage" => Expression::PreImage(Metadata::new(), left, right),
+        "inverse" => Expression::Inverse(Metadata::new(), left, right),
+        "restrict" => Expression::Restrict(Metadata::new(), left, right),
+        "party" => Expression::Party(Metadata::new(), left, right),
+        "together" => Expression::Together(Metadata::new(), left, right),
+        "apart" => Expression::Apart(Metadata::new(), left, right),
+        _ => {
+            ctx.record_error(RecoverableParseError::new(
+                format!("Invalid operator: '{op_str}'"),
+                Some(op_node.range()),
+            ));
+            return Ok(None);
+        }
+    };
+
+    ctx.add_span_and_doc_hover(&op_node, op_str, SymbolKind::Function, None, None);
+    Ok(Some(expr))
+}
+
+/// Parses `active(v, name)`, which holds if the variant `v` is currently the field `name`.
+fn parse_active(
+    ctx: &mut ParseContext,
+    node: &Node,
+) -> Result<Option<Expression>, FatalParseError> {
+    let saved_context = ctx.typechecking_context;
+    ctx.typechecking_context = TypecheckingContext::Unknown;
+
+    let Some(variant_node) = field!(recover, ctx, node, "variant") else {
+        return Ok(None);
+    };
+    let Some(variant) = parse_atom(ctx, &variant_node)? else {
+        return Ok(None);
+    };
+
+    ctx.typechecking_context = saved_context;
+
+    let Some(name_node) = field!(recover, ctx, node, "name") else {
+        return Ok(None);
+    };
+    let name = Name::user(ctx.source_code[name_node.start_byte()..name_node.end_byte()].trim());
+
+    if let Some(GroundDomain::Variant(fields)) = variant
+        .domain_of()
+        .and_then(|domain| domain.resolve().ok())
+        .as_deref()
+        && !fields.iter().any(|field| field.name == name)
+    {
+        ctx.record_error(RecoverableParseError::new(
+            format!("`{name}` is not a valid field name for `{variant}`"),
+            Some(name_node.range()),
+        ));
+        return Ok(None);
+    }
+
+    let active_keyword_node = child!(node, 0, "active");
+    ctx.add_span_and_doc_hover(
+        &active_keyword_node,
+        "active",
+        SymbolKind::Function,
+        None,
+        None,
+    );
+    Ok(Some(Expression::Active(
+        Metadata::new(),
+        Moo::new(variant),
+        name,
+    )))
+}
+
+/// Parses `f(x)`, which is the image of a function or sequence, or a projection `R(x, _)` of a
+/// relation.
+fn parse_application(
+    ctx: &mut ParseContext,
+    node: &Node,
+) -> Result<Option<Expression>, FatalParseError> {
+    let saved_context = ctx.typechecking_context;
+    ctx.typechecking_context = TypecheckingContext::Unknown;
+    ctx.inner_typechecking_context = TypecheckingContext::Unknown;
+
+    let Some(function_node) = field!(recover, ctx, node, "function") else {
+        return Ok(None);
+    };
+    let Some(function) = parse_atom(ctx, &function_node)? else {
+        return Ok(None);
+    };
+
+    let mut args = Vec::new();
+    for arg_node in node.children_by_field_name("argument", &mut node.walk()) {
+        if arg_node.kind() == "wildcard" {
+            args.push(None);
+            continue;
+        }
+
+        ctx.typechecking_context = TypecheckingContext::Unknown;
+        let Some(arg) = parse_expression(ctx, arg_node)? else {
+            return Ok(None);
+        };
+        args.push(Some(arg));
+    }
+
+    ctx.typechecking_context = saved_context;
+
+    let is_relation = matches!(
+        function
+            .domain_of()
+            .and_then(|domain| domain.resolve().ok())
+            .as_deref(),
+        Some(GroundDomain::Relation(_, _))
+    );
+    if is_relation {
+        return Ok(Some(Expression::RelationProj(
+            Metadata::new(),
+            Moo::new(function),
+            args,
+        )));
+    }
+
+    match <[Option<Expression>; 1]>::try_from(args) {
+        Ok([Some(arg)]) => Ok(Some(Expression::Image(
+            Metadata::new(),
+            Moo::new(function),
+            Moo::new(arg),
+        ))),
+        _ => {
+            ctx.record_error(RecoverableParseError::new(
+                format!(
+                    "Expected a single argument when applying '{}', as it is not a relation",
+                    &ctx.source_code[function_node.start_byte()..function_node.end_byte()]
+                ),
+                Some(node.range()),
+            ));
+            Ok(None)
+        }
+    }
+}
+
 fn parse_table(ctx: &mut ParseContext, node: &Node) -> Result<Option<Expression>, FatalParseError> {
     // add error and return early if we're in a set context, since tables aren't allowed there
     if ctx.typechecking_context == TypecheckingContext::Set {
@@ -338,7 +584,8 @@ fn parse_variable(ctx: &mut ParseContext, node: &Node) -> Result<Option<Atom>, F
                 ty: decl.domain().map(|d| d.to_string()),
                 decl_span: ctx.lookup_decl_span(&name),
             };
-            span_with_hover(node, ctx.source_code, ctx.source_map, hover);
+            let span_id = span_with_hover(node, ctx.source_code, ctx.source_map, hover);
+            ctx.source_map.record_reference(span_id, &decl);
 
             // Type check the variable against the expected context
             if let Some(error_msg) = typecheck_variable(&decl, ctx.typechecking_context, raw_name) {
@@ -411,11 +658,14 @@ fn typecheck_variable(
         GroundDomain::Partition(_, _) => "partition",
         GroundDomain::Sequence(_, _) => "sequence",
         GroundDomain::Empty(_) => "empty",
+        GroundDomain::Enum(_, _) => "enum",
     };
 
     // If types match, no error
+    // (values of enumerated types are ordered, so they can be compared like integers)
     if expected == actual
         || (context == TypecheckingContext::SetOrMatrix && matches!(actual, "set" | "matrix"))
+        || (context == TypecheckingContext::Arithmetic && actual == "enum")
     {
         return None;
     }
@@ -493,6 +743,7 @@ fn parse_constant(ctx: &mut ParseContext, node: &Node) -> Result<Option<Literal>
         let actual = match &lit {
             Literal::Bool(_) => "bool",
             Literal::Int(_) => "int",
+            Literal::Enum(_) => "enum",
             Literal::AbstractLiteral(_) => return Ok(None), // Abstract literals aren't type-checked here
         };
 
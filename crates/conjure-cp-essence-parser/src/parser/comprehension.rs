@@ -1,4 +1,6 @@
 use crate::RecoverableParseError;
+use crate::diagnostics::diagnostics_api::SymbolKind;
+use crate::diagnostics::source_map::{HoverInfo, span_with_hover};
 use crate::errors::FatalParseError;
 use crate::expression::parse_expression;
 use crate::field;
@@ -71,8 +73,9 @@ pub fn parse_comprehension(
                 };
 
                 // Add generator using the builder
-                let decl = DeclarationPtr::new_find(var_name, var_domain);
+                let decl = DeclarationPtr::new_find(var_name.clone(), var_domain);
                 builder = builder.generator(decl);
+                record_generator_variable(ctx, &mut builder, &var_node, &var_name);
             }
             "condition" => {
                 // Parse the condition expression
@@ -127,6 +130,32 @@ pub fn parse_comprehension(
     )))
 }
 
+/// Adds a span for the variable of a generator, linked to the quantified declaration that the
+/// builder made for it, so that references in the comprehension resolve to it.
+fn record_generator_variable(
+    ctx: &mut ParseContext,
+    builder: &mut ComprehensionBuilder,
+    var_node: &Node,
+    var_name: &Name,
+) {
+    let Some(decl) = builder
+        .generator_symboltable()
+        .read()
+        .lookup_local(var_name)
+    else {
+        return;
+    };
+    let hover = HoverInfo {
+        description: format!("Quantified variable: {var_name}"),
+        doc_key: None,
+        kind: Some(SymbolKind::FindVar),
+        ty: decl.domain().map(|d| d.to_string()),
+        decl_span: None,
+    };
+    let span_id = span_with_hover(var_node, ctx.source_code, ctx.source_map, hover);
+    ctx.source_map.record_declaration(span_id, &decl);
+}
+
 /// Parse comprehension-style expressions
 /// - `forAll vars : domain . expr` → `And(Comprehension(...))`
 /// - `sum vars : domain . expr` → `Sum(Comprehension(...))`
@@ -159,7 +188,7 @@ pub fn parse_quantifier_or_aggregate_expr(
             "identifier" => {
                 let var_name_str = &ctx.source_code[child.start_byte()..child.end_byte()];
                 let var_name = Name::user(var_name_str);
-                variables.push(var_name);
+                variables.push((var_name, child));
             }
             "domain" => {
                 // Parse domains under Unknown context so arithmetic bounds in domains
@@ -227,9 +256,10 @@ pub fn parse_quantifier_or_aggregate_expr(
 
     // Add variables as generators
     if let Some(dom) = domain {
-        for var_name in variables {
-            let decl = DeclarationPtr::new_find(var_name, dom.clone());
+        for (var_name, var_node) in variables {
+            let decl = DeclarationPtr::new_find(var_name.clone(), dom.clone());
             builder = builder.generator(decl);
+            record_generator_variable(ctx, &mut builder, &var_node, &var_name);
         }
     } else if let Some(_coll_node) = collection_node {
         // TODO: support collection domains
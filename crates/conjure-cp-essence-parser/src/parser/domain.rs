@@ -7,7 +7,9 @@ use crate::expression::parse_expression;
 use crate::parser::ParseContext;
 use crate::{RecoverableParseError, child};
 use conjure_cp_core::ast::{
-    DeclarationPtr, Domain, DomainPtr, Field, IntVal, Moo, Name, Range, Reference, SetAttr,
+    BinaryAttr, DeclarationPtr, Domain, DomainPtr, Field, FuncAttr, IntVal, JectivityAttr,
+    MSetAttr, Moo, Name, PartialityAttr, PartitionAttr, Range, Reference, RelAttr, SequenceAttr,
+    SetAttr,
 };
 use tree_sitter::Node;
 
@@ -41,7 +43,7 @@ pub fn parse_domain(
             let Some(decl) = get_declaration_ptr_from_identifier(ctx, domain)? else {
                 return Ok(None);
             };
-            let Some(dom) = Domain::reference(decl) else {
+            let Some(dom) = Domain::reference(decl.clone()) else {
                 ctx.record_error(crate::errors::RecoverableParseError::new(
                     format!(
                         "The identifier '{}' is not a valid domain",
@@ -54,7 +56,7 @@ pub fn parse_domain(
             let name = &ctx.source_code[domain.start_byte()..domain.end_byte()];
 
             // Not form docs, because we need context specific hover info
-            span_with_hover(
+            let span_id = span_with_hover(
                 &domain,
                 ctx.source_code,
                 ctx.source_map,
@@ -66,12 +68,19 @@ pub fn parse_domain(
                     decl_span: None, // could link to the declaration span if we wanted
                 },
             );
+            ctx.source_map.record_reference(span_id, &decl);
             Ok(Some(dom))
         }
         "tuple_domain" => parse_tuple_domain(ctx, domain),
         "matrix_domain" => parse_matrix_domain(ctx, domain),
         "record_domain" => parse_record_domain(ctx, domain),
         "set_domain" => parse_set_domain(ctx, domain),
+        "mset_domain" => parse_mset_domain(ctx, domain),
+        "sequence_domain" => parse_sequence_domain(ctx, domain),
+        "function_domain" => parse_function_domain(ctx, domain),
+        "relation_domain" => parse_relation_domain(ctx, domain),
+        "partition_domain" => parse_partition_domain(ctx, domain),
+        "variant_domain" => parse_variant_domain(ctx, domain),
         _ => {
             ctx.record_error(RecoverableParseError::new(
                 format!("{} is not a supported domain type", domain.kind()),
@@ -421,3 +430,354 @@ pub fn parse_set_domain(
         Ok(None)
     }
 }
+
+pub fn parse_mset_domain(
+    ctx: &mut ParseContext,
+    mset_domain: Node,
+) -> Result<Option<DomainPtr>, FatalParseError> {
+    let Some(attrs) = parse_domain_attributes(
+        ctx,
+        &mset_domain,
+        &["size", "minSize", "maxSize", "minOccur", "maxOccur"],
+    ) else {
+        return Ok(None);
+    };
+    let Some(value_domain) = parse_value_domain(ctx, &mset_domain)? else {
+        return Ok(None);
+    };
+
+    let mset_keyword_node = child!(mset_domain, 0, "mset");
+    // No documentation available for mset domain, using fallback description
+    ctx.add_span_and_doc_hover(&mset_keyword_node, "mset", SymbolKind::Domain, None, None);
+    let attr = MSetAttr::new(
+        attribute_range(attrs.size),
+        attribute_range(attrs.occurrence),
+    );
+    Ok(Some(Domain::mset(attr, value_domain)))
+}
+
+fn parse_sequence_domain(
+    ctx: &mut ParseContext,
+    sequence_domain: Node,
+) -> Result<Option<DomainPtr>, FatalParseError> {
+    let Some(attrs) = parse_domain_attributes(
+        ctx,
+        &sequence_domain,
+        &[
+            "size",
+            "minSize",
+            "maxSize",
+            "injective",
+            "surjective",
+            "bijective",
+        ],
+    ) else {
+        return Ok(None);
+    };
+    let Some(value_domain) = parse_value_domain(ctx, &sequence_domain)? else {
+        return Ok(None);
+    };
+
+    let sequence_keyword_node = child!(sequence_domain, 0, "sequence");
+    ctx.add_span_and_doc_hover(
+        &sequence_keyword_node,
+        "sequence",
+        SymbolKind::Domain,
+        None,
+        None,
+    );
+    let attr = SequenceAttr {
+        size: attribute_range(attrs.size),
+        jectivity: attrs.jectivity.unwrap_or(JectivityAttr::None),
+    };
+    Ok(Some(Domain::sequence(attr, value_domain)))
+}
+
+fn parse_function_domain(
+    ctx: &mut ParseContext,
+    function_domain: Node,
+) -> Result<Option<DomainPtr>, FatalParseError> {
+    let Some(attrs) = parse_domain_attributes(
+        ctx,
+        &function_domain,
+        &[
+            "size",
+            "minSize",
+            "maxSize",
+            "total",
+            "partial",
+            "injective",
+            "surjective",
+            "bijective",
+        ],
+    ) else {
+        return Ok(None);
+    };
+
+    let Some(domain_node) = field!(recover, ctx, function_domain, "domain") else {
+        return Ok(None);
+    };
+    let Some(domain) = parse_domain(ctx, domain_node)? else {
+        return Ok(None);
+    };
+    let Some(codomain_node) = field!(recover, ctx, function_domain, "codomain") else {
+        return Ok(None);
+    };
+    let Some(codomain) = parse_domain(ctx, codomain_node)? else {
+        return Ok(None);
+    };
+
+    let function_keyword_node = child!(function_domain, 0, "function");
+    ctx.add_span_and_doc_hover(
+        &function_keyword_node,
+        "function",
+        SymbolKind::Domain,
+        None,
+        None,
+    );
+    let attr = FuncAttr {
+        size: attribute_range(attrs.size),
+        partiality: if attrs.total {
+            PartialityAttr::Total
+        } else {
+            PartialityAttr::Partial
+        },
+        jectivity: attrs.jectivity.unwrap_or(JectivityAttr::None),
+    };
+    Ok(Some(Domain::function(attr, domain, codomain)))
+}
+
+fn parse_relation_domain(
+    ctx: &mut ParseContext,
+    relation_domain: Node,
+) -> Result<Option<DomainPtr>, FatalParseError> {
+    let Some(attrs) = parse_domain_attributes(
+        ctx,
+        &relation_domain,
+        &[
+            "size",
+            "minSize",
+            "maxSize",
+            "reflexive",
+            "irreflexive",
+            "coreflexive",
+            "symmetric",
+            "antiSymmetric",
+            "aSymmetric",
+            "transitive",
+            "total",
+            "connex",
+            "Euclidean",
+            "serial",
+            "equivalence",
+            "partialOrder",
+        ],
+    ) else {
+        return Ok(None);
+    };
+
+    let mut domains: Vec<DomainPtr> = Vec::new();
+    for domain in relation_domain.children_by_field_name("domain", &mut relation_domain.walk()) {
+        let Some(parsed_domain) = parse_domain(ctx, domain)? else {
+            return Ok(None);
+        };
+        domains.push(parsed_domain);
+    }
+
+    let relation_keyword_node = child!(relation_domain, 0, "relation");
+    ctx.add_span_and_doc_hover(
+        &relation_keyword_node,
+        "relation",
+        SymbolKind::Domain,
+        None,
+        None,
+    );
+    let attr = RelAttr {
+        size: attribute_range(attrs.size),
+        binary: attrs.binary,
+    };
+    Ok(Some(Domain::relation(attr, domains)))
+}
+
+fn parse_partition_domain(
+    ctx: &mut ParseContext,
+    partition_domain: Node,
+) -> Result<Option<DomainPtr>, FatalParseError> {
+    let Some(attrs) = parse_domain_attributes(
+        ctx,
+        &partition_domain,
+        &[
+            "numParts",
+            "minNumParts",
+            "maxNumParts",
+            "partSize",
+            "minPartSize",
+            "maxPartSize",
+            "regular",
+        ],
+    ) else {
+        return Ok(None);
+    };
+    let Some(value_domain) = parse_value_domain(ctx, &partition_domain)? else {
+        return Ok(None);
+    };
+
+    let partition_keyword_node = child!(partition_domain, 0, "partition");
+    ctx.add_span_and_doc_hover(
+        &partition_keyword_node,
+        "partition",
+        SymbolKind::Domain,
+        None,
+        None,
+    );
+    let attr = PartitionAttr {
+        num_parts: attribute_range(attrs.num_parts),
+        part_len: attribute_range(attrs.part_size),
+        is_regular: attrs.regular,
+    };
+    Ok(Some(Domain::partition(attr, value_domain)))
+}
+
+fn parse_variant_domain(
+    ctx: &mut ParseContext,
+    variant_domain: Node,
+) -> Result<Option<DomainPtr>, FatalParseError> {
+    let mut variant_entries: Vec<Field<DomainPtr>> = Vec::new();
+    for variant_entry in named_children(&variant_domain) {
+        let Some(name_node) = field!(recover, ctx, variant_entry, "name") else {
+            return Ok(None);
+        };
+        let name = Name::user(&ctx.source_code[name_node.start_byte()..name_node.end_byte()]);
+        let Some(domain_node) = field!(recover, ctx, variant_entry, "domain") else {
+            return Ok(None);
+        };
+        let Some(value) = parse_domain(ctx, domain_node)? else {
+            return Ok(None);
+        };
+        variant_entries.push(Field { name, value });
+    }
+
+    let variant_keyword_node = child!(variant_domain, 0, "variant");
+    ctx.add_span_and_doc_hover(
+        &variant_keyword_node,
+        "variant",
+        SymbolKind::Domain,
+        None,
+        None,
+    );
+    Ok(Some(Domain::variant(variant_entries)))
+}
+
+/// Parses the `value_domain` field of an mset, sequence or partition domain.
+fn parse_value_domain(
+    ctx: &mut ParseContext,
+    domain: &Node,
+) -> Result<Option<DomainPtr>, FatalParseError> {
+    let Some(value_domain_node) = field!(recover, ctx, domain, "value_domain") else {
+        return Ok(None);
+    };
+    parse_domain(ctx, value_domain_node)
+}
+
+/// The attributes given to an mset, sequence, function, relation or partition domain.
+///
+/// Sizes are stored as a pair of min and max attributes, see [attribute_range].
+#[derive(Default)]
+struct DomainAttributes {
+    size: (Option<i32>, Option<i32>),
+    occurrence: (Option<i32>, Option<i32>),
+    num_parts: (Option<i32>, Option<i32>),
+    part_size: (Option<i32>, Option<i32>),
+    total: bool,
+    jectivity: Option<JectivityAttr>,
+    regular: bool,
+    binary: Vec<BinaryAttr>,
+}
+
+/// Parses the attributes of a domain, recording an error for any not in `allowed`.
+fn parse_domain_attributes(
+    ctx: &mut ParseContext,
+    domain: &Node,
+    allowed: &[&str],
+) -> Option<DomainAttributes> {
+    let mut attrs = DomainAttributes::default();
+    let Some(attributes) = named_children(domain).find(|x| x.kind() == "domain_attributes") else {
+        return Some(attrs);
+    };
+
+    let source_code = ctx.source_code;
+    for attribute in attributes.children_by_field_name("attribute", &mut attributes.walk()) {
+        let name_node = field!(recover, ctx, attribute, "name")?;
+        let name = &source_code[name_node.start_byte()..name_node.end_byte()];
+        if !allowed.contains(&name) {
+            ctx.record_error(RecoverableParseError::new(
+                format!(
+                    "The attribute '{name}' is not allowed for {} domains",
+                    domain.kind().trim_end_matches("_domain")
+                ),
+                Some(name_node.range()),
+            ));
+            return None;
+        }
+
+        let value = match attribute.child_by_field_name("value") {
+            Some(value_node) => parse_int(ctx, &value_node)?,
+            None => 0,
+        };
+        match name {
+            "size" => attrs.size = (Some(value), Some(value)),
+            "minSize" => attrs.size.0 = Some(value),
+            "maxSize" => attrs.size.1 = Some(value),
+            "minOccur" => attrs.occurrence.0 = Some(value),
+            "maxOccur" => attrs.occurrence.1 = Some(value),
+            "numParts" => attrs.num_parts = (Some(value), Some(value)),
+            "minNumParts" => attrs.num_parts.0 = Some(value),
+            "maxNumParts" => attrs.num_parts.1 = Some(value),
+            "partSize" => attrs.part_size = (Some(value), Some(value)),
+            "minPartSize" => attrs.part_size.0 = Some(value),
+            "maxPartSize" => attrs.part_size.1 = Some(value),
+            "partial" => {}
+            "injective" => attrs.jectivity = Some(JectivityAttr::Injective),
+            "surjective" => attrs.jectivity = Some(JectivityAttr::Surjective),
+            "bijective" => attrs.jectivity = Some(JectivityAttr::Bijective),
+            "regular" => attrs.regular = true,
+            "total" => {
+                // total is a partiality for functions, but a binary attribute for relations
+                attrs.total = true;
+                attrs.binary.push(BinaryAttr::Total);
+            }
+            "reflexive" => attrs.binary.push(BinaryAttr::Reflexive),
+            "irreflexive" => attrs.binary.push(BinaryAttr::Irreflexive),
+            "coreflexive" => attrs.binary.push(BinaryAttr::Coreflexive),
+            "symmetric" => attrs.binary.push(BinaryAttr::Symmetric),
+            "antiSymmetric" => attrs.binary.push(BinaryAttr::AntiSymmetric),
+            "aSymmetric" => attrs.binary.push(BinaryAttr::ASymmetric),
+            "transitive" => attrs.binary.push(BinaryAttr::Transitive),
+            "connex" => attrs.binary.push(BinaryAttr::Connex),
+            "Euclidean" => attrs.binary.push(BinaryAttr::Euclidean),
+            "serial" => attrs.binary.push(BinaryAttr::Serial),
+            "equivalence" => attrs.binary.push(BinaryAttr::Equivalence),
+            "partialOrder" => attrs.binary.push(BinaryAttr::PartialOrder),
+            other => {
+                ctx.record_error(RecoverableParseError::new(
+                    format!("Unrecognized domain attribute: {other}"),
+                    Some(name_node.range()),
+                ));
+                return None;
+            }
+        }
+    }
+
+    Some(attrs)
+}
+
+/// Builds the range given by a pair of min and max attributes, such as minSize and maxSize.
+fn attribute_range((min, max): (Option<i32>, Option<i32>)) -> Range<i32> {
+    match (min, max) {
+        (Some(min), Some(max)) if min == max => Range::Single(min),
+        (Some(min), Some(max)) => Range::Bounded(min, max),
+        (Some(min), None) => Range::UnboundedR(min),
+        (None, Some(max)) => Range::UnboundedL(max),
+        (None, None) => Range::Unbounded,
+    }
+}
@@ -49,6 +49,7 @@ pub fn parse_dominance_relation(
         decl_spans: ctx.decl_spans,
         typechecking_context: TypecheckingContext::Unknown,
         inner_typechecking_context: TypecheckingContext::Unknown,
+        record_expression_spans: ctx.record_expression_spans,
     };
 
     let Some(inner) = parse_expression(&mut inner_ctx, inner_node)? else {
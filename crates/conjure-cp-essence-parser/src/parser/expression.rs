@@ -5,13 +5,21 @@ use crate::parser::atom::parse_atom;
 use crate::parser::comprehension::parse_quantifier_or_aggregate_expr;
 use crate::util::TypecheckingContext;
 use crate::{child, field, named_child};
-use conjure_cp_core::ast::{Expression, GroundDomain, Metadata, Moo};
+use conjure_cp_core::ast::{Expression, GroundDomain, Metadata, Moo, ReturnType, Typeable};
 use conjure_cp_core::{domain_int, matrix_expr, range};
 use tree_sitter::Node;
 
 pub fn parse_expression(
     ctx: &mut ParseContext,
     node: Node,
+) -> Result<Option<Expression>, FatalParseError> {
+    let expr = parse_expression_kind(ctx, node)?;
+    Ok(expr.map(|expr| ctx.record_expression_span(expr, &node)))
+}
+
+fn parse_expression_kind(
+    ctx: &mut ParseContext,
+    node: Node,
 ) -> Result<Option<Expression>, FatalParseError> {
     match node.kind() {
         "atom" => parse_atom(ctx, &node),
@@ -86,7 +94,12 @@ fn parse_arithmetic_expression(
     };
     match inner.kind() {
         "atom" => parse_atom(ctx, &inner),
-        "negative_expr" | "abs_value" | "sub_arith_expr" | "factorial_expr" => {
+        "abs_value" => {
+            // |x| is also the size of a collection, so the operand does not have to be arithmetic
+            ctx.typechecking_context = TypecheckingContext::Unknown;
+            parse_unary_expression(ctx, &inner)
+        }
+        "negative_expr" | "sub_arith_expr" | "factorial_expr" => {
             parse_unary_expression(ctx, &inner)
         }
         "toInt_expr" => {
@@ -107,6 +120,7 @@ fn parse_arithmetic_expression(
             ctx.inner_typechecking_context = TypecheckingContext::Arithmetic;
             parse_quantifier_or_aggregate_expr(ctx, &inner)
         }
+        "freq_expr" => parse_freq_expression(ctx, &inner),
         _ => {
             ctx.record_error(RecoverableParseError::new(
                 format!("Expected arithmetic expression, found: {}", inner.kind()),
@@ -145,6 +159,11 @@ fn parse_comparison_expression(
             ctx.typechecking_context = TypecheckingContext::Set;
             parse_binary_expression(ctx, &inner)
         }
+        "sequence_comparison" => {
+            // Sequence comparisons require sequence operands
+            ctx.typechecking_context = TypecheckingContext::Sequence;
+            parse_binary_expression(ctx, &inner)
+        }
         "all_diff_comparison" => {
             ctx.typechecking_context = TypecheckingContext::Matrix;
             parse_all_diff_comparison(ctx, &inner)
@@ -238,6 +257,35 @@ fn parse_list_combining_expression(
     expr
 }
 
+fn parse_freq_expression(
+    ctx: &mut ParseContext,
+    node: &Node,
+) -> Result<Option<Expression>, FatalParseError> {
+    let Some(mset_node) = field!(recover, ctx, node, "mset") else {
+        return Ok(None);
+    };
+    ctx.typechecking_context = TypecheckingContext::MSet;
+    let Some(mset) = parse_atom(ctx, &mset_node)? else {
+        return Ok(None);
+    };
+
+    let Some(element_node) = field!(recover, ctx, node, "element") else {
+        return Ok(None);
+    };
+    ctx.typechecking_context = TypecheckingContext::Unknown;
+    let Some(element) = parse_expression(ctx, element_node)? else {
+        return Ok(None);
+    };
+
+    let freq_keyword_node = child!(node, 0, "freq");
+    ctx.add_span_and_doc_hover(&freq_keyword_node, "freq", SymbolKind::Function, None, None);
+    Ok(Some(Expression::Freq(
+        Metadata::new(),
+        Moo::new(mset),
+        Moo::new(element),
+    )))
+}
+
 fn parse_all_diff_comparison(
     ctx: &mut ParseContext,
     node: &Node,
@@ -273,7 +321,22 @@ fn parse_unary_expression(
 
     match node.kind() {
         "negative_expr" => Ok(Some(Expression::Neg(Metadata::new(), Moo::new(inner)))),
-        "abs_value" => Ok(Some(Expression::Abs(Metadata::new(), Moo::new(inner)))),
+        "abs_value" => match inner.return_type() {
+            ReturnType::Int | ReturnType::Unknown => {
+                Ok(Some(Expression::Abs(Metadata::new(), Moo::new(inner))))
+            }
+            ReturnType::Bool => {
+                ctx.record_error(RecoverableParseError::new(
+                    format!(
+                        "Type error: {}\n\tExpected: int or collection\n\tGot: bool",
+                        &ctx.source_code[expr_node.start_byte()..expr_node.end_byte()]
+                    ),
+                    Some(expr_node.range()),
+                ));
+                Ok(None)
+            }
+            _ => Ok(Some(Expression::Card(Metadata::new(), Moo::new(inner)))),
+        },
         "not_expr" => Ok(Some(Expression::Not(Metadata::new(), Moo::new(inner)))),
         "toInt_expr" => {
             let to_int_keyword_node = child!(node, 0, "toInt");
@@ -333,6 +396,16 @@ pub fn parse_binary_expression(
         ctx.typechecking_context = TypecheckingContext::Unknown
     }
 
+    // Set operators also work on msets, so work out which from the operands
+    let is_set_operator = saved_ctx == TypecheckingContext::Set
+        && matches!(
+            op_str,
+            "subset" | "subsetEq" | "supset" | "supsetEq" | "union" | "intersect"
+        );
+    if is_set_operator {
+        ctx.typechecking_context = TypecheckingContext::Unknown
+    }
+
     // parse left operand
     let Some(left_node) = field!(recover, ctx, node, "left") else {
         return Ok(None);
@@ -349,6 +422,20 @@ pub fn parse_binary_expression(
         ctx.typechecking_context = inferred_context_from_expression(&left);
     }
 
+    // the right operand must be the same kind of collection as the left
+    if is_set_operator {
+        let Some(left_ctx) = typecheck_set_or_mset(ctx, &left_node, &left) else {
+            return Ok(None);
+        };
+        ctx.typechecking_context = left_ctx;
+    }
+
+    // the right operand of 'in' can be a set or an mset
+    let is_membership = op_str == "in" && saved_ctx == TypecheckingContext::Set;
+    if is_membership {
+        ctx.typechecking_context = TypecheckingContext::Unknown;
+    }
+
     // parse right operand
     let Some(right_node) = field!(recover, ctx, node, "right") else {
         return Ok(None);
@@ -357,6 +444,10 @@ pub fn parse_binary_expression(
         return Ok(None);
     };
 
+    if is_membership && typecheck_set_or_mset(ctx, &right_node, &right).is_none() {
+        return Ok(None);
+    }
+
     // restore original contexts for parent expression parsing
     ctx.typechecking_context = saved_ctx;
 
@@ -583,6 +674,22 @@ pub fn parse_binary_expression(
                 Moo::new(right),
             )))
         }
+        "subsequence" => {
+            doc_name = "subsequence";
+            Ok(Some(Expression::Subsequence(
+                Metadata::new(),
+                Moo::new(left),
+                Moo::new(right),
+            )))
+        }
+        "substring" => {
+            doc_name = "substring";
+            Ok(Some(Expression::Substring(
+                Metadata::new(),
+                Moo::new(left),
+                Moo::new(right),
+            )))
+        }
         _ => {
             ctx.record_error(RecoverableParseError::new(
                 format!("Invalid operator: '{op_str}'"),
@@ -599,6 +706,31 @@ pub fn parse_binary_expression(
     expr
 }
 
+/// Checks that `expr` is a set or an mset, returning the typechecking context for it.
+///
+/// Records a type error and returns `None` if it is neither.
+fn typecheck_set_or_mset(
+    ctx: &mut ParseContext,
+    node: &Node,
+    expr: &Expression,
+) -> Option<TypecheckingContext> {
+    match inferred_context_from_expression(expr) {
+        TypecheckingContext::MSet => Some(TypecheckingContext::MSet),
+        TypecheckingContext::Set | TypecheckingContext::Unknown => Some(TypecheckingContext::Set),
+        _ => {
+            ctx.record_error(RecoverableParseError::new(
+                format!(
+                    "Type error: {}\n\tExpected: set or mset\n\tGot: {}",
+                    &ctx.source_code[node.start_byte()..node.end_byte()],
+                    expr.return_type()
+                ),
+                Some(node.range()),
+            ));
+            None
+        }
+    }
+}
+
 fn inferred_context_from_expression(expr: &Expression) -> TypecheckingContext {
     // TODO: typechecking for index/slice expressions
     if matches!(
@@ -628,6 +760,7 @@ fn inferred_context_from_expression(expr: &Expression) -> TypecheckingContext {
         GroundDomain::Function(_, _, _)
         | GroundDomain::Variant(_)
         | GroundDomain::Relation(_, _)
+        | GroundDomain::Enum(_, _)
         | GroundDomain::Empty(_) => TypecheckingContext::Unknown,
     }
 }
@@ -50,6 +50,14 @@ pub fn parse_given_statement(
         },
     );
 
+    if let Some(enum_declaration) = given_statement.child_by_field_name("enum_declaration") {
+        ctx.record_error(RecoverableParseError::new(
+            "Enumerated types are not supported yet".to_string(),
+            Some(enum_declaration.range()),
+        ));
+        return Ok(BTreeMap::new());
+    }
+
     let mut var_hashmap = BTreeMap::new();
     for var_decl in named_children(&given_statement) {
         if let Ok(mut decls) = parse_declaration_statement(ctx, var_decl, SymbolKind::GivenVar) {
@@ -1,4 +1,4 @@
-const KEYWORDS: [&str; 24] = [
+pub const KEYWORDS: [&str; 24] = [
     "forall",
     "exists",
     "such",
@@ -25,6 +25,91 @@ const KEYWORDS: [&str; 24] = [
     "maximising",
 ];
 
+/// Keywords that begin a top-level statement.
+pub const STATEMENT_KEYWORDS: [&str; 10] = [
+    "find",
+    "given",
+    "letting",
+    "such that",
+    "where",
+    "minimising",
+    "maximising",
+    "branching on",
+    "heuristic",
+    "dominance relation",
+];
+
+/// Keywords that begin an expression: quantifiers, aggregates and built-in operators.
+pub const EXPRESSION_KEYWORDS: [&str; 33] = [
+    "forAll",
+    "exists",
+    "sum",
+    "min",
+    "max",
+    "and",
+    "or",
+    "allDiff",
+    "table",
+    "negativeTable",
+    "toInt",
+    "freq",
+    "flatten",
+    "fromSolution",
+    "active",
+    "defined",
+    "range",
+    "toSet",
+    "toMSet",
+    "toRelation",
+    "participants",
+    "parts",
+    "image",
+    "imageSet",
+    "preImage",
+    "inverse",
+    "restrict",
+    "party",
+    "together",
+    "apart",
+    "true",
+    "false",
+    "pareto",
+];
+
+/// Operators written between (or before) their operands.
+pub const OPERATORS: [&str; 30] = [
+    "/\\",
+    "\\/",
+    "->",
+    "<->",
+    "!",
+    "=",
+    "!=",
+    "<",
+    "<=",
+    ">",
+    ">=",
+    "+",
+    "-",
+    "*",
+    "/",
+    "%",
+    "**",
+    "in",
+    "subset",
+    "subsetEq",
+    "supset",
+    "supsetEq",
+    "subsequence",
+    "substring",
+    "union",
+    "intersect",
+    "<lex",
+    "<=lex",
+    ">lex",
+    ">=lex",
+];
+
 pub fn is_keyword_identifier(identifier: &str) -> bool {
     KEYWORDS.contains(&identifier)
 }
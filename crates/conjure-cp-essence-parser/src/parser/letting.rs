@@ -11,7 +11,8 @@ use crate::diagnostics::diagnostics_api::SymbolKind;
 use crate::diagnostics::source_map::{HoverInfo, span_with_hover};
 use crate::errors::{FatalParseError, RecoverableParseError};
 use crate::expression::parse_expression;
-use conjure_cp_core::ast::DeclarationPtr;
+use crate::parser::util::TypecheckingContext;
+use conjure_cp_core::ast::{DeclarationPtr, Domain, Expression, Literal, eval_constant};
 use conjure_cp_core::ast::{Name, SymbolTable};
 
 /// Parse a letting statement into a SymbolTable containing the declared symbols
@@ -102,6 +103,20 @@ pub fn parse_letting_statement(
             ctx.save_decl_span(name, span_id);
         }
 
+        if let Some(new_type) = variable_decl.child_by_field_name("new_type") {
+            for name in temp_symbols {
+                let Some(decls) = parse_new_type(ctx, new_type, Name::user(name), &symbol_table)?
+                else {
+                    continue;
+                };
+                for decl in decls {
+                    ctx.record_declaration(&decl);
+                    symbol_table.insert(decl);
+                }
+            }
+            continue;
+        }
+
         let Some(expr_or_domain) = field!(recover, ctx, variable_decl, "expr_or_domain") else {
             return Ok(None);
         };
@@ -112,17 +127,111 @@ pub fn parse_letting_statement(
                     continue;
                 };
 
-                symbol_table.insert(DeclarationPtr::new_domain_letting(Name::user(name), domain));
+                let decl = DeclarationPtr::new_domain_letting(Name::user(name), domain);
+                ctx.record_declaration(&decl);
+                symbol_table.insert(decl);
             }
         } else {
             for name in temp_symbols {
                 let Some(expr) = parse_expression(ctx, expr_or_domain)? else {
                     continue;
                 };
-                symbol_table.insert(DeclarationPtr::new_value_letting(Name::user(name), expr));
+                let decl = DeclarationPtr::new_value_letting(Name::user(name), expr);
+                ctx.record_declaration(&decl);
+                symbol_table.insert(decl);
             }
         }
     }
 
     Ok(Some(symbol_table))
 }
+
+/// Parse a `new type enum {a, b, c}` or `new type of size n` declaration of the type `name`.
+///
+/// Returns the declarations of the type itself, and of the values of an enumerated type. The
+/// values of an unnamed type cannot be referred to, so are only given names for printing
+/// solutions.
+fn parse_new_type(
+    ctx: &mut ParseContext,
+    new_type: Node,
+    name: Name,
+    symbol_table: &SymbolTable,
+) -> Result<Option<Vec<DeclarationPtr>>, FatalParseError> {
+    if let Some(size_node) = new_type.child_by_field_name("size") {
+        let saved_context = ctx.typechecking_context;
+        ctx.typechecking_context = TypecheckingContext::Arithmetic;
+        let size = parse_expression(ctx, size_node)?;
+        ctx.typechecking_context = saved_context;
+
+        let Some(size) = size else {
+            return Ok(None);
+        };
+        let Some(Literal::Int(size)) = eval_constant(&size) else {
+            ctx.record_error(RecoverableParseError::new(
+                "The size of an unnamed type must be a constant integer".to_string(),
+                Some(size_node.range()),
+            ));
+            return Ok(None);
+        };
+
+        let values = (1..=size)
+            .map(|i| Name::user(&format!("{name}_{i}")))
+            .collect();
+        let domain = Domain::enumerated(name.clone(), values);
+        return Ok(Some(vec![DeclarationPtr::new_domain_letting(name, domain)]));
+    }
+
+    let mut values: Vec<Name> = vec![];
+    for value in new_type.children_by_field_name("value", &mut new_type.walk()) {
+        let raw_value = &ctx.source_code[value.start_byte()..value.end_byte()];
+        let value_name = Name::user(raw_value);
+
+        if is_keyword_identifier(raw_value) {
+            ctx.record_error(RecoverableParseError::new(
+                format!("Keyword '{raw_value}' used as identifier"),
+                Some(value.range()),
+            ));
+        }
+
+        let is_declared = values.contains(&value_name)
+            || symbol_table.lookup_local(&value_name).is_some()
+            || ctx
+                .symbols
+                .as_ref()
+                .is_some_and(|symbols| symbols.read().lookup(&value_name).is_some());
+        if is_declared {
+            ctx.record_error(RecoverableParseError::new(
+                format!("'{raw_value}' is already declared"),
+                Some(value.range()),
+            ));
+            continue;
+        }
+
+        let hover = HoverInfo {
+            description: format!("Value of enumerated type: {name}"),
+            doc_key: None,
+            kind: Some(SymbolKind::LettingVar),
+            ty: Some(name.to_string()),
+            decl_span: None,
+        };
+        let span_id = span_with_hover(&value, ctx.source_code, ctx.source_map, hover);
+        ctx.save_decl_span(value_name.clone(), span_id);
+        values.push(value_name);
+    }
+
+    let domain = Domain::enumerated(name.clone(), values.clone());
+    let mut decls = vec![DeclarationPtr::new_domain_letting(name.clone(), domain)];
+    for value in values {
+        // give each value an explicit domain, so that references to it can still be recognised
+        // after its definition has been rewritten
+        let literal = Literal::Enum(Box::new((name.clone(), value.clone())));
+        let value_domain = Domain::enumerated(name.clone(), vec![value.clone()]);
+        decls.push(DeclarationPtr::new_value_letting_with_domain(
+            value,
+            Expression::from(literal),
+            value_domain,
+        ));
+    }
+
+    Ok(Some(decls))
+}
@@ -8,12 +8,15 @@ pub mod find;
 pub mod keyword_checks;
 pub mod letting;
 mod macros;
+mod objective;
 pub mod parse_exprs;
 pub mod parse_literal;
 pub mod parse_model;
+mod search;
 pub mod syntax_errors;
 pub mod traversal;
 pub mod util;
+mod where_statement;
 
 pub use parse_exprs::*;
 pub use parse_literal::*;
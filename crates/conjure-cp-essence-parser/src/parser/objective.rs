@@ -0,0 +1,37 @@
+use crate::errors::FatalParseError;
+use crate::expression::parse_expression;
+use crate::field;
+use crate::parser::ParseContext;
+use crate::util::TypecheckingContext;
+use conjure_cp_core::ast::{Objective, ObjectiveDirection};
+use tree_sitter::Node;
+
+/// Parses a top-level `minimising <expr>` or `maximising <expr>` statement.
+pub fn parse_objective_statement(
+    ctx: &mut ParseContext,
+    node: &Node,
+) -> Result<Option<Objective>, FatalParseError> {
+    let direction_node = field!(node, "direction");
+    let direction_str = &ctx.source_code[direction_node.start_byte()..direction_node.end_byte()];
+    let direction = match direction_str {
+        "minimising" => ObjectiveDirection::Minimising,
+        "maximising" => ObjectiveDirection::Maximising,
+        _ => {
+            return Err(FatalParseError::internal_error(
+                format!("Unexpected objective direction: '{direction_str}'"),
+                Some(direction_node.range()),
+            ));
+        }
+    };
+
+    let Some(expression_node) = field!(recover, ctx, node, "expression") else {
+        return Ok(None);
+    };
+
+    ctx.typechecking_context = TypecheckingContext::Arithmetic;
+    let Some(expression) = parse_expression(ctx, expression_node)? else {
+        return Ok(None);
+    };
+
+    Ok(Some(Objective::new(direction, expression)))
+}
@@ -13,7 +13,10 @@ use super::ParseContext;
 use super::dominance::parse_dominance_relation;
 use super::find::{parse_find_statement, parse_given_statement};
 use super::letting::parse_letting_statement;
+use super::objective::parse_objective_statement;
+use super::search::{Heuristic, parse_branching_statement, parse_heuristic_statement};
 use super::util::{TypecheckingContext, get_tree};
+use super::where_statement::parse_where_statement;
 use crate::diagnostics::source_map::SourceMap;
 use crate::errors::{FatalParseError, ParseErrorCollection, RecoverableParseError};
 use crate::expression::parse_expression;
@@ -63,10 +66,11 @@ pub fn parse_essence_with_context(
 }
 
 /*
-    this function is used by both the file-based parser and the LSP parser (which needs the source map)
+    this function is used by both the file-based parser and the LSP parser (which needs the source map,
+    and goes through parse_essence_for_diagnostics)
     the LSP parser can also optionally pass in a pre-parsed tree to avoid parsing twice (which is how caching is implemented)
     if the tree is not passed in, we will parse it from scratch (this is what the file-based parser does)
-    when cache is dirty, LSP has to call parse_essence_with_context_and_map with None for the tree,
+    when cache is dirty, LSP has to call parse_essence_for_diagnostics with None for the tree,
     which will cause it to re-parse the source code and update the cache (Model = ast, SorceMap = map)
 */
 pub fn parse_essence_with_context_and_map(
@@ -74,6 +78,28 @@ pub fn parse_essence_with_context_and_map(
     context: Arc<RwLock<Context<'static>>>,
     errors: &mut Vec<RecoverableParseError>,
     tree: Option<&Tree>,
+) -> Result<(Option<Model>, SourceMap), FatalParseError> {
+    parse_essence_with_options(src, context, errors, tree, false)
+}
+
+/// Like [`parse_essence_with_context_and_map`], but also links each expression to its span in the
+/// source map, so that the LSP can report problems found in the model after parsing (see
+/// `check_model`).
+pub fn parse_essence_for_diagnostics(
+    src: &str,
+    context: Arc<RwLock<Context<'static>>>,
+    errors: &mut Vec<RecoverableParseError>,
+    tree: Option<&Tree>,
+) -> Result<(Option<Model>, SourceMap), FatalParseError> {
+    parse_essence_with_options(src, context, errors, tree, true)
+}
+
+fn parse_essence_with_options(
+    src: &str,
+    context: Arc<RwLock<Context<'static>>>,
+    errors: &mut Vec<RecoverableParseError>,
+    tree: Option<&Tree>,
+    record_expression_spans: bool,
 ) -> Result<(Option<Model>, SourceMap), FatalParseError> {
     let (tree, source_code) = if let Some(tree) = tree {
         (tree.clone(), src.to_string())
@@ -115,6 +141,7 @@ pub fn parse_essence_with_context_and_map(
         &mut source_map,
         &mut declaration_spans,
     );
+    ctx.record_expression_spans = record_expression_spans;
 
     let mut cursor = root_node.walk();
     for statement in root_node.children(&mut cursor) {
@@ -131,17 +158,17 @@ pub fn parse_essence_with_context_and_map(
             "find_statement" => {
                 let var_hashmap = parse_find_statement(&mut ctx, statement)?;
                 for (name, domain) in var_hashmap {
-                    model
-                        .symbols_mut()
-                        .insert(DeclarationPtr::new_find(name, domain));
+                    let decl = DeclarationPtr::new_find(name, domain);
+                    ctx.record_declaration(&decl);
+                    model.symbols_mut().insert(decl);
                 }
             }
             "given_statement" => {
                 let var_hashmap = parse_given_statement(&mut ctx, statement)?;
                 for (name, domain) in var_hashmap {
-                    model
-                        .symbols_mut()
-                        .insert(DeclarationPtr::new_given(name, domain));
+                    let decl = DeclarationPtr::new_given(name, domain);
+                    ctx.record_declaration(&decl);
+                    model.symbols_mut().insert(decl);
                 }
             }
             "bool_expr" | "atom" | "comparison_expr" => {
@@ -158,6 +185,41 @@ pub fn parse_essence_with_context_and_map(
                 };
                 model.symbols_mut().extend(letting_vars);
             }
+            "where_statement" => {
+                let conditions = parse_where_statement(&mut ctx, &statement)?;
+                model.where_conditions.extend(conditions);
+            }
+            "branching_statement" => {
+                let Some(search_order) = parse_branching_statement(&mut ctx, &statement)? else {
+                    continue;
+                };
+                if model.search_order.is_some() {
+                    ctx.record_error(RecoverableParseError::new(
+                        "Duplicate branching statement".to_string(),
+                        Some(statement.range()),
+                    ));
+                    continue;
+                }
+                model.search_order = Some(search_order);
+            }
+            "heuristic_statement" => {
+                let Some(heuristic) = parse_heuristic_statement(&mut ctx, &statement)? else {
+                    continue;
+                };
+                let heuristics = &mut model.search_heuristic;
+                let duplicate = match heuristic {
+                    Heuristic::Variable(variable) => {
+                        heuristics.variable.replace(variable).is_some()
+                    }
+                    Heuristic::Value(value) => heuristics.value.replace(value).is_some(),
+                };
+                if duplicate {
+                    ctx.record_error(RecoverableParseError::new(
+                        "Duplicate heuristic statement".to_string(),
+                        Some(statement.range()),
+                    ));
+                }
+            }
             "dominance_relation" => {
                 let Some(dominance) = parse_dominance_relation(&mut ctx, &statement)? else {
                     continue;
@@ -171,6 +233,19 @@ pub fn parse_essence_with_context_and_map(
                 }
                 model.dominance = Some(dominance);
             }
+            "objective_statement" => {
+                let Some(objective) = parse_objective_statement(&mut ctx, &statement)? else {
+                    continue;
+                };
+                if model.objective.is_some() {
+                    ctx.record_error(RecoverableParseError::new(
+                        "Duplicate objective statement".to_string(),
+                        Some(statement.range()),
+                    ));
+                    continue;
+                }
+                model.objective = Some(objective);
+            }
             _ => {
                 ctx.record_error(RecoverableParseError::new(
                     format!("Unexpected top-level statement: {}", statement.kind()),
@@ -213,7 +288,10 @@ mod test {
     #[allow(unused_imports)]
     use crate::parse_essence;
     #[allow(unused_imports)]
-    use conjure_cp_core::ast::{Atom, Expression, Metadata, Moo, Name};
+    use conjure_cp_core::ast::{
+        Atom, Expression, Metadata, Moo, Name, SearchHeuristic, SourceLocation, ValueHeuristic,
+        VariableHeuristic,
+    };
     #[allow(unused_imports)]
     use conjure_cp_core::{domain_int, matrix_expr, range};
     #[allow(unused_imports)]
@@ -416,4 +494,327 @@ mod test {
             ))
         );
     }
+
+    #[test]
+    pub fn test_parse_mset() {
+        use conjure_cp_core::ast::{AbstractLiteral, Domain, MSetAttr, Range};
+
+        let src = "
+        find m : mset (maxSize 4, minOccur 2) of int(1..3)
+        such that freq(m, 1) = 2, m subsetEq mset(1, 1, 2, 2), |m| >= 2
+        ";
+
+        let (model, _source_map) = parse_essence(src).unwrap();
+        let st = model.symbols();
+        let m = st.lookup(&Name::user("m")).unwrap();
+        assert_eq!(
+            m.domain(),
+            Some(Domain::mset(
+                MSetAttr::<i32>::new(Range::UnboundedL(4), Range::UnboundedR(2)),
+                domain_int!(1..3)
+            ))
+        );
+
+        let m_e = Expression::Atomic(Metadata::new(), Atom::new_ref(m));
+        let int = |x: i32| Expression::Atomic(Metadata::new(), x.into());
+        let constraints = model.constraints();
+        assert_eq!(
+            constraints.as_slice(),
+            [
+                Expression::Eq(
+                    Metadata::new(),
+                    Moo::new(Expression::Freq(
+                        Metadata::new(),
+                        Moo::new(m_e.clone()),
+                        Moo::new(int(1))
+                    )),
+                    Moo::new(int(2))
+                ),
+                Expression::SubsetEq(
+                    Metadata::new(),
+                    Moo::new(m_e.clone()),
+                    Moo::new(Expression::AbstractLiteral(
+                        Metadata::new(),
+                        AbstractLiteral::MSet(vec![int(1), int(1), int(2), int(2)])
+                    ))
+                ),
+                Expression::Geq(
+                    Metadata::new(),
+                    Moo::new(Expression::Card(Metadata::new(), Moo::new(m_e))),
+                    Moo::new(int(2))
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_parse_function_and_relation() {
+        use conjure_cp_core::ast::{
+            BinaryAttr, Domain, FuncAttr, JectivityAttr, PartialityAttr, Range, RelAttr,
+        };
+
+        let src = "
+        find f : function (total, injective) int(1..3) --> int(1..3)
+        find R : relation (symmetric) of (int(1..2) * int(1..2))
+        such that f(1) = 2, |R(1, _)| = 1
+        ";
+
+        let (model, _source_map) = parse_essence(src).unwrap();
+        let st = model.symbols();
+        let f = st.lookup(&Name::user("f")).unwrap();
+        let r = st.lookup(&Name::user("R")).unwrap();
+        assert_eq!(
+            f.domain(),
+            Some(Domain::function(
+                FuncAttr::<i32> {
+                    size: Range::Unbounded,
+                    partiality: PartialityAttr::Total,
+                    jectivity: JectivityAttr::Injective,
+                },
+                domain_int!(1..3),
+                domain_int!(1..3)
+            ))
+        );
+        assert_eq!(
+            r.domain(),
+            Some(Domain::relation(
+                RelAttr::<i32> {
+                    size: Range::Unbounded,
+                    binary: vec![BinaryAttr::Symmetric],
+                },
+                vec![domain_int!(1..2), domain_int!(1..2)]
+            ))
+        );
+
+        let f_e = Expression::Atomic(Metadata::new(), Atom::new_ref(f));
+        let r_e = Expression::Atomic(Metadata::new(), Atom::new_ref(r));
+        let int = |x: i32| Expression::Atomic(Metadata::new(), x.into());
+        let constraints = model.constraints();
+        assert_eq!(
+            constraints.as_slice(),
+            [
+                Expression::Eq(
+                    Metadata::new(),
+                    Moo::new(Expression::Image(
+                        Metadata::new(),
+                        Moo::new(f_e),
+                        Moo::new(int(1))
+                    )),
+                    Moo::new(int(2))
+                ),
+                Expression::Eq(
+                    Metadata::new(),
+                    Moo::new(Expression::Card(
+                        Metadata::new(),
+                        Moo::new(Expression::RelationProj(
+                            Metadata::new(),
+                            Moo::new(r_e),
+                            vec![Some(int(1)), None]
+                        ))
+                    )),
+                    Moo::new(int(1))
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_parse_search_annotations() {
+        let src = "
+        find x, y : int(1..3)
+        branching on [y, x]
+        heuristic sdf
+        heuristic descend
+        ";
+
+        let (model, _source_map) = parse_essence(src).unwrap();
+        assert_eq!(
+            model.search_order,
+            Some(vec![Name::user("y"), Name::user("x")])
+        );
+        assert_eq!(
+            model.search_heuristic,
+            SearchHeuristic {
+                variable: Some(VariableHeuristic::Sdf),
+                value: Some(ValueHeuristic::Descend),
+            }
+        );
+    }
+
+    #[test]
+    pub fn test_parse_invalid_search_annotations() {
+        let unknown_heuristic = "
+        find x : int(1..3)
+        heuristic fastest
+        ";
+        assert!(parse_essence(unknown_heuristic).is_err());
+
+        let branching_on_letting = "
+        find x : int(1..3)
+        letting y be 2
+        branching on [x, y]
+        ";
+        assert!(parse_essence(branching_on_letting).is_err());
+    }
+
+    #[test]
+    pub fn test_parse_where() {
+        let src = "
+        given n : int
+        where n > 0, n < 10
+        ";
+
+        let (model, _source_map) = parse_essence(src).unwrap();
+        let conditions = &model.where_conditions;
+        assert_eq!(conditions.len(), 2);
+        assert_eq!(conditions[0].expression.to_string(), "(n > 0)");
+        assert_eq!(
+            conditions[1].location,
+            Some(SourceLocation {
+                line: 3,
+                column: 22
+            })
+        );
+    }
+
+    #[allow(dead_code)]
+    fn domain_and_constraints(src: &str, name: &str) -> (String, Vec<String>) {
+        let (model, _source_map) = parse_essence(src).unwrap();
+        let domain = model
+            .symbols()
+            .lookup(&Name::user(name))
+            .and_then(|decl| decl.domain())
+            .unwrap();
+        let constraints = model.constraints().iter().map(|c| c.to_string()).collect();
+        (domain.to_string(), constraints)
+    }
+
+    #[test]
+    pub fn test_parse_sequence() {
+        let src = "
+        find s : sequence (maxSize 3, injective) of int(1..3)
+        such that s(1) = 2, sequence(1) subsequence s, sequence(2, 3) substring s
+        ";
+
+        let (domain, constraints) = domain_and_constraints(src, "s");
+        assert_eq!(domain, "sequence (maxSize(3), injective) of int(1..3)");
+        assert_eq!(
+            constraints,
+            [
+                "(image(s,1) = 2)",
+                "sequence(1) subsequence s",
+                "sequence(2,3) substring s",
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_parse_partition() {
+        let src = "
+        find p : partition (numParts 2, regular) from int(1..4)
+        such that party(1, p) = {1, 2}, together({1, 2}, p), apart({1, 3}, p),
+                  |parts(p)| = 2, participants(p) = {1, 2, 3, 4}
+        ";
+
+        let (domain, constraints) = domain_and_constraints(src, "p");
+        assert_eq!(domain, "partition (numParts(2), regular) from int(1..4)");
+        assert_eq!(
+            constraints,
+            [
+                "(party(1, p) = {1,2})",
+                "together({1,2}, p)",
+                "apart({1,3}, p)",
+                "(|parts(p)| = 2)",
+                "(participants(p) = {1,2,3,4})",
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_parse_variant() {
+        let src = "
+        find v : variant {a : int(1..3), b : bool}
+        such that active(v, a)
+        ";
+
+        let (domain, constraints) = domain_and_constraints(src, "v");
+        assert_eq!(domain, "variant {a: int(1..3), b: bool}");
+        assert_eq!(constraints, ["active(v, a)"]);
+
+        let invalid_field = "
+        find v : variant {a : int(1..3), b : bool}
+        such that active(v, c)
+        ";
+        assert!(parse_essence(invalid_field).is_err());
+    }
+
+    #[test]
+    pub fn test_parse_function_operators() {
+        let src = "
+        find f : function (total) int(1..3) --> int(1..3)
+        such that defined(f) = {1, 2, 3}, range(f) subsetEq {1, 2},
+                  |toSet(f)| = 3, |toMSet(f)| = 3, |toRelation(f)| = 3,
+                  image(f, 1) = 2, imageSet(f, 1) = {2}, preImage(f, 2) = {1},
+                  inverse(f, f), |restrict(f, {1})| = 1
+        ";
+
+        let (_, constraints) = domain_and_constraints(src, "f");
+        assert_eq!(
+            constraints,
+            [
+                "(defined(f) = {1,2,3})",
+                "(range(f) subsetEq {1,2})",
+                "(|toSet(f)| = 3)",
+                "(|toMSet(f)| = 3)",
+                "(|toRelation(f)| = 3)",
+                "(image(f,1) = 2)",
+                "(imageSet(f,1) = {2})",
+                "(preImage(f,2) = {1})",
+                "inverse(f,f)",
+                "(|restrict(f,{1})| = 1)",
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_parse_new_type() {
+        let src = "
+        letting C be new type enum {red, green}
+        find x : C
+        such that x != red
+        ";
+
+        let (domain, constraints) = domain_and_constraints(src, "x");
+        assert_eq!(domain, "C");
+        assert_eq!(constraints, ["(x != red)"]);
+
+        let src = "
+        letting S be new type of size 3
+        find x : S
+        ";
+
+        let (domain, _) = domain_and_constraints(src, "x");
+        assert_eq!(domain, "S");
+    }
+
+    #[test]
+    pub fn test_parse_unsupported_operators() {
+        // dontCare and catchUndef are in the grammar, but have no expression in the AST
+        for (op, src) in [
+            ("dontCare", "find x : int(1..3) such that dontCare(x)"),
+            (
+                "catchUndef",
+                "find x : int(1..3) such that catchUndef(x, 1) = 1",
+            ),
+        ] {
+            let Err(err) = parse_essence(src) else {
+                panic!("expected {op} to be rejected");
+            };
+            assert!(
+                err.to_string()
+                    .contains(&format!("'{op}' is not supported yet")),
+                "unexpected error for {op}: {err}"
+            );
+        }
+    }
 }
@@ -0,0 +1,81 @@
+use crate::errors::{FatalParseError, RecoverableParseError};
+use crate::expression::parse_expression;
+use crate::field;
+use crate::parser::ParseContext;
+use crate::util::TypecheckingContext;
+use conjure_cp_core::ast::{Atom, Expression, Name, ValueHeuristic, VariableHeuristic};
+use tree_sitter::Node;
+
+/// A heuristic given by a `heuristic` statement.
+pub enum Heuristic {
+    Variable(VariableHeuristic),
+    Value(ValueHeuristic),
+}
+
+/// Parses a top-level `branching on [x, y, ...]` statement into the names of the decision
+/// variables to branch on.
+pub fn parse_branching_statement(
+    ctx: &mut ParseContext,
+    node: &Node,
+) -> Result<Option<Vec<Name>>, FatalParseError> {
+    let Some(variables_node) = field!(recover, ctx, node, "variables") else {
+        return Ok(None);
+    };
+
+    let mut names = vec![];
+    let elements = variables_node
+        .children_by_field_name("elements", &mut variables_node.walk())
+        .filter(|x| x.is_named())
+        .collect::<Vec<_>>();
+    for element in elements {
+        ctx.typechecking_context = TypecheckingContext::Unknown;
+        let Some(expression) = parse_expression(ctx, element)? else {
+            continue;
+        };
+
+        match expression {
+            Expression::Atomic(_, Atom::Reference(reference))
+                if reference.ptr().as_find().is_some() =>
+            {
+                names.push(reference.name().clone());
+            }
+            _ => {
+                let text = &ctx.source_code[element.start_byte()..element.end_byte()];
+                ctx.record_error(RecoverableParseError::new(
+                    format!("'branching on' expects decision variables, but got '{text}'"),
+                    Some(element.range()),
+                ));
+            }
+        }
+    }
+
+    Ok(Some(names))
+}
+
+/// Parses a top-level `heuristic <name>` statement.
+///
+/// The name is either a variable heuristic (`static`, `sdf`, `srf`, `ldf`, `wdeg`, `domoverwdeg`
+/// or `conflict`) or a value heuristic (`ascend`, `descend` or `random`).
+pub fn parse_heuristic_statement(
+    ctx: &mut ParseContext,
+    node: &Node,
+) -> Result<Option<Heuristic>, FatalParseError> {
+    let Some(heuristic_node) = field!(recover, ctx, node, "heuristic") else {
+        return Ok(None);
+    };
+    let heuristic = &ctx.source_code[heuristic_node.start_byte()..heuristic_node.end_byte()];
+
+    if let Ok(variable) = heuristic.parse() {
+        Ok(Some(Heuristic::Variable(variable)))
+    } else if let Ok(value) = heuristic.parse() {
+        Ok(Some(Heuristic::Value(value)))
+    } else {
+        ctx.record_error(RecoverableParseError::new(
+            format!(
+                "Unknown heuristic '{heuristic}': expected one of static, sdf, srf, ldf, wdeg, domoverwdeg, conflict, ascend, descend or random"
+            ),
+            Some(heuristic_node.range()),
+        ));
+        Ok(None)
+    }
+}
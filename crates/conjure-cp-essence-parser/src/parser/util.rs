@@ -6,9 +6,11 @@ use tree_sitter_essence::LANGUAGE;
 
 use super::traversal::WalkDFS;
 use crate::diagnostics::diagnostics_api::SymbolKind;
-use crate::diagnostics::source_map::{HoverInfo, SourceMap, SpanId, span_with_hover};
+use crate::diagnostics::source_map::{
+    HoverInfo, SourceMap, SpanId, alloc_node_span, span_with_hover,
+};
 use crate::errors::RecoverableParseError;
-use conjure_cp_core::ast::{Name, SymbolTablePtr};
+use conjure_cp_core::ast::{DeclarationPtr, Expression, Name, SymbolTablePtr};
 
 /// Context for parsing, containing shared state passed through parser functions.
 pub struct ParseContext<'a> {
@@ -22,6 +24,8 @@ pub struct ParseContext<'a> {
     pub typechecking_context: TypecheckingContext,
     /// What type the elements within a collection should be
     pub inner_typechecking_context: TypecheckingContext,
+    /// Whether to link expressions to their spans, for diagnostics found after parsing (LSP only)
+    pub record_expression_spans: bool,
 }
 
 impl<'a> ParseContext<'a> {
@@ -42,6 +46,7 @@ impl<'a> ParseContext<'a> {
             decl_spans,
             typechecking_context: TypecheckingContext::Unknown,
             inner_typechecking_context: TypecheckingContext::Unknown,
+            record_expression_spans: false,
         }
     }
 
@@ -60,6 +65,7 @@ impl<'a> ParseContext<'a> {
             decl_spans: self.decl_spans,
             typechecking_context: self.typechecking_context,
             inner_typechecking_context: self.inner_typechecking_context,
+            record_expression_spans: self.record_expression_spans,
         }
     }
 
@@ -71,6 +77,26 @@ impl<'a> ParseContext<'a> {
         self.decl_spans.get(name).copied()
     }
 
+    /// Links a top-level declaration to the span of its name, saved earlier by `save_decl_span`.
+    pub fn record_declaration(&mut self, decl: &DeclarationPtr) {
+        if let Some(span_id) = self.lookup_decl_span(&decl.name()) {
+            self.source_map.record_declaration(span_id, decl);
+        }
+    }
+
+    /// Links an expression to the span of the node it was parsed from, through its metadata, so
+    /// that problems found with it after parsing can be reported at the right place.
+    ///
+    /// Expressions that already have a span (e.g. an atom inside brackets) keep it. Nothing is
+    /// recorded unless `record_expression_spans` is set.
+    pub fn record_expression_span(&mut self, expr: Expression, node: &Node) -> Expression {
+        if !self.record_expression_spans || expr.get_meta().span_id.is_some() {
+            return expr;
+        }
+        let span_id = alloc_node_span(node.range(), self.source_map);
+        expr.with_span_id(span_id)
+    }
+
     pub fn lookup_decl_line(&self, name: &Name) -> Option<u32> {
         let span_id = self.lookup_decl_span(name)?;
         let span = self.source_map.spans.get(span_id as usize)?;
@@ -141,10 +167,6 @@ pub fn get_expr_tree(src: &str) -> Option<(Tree, String)> {
 
     parser.parse(src, None).and_then(|tree| {
         let root = tree.root_node();
-        if root.is_error() {
-            return None;
-        }
-
         let children: Vec<_> = named_children(&root).collect();
         let first_child = children.first()?;
 
@@ -153,7 +175,7 @@ pub fn get_expr_tree(src: &str) -> Option<(Tree, String)> {
         // However, we can use a dummy _FRAGMENT_EXPRESSION prefix (which we insert as necessary)
         // to trick the parser into accepting an isolated expression.
         // This way we can parse an isolated expression and it is only slightly cursed :)
-        if first_child.is_error() {
+        if root.is_error() || first_child.is_error() {
             if src.starts_with("_FRAGMENT_EXPRESSION") {
                 None
             } else {
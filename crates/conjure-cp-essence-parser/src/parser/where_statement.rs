@@ -0,0 +1,29 @@
+use crate::errors::FatalParseError;
+use crate::expression::parse_expression;
+use crate::parser::ParseContext;
+use crate::util::TypecheckingContext;
+use conjure_cp_core::ast::{SourceLocation, WhereCondition};
+use tree_sitter::Node;
+
+/// Parses a top-level `where <expr>, <expr>, ...` statement into one condition per expression.
+pub fn parse_where_statement(
+    ctx: &mut ParseContext,
+    node: &Node,
+) -> Result<Vec<WhereCondition>, FatalParseError> {
+    let mut conditions = vec![];
+    for expression_node in node.children_by_field_name("expression", &mut node.walk()) {
+        ctx.typechecking_context = TypecheckingContext::Boolean;
+        let Some(expression) = parse_expression(ctx, expression_node)? else {
+            continue;
+        };
+
+        // tree-sitter positions are 0-indexed
+        let location = SourceLocation {
+            line: expression_node.start_position().row + 1,
+            column: expression_node.start_position().column + 1,
+        };
+        conditions.push(WhereCondition::new(expression, Some(location)));
+    }
+
+    Ok(conditions)
+}
@@ -0,0 +1,105 @@
+use conjure_cp_essence_parser::diagnostics::completion::{
+    CompletionContext, completion_context, domain_attributes, quantified_names_at,
+};
+use conjure_cp_essence_parser::util::get_tree;
+
+// the completion context at the `|` in source
+fn context_at(source: &str) -> CompletionContext {
+    let byte = source.find('|').expect("source should contain a cursor");
+    let source = source.replacen('|', "", 1);
+    let (tree, _) = get_tree(&source).expect("source should produce a CST");
+    completion_context(&tree, &source, byte)
+}
+
+#[test]
+fn statements_at_the_start_of_a_line() {
+    assert_eq!(context_at("|"), CompletionContext::Statement);
+    assert_eq!(
+        context_at("find x : int(1..3)\n|"),
+        CompletionContext::Statement
+    );
+    assert_eq!(
+        context_at("find x : int(1..3)\nsu|"),
+        CompletionContext::Statement
+    );
+}
+
+#[test]
+fn domains_where_a_domain_is_expected() {
+    assert_eq!(context_at("find x : |"), CompletionContext::Domain);
+    assert_eq!(context_at("find x : in|"), CompletionContext::Domain);
+    assert_eq!(
+        context_at("letting D be domain |"),
+        CompletionContext::Domain
+    );
+    assert_eq!(context_at("find s : set of |"), CompletionContext::Domain);
+    assert_eq!(
+        context_at("find m : matrix indexed by [|] of int(1..2)"),
+        CompletionContext::Domain
+    );
+    assert_eq!(
+        context_at("find f : function int(1..2) --> |"),
+        CompletionContext::Domain
+    );
+    assert_eq!(
+        context_at("find x : bool\nsuch that forAll i : |"),
+        CompletionContext::Domain
+    );
+}
+
+#[test]
+fn attributes_inside_domain_constructors() {
+    assert_eq!(
+        context_at("find s : set (|) of int(1..3)"),
+        CompletionContext::DomainAttribute("set".to_string())
+    );
+    assert_eq!(
+        context_at("find f : function (total, |) int(1..2) --> int(1..2)"),
+        CompletionContext::DomainAttribute("function".to_string())
+    );
+    assert!(domain_attributes("function").contains(&"injective"));
+    assert!(domain_attributes("set").contains(&"minSize"));
+}
+
+#[test]
+fn expressions_in_constraints() {
+    let source = "find x : int(1..3)\nsuch that x = |";
+    assert_eq!(context_at(source), CompletionContext::Expression);
+    assert_eq!(
+        context_at("find x : int(1..3)\nsuch that x = 1,\n|"),
+        CompletionContext::Expression
+    );
+    assert_eq!(
+        context_at("find x : int(1..3)\nsuch that allDiff([x, |])"),
+        CompletionContext::Expression
+    );
+}
+
+#[test]
+fn nothing_for_new_names_and_comments() {
+    assert_eq!(context_at("find |"), CompletionContext::None);
+    assert_eq!(context_at("find x, |"), CompletionContext::None);
+    assert_eq!(
+        context_at("find x : bool\nsuch that forAll |"),
+        CompletionContext::None
+    );
+    assert_eq!(
+        context_at("find x : bool\n$ a comment |"),
+        CompletionContext::None
+    );
+}
+
+#[test]
+fn quantified_names_in_scope() {
+    let source =
+        "find x : int(1..9)\nsuch that forAll i : int(1..3) . x = sum([ j | j : int(1..i)])";
+    let (tree, _) = get_tree(source).unwrap();
+
+    let inner = source.find("j |").unwrap();
+    assert_eq!(quantified_names_at(&tree, source, inner), vec!["j", "i"]);
+
+    let outer = source.find("x =").unwrap();
+    assert_eq!(quantified_names_at(&tree, source, outer), vec!["i"]);
+
+    assert!(quantified_names_at(&tree, source, 0).is_empty());
+}
@@ -0,0 +1,92 @@
+use std::fs;
+use std::path::Path;
+
+use conjure_cp_essence_parser::{FatalParseError, format_essence};
+
+#[test]
+fn formats_statements_and_spacing() {
+    let source = "find  x,y:int( 1 .. 3 )\nfind s : set(minSize 1,maxSize 2)of int(1..3)\n\n\n\
+                  letting M be [ 1,2 ; int(1..2) ]\nsuch that x=M[ 1 ]+-y,allDiff( [x,y] )\n\
+                  such that forAll i:int(1..2).i in s";
+    let expected = "find x, y : int(1..3)\n\
+                    find s : set (minSize 1, maxSize 2) of int(1..3)\n\
+                    \n\
+                    letting M be [1, 2; int(1..2)]\n\
+                    such that\n    x = M[1] + -y,\n    allDiff([x, y])\n\
+                    such that forAll i : int(1..2) . i in s\n";
+    assert_eq!(format_essence(source).unwrap(), expected);
+}
+
+#[test]
+fn keeps_comments() {
+    let source =
+        "$ header\nfind x : bool $ trailing\n$ before constraints\n\nsuch that x, $ first\n  !x\n";
+    let expected = "$ header\nfind x : bool $ trailing\n$ before constraints\n\n\
+                    such that\n    x, $ first\n    !x\n";
+    assert_eq!(format_essence(source).unwrap(), expected);
+}
+
+#[test]
+fn keeps_tokens_apart_that_would_lex_differently() {
+    let source = "find x : int(-5..-1)\nsuch that - 1 = x, (-x) = 1, |x| > 0";
+    let expected = "find x : int(-5..-1)\nsuch that\n    - 1 = x,\n    (-x) = 1,\n    |x| > 0\n";
+    assert_eq!(format_essence(source).unwrap(), expected);
+}
+
+#[test]
+fn wraps_long_lines() {
+    let source = "find a, b, c : bool\nfind x : int(0..100)\n\
+                  such that x = sum([toInt(a) * 1000000, toInt(b) * 2000000, toInt(c) * 3000000, toInt(a /\\ b) * 4000000])\n\
+                  such that (a -> b) /\\ (b -> c) /\\ (c -> a) /\\ (a \\/ b \\/ c) /\\ (!a \\/ !b \\/ !c) /\\ (a <-> (b /\\ c /\\ x > 1))";
+    let expected = "find a, b, c : bool\nfind x : int(0..100)\n\
+                    such that x = sum([\n    toInt(a) * 1000000,\n    toInt(b) * 2000000,\n    toInt(c) * 3000000,\n    toInt(a /\\ b) * 4000000\n])\n\
+                    such that (a -> b) /\\\n    (b -> c) /\\\n    (c -> a) /\\\n    (a \\/ b \\/ c) /\\\n    (!a \\/ !b \\/ !c) /\\\n    (a <-> (b /\\ c /\\ x > 1))\n";
+    assert_eq!(format_essence(source).unwrap(), expected);
+}
+
+#[test]
+fn rejects_syntax_errors() {
+    let err = format_essence("find x : int(1..3\nsuch that x =").unwrap_err();
+    assert!(err.to_string().contains("syntax error"), "{err}");
+}
+
+// every test model that parses is formatted, and formatting it again changes nothing
+#[test]
+fn formatting_test_models_is_idempotent() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../test-suite/tests");
+    let mut formatted_any = false;
+    let mut failures = vec![];
+    for path in essence_files(&root) {
+        let source = fs::read_to_string(&path).unwrap();
+        let formatted = match format_essence(&source) {
+            Ok(formatted) => formatted,
+            // some test models have syntax errors on purpose
+            Err(FatalParseError::TreeSitterError(_)) => continue,
+            Err(err) => {
+                failures.push(format!("formatting {} failed: {err}", path.display()));
+                continue;
+            }
+        };
+        formatted_any = true;
+        match format_essence(&formatted) {
+            Ok(reformatted) if reformatted == formatted => {}
+            Ok(_) => failures.push(format!("formatting {} is not idempotent", path.display())),
+            Err(err) => failures.push(format!("formatting {} again failed: {err}", path.display())),
+        }
+    }
+    assert!(formatted_any);
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+fn essence_files(dir: &Path) -> Vec<std::path::PathBuf> {
+    let mut files = vec![];
+    for entry in fs::read_dir(dir).unwrap().flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            files.extend(essence_files(&path));
+        } else if path.extension().is_some_and(|ext| ext == "essence") {
+            files.push(path);
+        }
+    }
+    files
+}
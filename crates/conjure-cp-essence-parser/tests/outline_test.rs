@@ -0,0 +1,102 @@
+use conjure_cp_essence_parser::diagnostics::diagnostics_api::{DocumentSymbol, SymbolKind};
+use conjure_cp_essence_parser::diagnostics::outline::{document_symbols, folding_ranges};
+use conjure_cp_essence_parser::util::get_tree;
+
+fn symbols_of(source: &str) -> Vec<DocumentSymbol> {
+    let (tree, _) = get_tree(source).expect("source should produce a CST");
+    document_symbols(source, &tree)
+}
+
+// the name, kind and detail of each symbol
+fn summary(symbols: &[DocumentSymbol]) -> Vec<(&str, SymbolKind, Option<&str>)> {
+    symbols
+        .iter()
+        .map(|symbol| (symbol.name.as_str(), symbol.kind, symbol.detail.as_deref()))
+        .collect()
+}
+
+// the lines that each folding range starts and ends on
+fn folded_lines(source: &str) -> Vec<(u32, u32)> {
+    let (tree, _) = get_tree(source).expect("source should produce a CST");
+    folding_ranges(&tree)
+        .into_iter()
+        .map(|range| (range.start.line, range.end.line))
+        .collect()
+}
+
+#[test]
+fn declarations_with_their_domains() {
+    let source = "given n : int(1..10)\nletting D be domain int(1..n)\nletting k be n * 2\nletting C be new type enum {red, green}\nfind x, y : D";
+    let symbols = symbols_of(source);
+
+    assert_eq!(
+        summary(&symbols),
+        vec![
+            ("n", SymbolKind::GivenVar, Some("int(1..10)")),
+            ("D", SymbolKind::Domain, Some("int(1..n)")),
+            ("k", SymbolKind::LettingVar, Some("n * 2")),
+            ("C", SymbolKind::Domain, Some("new type enum {red, green}")),
+            ("x", SymbolKind::FindVar, Some("D")),
+            ("y", SymbolKind::FindVar, Some("D")),
+        ]
+    );
+
+    // the name is selected, within the whole declaration
+    let y = &symbols[5];
+    assert_eq!(
+        (
+            y.selection_range.start.line,
+            y.selection_range.start.character
+        ),
+        (4, 8)
+    );
+    assert_eq!((y.range.start.character, y.range.end.character), (5, 13));
+}
+
+#[test]
+fn such_that_blocks_with_their_constraints() {
+    let source = "find x, y : int(1..10)\nsuch that\n    x < y,\n    x + y = 10\nsuch that x != 3";
+    let symbols = symbols_of(source);
+
+    assert_eq!(symbols.len(), 4);
+    let block = &symbols[2];
+    assert_eq!(block.name, "such that");
+    assert_eq!((block.range.start.line, block.range.end.line), (1, 3));
+    let constraints = block.children.as_deref().unwrap_or_default();
+    assert_eq!(
+        summary(constraints),
+        vec![
+            ("x < y", SymbolKind::Constraint, None),
+            ("x + y = 10", SymbolKind::Constraint, None),
+        ]
+    );
+
+    let block = &symbols[3];
+    assert_eq!(block.name, "such that");
+    assert_eq!(block.children.as_ref().map(Vec::len), Some(1));
+}
+
+#[test]
+fn long_text_is_shortened() {
+    let source =
+        "letting m be [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20]";
+    let symbols = symbols_of(source);
+
+    let detail = symbols[0].detail.as_deref().unwrap_or_default();
+    assert!(detail.ends_with("..."), "got {detail}");
+    assert!(detail.chars().count() <= 60, "got {detail}");
+}
+
+#[test]
+fn folds_multi_line_statements_comprehensions_and_matrices() {
+    let source = "letting m be [\n    1,\n    2\n]\nfind x : int(1..10)\nsuch that\n    x > 1,\n    sum([\n        i\n        | i : int(1..3)\n    ]) < x";
+
+    assert_eq!(folded_lines(source), vec![(0, 3), (5, 10), (7, 10)]);
+}
+
+#[test]
+fn no_folding_for_single_lines() {
+    let source = "find x : int(1..10)\nsuch that x > sum([i | i : int(1..3)])";
+
+    assert_eq!(folded_lines(source), vec![]);
+}
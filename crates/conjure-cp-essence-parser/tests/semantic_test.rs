@@ -1,5 +1,11 @@
+use std::sync::{Arc, RwLock};
+
+use conjure_cp_core::ast::{DeclarationPtr, Domain, Expression, Model, Name, Range};
+use conjure_cp_core::context::Context;
 use conjure_cp_essence_parser::diagnostics::diagnostics_api::get_diagnostics;
 use conjure_cp_essence_parser::diagnostics::error_detection::collect_errors::check_diagnostic;
+use conjure_cp_essence_parser::diagnostics::error_detection::semantic_checks::check_model;
+use conjure_cp_essence_parser::diagnostics::source_map::SourceMap;
 use conjure_cp_essence_parser::util::get_tree;
 
 #[test]
@@ -208,3 +214,128 @@ fn extra_comma_in_variable_list() {
         "Semantic Error: Extra ',' at the end of 'variable_list'",
     );
 }
+
+#[test]
+fn no_errors_for_enumerated_types() {
+    let source =
+        "letting Colour be new type enum {red, green, blue}\nfind x: Colour\nsuch that x != red";
+    let (cst, _) = get_tree(source).unwrap();
+
+    let diagnostics = get_diagnostics(source, &cst);
+
+    assert_eq!(
+        diagnostics.len(),
+        0,
+        "Expected no diagnostics for valid code, got: {:?}",
+        diagnostics
+    );
+}
+
+#[test]
+fn duplicate_value_in_enumerated_type() {
+    let source = "letting Colour be new type enum {red, red}";
+    let (cst, _) = get_tree(source).unwrap();
+
+    let diagnostics = get_diagnostics(source, &cst);
+
+    assert_eq!(
+        diagnostics.len(),
+        1,
+        "Expected exactly one diagnostic for duplicate value"
+    );
+
+    let diag = &diagnostics[0];
+
+    check_diagnostic(diag, 0, 38, 0, 41, "'red' is already declared");
+}
+
+#[test]
+fn incorrect_type_for_comparison() {
+    let source =
+        "find b: bool\nfind m: matrix indexed by [int(1..2)] of int(1..3)\nsuch that m[1] = b";
+    let (cst, _) = get_tree(source).unwrap();
+
+    let diagnostics = get_diagnostics(source, &cst);
+
+    assert_eq!(
+        diagnostics.len(),
+        1,
+        "Expected exactly one diagnostic for incorrect type"
+    );
+
+    let diag = &diagnostics[0];
+
+    check_diagnostic(
+        diag,
+        2,
+        17,
+        2,
+        18,
+        "Incorrect type 'bool' for variable 'b', expected 'int'",
+    );
+}
+
+#[test]
+fn incorrect_type_for_indexed_expression() {
+    let source = "find x: int(1..3)\nsuch that x[1] = 2";
+    let (cst, _) = get_tree(source).unwrap();
+
+    let diagnostics = get_diagnostics(source, &cst);
+
+    assert_eq!(
+        diagnostics.len(),
+        1,
+        "Expected exactly one diagnostic for indexing an integer"
+    );
+
+    let diag = &diagnostics[0];
+
+    check_diagnostic(
+        diag,
+        1,
+        10,
+        1,
+        11,
+        "Cannot index variable 'x' of type 'int'",
+    );
+}
+
+#[test]
+fn no_type_errors_for_valid_code() {
+    let source = "find s: set of int(1..3)\nfind m: matrix indexed by [int(1..2)] of bool\nsuch that 2 in s, s != {1}, sum([i | i : int(1..2), m[i]]) > 1, and(m)";
+    let (cst, _) = get_tree(source).unwrap();
+
+    let diagnostics = get_diagnostics(source, &cst);
+
+    assert_eq!(
+        diagnostics.len(),
+        0,
+        "Expected no diagnostics for valid code, got: {:?}",
+        diagnostics
+    );
+}
+
+#[test]
+fn value_letting_outside_its_domain() {
+    // the grammar has no syntax for lettings with a domain, so the model is built by hand
+    let mut model = Model::new(Arc::new(RwLock::new(Context::default())));
+    model
+        .symbols_mut()
+        .insert(DeclarationPtr::new_value_letting_with_domain(
+            Name::User("n".into()),
+            Expression::from(7),
+            Domain::int(vec![Range::Bounded(1, 5)]),
+        ));
+
+    let errors = check_model("", &model, &SourceMap::default());
+
+    assert_eq!(
+        errors.len(),
+        1,
+        "Expected exactly one error for a letting outside its domain"
+    );
+    assert_eq!(
+        errors[0].msg,
+        "The value 7 of 'n' is not in its domain int(1..5)"
+    );
+}
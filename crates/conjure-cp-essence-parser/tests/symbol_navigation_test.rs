@@ -0,0 +1,123 @@
+use std::sync::{Arc, RwLock};
+
+use conjure_cp_core::context::Context;
+use conjure_cp_essence_parser::diagnostics::source_map::{OccurrenceKind, SourceMap};
+use conjure_cp_essence_parser::parse_essence_with_context_and_map;
+
+fn source_map_for(source: &str) -> SourceMap {
+    let context = Arc::new(RwLock::new(Context::default()));
+    let mut errors = vec![];
+    let (_, source_map) = parse_essence_with_context_and_map(source, context, &mut errors, None)
+        .expect("source should parse");
+    assert!(errors.is_empty(), "unexpected errors: {errors:?}");
+    source_map
+}
+
+// byte offset of the nth (0-based) occurrence of the identifier name in source
+fn nth_byte(source: &str, name: &str, n: usize) -> usize {
+    let is_ident = |c: char| c.is_ascii_alphanumeric() || c == '_';
+    source
+        .match_indices(name)
+        .map(|(idx, _)| idx)
+        .filter(|&idx| {
+            !source[..idx].ends_with(is_ident) && !source[idx + name.len()..].starts_with(is_ident)
+        })
+        .nth(n)
+        .unwrap_or_else(|| panic!("'{name}' occurs fewer than {} times", n + 1))
+}
+
+// byte offsets of every identifier linked to the same declaration as the one at byte
+fn linked_bytes(source_map: &SourceMap, byte: usize) -> Vec<(usize, OccurrenceKind)> {
+    let occurrence = source_map
+        .occurrence_at_byte(byte)
+        .expect("expected an identifier at this byte");
+    source_map
+        .occurrences_of(&occurrence.declaration)
+        .into_iter()
+        .map(|occ| (source_map.spans[occ.span as usize].start_byte, occ.kind))
+        .collect()
+}
+
+#[test]
+fn links_references_to_top_level_declarations() {
+    let source = "find x : int(1..3)\nletting n be 2\nsuch that x = n, x != 1";
+    let source_map = source_map_for(source);
+
+    let x_use = nth_byte(source, "x", 2);
+    let x_decl = source_map
+        .declaration_span(&source_map.occurrence_at_byte(x_use).unwrap().declaration)
+        .unwrap();
+    assert_eq!(x_decl.start_byte, nth_byte(source, "x", 0));
+
+    assert_eq!(
+        linked_bytes(&source_map, x_use),
+        vec![
+            (nth_byte(source, "x", 0), OccurrenceKind::Declaration),
+            (nth_byte(source, "x", 1), OccurrenceKind::Reference),
+            (nth_byte(source, "x", 2), OccurrenceKind::Reference),
+        ]
+    );
+
+    assert_eq!(
+        linked_bytes(&source_map, nth_byte(source, "n", 1)),
+        vec![
+            (nth_byte(source, "n", 0), OccurrenceKind::Declaration),
+            (nth_byte(source, "n", 1), OccurrenceKind::Reference),
+        ]
+    );
+}
+
+#[test]
+fn links_domain_references_to_domain_lettings() {
+    let source = "letting D be domain int(1..3)\nfind x : D";
+    let source_map = source_map_for(source);
+
+    assert_eq!(
+        linked_bytes(&source_map, nth_byte(source, "D", 1)),
+        vec![
+            (nth_byte(source, "D", 0), OccurrenceKind::Declaration),
+            (nth_byte(source, "D", 1), OccurrenceKind::Reference),
+        ]
+    );
+}
+
+#[test]
+fn quantified_variables_shadow_top_level_declarations() {
+    let source = "find i : int(1..3)\nsuch that i > 1\nsuch that forAll i : int(1..2) . i < 3";
+    let source_map = source_map_for(source);
+
+    // the `i` inside the forAll resolves to the quantified variable
+    assert_eq!(
+        linked_bytes(&source_map, nth_byte(source, "i", 3)),
+        vec![
+            (nth_byte(source, "i", 2), OccurrenceKind::Declaration),
+            (nth_byte(source, "i", 3), OccurrenceKind::Reference),
+        ]
+    );
+
+    // the top-level `i` is unaffected
+    assert_eq!(
+        linked_bytes(&source_map, nth_byte(source, "i", 0)),
+        vec![
+            (nth_byte(source, "i", 0), OccurrenceKind::Declaration),
+            (nth_byte(source, "i", 1), OccurrenceKind::Reference),
+        ]
+    );
+}
+
+#[test]
+fn links_comprehension_generators() {
+    let source = "find x : int(1..9)\nsuch that x = sum([ j * j | j : int(1..2), j > 1 ])";
+    let source_map = source_map_for(source);
+
+    // the return expression comes before the generator in the source
+    assert_eq!(
+        linked_bytes(&source_map, nth_byte(source, "j", 0)),
+        vec![
+            (nth_byte(source, "j", 0), OccurrenceKind::Reference),
+            (nth_byte(source, "j", 1), OccurrenceKind::Reference),
+            (nth_byte(source, "j", 2), OccurrenceKind::Declaration),
+            (nth_byte(source, "j", 3), OccurrenceKind::Reference),
+        ]
+    );
+}
@@ -8,6 +8,7 @@ use tree_sitter::Tree;
 #[derive(Clone, Debug)]
 pub struct CacheCont {
     pub sourcemap: Option<SourceMap>,
+    // the last model that parsed without errors
     pub ast: Option<Model>,
     pub errors: Vec<RecoverableParseError>,
     pub cst: Option<Tree>,
@@ -0,0 +1,129 @@
+use crate::handlers::cache::CacheCont;
+use crate::handlers::sync_event::position_to_byte;
+use crate::server::Backend;
+use conjure_cp_core::ast::DeclarationKind;
+use conjure_cp_essence_parser::diagnostics::completion::{
+    CompletionContext, completion_context, domain_attributes, domain_snippets, quantified_names_at,
+};
+use conjure_cp_essence_parser::parser::keyword_checks::{
+    EXPRESSION_KEYWORDS, OPERATORS, STATEMENT_KEYWORDS,
+};
+use tower_lsp::{jsonrpc::Error, lsp_types::*};
+
+impl Backend {
+    pub async fn handle_completion(
+        &self,
+        params: CompletionParams,
+    ) -> Result<Option<CompletionResponse>, Error> {
+        let uri = params.text_document_position.text_document.uri;
+        let position = params.text_document_position.position;
+
+        let Some(cache_conts) = self.cached_document(&uri).await else {
+            return Ok(None);
+        };
+        let Some(cst) = &cache_conts.cst else {
+            return Ok(None);
+        };
+
+        let source = &cache_conts.contents;
+        let byte = position_to_byte(source, position);
+
+        let items = match completion_context(cst, source, byte) {
+            CompletionContext::Statement => keyword_items(&STATEMENT_KEYWORDS),
+            CompletionContext::Domain => {
+                let mut items: Vec<CompletionItem> = domain_snippets()
+                    .into_iter()
+                    .map(|snippet| CompletionItem {
+                        label: snippet.label.to_string(),
+                        kind: Some(CompletionItemKind::KEYWORD),
+                        detail: Some("domain".to_string()),
+                        insert_text: Some(snippet.snippet),
+                        insert_text_format: Some(InsertTextFormat::SNIPPET),
+                        ..Default::default()
+                    })
+                    .collect();
+                items.extend(declared_names(&cache_conts, true));
+                items
+            }
+            CompletionContext::DomainAttribute(constructor) => domain_attributes(&constructor)
+                .into_iter()
+                .map(|attribute| CompletionItem {
+                    label: attribute.to_string(),
+                    kind: Some(CompletionItemKind::PROPERTY),
+                    detail: Some(format!("{constructor} attribute")),
+                    ..Default::default()
+                })
+                .collect(),
+            CompletionContext::Expression => {
+                let mut items = declared_names(&cache_conts, false);
+                for name in quantified_names_at(cst, source, byte) {
+                    if !items.iter().any(|item| item.label == name) {
+                        items.push(CompletionItem {
+                            label: name,
+                            kind: Some(CompletionItemKind::VARIABLE),
+                            detail: Some("quantified variable".to_string()),
+                            ..Default::default()
+                        });
+                    }
+                }
+                items.extend(keyword_items(&EXPRESSION_KEYWORDS));
+                items.extend(OPERATORS.iter().map(|op| CompletionItem {
+                    label: op.to_string(),
+                    kind: Some(CompletionItemKind::OPERATOR),
+                    ..Default::default()
+                }));
+                items
+            }
+            CompletionContext::None => return Ok(None),
+        };
+
+        Ok(Some(CompletionResponse::Array(items)))
+    }
+}
+
+fn keyword_items(keywords: &[&str]) -> Vec<CompletionItem> {
+    keywords
+        .iter()
+        .map(|keyword| CompletionItem {
+            label: keyword.to_string(),
+            kind: Some(CompletionItemKind::KEYWORD),
+            ..Default::default()
+        })
+        .collect()
+}
+
+// names declared at the top level of the last model that parsed: either the domain lettings, or
+// the finds, givens and value lettings
+fn declared_names(cache_conts: &CacheCont, domains: bool) -> Vec<CompletionItem> {
+    let Some(model) = &cache_conts.ast else {
+        return vec![];
+    };
+
+    let mut items = vec![];
+    for (name, decl) in model.symbols().iter_local() {
+        let (kind, detail) = match &*decl.kind() {
+            DeclarationKind::DomainLetting(domain) if domains => {
+                (CompletionItemKind::STRUCT, format!("domain {domain}"))
+            }
+            DeclarationKind::Find(var) if !domains => (
+                CompletionItemKind::VARIABLE,
+                format!("find : {}", var.domain),
+            ),
+            DeclarationKind::Given(domain) if !domains => {
+                (CompletionItemKind::VARIABLE, format!("given : {domain}"))
+            }
+            DeclarationKind::ValueLetting(expr, _) if !domains => {
+                (CompletionItemKind::CONSTANT, format!("letting be {expr}"))
+            }
+            _ => continue,
+        };
+        items.push(CompletionItem {
+            label: name.to_string(),
+            kind: Some(kind),
+            detail: Some(detail),
+            ..Default::default()
+        });
+    }
+    items.sort_by(|a, b| a.label.cmp(&b.label));
+    items
+}
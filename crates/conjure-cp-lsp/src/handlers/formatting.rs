@@ -0,0 +1,39 @@
+use crate::handlers::sync_event::byte_to_position;
+use crate::server::Backend;
+use conjure_cp_essence_parser::format_essence;
+use tower_lsp::{jsonrpc::Error, lsp_types::*};
+
+impl Backend {
+    pub async fn handle_formatting(
+        &self,
+        params: DocumentFormattingParams,
+    ) -> Result<Option<Vec<TextEdit>>, Error> {
+        let Some(cache_conts) = self.cached_document(&params.text_document.uri).await else {
+            return Ok(None);
+        };
+        let text = &cache_conts.contents;
+
+        // the formatter always indents with 4 spaces, so the client's options are not used
+        let formatted = match format_essence(text) {
+            Ok(formatted) => formatted,
+            Err(err) => {
+                self.client
+                    .log_message(MessageType::INFO, format!("Not formatting: {err}"))
+                    .await;
+                return Ok(None);
+            }
+        };
+
+        if &formatted == text {
+            return Ok(Some(vec![]));
+        }
+
+        Ok(Some(vec![TextEdit {
+            range: Range {
+                start: Position::new(0, 0),
+                end: byte_to_position(text, text.len()),
+            },
+            new_text: formatted,
+        }]))
+    }
+}
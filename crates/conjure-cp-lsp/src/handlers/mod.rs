@@ -1,4 +1,8 @@
 pub mod cache;
+pub mod completion;
+pub mod formatting;
 pub mod hovering;
+pub mod navigation;
 pub mod semantic_highlighting;
+pub mod symbols;
 pub mod sync_event;
@@ -0,0 +1,198 @@
+use std::collections::HashMap;
+
+use crate::handlers::cache::CacheCont;
+use crate::handlers::sync_event::{byte_to_position, position_to_byte};
+use crate::server::Backend;
+use conjure_cp_essence_parser::diagnostics::source_map::{
+    OccurrenceKind, SourceMap, SourceSpan, SymbolOccurrence,
+};
+use conjure_cp_essence_parser::parser::keyword_checks::is_keyword_identifier;
+use tower_lsp::{jsonrpc::Error, lsp_types::*};
+
+impl Backend {
+    pub async fn handle_goto_definition(
+        &self,
+        params: GotoDefinitionParams,
+    ) -> Result<Option<GotoDefinitionResponse>, Error> {
+        let uri = params.text_document_position_params.text_document.uri;
+        let position = params.text_document_position_params.position;
+
+        let Some(cache_conts) = self.cached_document(&uri).await else {
+            return Ok(None);
+        };
+        let Some((source_map, occurrence)) = occurrence_at(&cache_conts, position) else {
+            return Ok(None);
+        };
+
+        let Some(span) = source_map.declaration_span(&occurrence.declaration) else {
+            return Ok(None);
+        };
+
+        Ok(Some(GotoDefinitionResponse::Scalar(Location {
+            uri,
+            range: span_to_range(&cache_conts.contents, span),
+        })))
+    }
+
+    pub async fn handle_references(
+        &self,
+        params: ReferenceParams,
+    ) -> Result<Option<Vec<Location>>, Error> {
+        let uri = params.text_document_position.text_document.uri;
+        let position = params.text_document_position.position;
+        let include_declaration = params.context.include_declaration;
+
+        let Some(cache_conts) = self.cached_document(&uri).await else {
+            return Ok(None);
+        };
+        let Some((source_map, occurrence)) = occurrence_at(&cache_conts, position) else {
+            return Ok(None);
+        };
+
+        let locations = symbol_ranges(&cache_conts.contents, source_map, occurrence, |occ| {
+            include_declaration || occ.kind == OccurrenceKind::Reference
+        })
+        .into_iter()
+        .map(|range| Location {
+            uri: uri.clone(),
+            range,
+        })
+        .collect();
+
+        Ok(Some(locations))
+    }
+
+    pub async fn handle_prepare_rename(
+        &self,
+        params: TextDocumentPositionParams,
+    ) -> Result<Option<PrepareRenameResponse>, Error> {
+        let Some(cache_conts) = self.cached_document(&params.text_document.uri).await else {
+            return Ok(None);
+        };
+        let Some((source_map, occurrence)) = occurrence_at(&cache_conts, params.position) else {
+            return Ok(None);
+        };
+
+        let span = &source_map.spans[occurrence.span as usize];
+        Ok(Some(PrepareRenameResponse::Range(span_to_range(
+            &cache_conts.contents,
+            span,
+        ))))
+    }
+
+    pub async fn handle_rename(
+        &self,
+        params: RenameParams,
+    ) -> Result<Option<WorkspaceEdit>, Error> {
+        let uri = params.text_document_position.text_document.uri;
+        let position = params.text_document_position.position;
+        let new_name = params.new_name;
+
+        if !is_valid_identifier(&new_name) {
+            return Err(Error::invalid_params(format!(
+                "'{new_name}' is not a valid identifier"
+            )));
+        }
+
+        let Some(cache_conts) = self.cached_document(&uri).await else {
+            return Ok(None);
+        };
+        let Some((source_map, occurrence)) = occurrence_at(&cache_conts, position) else {
+            return Ok(None);
+        };
+
+        // renaming onto an existing name could change what other identifiers resolve to
+        let clashes = source_map
+            .occurrences
+            .iter()
+            .filter(|occ| occ.kind == OccurrenceKind::Declaration)
+            .any(|occ| {
+                span_text(&cache_conts.contents, &source_map.spans[occ.span as usize]) == new_name
+            });
+        if clashes {
+            return Err(Error::invalid_params(format!(
+                "'{new_name}' is already declared"
+            )));
+        }
+
+        let edits = symbol_ranges(&cache_conts.contents, source_map, occurrence, |_| true)
+            .into_iter()
+            .map(|range| TextEdit {
+                range,
+                new_text: new_name.clone(),
+            })
+            .collect();
+
+        Ok(Some(WorkspaceEdit {
+            changes: Some(HashMap::from([(uri, edits)])),
+            ..Default::default()
+        }))
+    }
+
+    pub(crate) async fn cached_document(&self, uri: &Url) -> Option<CacheCont> {
+        let cache_conts = self.lsp_cache.get(uri).await;
+        if cache_conts.is_none() {
+            self.client
+                .log_message(MessageType::WARNING, "Document not found in cache")
+                .await;
+        }
+        cache_conts
+    }
+}
+
+// the identifier under the cursor, if it resolves to a declaration
+fn occurrence_at(
+    cache_conts: &CacheCont,
+    position: Position,
+) -> Option<(&SourceMap, &SymbolOccurrence)> {
+    let source_map = cache_conts.sourcemap.as_ref()?;
+    let byte = position_to_byte(&cache_conts.contents, position);
+    let occurrence = source_map.occurrence_at_byte(byte)?;
+    Some((source_map, occurrence))
+}
+
+// ranges of all identifiers linked to the same declaration as `occurrence`
+fn symbol_ranges(
+    text: &str,
+    source_map: &SourceMap,
+    occurrence: &SymbolOccurrence,
+    include: impl Fn(&SymbolOccurrence) -> bool,
+) -> Vec<Range> {
+    let mut ranges: Vec<Range> = vec![];
+    for occ in source_map.occurrences_of(&occurrence.declaration) {
+        let span = &source_map.spans[occ.span as usize];
+        // spans invalidated by an edit are empty until the document is re-parsed
+        if !include(occ) || span.start_byte >= span.end_byte {
+            continue;
+        }
+
+        // an expression shared by several names (e.g. `letting a, b be x`) is parsed once per
+        // name, so the same identifier can be recorded more than once
+        let range = span_to_range(text, span);
+        if !ranges.contains(&range) {
+            ranges.push(range);
+        }
+    }
+    ranges
+}
+
+fn span_to_range(text: &str, span: &SourceSpan) -> Range {
+    Range {
+        start: byte_to_position(text, span.start_byte),
+        end: byte_to_position(text, span.end_byte),
+    }
+}
+
+fn span_text<'a>(text: &'a str, span: &SourceSpan) -> &'a str {
+    text.get(span.start_byte..span.end_byte).unwrap_or_default()
+}
+
+fn is_valid_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    let Some(first) = chars.next() else {
+        return false;
+    };
+    (first.is_ascii_alphabetic() || first == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && !is_keyword_identifier(name)
+}
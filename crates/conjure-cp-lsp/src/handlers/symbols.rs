@@ -0,0 +1,162 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::handlers::sync_event::parser_to_lsp_range;
+use crate::server::Backend;
+use conjure_cp_essence_parser::diagnostics::diagnostics_api::{
+    DocumentSymbol as ParserSymbol, SymbolKind as ParserSymbolKind,
+};
+use conjure_cp_essence_parser::diagnostics::outline::{document_symbols, folding_ranges};
+use conjure_cp_essence_parser::util::get_tree;
+use tower_lsp::{jsonrpc::Error, lsp_types::*};
+
+// the files searched for workspace symbols
+const ESSENCE_EXTENSIONS: [&str; 2] = ["essence", "param"];
+
+impl Backend {
+    pub async fn handle_document_symbol(
+        &self,
+        params: DocumentSymbolParams,
+    ) -> Result<Option<DocumentSymbolResponse>, Error> {
+        let Some(cache_conts) = self.cached_document(&params.text_document.uri).await else {
+            return Ok(None);
+        };
+        let Some(cst) = &cache_conts.cst else {
+            return Ok(None);
+        };
+
+        let symbols = document_symbols(&cache_conts.contents, cst)
+            .into_iter()
+            .map(to_lsp_symbol)
+            .collect();
+        Ok(Some(DocumentSymbolResponse::Nested(symbols)))
+    }
+
+    pub async fn handle_folding_range(
+        &self,
+        params: FoldingRangeParams,
+    ) -> Result<Option<Vec<FoldingRange>>, Error> {
+        let Some(cache_conts) = self.cached_document(&params.text_document.uri).await else {
+            return Ok(None);
+        };
+        let Some(cst) = &cache_conts.cst else {
+            return Ok(None);
+        };
+
+        let ranges = folding_ranges(cst)
+            .into_iter()
+            .map(|range| FoldingRange {
+                start_line: range.start.line,
+                start_character: None,
+                end_line: range.end.line,
+                end_character: None,
+                kind: Some(FoldingRangeKind::Region),
+                collapsed_text: None,
+            })
+            .collect();
+        Ok(Some(ranges))
+    }
+
+    // the declarations in the .essence and .param files of the workspace whose names contain the
+    // query (ignoring case)
+    pub async fn handle_workspace_symbol(
+        &self,
+        params: WorkspaceSymbolParams,
+    ) -> Result<Option<Vec<SymbolInformation>>, Error> {
+        let query = params.query.to_lowercase();
+        let roots = self.workspace_roots.read().await.clone();
+
+        let mut symbols = vec![];
+        for path in roots.iter().flat_map(|root| essence_files(root)) {
+            let Ok(uri) = Url::from_file_path(&path) else {
+                continue;
+            };
+            // open documents may have changes that are not saved yet
+            let contents = match self.lsp_cache.get(&uri).await {
+                Some(cache_conts) => cache_conts.contents,
+                None => match fs::read_to_string(&path) {
+                    Ok(contents) => contents,
+                    Err(_) => continue,
+                },
+            };
+            let Some((cst, _)) = get_tree(&contents) else {
+                continue;
+            };
+
+            for symbol in document_symbols(&contents, &cst) {
+                if symbol.kind == ParserSymbolKind::Constraint
+                    || !symbol.name.to_lowercase().contains(&query)
+                {
+                    continue;
+                }
+                #[allow(deprecated)]
+                symbols.push(SymbolInformation {
+                    kind: lsp_symbol_kind(symbol.kind),
+                    tags: None,
+                    deprecated: None,
+                    location: Location {
+                        uri: uri.clone(),
+                        range: parser_to_lsp_range(symbol.selection_range),
+                    },
+                    container_name: None,
+                    name: symbol.name,
+                });
+            }
+        }
+        Ok(Some(symbols))
+    }
+}
+
+// the .essence and .param files in a directory and its subdirectories, other than hidden ones
+fn essence_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files = vec![];
+    let Ok(entries) = fs::read_dir(dir) else {
+        return files;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if entry.file_name().to_string_lossy().starts_with('.') {
+            continue;
+        }
+        // symlinks are not followed, so that cycles are not searched forever
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        if file_type.is_dir() {
+            files.extend(essence_files(&path));
+        } else if file_type.is_file()
+            && path
+                .extension()
+                .is_some_and(|ext| ESSENCE_EXTENSIONS.iter().any(|e| ext == *e))
+        {
+            files.push(path);
+        }
+    }
+    files
+}
+
+fn to_lsp_symbol(symbol: ParserSymbol) -> DocumentSymbol {
+    #[allow(deprecated)]
+    DocumentSymbol {
+        name: symbol.name,
+        detail: symbol.detail,
+        kind: lsp_symbol_kind(symbol.kind),
+        tags: None,
+        deprecated: None,
+        range: parser_to_lsp_range(symbol.range),
+        selection_range: parser_to_lsp_range(symbol.selection_range),
+        children: symbol
+            .children
+            .map(|children| children.into_iter().map(to_lsp_symbol).collect()),
+    }
+}
+
+fn lsp_symbol_kind(kind: ParserSymbolKind) -> SymbolKind {
+    match kind {
+        ParserSymbolKind::FindVar => SymbolKind::VARIABLE,
+        ParserSymbolKind::GivenVar | ParserSymbolKind::LettingVar => SymbolKind::CONSTANT,
+        ParserSymbolKind::Domain => SymbolKind::TYPE_PARAMETER,
+        ParserSymbolKind::Constraint => SymbolKind::BOOLEAN,
+        _ => SymbolKind::VARIABLE,
+    }
+}
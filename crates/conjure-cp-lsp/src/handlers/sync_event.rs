@@ -1,12 +1,14 @@
 use std::sync::Arc;
 use std::sync::RwLock;
 
+use conjure_cp_core::ast::Model;
 use conjure_cp_core::context::Context;
 use conjure_cp_essence_parser::RecoverableParseError;
 use conjure_cp_essence_parser::diagnostics::diagnostics_api::Diagnostic;
 use conjure_cp_essence_parser::diagnostics::error_detection::collect_errors::error_to_diagnostic;
+use conjure_cp_essence_parser::diagnostics::error_detection::semantic_checks::check_model;
 use conjure_cp_essence_parser::diagnostics::source_map::SourceMap;
-use conjure_cp_essence_parser::parse_essence_with_context_and_map;
+use conjure_cp_essence_parser::parse_essence_for_diagnostics;
 use conjure_cp_essence_parser::util::get_tree;
 use tower_lsp::{lsp_types::Diagnostic as LspDiagnostic, lsp_types::*};
 
@@ -44,18 +46,14 @@ impl Backend {
                 let context = Arc::new(RwLock::new(Context::default()));
                 let mut errors: Vec<RecoverableParseError> = Vec::new();
 
-                let parsed = parse_essence_with_context_and_map(
-                    &text,
-                    context,
-                    &mut errors,
-                    Some(&cst_tree),
-                );
+                let parsed =
+                    parse_essence_for_diagnostics(&text, context, &mut errors, Some(&cst_tree));
 
                 match parsed {
                     Ok((Some(ast_model), source_map)) => CacheCont {
+                        errors: with_semantic_errors(errors, &text, &ast_model, &source_map),
                         sourcemap: Some(source_map),
                         ast: Some(ast_model),
-                        errors,
                         cst: Some(cst_tree),
                         contents: text.clone(),
                         version: params.text_document.version,
@@ -208,7 +206,7 @@ impl Backend {
 
             let context = Arc::new(RwLock::new(Context::default()));
             let mut errors: Vec<RecoverableParseError> = Vec::new();
-            let parsed = parse_essence_with_context_and_map(
+            let parsed = parse_essence_for_diagnostics(
                 &current.contents,
                 context,
                 &mut errors,
@@ -217,16 +215,23 @@ impl Backend {
 
             let parsed_cache = match parsed {
                 Ok((Some(ast_model), source_map)) => CacheCont {
+                    errors: with_semantic_errors(
+                        errors,
+                        &current.contents,
+                        &ast_model,
+                        &source_map,
+                    ),
                     sourcemap: Some(source_map),
                     ast: Some(ast_model),
-                    errors,
                     cst: current.cst.clone(),
                     contents: current.contents.clone(),
                     version: incoming_version,
                 },
+                // keep the last model that parsed, so that its names are still available (e.g.
+                // for completion) while the document is being edited
                 Ok((None, source_map)) => CacheCont {
                     sourcemap: Some(source_map),
-                    ast: None,
+                    ast: current.ast.clone(),
                     errors,
                     cst: current.cst.clone(),
                     contents: current.contents.clone(),
@@ -234,7 +239,7 @@ impl Backend {
                 },
                 Err(fatal) => CacheCont {
                     sourcemap: None,
-                    ast: None,
+                    ast: current.ast.clone(),
                     errors: vec![RecoverableParseError::new(fatal.to_string(), None)],
                     cst: current.cst.clone(),
                     contents: current.contents.clone(),
@@ -263,9 +268,21 @@ impl Backend {
     }
 }
 
+// add the problems found by type-checking a model that parsed, e.g. operands of the wrong type
+fn with_semantic_errors(
+    mut errors: Vec<RecoverableParseError>,
+    source: &str,
+    model: &Model,
+    source_map: &SourceMap,
+) -> Vec<RecoverableParseError> {
+    errors.extend(check_model(source, model, source_map));
+    errors
+}
+
 async fn publish_diagnostics(client: &tower_lsp::Client, uri: &Url, cache_conts: CacheCont) {
     // Build diagnostics from the parse errors cached for this document.
-    // parse_essence_with_context_and_map already produces both syntactic and semantic errors.
+    // these are the syntactic and semantic errors from parse_essence_for_diagnostics, and
+    // the problems found by type-checking the model, if it parsed.
     let diagnostics: Vec<Diagnostic> = cache_conts
         .errors
         .into_iter()
@@ -334,6 +351,16 @@ pub fn position_to_byte(text: &str, position: Position) -> usize {
     line_start + col_bytes
 }
 
+//convert a byte offset back to a line and UTF-16 character, as used by LSP
+pub fn byte_to_position(text: &str, byte: usize) -> Position {
+    let byte = byte.min(text.len());
+    let line_start = text[..byte].rfind('\n').map(|idx| idx + 1).unwrap_or(0);
+    Position {
+        line: text[..line_start].matches('\n').count() as u32,
+        character: text[line_start..byte].encode_utf16().count() as u32,
+    }
+}
+
 //need to convert from character and line to row and line
 //this allows for incremental editing of treesitter
 fn position_to_treesitter_point(text: &str, position: Position) -> Point {
@@ -412,9 +439,10 @@ fn shift_sourcemap_after_edit(
         span.hover_info = None;
     }
 
+    // spans without hover info belong to AST nodes (see alloc_node_span), and are not indexed
     source_map.by_byte = Default::default();
     for (idx, span) in source_map.spans.iter().enumerate() {
-        if span.start_byte < span.end_byte {
+        if span.hover_info.is_some() && span.start_byte < span.end_byte {
             source_map
                 .by_byte
                 .insert(span.start_byte..span.end_byte, idx as u32);
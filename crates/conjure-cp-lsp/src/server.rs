@@ -15,25 +15,44 @@ use tower_lsp::lsp_types::{
 use crate::handlers::cache::{CacheCont, create_cache};
 
 use moka::future::Cache;
+use std::path::PathBuf;
+use tokio::sync::RwLock;
 
 #[derive(Debug)]
 pub struct Backend {
     pub client: Client,
     //cache is a member of backend and therefore can be accessed from within backend
     pub lsp_cache: Cache<Url, CacheCont>,
+    // the folders open in the client, searched for workspace symbols
+    pub workspace_roots: RwLock<Vec<PathBuf>>,
 }
 
 impl Backend {
     pub fn new(client: Client, lsp_cache: Cache<Url, CacheCont>) -> Self {
-        Backend { client, lsp_cache } //add cache here
+        Backend {
+            client,
+            lsp_cache,
+            workspace_roots: RwLock::new(vec![]),
+        }
     }
 }
 
 #[tower_lsp::async_trait]
 impl LanguageServer for Backend {
     //this is the server implementation and manages the server response to client requests
-    async fn initialize(&self, _: InitializeParams) -> Result<InitializeResult> {
+    async fn initialize(&self, params: InitializeParams) -> Result<InitializeResult> {
         //first request of client to server
+        // clients that do not support workspace folders only send the root
+        #[allow(deprecated)]
+        let roots = match params.workspace_folders {
+            Some(folders) => folders.into_iter().map(|folder| folder.uri).collect(),
+            None => params.root_uri.into_iter().collect::<Vec<_>>(),
+        };
+        *self.workspace_roots.write().await = roots
+            .iter()
+            .filter_map(|uri| uri.to_file_path().ok())
+            .collect();
+
         Ok(InitializeResult {
             server_info: None,
             capabilities: ServerCapabilities {
@@ -83,6 +102,25 @@ impl LanguageServer for Backend {
                 ),
                 //provides some simple hovering
                 hover_provider: Some(HoverProviderCapability::Simple(true)),
+                // navigation between declarations and references
+                definition_provider: Some(OneOf::Left(true)),
+                references_provider: Some(OneOf::Left(true)),
+                rename_provider: Some(OneOf::Right(RenameOptions {
+                    prepare_provider: Some(true),
+                    work_done_progress_options: Default::default(),
+                })),
+                // keywords, domains and declared names, depending on the position
+                completion_provider: Some(CompletionOptions {
+                    trigger_characters: Some(vec![":".to_string(), "(".to_string()]),
+                    ..Default::default()
+                }),
+                // formats the whole document, see conjure_cp_essence_parser::formatter
+                document_formatting_provider: Some(OneOf::Left(true)),
+                // outline, folding and search for declarations, see
+                // conjure_cp_essence_parser::diagnostics::outline
+                document_symbol_provider: Some(OneOf::Left(true)),
+                folding_range_provider: Some(FoldingRangeProviderCapability::Simple(true)),
+                workspace_symbol_provider: Some(OneOf::Left(true)),
                 ..ServerCapabilities::default()
             },
         })
@@ -117,6 +155,75 @@ impl LanguageServer for Backend {
         self.handle_hovering(params).await
     }
 
+    // set up navigation handlers
+    async fn goto_definition(
+        &self,
+        params: GotoDefinitionParams,
+    ) -> Result<Option<GotoDefinitionResponse>> {
+        self.client
+            .log_message(MessageType::INFO, "go to definition")
+            .await;
+        self.handle_goto_definition(params).await
+    }
+    async fn references(&self, params: ReferenceParams) -> Result<Option<Vec<Location>>> {
+        self.client
+            .log_message(MessageType::INFO, "find references")
+            .await;
+        self.handle_references(params).await
+    }
+    async fn prepare_rename(
+        &self,
+        params: TextDocumentPositionParams,
+    ) -> Result<Option<PrepareRenameResponse>> {
+        self.handle_prepare_rename(params).await
+    }
+    async fn rename(&self, params: RenameParams) -> Result<Option<WorkspaceEdit>> {
+        self.client.log_message(MessageType::INFO, "rename").await;
+        self.handle_rename(params).await
+    }
+
+    // set up completion handler
+    async fn completion(&self, params: CompletionParams) -> Result<Option<CompletionResponse>> {
+        self.client
+            .log_message(MessageType::INFO, "completion")
+            .await;
+        self.handle_completion(params).await
+    }
+
+    // set up formatting handler
+    async fn formatting(&self, params: DocumentFormattingParams) -> Result<Option<Vec<TextEdit>>> {
+        self.client
+            .log_message(MessageType::INFO, "formatting")
+            .await;
+        self.handle_formatting(params).await
+    }
+
+    // set up outline, folding and workspace symbol handlers
+    async fn document_symbol(
+        &self,
+        params: DocumentSymbolParams,
+    ) -> Result<Option<DocumentSymbolResponse>> {
+        self.client
+            .log_message(MessageType::INFO, "document symbols")
+            .await;
+        self.handle_document_symbol(params).await
+    }
+    async fn folding_range(&self, params: FoldingRangeParams) -> Result<Option<Vec<FoldingRange>>> {
+        self.client
+            .log_message(MessageType::INFO, "folding ranges")
+            .await;
+        self.handle_folding_range(params).await
+    }
+    async fn symbol(
+        &self,
+        params: WorkspaceSymbolParams,
+    ) -> Result<Option<Vec<SymbolInformation>>> {
+        self.client
+            .log_message(MessageType::INFO, "workspace symbols")
+            .await;
+        self.handle_workspace_symbol(params).await
+    }
+
     // set up semantic highlighting
     async fn semantic_tokens_full(
         &self,
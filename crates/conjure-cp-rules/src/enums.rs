@@ -0,0 +1,182 @@
+//! Rules for enumerated and unnamed types.
+//!
+//! Values of these types are lowered to integers: each value is replaced by its position in the
+//! definition of its type, counting from 1. Variables are lowered by the `enum_int`
+//! representation, which maps the integers in a solution back to the names of the values.
+//!
+//! These rules have a higher priority than the solver specific rules, so that the solver specific
+//! rules only ever see integers.
+
+use conjure_cp::ast::{
+    Atom, DeclarationKind, Expression as Expr, GroundDomain, Literal, Metadata, Name, SymbolTable,
+};
+use conjure_cp::rule_engine::{
+    ApplicationError::RuleNotApplicable, ApplicationResult, Reduction, register_rule,
+};
+use itertools::Itertools;
+use uniplate::Uniplate;
+
+/// Returns all the values of the enumerated type `enum_name`, in order, if it is declared in
+/// `symbols`.
+pub(crate) fn enum_type_values(enum_name: &Name, symbols: &SymbolTable) -> Option<Vec<Name>> {
+    let decl = symbols.lookup(enum_name)?;
+    let domain = decl.as_domain_letting()?.resolve().ok()?;
+
+    let GroundDomain::Enum(_, values) = domain.as_ref() else {
+        return None;
+    };
+
+    Some(values.clone())
+}
+
+/// Returns the integer that represents `value`, a value of the enumerated type `enum_name`.
+fn enum_value_to_int(enum_name: &Name, value: &Name, symbols: &SymbolTable) -> Option<i32> {
+    let i = enum_type_values(enum_name, symbols)?
+        .iter()
+        .position(|x| x == value)?;
+    i32::try_from(i + 1).ok()
+}
+
+// selects the enum_int representation for all variables of enumerated types in one go.
+//
+// this happens before the solver specific rules run, as otherwise they would mistake references
+// to these variables for booleans.
+#[register_rule("Base", 8701, [Root])]
+fn select_enum_representation(expr: &Expr, symbols: &SymbolTable) -> ApplicationResult {
+    let Expr::Root(_, _) = expr else {
+        return Err(RuleNotApplicable);
+    };
+
+    // cannot create representations on non-local variables, so use into_iter_local.
+    let enum_vars = symbols
+        .clone()
+        .into_iter_local()
+        .filter(|(name, decl)| {
+            let is_enum = decl.as_find().is_some_and(|var| {
+                var.domain
+                    .resolve()
+                    .is_ok_and(|domain| matches!(domain.as_ref(), GroundDomain::Enum(_, _)))
+            });
+            is_enum
+                && symbols
+                    .representations_for(name)
+                    .is_some_and(|reprs| reprs.is_empty())
+        })
+        .map(|(name, _)| name)
+        .collect_vec();
+
+    if enum_vars.is_empty() {
+        return Err(RuleNotApplicable);
+    }
+
+    let mut symbols = symbols.clone();
+    for name in enum_vars {
+        // (creates the represented variables as a side effect)
+        symbols
+            .get_or_add_representation(&name, &["enum_int"])
+            .ok_or(RuleNotApplicable)?;
+    }
+
+    Ok(Reduction::with_symbols(expr.clone(), symbols))
+}
+
+// x ~~> x#enum_int_value
+#[register_rule("Base", 8700, [Atomic])]
+fn enum_variable_to_int(expr: &Expr, symbols: &SymbolTable) -> ApplicationResult {
+    let Expr::Atomic(_, Atom::Reference(decl)) = expr else {
+        return Err(RuleNotApplicable);
+    };
+
+    let name = match &decl.name() as &Name {
+        Name::WithRepresentation(name, reprs)
+            if reprs.first().is_some_and(|x| x.as_str() == "enum_int") =>
+        {
+            name.as_ref().clone()
+        }
+        name => name.clone(),
+    };
+
+    let repr = symbols
+        .get_representation(&name, &["enum_int"])
+        .ok_or(RuleNotApplicable)?[0]
+        .clone();
+
+    let subject = repr
+        .expression_down(symbols)?
+        .into_values()
+        .next()
+        .ok_or(RuleNotApplicable)?;
+
+    Ok(Reduction::pure(subject))
+}
+
+// red ~~> 1, where red is a reference to the first value of its enumerated type
+#[register_rule("Base", 8700, [Atomic])]
+fn enum_constant_to_int(expr: &Expr, symbols: &SymbolTable) -> ApplicationResult {
+    let Expr::Atomic(_, Atom::Reference(reference)) = expr else {
+        return Err(RuleNotApplicable);
+    };
+
+    // the values of an enumerated type are declared as lettings with a domain containing only
+    // themselves. unlike their definitions, these domains are not rewritten into solver specific
+    // forms.
+    let DeclarationKind::ValueLetting(_, Some(domain)) =
+        &reference.ptr().kind() as &DeclarationKind
+    else {
+        return Err(RuleNotApplicable);
+    };
+
+    let domain = domain.resolve().map_err(|_| RuleNotApplicable)?;
+    let GroundDomain::Enum(enum_name, values) = domain.as_ref() else {
+        return Err(RuleNotApplicable);
+    };
+
+    let [value] = values.as_slice() else {
+        return Err(RuleNotApplicable);
+    };
+
+    let i = enum_value_to_int(enum_name, value, symbols).ok_or(RuleNotApplicable)?;
+    Ok(Reduction::pure(Expr::from(i)))
+}
+
+// red ~~> 1, where red is the first value of its enumerated type
+//
+// {red, blue} ~~> {1, 3}
+#[register_rule("Base", 8700, [Atomic])]
+fn enum_literal_to_int(expr: &Expr, symbols: &SymbolTable) -> ApplicationResult {
+    let Expr::Atomic(_, Atom::Literal(literal)) = expr else {
+        return Err(RuleNotApplicable);
+    };
+
+    let enum_values = literal
+        .universe()
+        .into_iter()
+        .filter_map(|x| match x {
+            Literal::Enum(value) => Some(value),
+            _ => None,
+        })
+        .collect_vec();
+
+    // all the values must be of types that are declared
+    if enum_values.is_empty()
+        || enum_values
+            .iter()
+            .any(|value| enum_value_to_int(&value.0, &value.1, symbols).is_none())
+    {
+        return Err(RuleNotApplicable);
+    }
+
+    let new_literal = literal.transform(&|x| {
+        if let Literal::Enum(value) = &x
+            && let Some(i) = enum_value_to_int(&value.0, &value.1, symbols)
+        {
+            return Literal::Int(i);
+        }
+        x
+    });
+
+    Ok(Reduction::pure(Expr::Atomic(
+        Metadata::new(),
+        Atom::Literal(new_literal),
+    )))
+}
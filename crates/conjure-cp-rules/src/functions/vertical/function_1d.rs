@@ -0,0 +1,314 @@
+use conjure_cp::ast::{Atom, Expression as Expr, Literal, Metadata, Moo, SymbolTable};
+use conjure_cp::into_matrix_expr;
+use conjure_cp::rule_engine::{
+    ApplicationError, ApplicationError::RuleNotApplicable, ApplicationResult, Reduction,
+    register_rule,
+};
+use itertools::Itertools;
+
+use super::represented_function;
+use crate::representation::function_1d::Function1D;
+
+/// If `expr` is a (possibly restricted) function with the `function_1d` representation, returns
+/// that representation and the values in its domain that are not restricted away.
+fn function_1d(expr: &Expr, symbols: &SymbolTable) -> Option<(Function1D, Vec<Literal>)> {
+    let (repr, restrictions): (Function1D, _) = represented_function(expr, "function_1d", symbols)?;
+
+    let values = repr
+        .domain_values()
+        .iter()
+        .filter(|x| {
+            restrictions
+                .iter()
+                .all(|domain| domain.contains(x).unwrap_or(false))
+        })
+        .cloned()
+        .collect_vec();
+
+    Some((repr, values))
+}
+
+/// Returns `or([ and(conjuncts(v, f(v), defined(v))) | v in values ])`.
+fn exists_value(
+    repr: &Function1D,
+    values: &[Literal],
+    symbols: &SymbolTable,
+    conjuncts: impl Fn(&Literal, Expr, Expr) -> Vec<Expr>,
+) -> Result<Expr, ApplicationError> {
+    let disjuncts = values
+        .iter()
+        .map(|x| {
+            let image = repr.image(x, symbols).ok_or(RuleNotApplicable)?;
+            let defined = repr.defined(x, symbols).ok_or(RuleNotApplicable)?;
+            Ok(Expr::And(
+                Metadata::new(),
+                Moo::new(into_matrix_expr![conjuncts(x, image, defined)]),
+            ))
+        })
+        .collect::<Result<Vec<_>, ApplicationError>>()?;
+
+    Ok(Expr::Or(
+        Metadata::new(),
+        Moo::new(into_matrix_expr![disjuncts]),
+    ))
+}
+
+fn eq(a: Expr, b: Expr) -> Expr {
+    Expr::Eq(Metadata::new(), Moo::new(a), Moo::new(b))
+}
+
+// f(x) ~~> f#function_1d_values_x
+//
+// f(x) ~~> {y @ or([ x = v /\ defined(v) | v in dom(f) ])} ;
+//          and([ x = v -> y = f#function_1d_values_v | v in dom(f) ]),
+//          or([ x = v | v in dom(f) ] ++ [ y = min(codomain(f)) ])
+//
+//   where y is a new variable. The top level constraints fix y to the image of x, or to a single
+//   value if x is not in the domain, so that y has exactly one assignment.
+#[register_rule("Base", 2000, [Image])]
+fn image_function_1d(expr: &Expr, symbols: &SymbolTable) -> ApplicationResult {
+    let Expr::Image(_, function, x) = expr else {
+        return Err(RuleNotApplicable);
+    };
+
+    let (repr, values) = function_1d(function, symbols).ok_or(RuleNotApplicable)?;
+
+    if let Some(x) = x.as_ref().clone().into_literal() {
+        // f(x) is undefined outside the domain of f
+        if !values.contains(&x) {
+            return Ok(Reduction::pure(Expr::Bubble(
+                Metadata::new(),
+                Moo::new(Expr::from(repr.smallest_image().clone())),
+                Moo::new(Expr::from(false)),
+            )));
+        }
+
+        let image = repr.image(&x, symbols).ok_or(RuleNotApplicable)?;
+        if !repr.is_partial() {
+            return Ok(Reduction::pure(image));
+        }
+
+        let defined = repr.defined(&x, symbols).ok_or(RuleNotApplicable)?;
+        return Ok(Reduction::pure(Expr::Bubble(
+            Metadata::new(),
+            Moo::new(image),
+            Moo::new(defined),
+        )));
+    }
+
+    let domain = expr.domain_of().ok_or(ApplicationError::DomainError)?;
+    let mut symbols = symbols.clone();
+    let y = Expr::from(Atom::new_ref(symbols.gen_find(&domain)));
+
+    let x_is = |v: &Literal| eq(x.as_ref().clone(), Expr::from(v.clone()));
+
+    let mut new_top = values
+        .iter()
+        .map(|v| {
+            let image = repr.image(v, &symbols).ok_or(RuleNotApplicable)?;
+            Ok(Expr::Imply(
+                Metadata::new(),
+                Moo::new(x_is(v)),
+                Moo::new(eq(y.clone(), image)),
+            ))
+        })
+        .collect::<Result<Vec<_>, ApplicationError>>()?;
+
+    let mut in_domain = values.iter().map(x_is).collect_vec();
+    in_domain.push(eq(y.clone(), Expr::from(repr.smallest_image().clone())));
+    new_top.push(Expr::Or(
+        Metadata::new(),
+        Moo::new(into_matrix_expr![in_domain]),
+    ));
+
+    let is_defined = exists_value(&repr, &values, &symbols, |v, _, defined| {
+        vec![x_is(v), defined]
+    })?;
+
+    Ok(Reduction::new(
+        Expr::Bubble(Metadata::new(), Moo::new(y), Moo::new(is_defined)),
+        new_top,
+        symbols,
+    ))
+}
+
+// y in imageSet(f, x) ~~> or([ x = v /\ defined(v) /\ f#function_1d_values_v = y | v in dom(f) ])
+#[register_rule("Base", 2000, [In])]
+fn in_image_set_function_1d(expr: &Expr, symbols: &SymbolTable) -> ApplicationResult {
+    let Expr::In(_, y, image_set) = expr else {
+        return Err(RuleNotApplicable);
+    };
+
+    let Expr::ImageSet(_, function, x) = image_set.as_ref() else {
+        return Err(RuleNotApplicable);
+    };
+
+    let (repr, values) = function_1d(function, symbols).ok_or(RuleNotApplicable)?;
+
+    Ok(Reduction::pure(exists_value(
+        &repr,
+        &values,
+        symbols,
+        |v, image, defined| {
+            vec![
+                eq(x.as_ref().clone(), Expr::from(v.clone())),
+                defined,
+                eq(image, y.as_ref().clone()),
+            ]
+        },
+    )?))
+}
+
+// |imageSet(f, x)| ~~> toInt(or([ x = v /\ defined(v) | v in dom(f) ]))
+#[register_rule("Base", 2000, [Card])]
+fn card_image_set_function_1d(expr: &Expr, symbols: &SymbolTable) -> ApplicationResult {
+    let Expr::Card(_, image_set) = expr else {
+        return Err(RuleNotApplicable);
+    };
+
+    let Expr::ImageSet(_, function, x) = image_set.as_ref() else {
+        return Err(RuleNotApplicable);
+    };
+
+    let (repr, values) = function_1d(function, symbols).ok_or(RuleNotApplicable)?;
+
+    let is_defined = exists_value(&repr, &values, symbols, |v, _, defined| {
+        vec![eq(x.as_ref().clone(), Expr::from(v.clone())), defined]
+    })?;
+
+    Ok(Reduction::pure(Expr::ToInt(
+        Metadata::new(),
+        Moo::new(is_defined),
+    )))
+}
+
+// x in preImage(f, y) ~~> or([ x = v /\ defined(v) /\ f#function_1d_values_v = y | v in dom(f) ])
+#[register_rule("Base", 2000, [In])]
+fn in_pre_image_function_1d(expr: &Expr, symbols: &SymbolTable) -> ApplicationResult {
+    let Expr::In(_, x, pre_image) = expr else {
+        return Err(RuleNotApplicable);
+    };
+
+    let Expr::PreImage(_, function, y) = pre_image.as_ref() else {
+        return Err(RuleNotApplicable);
+    };
+
+    let (repr, values) = function_1d(function, symbols).ok_or(RuleNotApplicable)?;
+
+    Ok(Reduction::pure(exists_value(
+        &repr,
+        &values,
+        symbols,
+        |v, image, defined| {
+            vec![
+                eq(x.as_ref().clone(), Expr::from(v.clone())),
+                defined,
+                eq(image, y.as_ref().clone()),
+            ]
+        },
+    )?))
+}
+
+// |preImage(f, y)| ~~> sum([ toInt(defined(v) /\ f#function_1d_values_v = y) | v in dom(f) ])
+#[register_rule("Base", 2000, [Card])]
+fn card_pre_image_function_1d(expr: &Expr, symbols: &SymbolTable) -> ApplicationResult {
+    let Expr::Card(_, pre_image) = expr else {
+        return Err(RuleNotApplicable);
+    };
+
+    let Expr::PreImage(_, function, y) = pre_image.as_ref() else {
+        return Err(RuleNotApplicable);
+    };
+
+    let (repr, values) = function_1d(function, symbols).ok_or(RuleNotApplicable)?;
+
+    let terms = values
+        .iter()
+        .map(|v| {
+            let image = repr.image(v, symbols).ok_or(RuleNotApplicable)?;
+            let defined = repr.defined(v, symbols).ok_or(RuleNotApplicable)?;
+            Ok(Expr::ToInt(
+                Metadata::new(),
+                Moo::new(Expr::And(
+                    Metadata::new(),
+                    Moo::new(into_matrix_expr![vec![
+                        defined,
+                        eq(image, y.as_ref().clone())
+                    ]]),
+                )),
+            ))
+        })
+        .collect::<Result<Vec<_>, ApplicationError>>()?;
+
+    Ok(Reduction::pure(Expr::Sum(
+        Metadata::new(),
+        Moo::new(into_matrix_expr![terms]),
+    )))
+}
+
+// |f| ~~> sum([ toInt(defined(v)) | v in dom(f) ])
+#[register_rule("Base", 2000, [Card])]
+fn card_function_1d(expr: &Expr, symbols: &SymbolTable) -> ApplicationResult {
+    let Expr::Card(_, function) = expr else {
+        return Err(RuleNotApplicable);
+    };
+
+    let (repr, values) = function_1d(function, symbols).ok_or(RuleNotApplicable)?;
+
+    let terms = values
+        .iter()
+        .map(|v| {
+            let defined = repr.defined(v, symbols).ok_or(RuleNotApplicable)?;
+            Ok(Expr::ToInt(Metadata::new(), Moo::new(defined)))
+        })
+        .collect::<Result<Vec<_>, ApplicationError>>()?;
+
+    Ok(Reduction::pure(Expr::Sum(
+        Metadata::new(),
+        Moo::new(into_matrix_expr![terms]),
+    )))
+}
+
+// inverse(f, g) ~~> and([ defined_f(v) -> v in imageSet(g, f(v)) | v in dom(f) ]
+//                    ++ [ defined_g(w) -> w in imageSet(f, g(w)) | w in dom(g) ])
+#[register_rule("Base", 2000, [Inverse])]
+fn inverse_function_1d(expr: &Expr, symbols: &SymbolTable) -> ApplicationResult {
+    let Expr::Inverse(_, f, g) = expr else {
+        return Err(RuleNotApplicable);
+    };
+
+    let (f_repr, f_values) = function_1d(f, symbols).ok_or(RuleNotApplicable)?;
+    let (g_repr, g_values) = function_1d(g, symbols).ok_or(RuleNotApplicable)?;
+
+    // every mapping x --> y in `function` has the mapping y --> x in `other`
+    let maps_back = |repr: &Function1D, values: &[Literal], other: &Moo<Expr>| {
+        values
+            .iter()
+            .map(|x| {
+                let image = repr.image(x, symbols).ok_or(RuleNotApplicable)?;
+                let defined = repr.defined(x, symbols).ok_or(RuleNotApplicable)?;
+                Ok(Expr::Imply(
+                    Metadata::new(),
+                    Moo::new(defined),
+                    Moo::new(Expr::In(
+                        Metadata::new(),
+                        Moo::new(Expr::from(x.clone())),
+                        Moo::new(Expr::ImageSet(
+                            Metadata::new(),
+                            other.clone(),
+                            Moo::new(image),
+                        )),
+                    )),
+                ))
+            })
+            .collect::<Result<Vec<_>, ApplicationError>>()
+    };
+
+    let mut conjuncts = maps_back(&f_repr, &f_values, g)?;
+    conjuncts.extend(maps_back(&g_repr, &g_values, f)?);
+
+    Ok(Reduction::pure(Expr::And(
+        Metadata::new(),
+        Moo::new(into_matrix_expr![conjuncts]),
+    )))
+}
@@ -0,0 +1,35 @@
+use conjure_cp::ast::{Atom, Expression as Expr, GroundDomain, Moo, Name, SymbolTable};
+use conjure_cp::representation::Representation;
+
+mod function_1d;
+
+/// If `expr` is a reference to a function with the representation `repr_name`, returns that
+/// representation.
+///
+/// The function may be restricted to a subset of its domain using `restrict`. The domains it is
+/// restricted to are returned alongside the representation.
+fn represented_function<R: Representation>(
+    expr: &Expr,
+    repr_name: &str,
+    symbols: &SymbolTable,
+) -> Option<(R, Vec<Moo<GroundDomain>>)> {
+    match expr {
+        Expr::Restrict(_, function, domain) => {
+            let (repr, mut restrictions) = represented_function(function, repr_name, symbols)?;
+            restrictions.push(domain.domain_of()?.resolve().ok()?);
+            Some((repr, restrictions))
+        }
+        Expr::Atomic(_, Atom::Reference(decl)) => {
+            let Name::WithRepresentation(name, reprs) = &decl.name() as &Name else {
+                return None;
+            };
+
+            if reprs.first().is_none_or(|x| x.as_str() != repr_name) {
+                return None;
+            }
+
+            Some((R::init(name, symbols)?, vec![]))
+        }
+        _ => None,
+    }
+}
@@ -9,14 +9,20 @@ mod bottom_up_adaptor;
 mod bubble;
 mod comprehensions;
 mod constant_eval;
+mod enums;
+mod functions;
 mod lex;
 mod matrix;
 mod minion;
+mod msets;
 mod normalisers;
+mod partitions;
 mod records;
+mod relations;
 mod representation;
 mod sat;
 mod select_representation;
+mod sequences;
 mod sets;
 mod tuple;
 mod utils;
@@ -53,7 +53,12 @@ fn index_matrix_to_atom_impl(expr: &Expr, symbols: &SymbolTable) -> ApplicationR
         let mut indices_as_lits: Vec<Literal> = vec![];
 
         for index in indices {
-            let Some(index) = index.clone().into_literal() else {
+            // SAT encodes integer constants as SATInts before they get here
+            let index = match index {
+                Expr::SATInt(_, _, _, (min, max)) if min == max => Some(Literal::Int(*min)),
+                _ => index.clone().into_literal(),
+            };
+            let Some(index) = index else {
                 indices_are_const = false;
                 break;
             };
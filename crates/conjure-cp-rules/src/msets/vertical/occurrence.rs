@@ -0,0 +1,278 @@
+use conjure_cp::ast::{
+    AbstractLiteral, Atom, Expression as Expr, Literal, Metadata, Moo, SymbolTable,
+};
+use conjure_cp::into_matrix_expr;
+use conjure_cp::rule_engine::{
+    ApplicationError::RuleNotApplicable, ApplicationResult, Reduction, register_rule,
+};
+use itertools::Itertools;
+
+use crate::representation::mset_occurrence::MSetOccurrence;
+use crate::utils::represented_as;
+
+const REPR_NAME: &str = "mset_occurrence";
+
+/// The number of times each value that can be in a multi-set occurs in it.
+///
+/// Values that are not listed never occur in the multi-set.
+type Occurrences = Vec<(Literal, Expr)>;
+
+fn and(conjuncts: Vec<Expr>) -> Expr {
+    Expr::And(Metadata::new(), Moo::new(into_matrix_expr![conjuncts]))
+}
+
+fn or(disjuncts: Vec<Expr>) -> Expr {
+    Expr::Or(Metadata::new(), Moo::new(into_matrix_expr![disjuncts]))
+}
+
+fn eq(a: Expr, b: Expr) -> Expr {
+    Expr::Eq(Metadata::new(), Moo::new(a), Moo::new(b))
+}
+
+fn lookup(occurrences: &Occurrences, value: &Literal) -> Option<Expr> {
+    occurrences
+        .iter()
+        .find(|(x, _)| x == value)
+        .map(|(_, occurrence)| occurrence.clone())
+}
+
+/// Returns the value of `expr`, if it is a literal or only has one possible value.
+///
+/// The elements of a multi-set literal may already have been rewritten into solver-specific
+/// forms (e.g. SAT integers) by the time it is compared with a represented multi-set.
+fn constant_value(expr: &Expr) -> Option<Literal> {
+    if let Some(x) = expr.clone().into_literal() {
+        return Some(x);
+    }
+
+    let domain = expr.domain_of()?.resolve().ok()?;
+    domain.values().ok()?.exactly_one().ok()
+}
+
+/// Returns the number of occurrences of each value in `expr`, if it is a multi-set with the
+/// occurrence representation, a multi-set literal, or a union or intersection of these.
+///
+/// M#mset_occurrence                 ~~> [ (v, M#mset_occurrence_v) | v in elements of M ]
+/// mset(x_1, .., x_n)                ~~> [ (x_i, number of times x_i occurs) | i in 1..n ]
+/// A union B                         ~~> [ (v, max(occ(A, v), occ(B, v))) | v in A or B ]
+/// A intersect B                     ~~> [ (v, min(occ(A, v), occ(B, v))) | v in A and B ]
+fn occurrences(expr: &Expr, symbols: &SymbolTable) -> Option<Occurrences> {
+    let literal_occurrences = |elems: Vec<Literal>| {
+        let mut counts: Vec<(Literal, i32)> = vec![];
+        for x in elems {
+            match counts.iter_mut().find(|(y, _)| *y == x) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((x, 1)),
+            }
+        }
+        counts
+            .into_iter()
+            .map(|(x, count)| (x, Expr::from(count)))
+            .collect()
+    };
+
+    match expr {
+        Expr::Atomic(_, Atom::Literal(Literal::AbstractLiteral(AbstractLiteral::MSet(elems)))) => {
+            Some(literal_occurrences(elems.clone()))
+        }
+        Expr::AbstractLiteral(_, AbstractLiteral::MSet(elems)) => {
+            let elems = elems
+                .iter()
+                .map(constant_value)
+                .collect::<Option<Vec<_>>>()?;
+            Some(literal_occurrences(elems))
+        }
+        Expr::Union(_, a, b) => {
+            let a = occurrences(a, symbols)?;
+            let b = occurrences(b, symbols)?;
+            let values = a.iter().chain(&b).map(|(x, _)| x.clone()).unique();
+
+            Some(
+                values
+                    .map(|x| {
+                        let occurrence = match (lookup(&a, &x), lookup(&b, &x)) {
+                            (Some(a), Some(b)) => {
+                                Expr::Max(Metadata::new(), Moo::new(into_matrix_expr![vec![a, b]]))
+                            }
+                            (Some(a), None) => a,
+                            (None, Some(b)) => b,
+                            (None, None) => unreachable!(),
+                        };
+                        (x, occurrence)
+                    })
+                    .collect(),
+            )
+        }
+        Expr::Intersect(_, a, b) => {
+            let a = occurrences(a, symbols)?;
+            let b = occurrences(b, symbols)?;
+
+            Some(
+                a.into_iter()
+                    .filter_map(|(x, a)| {
+                        let b = lookup(&b, &x)?;
+                        let occurrence =
+                            Expr::Min(Metadata::new(), Moo::new(into_matrix_expr![vec![a, b]]));
+                        Some((x, occurrence))
+                    })
+                    .collect(),
+            )
+        }
+        _ => {
+            let repr: MSetOccurrence = represented_as(expr, REPR_NAME, symbols)?;
+            repr.elements()
+                .iter()
+                .map(|x| Some((x.clone(), repr.occurrence(x, symbols)?)))
+                .collect()
+        }
+    }
+}
+
+/// Whether `expr` mentions a multi-set with the occurrence representation.
+///
+/// Expressions over only multi-set literals are left to be evaluated instead.
+fn is_represented(expr: &Expr, symbols: &SymbolTable) -> bool {
+    match expr {
+        Expr::Union(_, a, b) | Expr::Intersect(_, a, b) => {
+            is_represented(a, symbols) || is_represented(b, symbols)
+        }
+        _ => represented_as::<MSetOccurrence>(expr, REPR_NAME, symbols).is_some(),
+    }
+}
+
+/// Returns the occurrences of `expr`, if it mentions a multi-set with the occurrence
+/// representation.
+fn represented_occurrences(expr: &Expr, symbols: &SymbolTable) -> Option<Occurrences> {
+    if !is_represented(expr, symbols) {
+        return None;
+    }
+    occurrences(expr, symbols)
+}
+
+// x in M ~~> M#mset_occurrence_x > 0
+// x in M ~~> or([ x = v /\ M#mset_occurrence_v > 0 | v in elements of M ])
+#[register_rule("Base", 2000, [In])]
+fn in_mset_occurrence(expr: &Expr, symbols: &SymbolTable) -> ApplicationResult {
+    let Expr::In(_, x, mset) = expr else {
+        return Err(RuleNotApplicable);
+    };
+
+    let occurrences = represented_occurrences(mset, symbols).ok_or(RuleNotApplicable)?;
+    let occurs = |occurrence: Expr| {
+        Expr::Gt(
+            Metadata::new(),
+            Moo::new(occurrence),
+            Moo::new(Expr::from(0)),
+        )
+    };
+
+    if let Some(x) = x.as_ref().clone().into_literal() {
+        return Ok(Reduction::pure(
+            lookup(&occurrences, &x).map_or(Expr::from(false), occurs),
+        ));
+    }
+
+    let disjuncts = occurrences
+        .into_iter()
+        .map(|(v, occurrence)| {
+            and(vec![
+                eq(x.as_ref().clone(), Expr::from(v)),
+                occurs(occurrence),
+            ])
+        })
+        .collect_vec();
+
+    Ok(Reduction::pure(or(disjuncts)))
+}
+
+// freq(M, x) ~~> M#mset_occurrence_x
+//
+// freq(M, x) ~~> f
+//
+//   new variable f, with the top level constraints:
+//
+//     and([ x = v -> f = M#mset_occurrence_v | v in elements of M ])
+//     f = 0 \/ or([ x = v | v in elements of M ])
+#[register_rule("Base", 2000, [Freq])]
+fn freq_mset_occurrence(expr: &Expr, symbols: &SymbolTable) -> ApplicationResult {
+    let Expr::Freq(_, mset, x) = expr else {
+        return Err(RuleNotApplicable);
+    };
+
+    let occurrences = represented_occurrences(mset, symbols).ok_or(RuleNotApplicable)?;
+
+    if let Some(x) = x.as_ref().clone().into_literal() {
+        return Ok(Reduction::pure(
+            lookup(&occurrences, &x).unwrap_or(Expr::from(0)),
+        ));
+    }
+
+    let domain = expr.domain_of().ok_or(RuleNotApplicable)?;
+    if !domain.resolve().is_ok_and(|domain| domain.is_finite()) {
+        return Err(RuleNotApplicable);
+    }
+
+    let mut symbols = symbols.clone();
+    let f = Expr::from(Atom::new_ref(symbols.gen_find(&domain)));
+
+    let mut new_top = vec![];
+    let mut is_element = vec![eq(f.clone(), Expr::from(0))];
+    for (v, occurrence) in occurrences {
+        let is_v = eq(x.as_ref().clone(), Expr::from(v));
+        new_top.push(Expr::Imply(
+            Metadata::new(),
+            Moo::new(is_v.clone()),
+            Moo::new(eq(f.clone(), occurrence)),
+        ));
+        is_element.push(is_v);
+    }
+    new_top.push(or(is_element));
+
+    Ok(Reduction::new(f, new_top, symbols))
+}
+
+// |M| ~~> sum([ M#mset_occurrence_v | v in elements of M ])
+#[register_rule("Base", 2000, [Card])]
+fn card_mset_occurrence(expr: &Expr, symbols: &SymbolTable) -> ApplicationResult {
+    let Expr::Card(_, mset) = expr else {
+        return Err(RuleNotApplicable);
+    };
+
+    let occurrences = represented_occurrences(mset, symbols).ok_or(RuleNotApplicable)?;
+    let occurrences = occurrences.into_iter().map(|(_, x)| x).collect_vec();
+
+    Ok(Reduction::pure(Expr::Sum(
+        Metadata::new(),
+        Moo::new(into_matrix_expr![occurrences]),
+    )))
+}
+
+// A subsetEq B ~~> and([ occ(A, v) <= occ(B, v) | v in elements of A ])
+//
+//   where occ(B, v) is 0 if v cannot be in B.
+#[register_rule("Base", 2000, [SubsetEq])]
+fn subseteq_mset_occurrence(expr: &Expr, symbols: &SymbolTable) -> ApplicationResult {
+    let Expr::SubsetEq(_, a, b) = expr else {
+        return Err(RuleNotApplicable);
+    };
+
+    if !is_represented(a, symbols) && !is_represented(b, symbols) {
+        return Err(RuleNotApplicable);
+    }
+
+    let a = occurrences(a, symbols).ok_or(RuleNotApplicable)?;
+    let b = occurrences(b, symbols).ok_or(RuleNotApplicable)?;
+
+    let conjuncts = a
+        .into_iter()
+        .map(|(v, occurrence)| {
+            Expr::Leq(
+                Metadata::new(),
+                Moo::new(occurrence),
+                Moo::new(lookup(&b, &v).unwrap_or(Expr::from(0))),
+            )
+        })
+        .collect_vec();
+
+    Ok(Reduction::pure(and(conjuncts)))
+}
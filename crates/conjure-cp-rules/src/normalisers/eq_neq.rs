@@ -5,7 +5,7 @@ use conjure_cp::rule_engine::{
     ApplicationError::RuleNotApplicable, ApplicationResult, Reduction, register_rule,
 };
 
-use conjure_cp::ast::ReturnType::{Matrix, Set};
+use conjure_cp::ast::ReturnType::{MSet, Matrix, Set};
 use conjure_cp::essence_expr;
 
 /// Converts a negated `Neq` to an `Eq`
@@ -31,7 +31,7 @@ fn negated_neq_to_eq(expr: &Expr, _: &SymbolTable) -> ApplicationResult {
 /// ```text
 /// not(eq(x)) ~> neq(x)
 /// ```
-/// don't want this to apply to sets
+/// don't want this to apply to sets or multi-sets
 ///
 /// Also can't apply to matrices, since undefinedness between two matrices with different domains
 /// causes a != b to actually have a different meaning than !(a = b)
@@ -40,10 +40,10 @@ fn negated_eq_to_neq(expr: &Expr, _: &SymbolTable) -> ApplicationResult {
     match expr {
         Expr::Not(_, a) => match a.as_ref() {
             Expr::Eq(_, b, c) if (b.is_safe() && c.is_safe()) => {
-                if matches!(b.as_ref().return_type(), Set(_) | Matrix(_)) {
+                if matches!(b.as_ref().return_type(), Set(_) | MSet(_) | Matrix(_)) {
                     return Err(RuleNotApplicable);
                 }
-                if matches!(c.as_ref().return_type(), Set(_) | Matrix(_)) {
+                if matches!(c.as_ref().return_type(), Set(_) | MSet(_) | Matrix(_)) {
                     return Err(RuleNotApplicable);
                 }
                 Ok(Reduction::pure(essence_expr!(&b != &c)))
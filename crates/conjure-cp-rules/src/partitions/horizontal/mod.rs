@@ -0,0 +1,2 @@
+mod participants;
+mod party;
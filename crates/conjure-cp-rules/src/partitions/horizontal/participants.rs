@@ -0,0 +1,33 @@
+use conjure_cp::ast::{AbstractLiteral, Expression as Expr, GroundDomain, Literal, SymbolTable};
+use conjure_cp::rule_engine::{
+    ApplicationError, ApplicationError::RuleNotApplicable, ApplicationResult, Reduction,
+    register_rule,
+};
+
+// participants(P) ~~> { v | v in dom(P) }
+//
+//   as every value in the domain of a partition is in one of its parts.
+#[register_rule("Base", 8700, [Participants])]
+fn participants_to_domain(expr: &Expr, _: &SymbolTable) -> ApplicationResult {
+    let Expr::Participants(_, partition) = expr else {
+        return Err(RuleNotApplicable);
+    };
+
+    let domain = partition
+        .domain_of()
+        .and_then(|domain| domain.resolve().ok())
+        .ok_or(ApplicationError::DomainError)?;
+
+    let GroundDomain::Partition(_, elem_domain) = domain.as_ref() else {
+        return Err(RuleNotApplicable);
+    };
+
+    let values = elem_domain
+        .values()
+        .map_err(|_| RuleNotApplicable)?
+        .collect();
+
+    Ok(Reduction::pure(Expr::from(Literal::AbstractLiteral(
+        AbstractLiteral::Set(values),
+    ))))
+}
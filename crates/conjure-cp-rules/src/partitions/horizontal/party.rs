@@ -0,0 +1,71 @@
+use conjure_cp::ast::{
+    AbstractLiteral, Expression as Expr, GroundDomain, Metadata, Moo, SymbolTable,
+};
+use conjure_cp::into_matrix_expr;
+use conjure_cp::rule_engine::{
+    ApplicationError, ApplicationError::RuleNotApplicable, ApplicationResult, Reduction,
+    register_rule,
+};
+use itertools::Itertools;
+
+fn together(x: &Moo<Expr>, y: Expr, partition: &Moo<Expr>) -> Expr {
+    Expr::Together(
+        Metadata::new(),
+        Moo::new(Expr::AbstractLiteral(
+            Metadata::new(),
+            AbstractLiteral::Set(vec![x.as_ref().clone(), y]),
+        )),
+        partition.clone(),
+    )
+}
+
+// y in party(x, P) ~~> together({x, y}, P)
+#[register_rule("Base", 8700, [In])]
+fn in_party_to_together(expr: &Expr, _: &SymbolTable) -> ApplicationResult {
+    let Expr::In(_, y, party) = expr else {
+        return Err(RuleNotApplicable);
+    };
+
+    let Expr::Party(_, x, partition) = party.as_ref() else {
+        return Err(RuleNotApplicable);
+    };
+
+    Ok(Reduction::pure(together(x, y.as_ref().clone(), partition)))
+}
+
+// |party(x, P)| ~~> sum([ toInt(together({x, v}, P)) | v in dom(P) ])
+#[register_rule("Base", 8700, [Card])]
+fn card_party_to_together(expr: &Expr, _: &SymbolTable) -> ApplicationResult {
+    let Expr::Card(_, party) = expr else {
+        return Err(RuleNotApplicable);
+    };
+
+    let Expr::Party(_, x, partition) = party.as_ref() else {
+        return Err(RuleNotApplicable);
+    };
+
+    let domain = partition
+        .domain_of()
+        .and_then(|domain| domain.resolve().ok())
+        .ok_or(ApplicationError::DomainError)?;
+
+    let GroundDomain::Partition(_, elem_domain) = domain.as_ref() else {
+        return Err(RuleNotApplicable);
+    };
+
+    let occurrences = elem_domain
+        .values()
+        .map_err(|_| RuleNotApplicable)?
+        .map(|v| {
+            Expr::ToInt(
+                Metadata::new(),
+                Moo::new(together(x, Expr::from(v), partition)),
+            )
+        })
+        .collect_vec();
+
+    Ok(Reduction::pure(Expr::Sum(
+        Metadata::new(),
+        Moo::new(into_matrix_expr![occurrences]),
+    )))
+}
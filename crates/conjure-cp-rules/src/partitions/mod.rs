@@ -0,0 +1,2 @@
+mod horizontal;
+mod vertical;
@@ -0,0 +1 @@
+mod partition_as_set;
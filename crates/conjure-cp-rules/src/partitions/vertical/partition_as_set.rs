@@ -0,0 +1,215 @@
+use conjure_cp::ast::{
+    AbstractLiteral, Atom, Domain, Expression as Expr, Literal, Metadata, Moo, SymbolTable,
+};
+use conjure_cp::into_matrix_expr;
+use conjure_cp::rule_engine::{
+    ApplicationError, ApplicationError::RuleNotApplicable, ApplicationResult, Reduction,
+    register_rule,
+};
+use itertools::Itertools;
+
+use crate::representation::partition_as_set::PartitionAsSet;
+use crate::utils::represented_as;
+
+const REPR_NAME: &str = "partition_as_set";
+
+/// Returns the elements of `expr`, if it is a set or matrix literal.
+fn set_elements(expr: &Expr) -> Option<Vec<Expr>> {
+    if let Some(elems) = expr.unwrap_list() {
+        return Some(elems);
+    }
+
+    match expr {
+        Expr::AbstractLiteral(_, AbstractLiteral::Set(elems)) => Some(elems.clone()),
+        Expr::Atomic(_, Atom::Literal(Literal::AbstractLiteral(AbstractLiteral::Set(elems)))) => {
+            Some(elems.iter().cloned().map(Expr::from).collect())
+        }
+        _ => None,
+    }
+}
+
+fn eq(a: Expr, b: Expr) -> Expr {
+    Expr::Eq(Metadata::new(), Moo::new(a), Moo::new(b))
+}
+
+fn imply(a: Expr, b: Expr) -> Expr {
+    Expr::Imply(Metadata::new(), Moo::new(a), Moo::new(b))
+}
+
+fn and(conjuncts: Vec<Expr>) -> Expr {
+    Expr::And(Metadata::new(), Moo::new(into_matrix_expr![conjuncts]))
+}
+
+fn or(disjuncts: Vec<Expr>) -> Expr {
+    Expr::Or(Metadata::new(), Moo::new(into_matrix_expr![disjuncts]))
+}
+
+/// The parts of a partition that each element of a set of elements is in.
+struct ElementParts {
+    /// `in_part[i][p - 1]` is true iff the `i`th element is in part `p`.
+    in_part: Vec<Vec<Expr>>,
+
+    /// Constraints that hold iff every element is in the domain of the partition.
+    in_domain: Vec<Expr>,
+
+    /// Top level constraints defining any new variables.
+    new_top: Vec<Expr>,
+}
+
+/// Returns expressions for whether each element of `elements` is in each part of the partition.
+///
+/// For a non-literal element x, the expression for part p is a new variable a, fixed by the
+/// top level constraint:
+///
+///   a <-> and([ x = v -> P#partition_as_set_parts_p_v | v in dom(P) ])
+///
+/// As every value is in exactly one part, this is true iff x is in part p, as long as x is in
+/// the domain of the partition.
+fn element_parts(
+    repr: &PartitionAsSet,
+    elements: &[Expr],
+    symbols: &mut SymbolTable,
+) -> Result<Option<ElementParts>, ApplicationError> {
+    let mut in_part = vec![];
+    let mut in_domain = vec![];
+    let mut new_top = vec![];
+
+    for element in elements {
+        if let Some(x) = element.clone().into_literal() {
+            if !repr.elem_values().contains(&x) {
+                return Ok(None);
+            }
+
+            in_part.push(
+                (1..=repr.max_parts())
+                    .map(|p| repr.in_part(p, &x, symbols).ok_or(RuleNotApplicable))
+                    .collect::<Result<Vec<_>, _>>()?,
+            );
+            continue;
+        }
+
+        in_domain.push(or(repr
+            .elem_values()
+            .iter()
+            .map(|v| eq(element.clone(), Expr::from(v.clone())))
+            .collect()));
+
+        let mut parts = vec![];
+        for p in 1..=repr.max_parts() {
+            let definition = repr
+                .elem_values()
+                .iter()
+                .map(|v| {
+                    let member = repr.in_part(p, v, symbols).ok_or(RuleNotApplicable)?;
+                    Ok(imply(eq(element.clone(), Expr::from(v.clone())), member))
+                })
+                .collect::<Result<Vec<_>, ApplicationError>>()?;
+
+            let a = Expr::from(Atom::new_ref(symbols.gen_find(&Domain::bool())));
+            new_top.push(Expr::Iff(
+                Metadata::new(),
+                Moo::new(a.clone()),
+                Moo::new(and(definition)),
+            ));
+            parts.push(a);
+        }
+        in_part.push(parts);
+    }
+
+    Ok(Some(ElementParts {
+        in_part,
+        in_domain,
+        new_top,
+    }))
+}
+
+// together(S, P) ~~> and([ x_1 in part p -> x_i in part p | p in parts of P, x_i in S ])
+//
+//   where x_1 is the first element of S, and each element of S is in the domain of P.
+#[register_rule("Base", 2000, [Together])]
+fn together_partition_as_set(expr: &Expr, symbols: &SymbolTable) -> ApplicationResult {
+    let Expr::Together(_, elements, partition) = expr else {
+        return Err(RuleNotApplicable);
+    };
+
+    let repr: PartitionAsSet =
+        represented_as(partition, REPR_NAME, symbols).ok_or(RuleNotApplicable)?;
+    let elements = set_elements(elements).ok_or(RuleNotApplicable)?;
+
+    let mut symbols = symbols.clone();
+    let Some(parts) = element_parts(&repr, &elements, &mut symbols)? else {
+        return Ok(Reduction::pure(Expr::from(false)));
+    };
+
+    let mut conjuncts = parts.in_domain;
+    if let Some((first, rest)) = parts.in_part.split_first() {
+        for (p, in_first) in first.iter().enumerate() {
+            for in_other in rest {
+                conjuncts.push(imply(in_first.clone(), in_other[p].clone()));
+            }
+        }
+    }
+
+    Ok(Reduction::new(and(conjuncts), parts.new_top, symbols))
+}
+
+// apart(S, P) ~~> and([ x_1 in part p -> or([ !(x_i in part p) | x_i in S ]) | p in parts of P ])
+//
+//   where x_1 is the first element of S, and each element of S is in the domain of P.
+#[register_rule("Base", 2000, [Apart])]
+fn apart_partition_as_set(expr: &Expr, symbols: &SymbolTable) -> ApplicationResult {
+    let Expr::Apart(_, elements, partition) = expr else {
+        return Err(RuleNotApplicable);
+    };
+
+    let repr: PartitionAsSet =
+        represented_as(partition, REPR_NAME, symbols).ok_or(RuleNotApplicable)?;
+    let elements = set_elements(elements).ok_or(RuleNotApplicable)?;
+
+    let mut symbols = symbols.clone();
+    let Some(parts) = element_parts(&repr, &elements, &mut symbols)? else {
+        return Ok(Reduction::pure(Expr::from(false)));
+    };
+
+    let mut conjuncts = parts.in_domain;
+    if let Some((first, rest)) = parts.in_part.split_first() {
+        for (p, in_first) in first.iter().enumerate() {
+            let not_in_part = rest
+                .iter()
+                .map(|in_other| Expr::Not(Metadata::new(), Moo::new(in_other[p].clone())))
+                .collect_vec();
+            conjuncts.push(imply(in_first.clone(), or(not_in_part)));
+        }
+    }
+
+    Ok(Reduction::new(and(conjuncts), parts.new_top, symbols))
+}
+
+// |parts(P)| ~~> sum([ toInt(or(P#partition_as_set_parts_p)) | p in parts of P ])
+#[register_rule("Base", 2000, [Card])]
+fn card_parts_partition_as_set(expr: &Expr, symbols: &SymbolTable) -> ApplicationResult {
+    let Expr::Card(_, parts) = expr else {
+        return Err(RuleNotApplicable);
+    };
+
+    let Expr::Parts(_, partition) = parts.as_ref() else {
+        return Err(RuleNotApplicable);
+    };
+
+    let repr: PartitionAsSet =
+        represented_as(partition, REPR_NAME, symbols).ok_or(RuleNotApplicable)?;
+
+    let used = (1..=repr.max_parts())
+        .map(|p| {
+            Ok(Expr::ToInt(
+                Metadata::new(),
+                Moo::new(repr.is_used(p, symbols)?),
+            ))
+        })
+        .collect::<Result<Vec<_>, ApplicationError>>()?;
+
+    Ok(Reduction::pure(Expr::Sum(
+        Metadata::new(),
+        Moo::new(into_matrix_expr![used]),
+    )))
+}
@@ -0,0 +1 @@
+mod relation_as_matrix;
@@ -0,0 +1,149 @@
+use conjure_cp::ast::{
+    AbstractLiteral, Atom, Expression as Expr, Literal, Metadata, Moo, SymbolTable,
+};
+use conjure_cp::into_matrix_expr;
+use conjure_cp::rule_engine::{
+    ApplicationError, ApplicationError::RuleNotApplicable, ApplicationResult, Reduction,
+    register_rule,
+};
+use itertools::Itertools;
+
+use crate::representation::relation_as_matrix::RelationAsMatrix;
+use crate::utils::represented_as;
+
+const REPR_NAME: &str = "relation_as_matrix";
+
+/// Returns the components of `expr`, if it is a tuple literal.
+fn tuple_components(expr: &Expr) -> Option<Vec<Expr>> {
+    match expr {
+        Expr::AbstractLiteral(_, AbstractLiteral::Tuple(elems)) => Some(elems.clone()),
+        Expr::Atomic(_, Atom::Literal(Literal::AbstractLiteral(AbstractLiteral::Tuple(elems)))) => {
+            Some(elems.iter().cloned().map(Expr::from).collect())
+        }
+        _ => None,
+    }
+}
+
+fn eq(a: Expr, b: Expr) -> Expr {
+    Expr::Eq(Metadata::new(), Moo::new(a), Moo::new(b))
+}
+
+fn and(conjuncts: Vec<Expr>) -> Expr {
+    Expr::And(Metadata::new(), Moo::new(into_matrix_expr![conjuncts]))
+}
+
+fn or(disjuncts: Vec<Expr>) -> Expr {
+    Expr::Or(Metadata::new(), Moo::new(into_matrix_expr![disjuncts]))
+}
+
+/// Returns an expression that is true iff the tuple with the given components is in the relation.
+///
+/// t in R ~~> R#relation_as_matrix_values_t
+///
+/// t in R ~~> and([ or([ t[i] = v | v in dom_i(R) ]) | i in 1..n ])
+///            /\ and([ (t = u) -> R#relation_as_matrix_values_u | u in tuples of R ])
+fn membership(
+    repr: &RelationAsMatrix,
+    components: Vec<Expr>,
+    symbols: &SymbolTable,
+) -> Result<Expr, ApplicationError> {
+    if components.len() != repr.domain_values().len() {
+        return Err(RuleNotApplicable);
+    }
+
+    let literals = components
+        .iter()
+        .map(|x| x.clone().into_literal())
+        .collect_vec();
+
+    if let Some(tuple) = literals.iter().cloned().collect::<Option<Vec<_>>>() {
+        return Ok(repr.contains(&tuple, symbols).unwrap_or(Expr::from(false)));
+    }
+
+    let mut conjuncts = vec![];
+    for ((component, literal), values) in components.iter().zip(&literals).zip(repr.domain_values())
+    {
+        match literal {
+            Some(literal) if !values.contains(literal) => return Ok(Expr::from(false)),
+            Some(_) => {}
+            None => conjuncts.push(or(values
+                .iter()
+                .map(|v| eq(component.clone(), Expr::from(v.clone())))
+                .collect())),
+        }
+    }
+
+    // only the tuples that agree with the fixed components of t
+    for tuple in repr.tuples() {
+        if literals
+            .iter()
+            .zip(tuple)
+            .any(|(literal, v)| literal.as_ref().is_some_and(|literal| literal != v))
+        {
+            continue;
+        }
+
+        let is_tuple = components
+            .iter()
+            .zip(&literals)
+            .zip(tuple)
+            .filter(|((_, literal), _)| literal.is_none())
+            .map(|((component, _), v)| eq(component.clone(), Expr::from(v.clone())))
+            .collect_vec();
+
+        conjuncts.push(Expr::Imply(
+            Metadata::new(),
+            Moo::new(and(is_tuple)),
+            Moo::new(repr.contains(tuple, symbols).ok_or(RuleNotApplicable)?),
+        ));
+    }
+
+    Ok(and(conjuncts))
+}
+
+// t in R ~~> see membership
+#[register_rule("Base", 2000, [In])]
+fn in_relation_as_matrix(expr: &Expr, symbols: &SymbolTable) -> ApplicationResult {
+    let Expr::In(_, t, relation) = expr else {
+        return Err(RuleNotApplicable);
+    };
+
+    let repr: RelationAsMatrix =
+        represented_as(relation, REPR_NAME, symbols).ok_or(RuleNotApplicable)?;
+    let components = tuple_components(t).ok_or(RuleNotApplicable)?;
+
+    Ok(Reduction::pure(membership(&repr, components, symbols)?))
+}
+
+// R(x, y) ~~> (x, y) in R
+#[register_rule("Base", 2000, [RelationProj])]
+fn relation_proj_relation_as_matrix(expr: &Expr, symbols: &SymbolTable) -> ApplicationResult {
+    let Expr::RelationProj(_, relation, projections) = expr else {
+        return Err(RuleNotApplicable);
+    };
+
+    let repr: RelationAsMatrix =
+        represented_as(relation, REPR_NAME, symbols).ok_or(RuleNotApplicable)?;
+
+    // partial projections produce a relation, rather than testing membership
+    let components = projections
+        .iter()
+        .cloned()
+        .collect::<Option<Vec<_>>>()
+        .ok_or(RuleNotApplicable)?;
+
+    Ok(Reduction::pure(membership(&repr, components, symbols)?))
+}
+
+// |R| ~~> sum([ toInt(R#relation_as_matrix_values_u) | u in tuples of R ])
+#[register_rule("Base", 2000, [Card])]
+fn card_relation_as_matrix(expr: &Expr, symbols: &SymbolTable) -> ApplicationResult {
+    let Expr::Card(_, relation) = expr else {
+        return Err(RuleNotApplicable);
+    };
+
+    let repr: RelationAsMatrix =
+        represented_as(relation, REPR_NAME, symbols).ok_or(RuleNotApplicable)?;
+
+    Ok(Reduction::pure(repr.cardinality(symbols)?))
+}
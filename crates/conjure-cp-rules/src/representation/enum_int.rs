@@ -0,0 +1,129 @@
+use conjure_cp::ast::{GroundDomain, Moo, Reference};
+use std::collections::{BTreeMap, BTreeSet};
+
+use super::prelude::*;
+use crate::enums::enum_type_values;
+
+register_representation!(EnumInt, "enum_int");
+
+/// Represents a value of an enumerated or unnamed type as an integer: the position of the value
+/// in the definition of its type, counting from 1.
+#[derive(Clone, Debug)]
+pub struct EnumInt {
+    src_var: Name,
+
+    // the name of the enumerated type.
+    enum_name: Name,
+
+    // all the values of the enumerated type, in order.
+    type_values: Vec<Name>,
+
+    // the values the variable can take.
+    values: Vec<Name>,
+}
+
+impl EnumInt {
+    /// Gets the name of the integer representation variable.
+    fn int_name(&self) -> Name {
+        Name::Represented(Box::new((
+            self.src_var.clone(),
+            self.repr_name().into(),
+            "value".into(),
+        )))
+    }
+
+    /// Gets the integer that represents `value`.
+    fn value_to_int(&self, value: &Name) -> Option<i32> {
+        let i = self.type_values.iter().position(|x| x == value)?;
+        i32::try_from(i + 1).ok()
+    }
+
+    /// Gets the value represented by the integer `i`.
+    fn int_to_value(&self, i: i32) -> Option<Name> {
+        let i = usize::try_from(i).ok()?.checked_sub(1)?;
+        self.type_values.get(i).cloned()
+    }
+}
+
+impl Representation for EnumInt {
+    fn init(name: &Name, symtab: &SymbolTable) -> Option<Self> {
+        let domain = symtab.resolve_domain(name)?;
+
+        let GroundDomain::Enum(enum_name, values) = domain.as_ref() else {
+            return None;
+        };
+
+        // values of unnamed types, and types without a declaration in scope, are numbered by
+        // their position in the domain
+        let type_values = enum_type_values(enum_name, symtab).unwrap_or_else(|| values.clone());
+
+        Some(EnumInt {
+            src_var: name.clone(),
+            enum_name: enum_name.clone(),
+            type_values,
+            values: values.clone(),
+        })
+    }
+
+    fn variable_name(&self) -> &Name {
+        &self.src_var
+    }
+
+    fn value_down(&self, value: Literal) -> Result<BTreeMap<Name, Literal>, ApplicationError> {
+        let Literal::Enum(value) = value else {
+            return Err(RuleNotApplicable);
+        };
+
+        let (enum_name, value) = value.as_ref();
+        if *enum_name != self.enum_name {
+            return Err(RuleNotApplicable);
+        }
+
+        let i = self.value_to_int(value).ok_or(RuleNotApplicable)?;
+        Ok(BTreeMap::from([(self.int_name(), Literal::Int(i))]))
+    }
+
+    fn value_up(&self, values: &BTreeMap<Name, Literal>) -> Result<Literal, ApplicationError> {
+        let Some(Literal::Int(i)) = values.get(&self.int_name()) else {
+            return Err(RuleNotApplicable);
+        };
+
+        let value = self.int_to_value(*i).ok_or(RuleNotApplicable)?;
+        Ok(Literal::Enum(Box::new((self.enum_name.clone(), value))))
+    }
+
+    fn expression_down(
+        &self,
+        symtab: &SymbolTable,
+    ) -> Result<BTreeMap<Name, Expression>, ApplicationError> {
+        let name = self.int_name();
+        // the declaration of the representation variable is added before expression_down is called
+        let decl = symtab.lookup(&name).ok_or(RuleNotApplicable)?;
+        Ok(BTreeMap::from([(
+            name,
+            Expression::from(Reference::new(decl)),
+        )]))
+    }
+
+    fn declaration_down(&self) -> Result<Vec<DeclarationPtr>, ApplicationError> {
+        let ints = self
+            .values
+            .iter()
+            .map(|value| self.value_to_int(value).ok_or(RuleNotApplicable))
+            .collect::<Result<BTreeSet<_>, _>>()?;
+
+        let domain = Moo::new(GroundDomain::from_set_i32(&ints));
+        Ok(vec![DeclarationPtr::new_find(
+            self.int_name(),
+            domain.into(),
+        )])
+    }
+
+    fn repr_name(&self) -> &str {
+        "enum_int"
+    }
+
+    fn box_clone(&self) -> Box<dyn Representation> {
+        Box::new(self.clone()) as _
+    }
+}
@@ -0,0 +1,353 @@
+use conjure_cp::ast::{
+    Domain, DomainPtr, FuncAttr, GroundDomain, JectivityAttr, Moo, PartialityAttr, Range, Reference,
+};
+use conjure_cp::{into_matrix_expr, matrix_expr};
+use itertools::Itertools;
+use std::collections::BTreeMap;
+
+use super::prelude::*;
+
+register_representation!(Function1D, "function_1d");
+
+/// Represents a function with an integer or boolean domain as a matrix of its images, indexed by
+/// its domain.
+///
+/// Partial functions also have a boolean for each value in the domain, which is true iff the
+/// function is defined on that value. Where the function is undefined, its image is fixed to the
+/// smallest value in the codomain.
+#[derive(Clone, Debug)]
+pub struct Function1D {
+    src_var: Name,
+
+    attr: FuncAttr,
+
+    // the codomain of the function.
+    codomain: Moo<GroundDomain>,
+
+    // all the values in the domain, in order.
+    domain_values: Vec<Literal>,
+
+    // all the values in the codomain, in order.
+    codomain_values: Vec<Literal>,
+}
+
+impl Function1D {
+    /// Gets the name of the representation variable holding the image of `x`.
+    fn image_name(&self, x: &Literal) -> Name {
+        self.represented_name(format!("values_{x}"))
+    }
+
+    /// Gets the name of the representation variable that is true iff the function is defined on
+    /// `x`.
+    fn flag_name(&self, x: &Literal) -> Name {
+        self.represented_name(format!("flags_{x}"))
+    }
+
+    fn represented_name(&self, suffix: String) -> Name {
+        Name::Represented(Box::new((
+            self.src_var.clone(),
+            self.repr_name().into(),
+            suffix.into(),
+        )))
+    }
+
+    /// Returns the names of the representation variables.
+    fn names(&self) -> impl Iterator<Item = Name> + '_ {
+        let flags = self
+            .is_partial()
+            .then(|| self.domain_values.iter().map(|x| self.flag_name(x)))
+            .into_iter()
+            .flatten();
+
+        self.domain_values
+            .iter()
+            .map(|x| self.image_name(x))
+            .chain(flags)
+    }
+
+    fn lookup(&self, name: &Name, symtab: &SymbolTable) -> Result<Expression, ApplicationError> {
+        let decl = symtab.lookup(name).ok_or(RuleNotApplicable)?;
+        Ok(Expression::from(Reference::new(decl)))
+    }
+
+    /// Whether the function can be undefined on some values in its domain.
+    pub(crate) fn is_partial(&self) -> bool {
+        self.attr.partiality == PartialityAttr::Partial
+    }
+
+    /// The values in the domain of the function, in order.
+    pub(crate) fn domain_values(&self) -> &[Literal] {
+        &self.domain_values
+    }
+
+    /// The smallest value in the codomain of the function.
+    pub(crate) fn smallest_image(&self) -> &Literal {
+        &self.codomain_values[0]
+    }
+
+    /// Returns the representation variable holding the image of `x`, or `None` if `x` is not in
+    /// the domain.
+    pub(crate) fn image(&self, x: &Literal, symtab: &SymbolTable) -> Option<Expression> {
+        if !self.domain_values.contains(x) {
+            return None;
+        }
+
+        self.lookup(&self.image_name(x), symtab).ok()
+    }
+
+    /// Returns an expression that is true iff the function is defined on `x`, or `None` if `x` is
+    /// not in the domain.
+    pub(crate) fn defined(&self, x: &Literal, symtab: &SymbolTable) -> Option<Expression> {
+        if !self.domain_values.contains(x) {
+            return None;
+        }
+
+        if !self.is_partial() {
+            return Some(Expression::from(true));
+        }
+
+        self.lookup(&self.flag_name(x), symtab).ok()
+    }
+}
+
+impl Representation for Function1D {
+    fn init(name: &Name, symtab: &SymbolTable) -> Option<Self> {
+        let domain = symtab.resolve_domain(name)?;
+
+        if !domain.is_finite() {
+            return None;
+        }
+
+        let GroundDomain::Function(attr, dom, codom) = domain.as_ref() else {
+            return None;
+        };
+
+        if !matches!(dom.as_ref(), GroundDomain::Bool | GroundDomain::Int(_))
+            || !matches!(codom.as_ref(), GroundDomain::Bool | GroundDomain::Int(_))
+        {
+            return None;
+        }
+
+        let codomain_values: Vec<Literal> = codom.values().ok()?.collect();
+        if codomain_values.is_empty() {
+            return None;
+        }
+
+        Some(Function1D {
+            src_var: name.clone(),
+            attr: attr.clone(),
+            codomain: codom.clone(),
+            domain_values: dom.values().ok()?.collect(),
+            codomain_values,
+        })
+    }
+
+    fn variable_name(&self) -> &Name {
+        &self.src_var
+    }
+
+    fn value_down(&self, value: Literal) -> Result<BTreeMap<Name, Literal>, ApplicationError> {
+        let Literal::AbstractLiteral(AbstractLiteral::Function(mappings)) = value else {
+            return Err(RuleNotApplicable);
+        };
+
+        if mappings
+            .iter()
+            .any(|(x, _)| !self.domain_values.contains(x))
+        {
+            return Err(RuleNotApplicable);
+        }
+
+        let mut values = BTreeMap::new();
+        for x in &self.domain_values {
+            let image = mappings
+                .iter()
+                .find(|(y, _)| y == x)
+                .map(|(_, image)| image.clone());
+
+            if !self.is_partial() && image.is_none() {
+                return Err(RuleNotApplicable);
+            }
+
+            if self.is_partial() {
+                values.insert(self.flag_name(x), Literal::Bool(image.is_some()));
+            }
+
+            values.insert(
+                self.image_name(x),
+                image.unwrap_or_else(|| self.smallest_image().clone()),
+            );
+        }
+
+        Ok(values)
+    }
+
+    fn value_up(&self, values: &BTreeMap<Name, Literal>) -> Result<Literal, ApplicationError> {
+        let mut mappings = vec![];
+        for x in &self.domain_values {
+            if self.is_partial() {
+                match values.get(&self.flag_name(x)).ok_or(RuleNotApplicable)? {
+                    Literal::Int(1) | Literal::Bool(true) => {}
+                    Literal::Int(0) | Literal::Bool(false) => continue,
+                    _ => return Err(RuleNotApplicable),
+                }
+            }
+
+            let image = values
+                .get(&self.image_name(x))
+                .ok_or(RuleNotApplicable)?
+                .clone();
+            mappings.push((x.clone(), image));
+        }
+
+        Ok(Literal::AbstractLiteral(AbstractLiteral::Function(
+            mappings,
+        )))
+    }
+
+    fn expression_down(
+        &self,
+        symtab: &SymbolTable,
+    ) -> Result<BTreeMap<Name, Expression>, ApplicationError> {
+        self.names()
+            .map(|name| {
+                let expr = self.lookup(&name, symtab)?;
+                Ok((name, expr))
+            })
+            .collect()
+    }
+
+    fn declaration_down(&self) -> Result<Vec<DeclarationPtr>, ApplicationError> {
+        let codomain: DomainPtr = self.codomain.clone().into();
+
+        Ok(self
+            .domain_values
+            .iter()
+            .map(|x| DeclarationPtr::new_find(self.image_name(x), codomain.clone()))
+            .chain(
+                self.is_partial()
+                    .then(|| {
+                        self.domain_values
+                            .iter()
+                            .map(|x| DeclarationPtr::new_find(self.flag_name(x), Domain::bool()))
+                    })
+                    .into_iter()
+                    .flatten(),
+            )
+            .collect_vec())
+    }
+
+    fn structural_constraints(
+        &self,
+        symtab: &SymbolTable,
+    ) -> Result<Vec<Expression>, ApplicationError> {
+        // (x, image of x, whether the function is defined on x)
+        let entries = self
+            .domain_values
+            .iter()
+            .map(|x| {
+                let image = self.image(x, symtab).ok_or(RuleNotApplicable)?;
+                let defined = self.defined(x, symtab).ok_or(RuleNotApplicable)?;
+                Ok((image, defined))
+            })
+            .collect::<Result<Vec<_>, ApplicationError>>()?;
+
+        let mut constraints = vec![];
+
+        if self.is_partial() {
+            // the images of undefined values are fixed, so they have exactly one assignment
+            for (image, defined) in &entries {
+                constraints.push(Expression::Or(
+                    Metadata::new(),
+                    Moo::new(matrix_expr![
+                        defined.clone(),
+                        Expression::Eq(
+                            Metadata::new(),
+                            Moo::new(image.clone()),
+                            Moo::new(Expression::from(self.smallest_image().clone())),
+                        ),
+                    ]),
+                ));
+            }
+
+            if !matches!(self.attr.size, Range::Unbounded) {
+                let cardinality = Expression::Sum(
+                    Metadata::new(),
+                    Moo::new(into_matrix_expr![
+                        entries
+                            .iter()
+                            .map(|(_, defined)| {
+                                Expression::ToInt(Metadata::new(), Moo::new(defined.clone()))
+                            })
+                            .collect_vec()
+                    ]),
+                );
+                constraints.extend(super::size_constraints(&self.attr.size, cardinality));
+            }
+        }
+
+        let injective = matches!(
+            self.attr.jectivity,
+            JectivityAttr::Injective | JectivityAttr::Bijective
+        );
+        let surjective = matches!(
+            self.attr.jectivity,
+            JectivityAttr::Surjective | JectivityAttr::Bijective
+        );
+
+        // no two defined values have the same image
+        if injective {
+            for [(image1, defined1), (image2, defined2)] in entries.iter().array_combinations() {
+                constraints.push(Expression::Imply(
+                    Metadata::new(),
+                    Moo::new(Expression::And(
+                        Metadata::new(),
+                        Moo::new(matrix_expr![defined1.clone(), defined2.clone()]),
+                    )),
+                    Moo::new(Expression::Neq(
+                        Metadata::new(),
+                        Moo::new(image1.clone()),
+                        Moo::new(image2.clone()),
+                    )),
+                ));
+            }
+        }
+
+        // every value in the codomain is the image of some defined value
+        if surjective {
+            for y in &self.codomain_values {
+                let disjuncts = entries
+                    .iter()
+                    .map(|(image, defined)| {
+                        Expression::And(
+                            Metadata::new(),
+                            Moo::new(matrix_expr![
+                                defined.clone(),
+                                Expression::Eq(
+                                    Metadata::new(),
+                                    Moo::new(image.clone()),
+                                    Moo::new(Expression::from(y.clone())),
+                                ),
+                            ]),
+                        )
+                    })
+                    .collect_vec();
+
+                constraints.push(Expression::Or(
+                    Metadata::new(),
+                    Moo::new(into_matrix_expr![disjuncts]),
+                ));
+            }
+        }
+
+        Ok(constraints)
+    }
+
+    fn repr_name(&self) -> &str {
+        "function_1d"
+    }
+
+    fn box_clone(&self) -> Box<dyn Representation> {
+        Box::new(self.clone()) as _
+    }
+}
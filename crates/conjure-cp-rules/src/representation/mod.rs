@@ -14,9 +14,52 @@ mod prelude {
     };
 }
 
+mod enum_int;
+pub(crate) mod function_1d;
 mod matrix_to_atom;
+pub(crate) mod mset_occurrence;
+pub(crate) mod partition_as_set;
 mod record_to_atom;
+pub(crate) mod relation_as_matrix;
 mod sat_direct_int;
 mod sat_log_int;
 mod sat_order_int;
+pub(crate) mod sequence_explicit_bounded;
+pub(crate) mod set_explicit;
+pub(crate) mod set_explicit_var_size_with_marker;
+pub(crate) mod set_occurrence;
 mod tuple_to_atom;
+
+use conjure_cp::ast::{Expression, Metadata, Moo, Range};
+
+/// Returns constraints restricting `cardinality` to the sizes allowed by a `size` attribute.
+fn size_constraints(size: &Range<i32>, cardinality: Expression) -> Vec<Expression> {
+    let cardinality = Moo::new(cardinality);
+
+    let at_least = |min: i32| {
+        Expression::Geq(
+            Metadata::new(),
+            cardinality.clone(),
+            Moo::new(Expression::from(min)),
+        )
+    };
+    let at_most = |max: i32| {
+        Expression::Leq(
+            Metadata::new(),
+            cardinality.clone(),
+            Moo::new(Expression::from(max)),
+        )
+    };
+
+    match *size {
+        Range::Single(size) => vec![Expression::Eq(
+            Metadata::new(),
+            cardinality.clone(),
+            Moo::new(Expression::from(size)),
+        )],
+        Range::Bounded(min, max) => vec![at_least(min), at_most(max)],
+        Range::UnboundedL(max) => vec![at_most(max)],
+        Range::UnboundedR(min) => vec![at_least(min)],
+        Range::Unbounded => vec![],
+    }
+}
@@ -0,0 +1,224 @@
+use conjure_cp::ast::{Domain, GroundDomain, Moo, Range, Reference};
+use conjure_cp::{into_matrix_expr, matrix_expr};
+use itertools::Itertools;
+use std::collections::BTreeMap;
+
+use super::prelude::*;
+
+register_representation!(MSetOccurrence, "mset_occurrence");
+
+/// Represents a multi-set as an integer for each value in its element domain, which is the
+/// number of times that value occurs in the multi-set.
+#[derive(Clone, Debug)]
+pub struct MSetOccurrence {
+    src_var: Name,
+
+    // all the values in the element domain, in order.
+    elements: Vec<Literal>,
+
+    // the allowed sizes of the multi-set.
+    size: Range<i32>,
+
+    // the allowed number of occurrences of each value that is in the multi-set.
+    occurrence: Range<i32>,
+
+    // the largest number of times a value can occur in the multi-set.
+    max_occurrence: i32,
+}
+
+impl MSetOccurrence {
+    /// Returns the names of the representation variables, in the same order as the elements.
+    fn names(&self) -> impl Iterator<Item = Name> + '_ {
+        self.elements.iter().map(|x| self.element_to_name(x))
+    }
+
+    /// Gets the representation variable name for a value in the element domain.
+    fn element_to_name(&self, element: &Literal) -> Name {
+        Name::Represented(Box::new((
+            self.src_var.clone(),
+            self.repr_name().into(),
+            element.to_string().into(),
+        )))
+    }
+
+    /// The values in the element domain of the multi-set, in order.
+    pub(crate) fn elements(&self) -> &[Literal] {
+        &self.elements
+    }
+
+    /// Returns the integer representation variable for the number of occurrences of `element`, or
+    /// `None` if `element` is not in the element domain.
+    pub(crate) fn occurrence(&self, element: &Literal, symtab: &SymbolTable) -> Option<Expression> {
+        if !self.elements.contains(element) {
+            return None;
+        }
+
+        let decl = symtab.lookup(&self.element_to_name(element))?;
+        Some(Expression::from(Reference::new(decl)))
+    }
+
+    /// Returns an expression for the number of elements in the multi-set, counting repeats.
+    pub(crate) fn cardinality(&self, symtab: &SymbolTable) -> Result<Expression, ApplicationError> {
+        let occurrences = self
+            .elements
+            .iter()
+            .map(|element| self.occurrence(element, symtab).ok_or(RuleNotApplicable))
+            .collect::<Result<Vec<_>, ApplicationError>>()?;
+
+        Ok(Expression::Sum(
+            Metadata::new(),
+            Moo::new(into_matrix_expr!(occurrences)),
+        ))
+    }
+}
+
+impl Representation for MSetOccurrence {
+    fn init(name: &Name, symtab: &SymbolTable) -> Option<Self> {
+        let domain = symtab.resolve_domain(name)?;
+
+        if !domain.is_finite() {
+            return None;
+        }
+
+        let GroundDomain::MSet(attr, elem_domain) = domain.as_ref() else {
+            return None;
+        };
+
+        if !matches!(
+            elem_domain.as_ref(),
+            GroundDomain::Bool | GroundDomain::Int(_)
+        ) {
+            return None;
+        }
+
+        // without a maxOccur or maxSize, a value could occur any number of times
+        let max_occurrence = [attr.occurrence.high(), attr.size.high()]
+            .into_iter()
+            .flatten()
+            .min()
+            .copied()?;
+
+        if max_occurrence < 0 {
+            return None;
+        }
+
+        Some(MSetOccurrence {
+            src_var: name.clone(),
+            elements: elem_domain.values().ok()?.collect(),
+            size: attr.size.clone(),
+            occurrence: attr.occurrence.clone(),
+            max_occurrence,
+        })
+    }
+
+    fn variable_name(&self) -> &Name {
+        &self.src_var
+    }
+
+    fn value_down(&self, value: Literal) -> Result<BTreeMap<Name, Literal>, ApplicationError> {
+        let Literal::AbstractLiteral(AbstractLiteral::MSet(members)) = value else {
+            return Err(RuleNotApplicable);
+        };
+
+        if members.iter().any(|x| !self.elements.contains(x)) {
+            return Err(RuleNotApplicable);
+        }
+
+        self.elements
+            .iter()
+            .map(|x| {
+                let count = members.iter().filter(|y| *y == x).count();
+                let count = i32::try_from(count).map_err(|_| RuleNotApplicable)?;
+                Ok((self.element_to_name(x), Literal::Int(count)))
+            })
+            .collect()
+    }
+
+    fn value_up(&self, values: &BTreeMap<Name, Literal>) -> Result<Literal, ApplicationError> {
+        let mut members = vec![];
+        for element in &self.elements {
+            let value = values
+                .get(&self.element_to_name(element))
+                .ok_or(RuleNotApplicable)?;
+
+            let Literal::Int(count) = value else {
+                return Err(RuleNotApplicable);
+            };
+
+            let count = usize::try_from(*count).map_err(|_| RuleNotApplicable)?;
+            members.extend(std::iter::repeat_n(element.clone(), count));
+        }
+
+        Ok(Literal::AbstractLiteral(AbstractLiteral::MSet(members)))
+    }
+
+    fn expression_down(
+        &self,
+        symtab: &SymbolTable,
+    ) -> Result<BTreeMap<Name, Expression>, ApplicationError> {
+        Ok(self
+            .names()
+            .map(|name| {
+                let decl = symtab.lookup(&name).expect("declarations of the representation variables should exist in the symbol table before expression_down is called");
+                (name, Expression::from(Reference::new(decl)))
+            })
+            .collect())
+    }
+
+    fn declaration_down(&self) -> Result<Vec<DeclarationPtr>, ApplicationError> {
+        let domain = Domain::int(vec![Range::Bounded(0, self.max_occurrence)]);
+        Ok(self
+            .names()
+            .map(|name| DeclarationPtr::new_find(name, domain.clone()))
+            .collect_vec())
+    }
+
+    fn structural_constraints(
+        &self,
+        symtab: &SymbolTable,
+    ) -> Result<Vec<Expression>, ApplicationError> {
+        let mut constraints = vec![];
+
+        if !matches!(self.size, Range::Unbounded) {
+            constraints.extend(super::size_constraints(
+                &self.size,
+                self.cardinality(symtab)?,
+            ));
+        }
+
+        // a value either does not occur, or occurs at least minOccur times
+        if let Some(&min) = self.occurrence.low()
+            && min > 1
+        {
+            for element in &self.elements {
+                let occurrence =
+                    Moo::new(self.occurrence(element, symtab).ok_or(RuleNotApplicable)?);
+                constraints.push(Expression::Or(
+                    Metadata::new(),
+                    Moo::new(matrix_expr![
+                        Expression::Eq(
+                            Metadata::new(),
+                            occurrence.clone(),
+                            Moo::new(Expression::from(0))
+                        ),
+                        Expression::Geq(
+                            Metadata::new(),
+                            occurrence,
+                            Moo::new(Expression::from(min))
+                        )
+                    ]),
+                ));
+            }
+        }
+
+        Ok(constraints)
+    }
+
+    fn repr_name(&self) -> &str {
+        "mset_occurrence"
+    }
+
+    fn box_clone(&self) -> Box<dyn Representation> {
+        Box::new(self.clone()) as _
+    }
+}
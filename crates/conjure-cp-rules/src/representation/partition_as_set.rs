@@ -0,0 +1,352 @@
+use conjure_cp::ast::{Domain, GroundDomain, Moo, Range, Reference};
+use conjure_cp::into_matrix_expr;
+use itertools::Itertools;
+use std::collections::BTreeMap;
+
+use super::prelude::*;
+
+register_representation!(PartitionAsSet, "partition_as_set");
+
+/// Represents a partition as a set of parts, where each part is represented by a boolean for
+/// each value in the domain of the partition, which is true iff the value is in that part.
+///
+/// Every value in the domain is in exactly one part. To give each partition a single
+/// representation, the parts are ordered by their smallest value, and the unused parts come last.
+#[derive(Clone, Debug)]
+pub struct PartitionAsSet {
+    src_var: Name,
+
+    // the maximum number of parts in the partition.
+    max_parts: usize,
+
+    num_parts: Range<i32>,
+    part_size: Range<i32>,
+    is_regular: bool,
+
+    // all the values in the domain of the partition, in order.
+    elem_values: Vec<Literal>,
+}
+
+impl PartitionAsSet {
+    /// Gets the representation variable name for whether `value` is in part `part` (starting from
+    /// 1).
+    fn member_name(&self, part: usize, value: &Literal) -> Name {
+        Name::Represented(Box::new((
+            self.src_var.clone(),
+            self.repr_name().into(),
+            format!("parts_{part}_{value}").into(),
+        )))
+    }
+
+    /// The values in the domain of the partition, in order.
+    pub(crate) fn elem_values(&self) -> &[Literal] {
+        &self.elem_values
+    }
+
+    /// The maximum number of parts in the partition.
+    pub(crate) fn max_parts(&self) -> usize {
+        self.max_parts
+    }
+
+    /// Returns the boolean representation variable for whether `value` is in part `part`
+    /// (starting from 1), or `None` if `value` is not in the domain of the partition.
+    pub(crate) fn in_part(
+        &self,
+        part: usize,
+        value: &Literal,
+        symtab: &SymbolTable,
+    ) -> Option<Expression> {
+        if !self.elem_values.contains(value) {
+            return None;
+        }
+
+        let decl = symtab.lookup(&self.member_name(part, value))?;
+        Some(Expression::from(Reference::new(decl)))
+    }
+
+    /// Returns the representation variables for part `part` (starting from 1), in the order of
+    /// the values in the domain of the partition.
+    pub(crate) fn part(
+        &self,
+        part: usize,
+        symtab: &SymbolTable,
+    ) -> Result<Vec<Expression>, ApplicationError> {
+        self.elem_values
+            .iter()
+            .map(|x| self.in_part(part, x, symtab).ok_or(RuleNotApplicable))
+            .collect()
+    }
+
+    /// Returns an expression that is true iff part `part` (starting from 1) is used.
+    pub(crate) fn is_used(
+        &self,
+        part: usize,
+        symtab: &SymbolTable,
+    ) -> Result<Expression, ApplicationError> {
+        Ok(Expression::Or(
+            Metadata::new(),
+            Moo::new(into_matrix_expr![self.part(part, symtab)?]),
+        ))
+    }
+
+    /// Returns an expression for the number of values in part `part` (starting from 1).
+    fn part_size(&self, part: usize, symtab: &SymbolTable) -> Result<Expression, ApplicationError> {
+        let occurrences = self
+            .part(part, symtab)?
+            .into_iter()
+            .map(|x| Expression::ToInt(Metadata::new(), Moo::new(x)))
+            .collect_vec();
+
+        Ok(Expression::Sum(
+            Metadata::new(),
+            Moo::new(into_matrix_expr![occurrences]),
+        ))
+    }
+
+    /// Returns the parts of a partition literal in their canonical order, or `None` if it is not a
+    /// partition of the domain.
+    fn canonical_parts(&self, parts: Vec<Vec<Literal>>) -> Option<Vec<Vec<Literal>>> {
+        let index = |x: &Literal| self.elem_values.iter().position(|y| y == x);
+
+        let mut parts = parts
+            .into_iter()
+            .map(|part| {
+                let mut part = part
+                    .into_iter()
+                    .map(|x| Some((index(&x)?, x)))
+                    .collect::<Option<Vec<_>>>()?;
+                part.sort_by_key(|(i, _)| *i);
+                Some(part)
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        if parts.iter().any(Vec::is_empty) {
+            return None;
+        }
+        parts.sort_by_key(|part| part[0].0);
+
+        let indices = parts
+            .iter()
+            .flatten()
+            .map(|(i, _)| *i)
+            .sorted()
+            .collect_vec();
+        if indices != (0..self.elem_values.len()).collect_vec() {
+            return None;
+        }
+
+        Some(
+            parts
+                .into_iter()
+                .map(|part| part.into_iter().map(|(_, x)| x).collect())
+                .collect(),
+        )
+    }
+}
+
+impl Representation for PartitionAsSet {
+    fn init(name: &Name, symtab: &SymbolTable) -> Option<Self> {
+        let domain = symtab.resolve_domain(name)?;
+
+        if !domain.is_finite() {
+            return None;
+        }
+
+        let GroundDomain::Partition(attr, elem_domain) = domain.as_ref() else {
+            return None;
+        };
+
+        if !matches!(
+            elem_domain.as_ref(),
+            GroundDomain::Bool | GroundDomain::Int(_)
+        ) {
+            return None;
+        }
+
+        let elem_values: Vec<Literal> = elem_domain.values().ok()?.collect();
+        if elem_values.is_empty() {
+            return None;
+        }
+
+        // each part has at least one value
+        let max_parts = match attr.num_parts.high() {
+            Some(&high) => usize::try_from(high).ok()?.min(elem_values.len()),
+            None => elem_values.len(),
+        };
+
+        Some(PartitionAsSet {
+            src_var: name.clone(),
+            max_parts,
+            num_parts: attr.num_parts.clone(),
+            part_size: attr.part_len.clone(),
+            is_regular: attr.is_regular,
+            elem_values,
+        })
+    }
+
+    fn variable_name(&self) -> &Name {
+        &self.src_var
+    }
+
+    fn value_down(&self, value: Literal) -> Result<BTreeMap<Name, Literal>, ApplicationError> {
+        let Literal::AbstractLiteral(AbstractLiteral::Partition(parts)) = value else {
+            return Err(RuleNotApplicable);
+        };
+
+        let parts = self.canonical_parts(parts).ok_or(RuleNotApplicable)?;
+        if parts.len() > self.max_parts {
+            return Err(RuleNotApplicable);
+        }
+
+        let mut values = BTreeMap::new();
+        for p in 1..=self.max_parts {
+            let part = parts.get(p - 1);
+            for x in &self.elem_values {
+                let is_member = part.is_some_and(|part| part.contains(x));
+                values.insert(self.member_name(p, x), Literal::Bool(is_member));
+            }
+        }
+
+        Ok(values)
+    }
+
+    fn value_up(&self, values: &BTreeMap<Name, Literal>) -> Result<Literal, ApplicationError> {
+        let mut parts = vec![];
+        for p in 1..=self.max_parts {
+            let mut part = vec![];
+            for x in &self.elem_values {
+                let value = values
+                    .get(&self.member_name(p, x))
+                    .ok_or(RuleNotApplicable)?;
+
+                match value {
+                    Literal::Int(1) | Literal::Bool(true) => part.push(x.clone()),
+                    Literal::Int(0) | Literal::Bool(false) => {}
+                    _ => return Err(RuleNotApplicable),
+                }
+            }
+
+            if !part.is_empty() {
+                parts.push(part);
+            }
+        }
+
+        Ok(Literal::AbstractLiteral(AbstractLiteral::Partition(parts)))
+    }
+
+    fn expression_down(
+        &self,
+        symtab: &SymbolTable,
+    ) -> Result<BTreeMap<Name, Expression>, ApplicationError> {
+        let mut exprs = BTreeMap::new();
+        for p in 1..=self.max_parts {
+            for x in &self.elem_values {
+                let expr = self.in_part(p, x, symtab).ok_or(RuleNotApplicable)?;
+                exprs.insert(self.member_name(p, x), expr);
+            }
+        }
+        Ok(exprs)
+    }
+
+    fn declaration_down(&self) -> Result<Vec<DeclarationPtr>, ApplicationError> {
+        Ok((1..=self.max_parts)
+            .cartesian_product(&self.elem_values)
+            .map(|(p, x)| DeclarationPtr::new_find(self.member_name(p, x), Domain::bool()))
+            .collect_vec())
+    }
+
+    fn structural_constraints(
+        &self,
+        symtab: &SymbolTable,
+    ) -> Result<Vec<Expression>, ApplicationError> {
+        let in_part = |p: usize, x: &Literal| self.in_part(p, x, symtab).ok_or(RuleNotApplicable);
+        let not = |x: Expression| Expression::Not(Metadata::new(), Moo::new(x));
+        let imply = |x: Expression, y: Expression| {
+            Expression::Imply(Metadata::new(), Moo::new(x), Moo::new(y))
+        };
+
+        let mut constraints = vec![];
+
+        // every value is in exactly one part
+        for x in &self.elem_values {
+            let parts = (1..=self.max_parts)
+                .map(|p| in_part(p, x))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            for [a, b] in parts.iter().array_combinations() {
+                constraints.push(imply(a.clone(), not(b.clone())));
+            }
+
+            constraints.push(Expression::Or(
+                Metadata::new(),
+                Moo::new(into_matrix_expr![parts]),
+            ));
+        }
+
+        // a value can only be in part p if a smaller value is in part p - 1. this orders the parts
+        // by their smallest value, and means that if part p is used, so are parts 1..p - 1.
+        for p in 2..=self.max_parts {
+            for (i, x) in self.elem_values.iter().enumerate() {
+                let smaller = self.elem_values[..i]
+                    .iter()
+                    .map(|y| in_part(p - 1, y))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                constraints.push(imply(
+                    in_part(p, x)?,
+                    Expression::Or(Metadata::new(), Moo::new(into_matrix_expr![smaller])),
+                ));
+            }
+        }
+
+        // as the used parts come first, there are at least k parts iff part k is used
+        if let Some(&min) = self.num_parts.low() {
+            match usize::try_from(min) {
+                Ok(0) | Err(_) => {}
+                Ok(min) if min > self.max_parts => constraints.push(Expression::from(false)),
+                Ok(min) => constraints.push(self.is_used(min, symtab)?),
+            }
+        }
+
+        if let Some(&max) = self.num_parts.high()
+            && let Ok(max) = usize::try_from(max)
+            && max < self.max_parts
+        {
+            constraints.push(not(self.is_used(max + 1, symtab)?));
+        }
+
+        if !matches!(self.part_size, Range::Unbounded) {
+            for p in 1..=self.max_parts {
+                let size = super::size_constraints(&self.part_size, self.part_size(p, symtab)?);
+                constraints.push(imply(
+                    self.is_used(p, symtab)?,
+                    Expression::And(Metadata::new(), Moo::new(into_matrix_expr![size])),
+                ));
+            }
+        }
+
+        // all used parts are the same size as the first part
+        if self.is_regular {
+            for p in 2..=self.max_parts {
+                constraints.push(imply(
+                    self.is_used(p, symtab)?,
+                    Expression::Eq(
+                        Metadata::new(),
+                        Moo::new(self.part_size(1, symtab)?),
+                        Moo::new(self.part_size(p, symtab)?),
+                    ),
+                ));
+            }
+        }
+
+        Ok(constraints)
+    }
+
+    fn repr_name(&self) -> &str {
+        "partition_as_set"
+    }
+
+    fn box_clone(&self) -> Box<dyn Representation> {
+        Box::new(self.clone()) as _
+    }
+}
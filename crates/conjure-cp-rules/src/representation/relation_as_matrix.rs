@@ -0,0 +1,303 @@
+use conjure_cp::ast::{BinaryAttr, Domain, GroundDomain, Moo, Range, Reference};
+use conjure_cp::{into_matrix_expr, matrix_expr};
+use itertools::Itertools;
+use std::collections::BTreeMap;
+
+use super::prelude::*;
+
+register_representation!(RelationAsMatrix, "relation_as_matrix");
+
+/// Represents a relation as a boolean for each tuple in the cartesian product of its domains,
+/// which is true iff that tuple is in the relation.
+#[derive(Clone, Debug)]
+pub struct RelationAsMatrix {
+    src_var: Name,
+
+    // the values in each domain of the relation, in order.
+    domain_values: Vec<Vec<Literal>>,
+
+    // all the tuples in the cartesian product of the domains, in order.
+    tuples: Vec<Vec<Literal>>,
+
+    // the allowed sizes of the relation.
+    size: Range<i32>,
+
+    binary: Vec<BinaryAttr>,
+}
+
+impl RelationAsMatrix {
+    /// Gets the representation variable name for a tuple.
+    fn tuple_to_name(&self, tuple: &[Literal]) -> Name {
+        Name::Represented(Box::new((
+            self.src_var.clone(),
+            self.repr_name().into(),
+            format!("values_{}", tuple.iter().join("_")).into(),
+        )))
+    }
+
+    /// The values in each domain of the relation, in order.
+    pub(crate) fn domain_values(&self) -> &[Vec<Literal>] {
+        &self.domain_values
+    }
+
+    /// All the tuples that could be in the relation, in order.
+    pub(crate) fn tuples(&self) -> &[Vec<Literal>] {
+        &self.tuples
+    }
+
+    /// Returns the boolean representation variable for `tuple`, or `None` if `tuple` is not in
+    /// the cartesian product of the domains.
+    pub(crate) fn contains(&self, tuple: &[Literal], symtab: &SymbolTable) -> Option<Expression> {
+        if tuple.len() != self.domain_values.len()
+            || tuple
+                .iter()
+                .zip(&self.domain_values)
+                .any(|(x, values)| !values.contains(x))
+        {
+            return None;
+        }
+
+        let decl = symtab.lookup(&self.tuple_to_name(tuple))?;
+        Some(Expression::from(Reference::new(decl)))
+    }
+
+    /// Returns an expression for the number of tuples in the relation.
+    pub(crate) fn cardinality(&self, symtab: &SymbolTable) -> Result<Expression, ApplicationError> {
+        let occurrences = self
+            .tuples
+            .iter()
+            .map(|tuple| {
+                let occurrence = self.contains(tuple, symtab).ok_or(RuleNotApplicable)?;
+                Ok(Expression::ToInt(Metadata::new(), Moo::new(occurrence)))
+            })
+            .collect::<Result<Vec<_>, ApplicationError>>()?;
+
+        Ok(Expression::Sum(
+            Metadata::new(),
+            Moo::new(into_matrix_expr!(occurrences)),
+        ))
+    }
+
+    /// Returns the constraints for a binary relation attribute.
+    fn binary_attr_constraints(
+        &self,
+        attr: &BinaryAttr,
+        symtab: &SymbolTable,
+    ) -> Result<Vec<Expression>, ApplicationError> {
+        let values = &self.domain_values[0];
+        let r = |x: &Literal, y: &Literal| {
+            self.contains(&[x.clone(), y.clone()], symtab)
+                .ok_or(RuleNotApplicable)
+        };
+        let not = |x: Expression| Expression::Not(Metadata::new(), Moo::new(x));
+        let imply = |x: Expression, y: Expression| {
+            Expression::Imply(Metadata::new(), Moo::new(x), Moo::new(y))
+        };
+        let and = |x: Expression, y: Expression| {
+            Expression::And(Metadata::new(), Moo::new(matrix_expr![x, y]))
+        };
+        let or = |x: Expression, y: Expression| {
+            Expression::Or(Metadata::new(), Moo::new(matrix_expr![x, y]))
+        };
+
+        let pairs = || values.iter().cartesian_product(values);
+        let distinct_pairs = || pairs().filter(|(x, y)| x != y);
+        let triples = || {
+            values
+                .iter()
+                .cartesian_product(values)
+                .cartesian_product(values)
+                .map(|((x, y), z)| (x, y, z))
+        };
+
+        let constraints = match attr {
+            BinaryAttr::Reflexive => values.iter().map(|x| r(x, x)).try_collect()?,
+            BinaryAttr::Irreflexive => values.iter().map(|x| Ok(not(r(x, x)?))).try_collect()?,
+            BinaryAttr::Coreflexive => distinct_pairs()
+                .map(|(x, y)| Ok(not(r(x, y)?)))
+                .try_collect()?,
+            BinaryAttr::Symmetric => distinct_pairs()
+                .map(|(x, y)| Ok(imply(r(x, y)?, r(y, x)?)))
+                .try_collect()?,
+            BinaryAttr::AntiSymmetric => distinct_pairs()
+                .map(|(x, y)| Ok(not(and(r(x, y)?, r(y, x)?))))
+                .try_collect()?,
+            BinaryAttr::ASymmetric => pairs()
+                .map(|(x, y)| Ok(imply(r(x, y)?, not(r(y, x)?))))
+                .try_collect()?,
+            BinaryAttr::Transitive => triples()
+                .map(|(x, y, z)| Ok(imply(and(r(x, y)?, r(y, z)?), r(x, z)?)))
+                .try_collect()?,
+            BinaryAttr::Total => pairs()
+                .map(|(x, y)| Ok(or(r(x, y)?, r(y, x)?)))
+                .try_collect()?,
+            BinaryAttr::Connex => distinct_pairs()
+                .map(|(x, y)| Ok(or(r(x, y)?, r(y, x)?)))
+                .try_collect()?,
+            BinaryAttr::Euclidean => triples()
+                .map(|(x, y, z)| Ok(imply(and(r(x, y)?, r(x, z)?), r(y, z)?)))
+                .try_collect()?,
+            BinaryAttr::Serial => values
+                .iter()
+                .map(|x| {
+                    let images = values.iter().map(|y| r(x, y)).try_collect()?;
+                    Ok(Expression::Or(
+                        Metadata::new(),
+                        Moo::new(into_matrix_expr![images]),
+                    ))
+                })
+                .try_collect()?,
+            BinaryAttr::Equivalence => [
+                BinaryAttr::Reflexive,
+                BinaryAttr::Symmetric,
+                BinaryAttr::Transitive,
+            ]
+            .iter()
+            .map(|attr| self.binary_attr_constraints(attr, symtab))
+            .flatten_ok()
+            .try_collect()?,
+            BinaryAttr::PartialOrder => [
+                BinaryAttr::Reflexive,
+                BinaryAttr::AntiSymmetric,
+                BinaryAttr::Transitive,
+            ]
+            .iter()
+            .map(|attr| self.binary_attr_constraints(attr, symtab))
+            .flatten_ok()
+            .try_collect()?,
+        };
+
+        Ok(constraints)
+    }
+}
+
+impl Representation for RelationAsMatrix {
+    fn init(name: &Name, symtab: &SymbolTable) -> Option<Self> {
+        let domain = symtab.resolve_domain(name)?;
+
+        if !domain.is_finite() {
+            return None;
+        }
+
+        let GroundDomain::Relation(attr, domains) = domain.as_ref() else {
+            return None;
+        };
+
+        if domains
+            .iter()
+            .any(|d| !matches!(d.as_ref(), GroundDomain::Bool | GroundDomain::Int(_)))
+        {
+            return None;
+        }
+
+        let domain_values: Vec<Vec<Literal>> = domains
+            .iter()
+            .map(|d| d.values().ok().map(|values| values.collect()))
+            .collect::<Option<_>>()?;
+
+        // binary relation attributes only make sense for relations from a domain to itself
+        if !attr.binary.is_empty() && (domains.len() != 2 || domain_values[0] != domain_values[1]) {
+            return None;
+        }
+
+        let tuples = domain_values
+            .iter()
+            .cloned()
+            .multi_cartesian_product()
+            .collect();
+
+        Some(RelationAsMatrix {
+            src_var: name.clone(),
+            domain_values,
+            tuples,
+            size: attr.size.clone(),
+            binary: attr.binary.clone(),
+        })
+    }
+
+    fn variable_name(&self) -> &Name {
+        &self.src_var
+    }
+
+    fn value_down(&self, value: Literal) -> Result<BTreeMap<Name, Literal>, ApplicationError> {
+        let Literal::AbstractLiteral(AbstractLiteral::Relation(members)) = value else {
+            return Err(RuleNotApplicable);
+        };
+
+        if members.iter().any(|x| !self.tuples.contains(x)) {
+            return Err(RuleNotApplicable);
+        }
+
+        Ok(self
+            .tuples
+            .iter()
+            .map(|x| (self.tuple_to_name(x), Literal::Bool(members.contains(x))))
+            .collect())
+    }
+
+    fn value_up(&self, values: &BTreeMap<Name, Literal>) -> Result<Literal, ApplicationError> {
+        let mut members = vec![];
+        for tuple in &self.tuples {
+            let value = values
+                .get(&self.tuple_to_name(tuple))
+                .ok_or(RuleNotApplicable)?;
+
+            match value {
+                Literal::Int(1) | Literal::Bool(true) => members.push(tuple.clone()),
+                Literal::Int(0) | Literal::Bool(false) => {}
+                _ => return Err(RuleNotApplicable),
+            }
+        }
+
+        Ok(Literal::AbstractLiteral(AbstractLiteral::Relation(members)))
+    }
+
+    fn expression_down(
+        &self,
+        symtab: &SymbolTable,
+    ) -> Result<BTreeMap<Name, Expression>, ApplicationError> {
+        self.tuples
+            .iter()
+            .map(|tuple| {
+                let expr = self.contains(tuple, symtab).ok_or(RuleNotApplicable)?;
+                Ok((self.tuple_to_name(tuple), expr))
+            })
+            .collect()
+    }
+
+    fn declaration_down(&self) -> Result<Vec<DeclarationPtr>, ApplicationError> {
+        Ok(self
+            .tuples
+            .iter()
+            .map(|tuple| DeclarationPtr::new_find(self.tuple_to_name(tuple), Domain::bool()))
+            .collect_vec())
+    }
+
+    fn structural_constraints(
+        &self,
+        symtab: &SymbolTable,
+    ) -> Result<Vec<Expression>, ApplicationError> {
+        let mut constraints = vec![];
+
+        if !matches!(self.size, Range::Unbounded) {
+            constraints.extend(super::size_constraints(
+                &self.size,
+                self.cardinality(symtab)?,
+            ));
+        }
+
+        for attr in &self.binary {
+            constraints.extend(self.binary_attr_constraints(attr, symtab)?);
+        }
+
+        Ok(constraints)
+    }
+
+    fn repr_name(&self) -> &str {
+        "relation_as_matrix"
+    }
+
+    fn box_clone(&self) -> Box<dyn Representation> {
+        Box::new(self.clone()) as _
+    }
+}
@@ -0,0 +1,285 @@
+use conjure_cp::ast::{Domain, DomainPtr, GroundDomain, JectivityAttr, Moo, Range, Reference};
+use conjure_cp::{into_matrix_expr, matrix_expr};
+use itertools::Itertools;
+use std::collections::BTreeMap;
+
+use super::prelude::*;
+
+register_representation!(SequenceExplicitBounded, "sequence_explicit_bounded");
+
+/// Represents a sequence as a variable holding its length, and a matrix of its elements of size
+/// `maxSize`.
+///
+/// Only the first `length` elements of the matrix are in the sequence; the rest of the matrix is
+/// fixed to the smallest value in the element domain.
+#[derive(Clone, Debug)]
+pub struct SequenceExplicitBounded {
+    src_var: Name,
+
+    // the minimum and maximum length of the sequence.
+    min_size: i32,
+    max_size: i32,
+
+    jectivity: JectivityAttr,
+
+    // the element domain of the sequence.
+    elem_domain: Moo<GroundDomain>,
+
+    // all the values in the element domain, in order.
+    elem_values: Vec<Literal>,
+}
+
+impl SequenceExplicitBounded {
+    /// Returns the names of the element representation variables, in order.
+    fn element_names(&self) -> impl Iterator<Item = Name> + '_ {
+        (1..=self.max_size).map(|i| self.represented_name(format!("values_{i}")))
+    }
+
+    /// Returns the name of the length representation variable.
+    fn length_name(&self) -> Name {
+        self.represented_name(String::from("length"))
+    }
+
+    fn represented_name(&self, suffix: String) -> Name {
+        Name::Represented(Box::new((
+            self.src_var.clone(),
+            self.repr_name().into(),
+            suffix.into(),
+        )))
+    }
+
+    fn lookup(&self, name: &Name, symtab: &SymbolTable) -> Result<Expression, ApplicationError> {
+        let decl = symtab.lookup(name).ok_or(RuleNotApplicable)?;
+        Ok(Expression::from(Reference::new(decl)))
+    }
+
+    /// The minimum length of the sequence.
+    pub(crate) fn min_size(&self) -> i32 {
+        self.min_size
+    }
+
+    /// The smallest value in the element domain, used to fill unused elements.
+    pub(crate) fn fill_value(&self) -> &Literal {
+        &self.elem_values[0]
+    }
+
+    /// Returns the representation variables for the elements of the sequence, in order.
+    ///
+    /// The element at index `i` (starting from 1) is only in the sequence if `i <= length`.
+    pub(crate) fn elements(
+        &self,
+        symtab: &SymbolTable,
+    ) -> Result<Vec<Expression>, ApplicationError> {
+        self.element_names()
+            .map(|name| self.lookup(&name, symtab))
+            .collect()
+    }
+
+    /// Returns the representation variable holding the length of the sequence.
+    pub(crate) fn length(&self, symtab: &SymbolTable) -> Result<Expression, ApplicationError> {
+        self.lookup(&self.length_name(), symtab)
+    }
+}
+
+impl Representation for SequenceExplicitBounded {
+    fn init(name: &Name, symtab: &SymbolTable) -> Option<Self> {
+        let domain = symtab.resolve_domain(name)?;
+
+        if !domain.is_finite() {
+            return None;
+        }
+
+        let GroundDomain::Sequence(attr, elem_domain) = domain.as_ref() else {
+            return None;
+        };
+
+        if !matches!(
+            elem_domain.as_ref(),
+            GroundDomain::Bool | GroundDomain::Int(_)
+        ) {
+            return None;
+        }
+
+        // the matrix of elements needs a fixed size
+        let max_size = *attr.size.high()?;
+        let min_size = attr.size.low().copied().unwrap_or(0);
+
+        let elem_values: Vec<Literal> = elem_domain.values().ok()?.collect();
+        if min_size > max_size || elem_values.is_empty() {
+            return None;
+        }
+
+        Some(SequenceExplicitBounded {
+            src_var: name.clone(),
+            min_size,
+            max_size,
+            jectivity: attr.jectivity.clone(),
+            elem_domain: elem_domain.clone(),
+            elem_values,
+        })
+    }
+
+    fn variable_name(&self) -> &Name {
+        &self.src_var
+    }
+
+    fn value_down(&self, value: Literal) -> Result<BTreeMap<Name, Literal>, ApplicationError> {
+        let Literal::AbstractLiteral(AbstractLiteral::Sequence(elems)) = value else {
+            return Err(RuleNotApplicable);
+        };
+
+        let size = i32::try_from(elems.len()).map_err(|_| RuleNotApplicable)?;
+        if size < self.min_size || size > self.max_size {
+            return Err(RuleNotApplicable);
+        }
+
+        if elems.iter().any(|x| !self.elem_values.contains(x)) {
+            return Err(RuleNotApplicable);
+        }
+
+        let padding = std::iter::repeat(self.fill_value().clone());
+        let mut values: BTreeMap<Name, Literal> = self
+            .element_names()
+            .zip(elems.into_iter().chain(padding))
+            .collect();
+        values.insert(self.length_name(), Literal::Int(size));
+
+        Ok(values)
+    }
+
+    fn value_up(&self, values: &BTreeMap<Name, Literal>) -> Result<Literal, ApplicationError> {
+        let Some(Literal::Int(size)) = values.get(&self.length_name()) else {
+            return Err(RuleNotApplicable);
+        };
+        let size = usize::try_from(*size).map_err(|_| RuleNotApplicable)?;
+
+        let elems = self
+            .element_names()
+            .take(size)
+            .map(|name| values.get(&name).cloned().ok_or(RuleNotApplicable))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Literal::AbstractLiteral(AbstractLiteral::Sequence(elems)))
+    }
+
+    fn expression_down(
+        &self,
+        symtab: &SymbolTable,
+    ) -> Result<BTreeMap<Name, Expression>, ApplicationError> {
+        let mut exprs: BTreeMap<Name, Expression> =
+            self.element_names().zip(self.elements(symtab)?).collect();
+        exprs.insert(self.length_name(), self.length(symtab)?);
+        Ok(exprs)
+    }
+
+    fn declaration_down(&self) -> Result<Vec<DeclarationPtr>, ApplicationError> {
+        let length_domain = Domain::int(vec![Range::Bounded(self.min_size, self.max_size)]);
+        let dom: DomainPtr = self.elem_domain.clone().into();
+
+        Ok(
+            std::iter::once(DeclarationPtr::new_find(self.length_name(), length_domain))
+                .chain(
+                    self.element_names()
+                        .map(|name| DeclarationPtr::new_find(name, dom.clone())),
+                )
+                .collect_vec(),
+        )
+    }
+
+    fn structural_constraints(
+        &self,
+        symtab: &SymbolTable,
+    ) -> Result<Vec<Expression>, ApplicationError> {
+        let length = Moo::new(self.length(symtab)?);
+        let elements = self.elements(symtab)?;
+
+        // i <= length, for the element at index i
+        let in_sequence = |i: usize| {
+            Expression::Leq(
+                Metadata::new(),
+                Moo::new(Expression::from(i as i32)),
+                length.clone(),
+            )
+        };
+
+        let mut constraints = vec![];
+
+        // the elements not in the sequence are fixed, so they have exactly one assignment
+        for (i, element) in elements.iter().enumerate() {
+            constraints.push(Expression::Or(
+                Metadata::new(),
+                Moo::new(matrix_expr![
+                    in_sequence(i + 1),
+                    Expression::Eq(
+                        Metadata::new(),
+                        Moo::new(element.clone()),
+                        Moo::new(Expression::from(self.fill_value().clone())),
+                    ),
+                ]),
+            ));
+        }
+
+        let injective = matches!(
+            self.jectivity,
+            JectivityAttr::Injective | JectivityAttr::Bijective
+        );
+        let surjective = matches!(
+            self.jectivity,
+            JectivityAttr::Surjective | JectivityAttr::Bijective
+        );
+
+        // no two elements in the sequence are equal. if the later element is in the sequence, so
+        // is the earlier one.
+        if injective {
+            for [(_, a), (j, b)] in elements.iter().enumerate().array_combinations() {
+                constraints.push(Expression::Imply(
+                    Metadata::new(),
+                    Moo::new(in_sequence(j + 1)),
+                    Moo::new(Expression::Neq(
+                        Metadata::new(),
+                        Moo::new(a.clone()),
+                        Moo::new(b.clone()),
+                    )),
+                ));
+            }
+        }
+
+        // every value in the element domain is in the sequence
+        if surjective {
+            for y in &self.elem_values {
+                let disjuncts = elements
+                    .iter()
+                    .enumerate()
+                    .map(|(i, element)| {
+                        Expression::And(
+                            Metadata::new(),
+                            Moo::new(matrix_expr![
+                                in_sequence(i + 1),
+                                Expression::Eq(
+                                    Metadata::new(),
+                                    Moo::new(element.clone()),
+                                    Moo::new(Expression::from(y.clone())),
+                                ),
+                            ]),
+                        )
+                    })
+                    .collect_vec();
+
+                constraints.push(Expression::Or(
+                    Metadata::new(),
+                    Moo::new(into_matrix_expr![disjuncts]),
+                ));
+            }
+        }
+
+        Ok(constraints)
+    }
+
+    fn repr_name(&self) -> &str {
+        "sequence_explicit_bounded"
+    }
+
+    fn box_clone(&self) -> Box<dyn Representation> {
+        Box::new(self.clone()) as _
+    }
+}
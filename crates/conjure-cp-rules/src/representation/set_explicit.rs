@@ -0,0 +1,156 @@
+use conjure_cp::ast::{DomainPtr, GroundDomain, Moo, Range, Reference};
+use itertools::Itertools;
+use std::collections::BTreeMap;
+
+use super::prelude::*;
+
+register_representation!(SetExplicit, "set_explicit");
+
+/// Represents a fixed size set as a strictly increasing matrix of its elements.
+#[derive(Clone, Debug)]
+pub struct SetExplicit {
+    src_var: Name,
+
+    // the number of elements in the set.
+    size: usize,
+
+    // the element domain of the set.
+    elem_domain: Moo<GroundDomain>,
+}
+
+impl SetExplicit {
+    /// Returns the names of the representation variables, in order.
+    fn names(&self) -> impl Iterator<Item = Name> + '_ {
+        (1..=self.size).map(|i| self.index_to_name(i))
+    }
+
+    /// Gets the representation variable name for the element at index `i` (starting from 1).
+    fn index_to_name(&self, i: usize) -> Name {
+        Name::Represented(Box::new((
+            self.src_var.clone(),
+            self.repr_name().into(),
+            i.to_string().into(),
+        )))
+    }
+
+    /// Returns the representation variables for the elements of the set, in increasing order.
+    pub(crate) fn elements(
+        &self,
+        symtab: &SymbolTable,
+    ) -> Result<Vec<Expression>, ApplicationError> {
+        self.names()
+            .map(|name| {
+                let decl = symtab.lookup(&name).ok_or(RuleNotApplicable)?;
+                Ok(Expression::from(Reference::new(decl)))
+            })
+            .collect()
+    }
+
+    /// The number of elements in the set.
+    pub(crate) fn size(&self) -> usize {
+        self.size
+    }
+}
+
+impl Representation for SetExplicit {
+    fn init(name: &Name, symtab: &SymbolTable) -> Option<Self> {
+        let domain = symtab.resolve_domain(name)?;
+
+        if !domain.is_finite() {
+            return None;
+        }
+
+        let GroundDomain::Set(attr, elem_domain) = domain.as_ref() else {
+            return None;
+        };
+
+        let Range::Single(size) = attr.size else {
+            return None;
+        };
+
+        // the elements are ordered using <, so must be integers
+        if !matches!(elem_domain.as_ref(), GroundDomain::Int(_)) {
+            return None;
+        }
+
+        Some(SetExplicit {
+            src_var: name.clone(),
+            size: usize::try_from(size).ok()?,
+            elem_domain: elem_domain.clone(),
+        })
+    }
+
+    fn variable_name(&self) -> &Name {
+        &self.src_var
+    }
+
+    fn value_down(&self, value: Literal) -> Result<BTreeMap<Name, Literal>, ApplicationError> {
+        let Literal::AbstractLiteral(AbstractLiteral::Set(members)) = value else {
+            return Err(RuleNotApplicable);
+        };
+
+        let mut members = members
+            .into_iter()
+            .map(|x| match x {
+                Literal::Int(i) => Ok(i),
+                _ => Err(RuleNotApplicable),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        members.sort_unstable();
+        members.dedup();
+
+        if members.len() != self.size {
+            return Err(RuleNotApplicable);
+        }
+
+        Ok(self
+            .names()
+            .zip(members.into_iter().map(Literal::Int))
+            .collect())
+    }
+
+    fn value_up(&self, values: &BTreeMap<Name, Literal>) -> Result<Literal, ApplicationError> {
+        let members = self
+            .names()
+            .map(|name| values.get(&name).cloned().ok_or(RuleNotApplicable))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Literal::AbstractLiteral(AbstractLiteral::Set(members)))
+    }
+
+    fn expression_down(
+        &self,
+        symtab: &SymbolTable,
+    ) -> Result<BTreeMap<Name, Expression>, ApplicationError> {
+        Ok(self.names().zip(self.elements(symtab)?).collect())
+    }
+
+    fn declaration_down(&self) -> Result<Vec<DeclarationPtr>, ApplicationError> {
+        let dom: DomainPtr = self.elem_domain.clone().into();
+        Ok(self
+            .names()
+            .map(|name| DeclarationPtr::new_find(name, dom.clone()))
+            .collect_vec())
+    }
+
+    fn structural_constraints(
+        &self,
+        symtab: &SymbolTable,
+    ) -> Result<Vec<Expression>, ApplicationError> {
+        // the elements are distinct, and sorted to break symmetry
+        Ok(self
+            .elements(symtab)?
+            .into_iter()
+            .tuple_windows()
+            .map(|(a, b)| Expression::Lt(Metadata::new(), Moo::new(a), Moo::new(b)))
+            .collect())
+    }
+
+    fn repr_name(&self) -> &str {
+        "set_explicit"
+    }
+
+    fn box_clone(&self) -> Box<dyn Representation> {
+        Box::new(self.clone()) as _
+    }
+}
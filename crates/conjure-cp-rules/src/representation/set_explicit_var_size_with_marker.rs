@@ -0,0 +1,242 @@
+use conjure_cp::ast::{Domain, DomainPtr, GroundDomain, Moo, Range, Reference};
+use conjure_cp::matrix_expr;
+use itertools::Itertools;
+use std::collections::BTreeMap;
+
+use super::prelude::*;
+
+register_representation!(
+    SetExplicitVarSizeWithMarker,
+    "set_explicit_var_size_with_marker"
+);
+
+/// Represents a variable size set as a matrix of its elements, and a marker holding the size of
+/// the set.
+///
+/// Only the first `marker` elements of the matrix are in the set. These are strictly increasing;
+/// the rest of the matrix is fixed to the smallest value in the element domain.
+#[derive(Clone, Debug)]
+pub struct SetExplicitVarSizeWithMarker {
+    src_var: Name,
+
+    // the minimum and maximum number of elements in the set.
+    min_size: i32,
+    max_size: i32,
+
+    // the element domain of the set.
+    elem_domain: Moo<GroundDomain>,
+
+    // the smallest value in the element domain, used to fill unused elements.
+    fill_value: i32,
+}
+
+impl SetExplicitVarSizeWithMarker {
+    /// Returns the names of the element representation variables, in order.
+    fn element_names(&self) -> impl Iterator<Item = Name> + '_ {
+        (1..=self.max_size).map(|i| self.represented_name(i.to_string()))
+    }
+
+    /// Returns the name of the marker representation variable.
+    fn marker_name(&self) -> Name {
+        self.represented_name(String::from("marker"))
+    }
+
+    fn represented_name(&self, suffix: String) -> Name {
+        Name::Represented(Box::new((
+            self.src_var.clone(),
+            self.repr_name().into(),
+            suffix.into(),
+        )))
+    }
+
+    fn lookup(&self, name: &Name, symtab: &SymbolTable) -> Result<Expression, ApplicationError> {
+        let decl = symtab.lookup(name).ok_or(RuleNotApplicable)?;
+        Ok(Expression::from(Reference::new(decl)))
+    }
+
+    /// Returns the representation variables for the elements of the set, in order.
+    ///
+    /// The element at index `i` (starting from 1) is only in the set if `i <= marker`.
+    pub(crate) fn elements(
+        &self,
+        symtab: &SymbolTable,
+    ) -> Result<Vec<Expression>, ApplicationError> {
+        self.element_names()
+            .map(|name| self.lookup(&name, symtab))
+            .collect()
+    }
+
+    /// Returns the representation variable holding the number of elements in the set.
+    pub(crate) fn marker(&self, symtab: &SymbolTable) -> Result<Expression, ApplicationError> {
+        self.lookup(&self.marker_name(), symtab)
+    }
+}
+
+impl Representation for SetExplicitVarSizeWithMarker {
+    fn init(name: &Name, symtab: &SymbolTable) -> Option<Self> {
+        let domain = symtab.resolve_domain(name)?;
+
+        if !domain.is_finite() {
+            return None;
+        }
+
+        let GroundDomain::Set(attr, elem_domain) = domain.as_ref() else {
+            return None;
+        };
+
+        // the elements are ordered using <, so must be integers
+        let GroundDomain::Int(ranges) = elem_domain.as_ref() else {
+            return None;
+        };
+
+        let n_elements = i32::try_from(elem_domain.length().ok()?).ok()?;
+        let min_size = attr.size.low().copied().unwrap_or(0);
+        let max_size = attr
+            .size
+            .high()
+            .copied()
+            .map_or(n_elements, |max| max.min(n_elements));
+
+        if min_size > max_size {
+            return None;
+        }
+
+        Some(SetExplicitVarSizeWithMarker {
+            src_var: name.clone(),
+            min_size,
+            max_size,
+            elem_domain: elem_domain.clone(),
+            fill_value: *Range::low_of(ranges)?,
+        })
+    }
+
+    fn variable_name(&self) -> &Name {
+        &self.src_var
+    }
+
+    fn value_down(&self, value: Literal) -> Result<BTreeMap<Name, Literal>, ApplicationError> {
+        let Literal::AbstractLiteral(AbstractLiteral::Set(members)) = value else {
+            return Err(RuleNotApplicable);
+        };
+
+        let mut members = members
+            .into_iter()
+            .map(|x| match x {
+                Literal::Int(i) => Ok(i),
+                _ => Err(RuleNotApplicable),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        members.sort_unstable();
+        members.dedup();
+
+        let size = i32::try_from(members.len()).map_err(|_| RuleNotApplicable)?;
+        if size < self.min_size || size > self.max_size {
+            return Err(RuleNotApplicable);
+        }
+
+        let padding = std::iter::repeat(self.fill_value);
+        let mut values: BTreeMap<Name, Literal> = self
+            .element_names()
+            .zip(members.into_iter().chain(padding).map(Literal::Int))
+            .collect();
+        values.insert(self.marker_name(), Literal::Int(size));
+
+        Ok(values)
+    }
+
+    fn value_up(&self, values: &BTreeMap<Name, Literal>) -> Result<Literal, ApplicationError> {
+        let Some(Literal::Int(size)) = values.get(&self.marker_name()) else {
+            return Err(RuleNotApplicable);
+        };
+        let size = usize::try_from(*size).map_err(|_| RuleNotApplicable)?;
+
+        let members = self
+            .element_names()
+            .take(size)
+            .map(|name| values.get(&name).cloned().ok_or(RuleNotApplicable))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Literal::AbstractLiteral(AbstractLiteral::Set(members)))
+    }
+
+    fn expression_down(
+        &self,
+        symtab: &SymbolTable,
+    ) -> Result<BTreeMap<Name, Expression>, ApplicationError> {
+        let mut exprs: BTreeMap<Name, Expression> =
+            self.element_names().zip(self.elements(symtab)?).collect();
+        exprs.insert(self.marker_name(), self.marker(symtab)?);
+        Ok(exprs)
+    }
+
+    fn declaration_down(&self) -> Result<Vec<DeclarationPtr>, ApplicationError> {
+        let marker_domain = Domain::int(vec![Range::Bounded(self.min_size, self.max_size)]);
+        let dom: DomainPtr = self.elem_domain.clone().into();
+
+        Ok(
+            std::iter::once(DeclarationPtr::new_find(self.marker_name(), marker_domain))
+                .chain(
+                    self.element_names()
+                        .map(|name| DeclarationPtr::new_find(name, dom.clone())),
+                )
+                .collect_vec(),
+        )
+    }
+
+    fn structural_constraints(
+        &self,
+        symtab: &SymbolTable,
+    ) -> Result<Vec<Expression>, ApplicationError> {
+        let marker = Moo::new(self.marker(symtab)?);
+        let elements = self.elements(symtab)?;
+
+        // i <= marker, for the element at index i
+        let in_set = |i: usize| {
+            Expression::Leq(
+                Metadata::new(),
+                Moo::new(Expression::from(i as i32)),
+                marker.clone(),
+            )
+        };
+
+        let mut constraints = vec![];
+
+        // the elements in the set are distinct, and sorted to break symmetry
+        for (i, (a, b)) in elements.iter().tuple_windows().enumerate() {
+            constraints.push(Expression::Imply(
+                Metadata::new(),
+                Moo::new(in_set(i + 2)),
+                Moo::new(Expression::Lt(
+                    Metadata::new(),
+                    Moo::new(a.clone()),
+                    Moo::new(b.clone()),
+                )),
+            ));
+        }
+
+        // the elements not in the set are fixed, so they have exactly one assignment
+        for (i, element) in elements.into_iter().enumerate() {
+            constraints.push(Expression::Or(
+                Metadata::new(),
+                Moo::new(matrix_expr![
+                    in_set(i + 1),
+                    Expression::Eq(
+                        Metadata::new(),
+                        Moo::new(element),
+                        Moo::new(Expression::from(self.fill_value)),
+                    ),
+                ]),
+            ));
+        }
+
+        Ok(constraints)
+    }
+
+    fn repr_name(&self) -> &str {
+        "set_explicit_var_size_with_marker"
+    }
+
+    fn box_clone(&self) -> Box<dyn Representation> {
+        Box::new(self.clone()) as _
+    }
+}
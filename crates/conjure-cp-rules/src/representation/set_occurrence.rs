@@ -0,0 +1,176 @@
+use conjure_cp::ast::{Domain, GroundDomain, Moo, Range, Reference};
+use conjure_cp::into_matrix_expr;
+use itertools::Itertools;
+use std::collections::BTreeMap;
+
+use super::prelude::*;
+
+register_representation!(SetOccurrence, "set_occurrence");
+
+/// Represents a set as a boolean for each value in its element domain, which is true iff that
+/// value is in the set.
+#[derive(Clone, Debug)]
+pub struct SetOccurrence {
+    src_var: Name,
+
+    // all the values in the element domain, in order.
+    elements: Vec<Literal>,
+
+    // the allowed sizes of the set.
+    size: Range<i32>,
+}
+
+impl SetOccurrence {
+    /// Returns the names of the representation variables, in the same order as the elements.
+    fn names(&self) -> impl Iterator<Item = Name> + '_ {
+        self.elements.iter().map(|x| self.element_to_name(x))
+    }
+
+    /// Gets the representation variable name for a value in the element domain.
+    fn element_to_name(&self, element: &Literal) -> Name {
+        Name::Represented(Box::new((
+            self.src_var.clone(),
+            self.repr_name().into(),
+            element.to_string().into(),
+        )))
+    }
+
+    /// The values in the element domain of the set, in order.
+    pub(crate) fn elements(&self) -> &[Literal] {
+        &self.elements
+    }
+
+    /// Returns the boolean representation variable for `element`, or `None` if `element` is not
+    /// in the element domain.
+    pub(crate) fn occurrence(&self, element: &Literal, symtab: &SymbolTable) -> Option<Expression> {
+        if !self.elements.contains(element) {
+            return None;
+        }
+
+        let decl = symtab.lookup(&self.element_to_name(element))?;
+        Some(Expression::from(Reference::new(decl)))
+    }
+
+    /// Returns an expression for the number of elements in the set.
+    pub(crate) fn cardinality(&self, symtab: &SymbolTable) -> Result<Expression, ApplicationError> {
+        let occurrences = self
+            .elements
+            .iter()
+            .map(|element| {
+                let occurrence = self.occurrence(element, symtab).ok_or(RuleNotApplicable)?;
+                Ok(Expression::ToInt(Metadata::new(), Moo::new(occurrence)))
+            })
+            .collect::<Result<Vec<_>, ApplicationError>>()?;
+
+        Ok(Expression::Sum(
+            Metadata::new(),
+            Moo::new(into_matrix_expr!(occurrences)),
+        ))
+    }
+}
+
+impl Representation for SetOccurrence {
+    fn init(name: &Name, symtab: &SymbolTable) -> Option<Self> {
+        let domain = symtab.resolve_domain(name)?;
+
+        if !domain.is_finite() {
+            return None;
+        }
+
+        let GroundDomain::Set(attr, elem_domain) = domain.as_ref() else {
+            return None;
+        };
+
+        if !matches!(
+            elem_domain.as_ref(),
+            GroundDomain::Bool | GroundDomain::Int(_)
+        ) {
+            return None;
+        }
+
+        Some(SetOccurrence {
+            src_var: name.clone(),
+            elements: elem_domain.values().ok()?.collect(),
+            size: attr.size.clone(),
+        })
+    }
+
+    fn variable_name(&self) -> &Name {
+        &self.src_var
+    }
+
+    fn value_down(&self, value: Literal) -> Result<BTreeMap<Name, Literal>, ApplicationError> {
+        let Literal::AbstractLiteral(AbstractLiteral::Set(members)) = value else {
+            return Err(RuleNotApplicable);
+        };
+
+        if members.iter().any(|x| !self.elements.contains(x)) {
+            return Err(RuleNotApplicable);
+        }
+
+        Ok(self
+            .elements
+            .iter()
+            .map(|x| (self.element_to_name(x), Literal::Bool(members.contains(x))))
+            .collect())
+    }
+
+    fn value_up(&self, values: &BTreeMap<Name, Literal>) -> Result<Literal, ApplicationError> {
+        let mut members = vec![];
+        for element in &self.elements {
+            let value = values
+                .get(&self.element_to_name(element))
+                .ok_or(RuleNotApplicable)?;
+
+            match value {
+                Literal::Int(1) | Literal::Bool(true) => members.push(element.clone()),
+                Literal::Int(0) | Literal::Bool(false) => {}
+                _ => return Err(RuleNotApplicable),
+            }
+        }
+
+        Ok(Literal::AbstractLiteral(AbstractLiteral::Set(members)))
+    }
+
+    fn expression_down(
+        &self,
+        symtab: &SymbolTable,
+    ) -> Result<BTreeMap<Name, Expression>, ApplicationError> {
+        Ok(self
+            .names()
+            .map(|name| {
+                let decl = symtab.lookup(&name).expect("declarations of the representation variables should exist in the symbol table before expression_down is called");
+                (name, Expression::from(Reference::new(decl)))
+            })
+            .collect())
+    }
+
+    fn declaration_down(&self) -> Result<Vec<DeclarationPtr>, ApplicationError> {
+        Ok(self
+            .names()
+            .map(|name| DeclarationPtr::new_find(name, Domain::bool()))
+            .collect_vec())
+    }
+
+    fn structural_constraints(
+        &self,
+        symtab: &SymbolTable,
+    ) -> Result<Vec<Expression>, ApplicationError> {
+        if matches!(self.size, Range::Unbounded) {
+            return Ok(vec![]);
+        }
+
+        Ok(super::size_constraints(
+            &self.size,
+            self.cardinality(symtab)?,
+        ))
+    }
+
+    fn repr_name(&self) -> &str {
+        "set_occurrence"
+    }
+
+    fn box_clone(&self) -> Box<dyn Representation> {
+        Box::new(self.clone()) as _
+    }
+}
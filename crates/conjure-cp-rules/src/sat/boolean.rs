@@ -11,7 +11,8 @@ use conjure_cp::ast::{Domain, SymbolTable};
 
 use crate::utils::is_literal;
 
-fn create_bool_aux(symbols: &mut SymbolTable) -> Expr {
+/// Creates a new boolean auxiliary variable
+pub fn create_bool_aux(symbols: &mut SymbolTable) -> Expr {
     let name = symbols.gen_find(&Domain::bool());
 
     symbols.insert(name.clone());
@@ -22,7 +23,9 @@ fn create_bool_aux(symbols: &mut SymbolTable) -> Expr {
     )
 }
 
-fn create_clause(exprs: Vec<Expr>) -> Option<CnfClause> {
+/// Creates a clause from a list of literals, removing `false` literals.
+/// Returns `None` if the clause is always satisfied, i.e. it contains a `true` literal.
+pub fn create_clause(exprs: Vec<Expr>) -> Option<CnfClause> {
     let mut new_terms = vec![];
     for expr in exprs {
         if let Expr::Atomic(_, Atom::Literal(Literal::Bool(x))) = expr {
@@ -48,6 +51,17 @@ fn create_clause(exprs: Vec<Expr>) -> Option<CnfClause> {
     Some(CnfClause::new(new_terms))
 }
 
+/// Returns the negation of a literal, simplifying constants and double negations.
+pub fn negate(lit: &Expr) -> Expr {
+    match lit {
+        Expr::Atomic(_, Atom::Literal(Literal::Bool(x))) => {
+            Expr::Atomic(Metadata::new(), Atom::Literal(Literal::Bool(!x)))
+        }
+        Expr::Not(_, inner) => inner.as_ref().clone(),
+        _ => Expr::Not(Metadata::new(), Moo::new(lit.clone())),
+    }
+}
+
 // TODO: Optimize all logic operators for constants
 // TODO: If a clause simplifies to false, it should skip the solver and give no solutions
 
@@ -66,7 +80,7 @@ pub fn tseytin_and(
             Expr::Not(Metadata::new(), Moo::new(new_expr.clone())),
             x.clone(),
         ]));
-        full_conj.push(Expr::Not(Metadata::new(), Moo::new(x.clone())));
+        full_conj.push(negate(x));
     }
     clauses.extend(create_clause(full_conj));
 
@@ -78,7 +92,7 @@ pub fn tseytin_not(x: Expr, clauses: &mut Vec<CnfClause>, symbols: &mut SymbolTa
     let new_expr = create_bool_aux(symbols);
 
     clauses.extend(create_clause(vec![
-        Expr::Not(Metadata::new(), Moo::new(x.clone())),
+        negate(&x),
         Expr::Not(Metadata::new(), Moo::new(new_expr.clone())),
     ]));
     clauses.extend(create_clause(vec![x, new_expr.clone()]));
@@ -97,10 +111,7 @@ pub fn tseytin_or(
     let mut full_conj: Vec<Expr> = vec![Expr::Not(Metadata::new(), Moo::new(new_expr.clone()))];
 
     for x in exprs {
-        clauses.extend(create_clause(vec![
-            Expr::Not(Metadata::new(), Moo::new(x.clone())),
-            new_expr.clone(),
-        ]));
+        clauses.extend(create_clause(vec![negate(x), new_expr.clone()]));
         full_conj.push(x.clone());
     }
 
@@ -0,0 +1,623 @@
+//! Cardinality and pseudo-Boolean encodings for sums of Booleans.
+//!
+//! Comparisons between (weighted) sums of Booleans and constants, such as at-most-k constraints,
+//! are encoded with the encodings in `rustsat` instead of integer adder circuits. The encoding is
+//! chosen with the `sat_cardinality_encoding` and `sat_pb_encoding` settings.
+
+use std::collections::HashMap;
+
+use conjure_cp::ast::{Atom, CnfClause, Expression as Expr, Literal, Metadata, SymbolTable};
+use conjure_cp::bug;
+use conjure_cp::rule_engine::{
+    ApplicationError::RuleNotApplicable, ApplicationResult, Reduction, register_rule,
+};
+use conjure_cp::settings::{
+    SatCardinalityEncoding, SatPbEncoding, sat_cardinality_encoding, sat_pb_encoding,
+};
+use rustsat::encodings::card::{self, BoundUpper as _};
+use rustsat::encodings::pb;
+use rustsat::instances::{BasicVarManager, Cnf, ManageVars};
+use rustsat::types::{Lit, Var};
+
+use crate::sat::boolean::{create_bool_aux, negate, tseytin_and, tseytin_not};
+use crate::utils::is_literal;
+
+/// Encodes a comparison between sums of Booleans and constants.
+///
+/// ```text
+/// sum([toInt(a), toInt(b), toInt(c)]) <= 1
+/// ~~>
+/// __0
+///
+/// new variables:
+/// find __0: bool
+/// (and the auxiliary variables of the encodings)
+///
+/// new clauses:
+/// clauses of an encoding of a + b + c <= 1, enforced when __0 is true
+/// clauses of an encoding of !a + !b + !c <= 1, enforced when __0 is false
+/// ```
+///
+/// Terms can be weighted by constants (`2 * toInt(a)`), in which case the pseudo-Boolean encoding
+/// is used instead of the cardinality encoding. `=` is encoded as the conjunction of `<=` and
+/// `>=`, and `!=` as its negation.
+#[register_rule("SAT", 9110, [Leq, Lt, Geq, Gt, Eq, Neq, FlatSumLeq, FlatSumGeq, FlatWeightedSumLeq, FlatWeightedSumGeq])]
+fn cnf_bool_sum(expr: &Expr, symbols: &SymbolTable) -> ApplicationResult {
+    let constraint = BoolSumConstraint::from_expr(expr).ok_or(RuleNotApplicable)?;
+
+    let mut new_clauses = vec![];
+    let mut new_symbols = symbols.clone();
+    let new_expr = constraint.reify(&mut new_clauses, &mut new_symbols);
+
+    Ok(Reduction::cnf(new_expr, new_clauses, new_symbols))
+}
+
+/// Encodes comparisons between sums of Booleans and constants at the top level of the model.
+///
+/// These constraints must always hold, so only one direction of the encoding is needed:
+///
+/// ```text
+/// sum([toInt(a), toInt(b), toInt(c)]) <= 1
+/// ~~>
+/// true
+///
+/// new clauses:
+/// clauses of an encoding of a + b + c <= 1
+/// ```
+#[register_rule("SAT", 9120, [Root])]
+fn cnf_bool_sum_top_level(expr: &Expr, symbols: &SymbolTable) -> ApplicationResult {
+    let Expr::Root(_, children) = expr else {
+        return Err(RuleNotApplicable);
+    };
+
+    let mut new_clauses = vec![];
+    let mut new_symbols = symbols.clone();
+    let mut changed = false;
+
+    let new_children = children
+        .iter()
+        .map(|child| {
+            let Some(constraint) = BoolSumConstraint::from_expr(child)
+                .filter(|constraint| constraint.comparison != Comparison::Neq)
+            else {
+                return child.clone();
+            };
+            changed = true;
+
+            let holds = constraint.at_most_constraints().iter().all(|at_most| {
+                let Some(lits) = at_most.encode(&mut new_clauses, &mut new_symbols) else {
+                    return false;
+                };
+                new_clauses.extend(lits.into_iter().map(|lit| CnfClause::new(vec![lit])));
+                true
+            });
+            Expr::Atomic(Metadata::new(), Atom::Literal(Literal::Bool(holds)))
+        })
+        .collect();
+
+    if !changed {
+        return Err(RuleNotApplicable);
+    }
+
+    Ok(Reduction::cnf(
+        Expr::Root(Metadata::new(), new_children),
+        new_clauses,
+        new_symbols,
+    ))
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Comparison {
+    Leq,
+    Geq,
+    Eq,
+    Neq,
+}
+
+/// A comparison `sum(weight * lit) <op> bound` between a weighted sum of Boolean literals and a
+/// constant.
+struct BoolSumConstraint {
+    terms: Vec<(Expr, i64)>,
+    comparison: Comparison,
+    bound: i64,
+}
+
+impl BoolSumConstraint {
+    /// Reads a comparison between sums of Booleans and constants, where at least one side is a
+    /// sum.
+    ///
+    /// Returns `None` if the sums contain anything other than constants and constant multiples of
+    /// `toInt` of Boolean literals.
+    fn from_expr(expr: &Expr) -> Option<BoolSumConstraint> {
+        let mut sum = LinearSum::default();
+        let (comparison, bound) = match expr {
+            // lhs < rhs ~> lhs - rhs <= -1
+            Expr::Leq(_, lhs, rhs)
+            | Expr::Lt(_, lhs, rhs)
+            | Expr::Geq(_, lhs, rhs)
+            | Expr::Gt(_, lhs, rhs)
+            | Expr::Eq(_, lhs, rhs)
+            | Expr::Neq(_, lhs, rhs) => {
+                if !matches!(lhs.as_ref(), Expr::Sum(_, _))
+                    && !matches!(rhs.as_ref(), Expr::Sum(_, _))
+                {
+                    return None;
+                }
+                sum.add(lhs, 1)?;
+                sum.add(rhs, -1)?;
+
+                match expr {
+                    Expr::Leq(_, _, _) => (Comparison::Leq, 0),
+                    Expr::Lt(_, _, _) => (Comparison::Leq, -1),
+                    Expr::Geq(_, _, _) => (Comparison::Geq, 0),
+                    Expr::Gt(_, _, _) => (Comparison::Geq, 1),
+                    Expr::Eq(_, _, _) => (Comparison::Eq, 0),
+                    _ => (Comparison::Neq, 0),
+                }
+            }
+            Expr::FlatSumLeq(_, atoms, total) | Expr::FlatSumGeq(_, atoms, total) => {
+                for atom in atoms {
+                    sum.add_atom(atom, 1)?;
+                }
+                sum.add_atom(total, -1)?;
+
+                match expr {
+                    Expr::FlatSumLeq(_, _, _) => (Comparison::Leq, 0),
+                    _ => (Comparison::Geq, 0),
+                }
+            }
+            Expr::FlatWeightedSumLeq(_, coefficients, atoms, total)
+            | Expr::FlatWeightedSumGeq(_, coefficients, atoms, total) => {
+                for (coefficient, atom) in coefficients.iter().zip(atoms) {
+                    let Literal::Int(coefficient) = coefficient else {
+                        return None;
+                    };
+                    sum.add_atom(atom, i64::from(*coefficient))?;
+                }
+                sum.add_atom(total, -1)?;
+
+                match expr {
+                    Expr::FlatWeightedSumLeq(_, _, _, _) => (Comparison::Leq, 0),
+                    _ => (Comparison::Geq, 0),
+                }
+            }
+            _ => return None,
+        };
+
+        if sum.terms.is_empty() {
+            // a comparison between constants, which the constant evaluator deals with
+            return None;
+        }
+
+        Some(BoolSumConstraint {
+            terms: sum.terms,
+            comparison,
+            bound: bound - sum.constant,
+        })
+    }
+
+    /// Returns at-most constraints whose conjunction is equivalent to this constraint.
+    ///
+    /// `!=` cannot be written as a conjunction, so gives the constraints for `=`.
+    fn at_most_constraints(&self) -> Vec<AtMost> {
+        let negated_terms: Vec<_> = self
+            .terms
+            .iter()
+            .map(|(lit, weight)| (lit.clone(), -weight))
+            .collect();
+
+        // sum(w * l) >= k <~> sum(-w * l) <= -k
+        let at_most = || AtMost::new(&self.terms, self.bound);
+        let at_least = || AtMost::new(&negated_terms, -self.bound);
+
+        match self.comparison {
+            Comparison::Leq => vec![at_most()],
+            Comparison::Geq => vec![at_least()],
+            Comparison::Eq | Comparison::Neq => vec![at_most(), at_least()],
+        }
+    }
+
+    /// Returns an expression which is true if and only if this constraint holds.
+    fn reify(&self, clauses: &mut Vec<CnfClause>, symbols: &mut SymbolTable) -> Expr {
+        let reified: Vec<Expr> = self
+            .at_most_constraints()
+            .iter()
+            .map(|at_most| at_most.reify(clauses, symbols))
+            .collect();
+
+        let holds = match reified.as_slice() {
+            [single] => single.clone(),
+            _ => tseytin_and(&reified, clauses, symbols),
+        };
+
+        match self.comparison {
+            Comparison::Neq => tseytin_not(holds, clauses, symbols),
+            _ => holds,
+        }
+    }
+}
+
+/// The terms `weight * lit` and constant part of a sum of Booleans.
+#[derive(Default)]
+struct LinearSum {
+    terms: Vec<(Expr, i64)>,
+    constant: i64,
+}
+
+impl LinearSum {
+    /// Adds `coefficient * expr` to the sum, or returns `None` if `expr` is not a sum of Booleans.
+    fn add(&mut self, expr: &Expr, coefficient: i64) -> Option<()> {
+        if let Some(value) = constant_value(expr) {
+            self.constant += coefficient * value;
+            return Some(());
+        }
+
+        match expr {
+            Expr::Sum(_, exprs) => {
+                for expr in exprs.as_ref().clone().unwrap_list()? {
+                    self.add(&expr, coefficient)?;
+                }
+            }
+            Expr::Neg(_, expr) => self.add(expr, -coefficient)?,
+            // a constant multiple of a single term
+            Expr::Product(_, exprs) => {
+                let mut coefficient = coefficient;
+                let mut term = None;
+                for factor in exprs.as_ref().clone().unwrap_list()? {
+                    match constant_value(&factor) {
+                        Some(value) => coefficient *= value,
+                        None if term.is_none() => term = Some(factor),
+                        None => return None,
+                    }
+                }
+                match term {
+                    Some(term) => self.add(&term, coefficient)?,
+                    None => self.constant += coefficient,
+                }
+            }
+            Expr::ToInt(_, lit) if is_literal(lit) => {
+                self.terms.push((lit.as_ref().clone(), coefficient));
+            }
+            _ => return None,
+        }
+
+        Some(())
+    }
+
+    /// Adds `coefficient * atom` to the sum, where `atom` is a constant or a Boolean variable.
+    fn add_atom(&mut self, atom: &Atom, coefficient: i64) -> Option<()> {
+        match atom {
+            Atom::Literal(Literal::Int(value)) => self.constant += coefficient * i64::from(*value),
+            Atom::Literal(Literal::Bool(value)) => self.constant += coefficient * i64::from(*value),
+            Atom::Reference(reference) if reference.domain().is_some_and(|d| d.is_bool()) => {
+                self.terms
+                    .push((Expr::Atomic(Metadata::new(), atom.clone()), coefficient));
+            }
+            _ => return None,
+        }
+
+        Some(())
+    }
+}
+
+/// Returns the value of `expr` if it is an integer constant.
+fn constant_value(expr: &Expr) -> Option<i64> {
+    match expr {
+        Expr::Atomic(_, Atom::Literal(Literal::Int(value))) => Some(i64::from(*value)),
+        Expr::SATInt(_, _, _, (min, max)) if min == max => Some(i64::from(*min)),
+        Expr::ToInt(_, inner) => match inner.as_ref() {
+            Expr::Atomic(_, Atom::Literal(Literal::Bool(value))) => Some(i64::from(*value)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// The constraint `sum(weights[i] * lits[i]) <= bound`, where all weights are positive.
+struct AtMost {
+    lits: Vec<Expr>,
+    weights: Vec<usize>,
+    bound: i64,
+}
+
+impl AtMost {
+    /// Creates the constraint `sum(weight * lit) <= bound` from terms with any weights.
+    fn new(terms: &[(Expr, i64)], bound: i64) -> AtMost {
+        let mut at_most = AtMost {
+            lits: vec![],
+            weights: vec![],
+            bound,
+        };
+
+        for (lit, weight) in terms {
+            if *weight > 0 {
+                at_most.lits.push(lit.clone());
+            } else if *weight < 0 {
+                // w * l = w - w * !l
+                at_most.lits.push(negate(lit));
+                at_most.bound -= weight;
+            } else {
+                continue;
+            }
+            at_most.weights.push(weight.unsigned_abs() as usize);
+        }
+
+        at_most
+    }
+
+    /// The negation of this constraint, `sum(weights[i] * !lits[i]) <= total - bound - 1`.
+    fn negation(&self) -> AtMost {
+        let total: i64 = self.weights.iter().map(|&weight| weight as i64).sum();
+        AtMost {
+            lits: self.lits.iter().map(negate).collect(),
+            weights: self.weights.clone(),
+            bound: total - self.bound - 1,
+        }
+    }
+
+    /// Returns an expression which is true if and only if this constraint holds.
+    fn reify(&self, clauses: &mut Vec<CnfClause>, symbols: &mut SymbolTable) -> Expr {
+        let Some(holds) = self.encode(clauses, symbols) else {
+            return Expr::Atomic(Metadata::new(), Atom::Literal(Literal::Bool(false)));
+        };
+        let Some(fails) = self.negation().encode(clauses, symbols) else {
+            return Expr::Atomic(Metadata::new(), Atom::Literal(Literal::Bool(true)));
+        };
+
+        let new_expr = create_bool_aux(symbols);
+        for lit in holds {
+            clauses.push(CnfClause::new(vec![negate(&new_expr), lit]));
+        }
+        for lit in fails {
+            clauses.push(CnfClause::new(vec![new_expr.clone(), lit]));
+        }
+
+        new_expr
+    }
+
+    /// Adds the clauses of an encoding of this constraint, returning literals which enforce it
+    /// when all of them are true.
+    ///
+    /// Returns `None` if the constraint can never hold.
+    fn encode(&self, clauses: &mut Vec<CnfClause>, symbols: &mut SymbolTable) -> Option<Vec<Expr>> {
+        let total: i64 = self.weights.iter().map(|&weight| weight as i64).sum();
+        if self.bound < 0 {
+            return None;
+        }
+        if self.bound >= total {
+            return Some(vec![]);
+        }
+        let bound = self.bound as usize;
+
+        // the literals of the constraint are the first variables given to the encoding
+        let inputs: Vec<Lit> = (0..self.lits.len())
+            .map(|i| Var::new(i as u32).pos_lit())
+            .collect();
+        let mut cnf = Cnf::new();
+        let mut var_manager = BasicVarManager::from_next_free(Var::new(self.lits.len() as u32));
+
+        let enforce = if self.weights.iter().all(|&weight| weight == 1) {
+            match sat_cardinality_encoding() {
+                SatCardinalityEncoding::Totalizer => {
+                    let mut encoding = card::Totalizer::from_iter(inputs);
+                    encoding
+                        .encode_ub(bound..=bound, &mut cnf, &mut var_manager)
+                        .unwrap_or_else(|e| bug!("could not encode totalizer: {e}"));
+                    encoding
+                        .enforce_ub(bound)
+                        .unwrap_or_else(|e| bug!("could not enforce totalizer bound: {e}"))
+                }
+                SatCardinalityEncoding::SequentialCounter => {
+                    sequential_counter(&inputs, bound, &mut cnf, &mut var_manager)
+                }
+            }
+        } else {
+            let inputs = inputs.into_iter().zip(self.weights.iter().copied());
+            match sat_pb_encoding() {
+                SatPbEncoding::Gte => {
+                    encode_pb::<pb::GeneralizedTotalizer>(inputs, bound, &mut cnf, &mut var_manager)
+                }
+                SatPbEncoding::Dpw => {
+                    encode_pb::<pb::DynamicPolyWatchdog>(inputs, bound, &mut cnf, &mut var_manager)
+                }
+                SatPbEncoding::Adder => {
+                    encode_pb::<pb::BinaryAdder>(inputs, bound, &mut cnf, &mut var_manager)
+                }
+            }
+        };
+
+        // translate the encoding back into expressions, creating a boolean variable for each of
+        // its auxiliary variables
+        let mut aux_vars: HashMap<Var, Expr> = HashMap::new();
+        let mut to_expr = |lit: Lit| {
+            let var = match self.lits.get(lit.vidx()) {
+                Some(input) => input.clone(),
+                None => aux_vars
+                    .entry(lit.var())
+                    .or_insert_with(|| create_bool_aux(symbols))
+                    .clone(),
+            };
+            if lit.is_neg() { negate(&var) } else { var }
+        };
+
+        for clause in cnf {
+            clauses.push(CnfClause::new(
+                clause.iter().map(|&lit| to_expr(lit)).collect(),
+            ));
+        }
+
+        Some(enforce.into_iter().map(to_expr).collect())
+    }
+}
+
+/// Encodes `sum(weight * lit) <= bound` with a pseudo-Boolean encoding from `rustsat`.
+fn encode_pb<E>(
+    inputs: impl Iterator<Item = (Lit, usize)>,
+    bound: usize,
+    cnf: &mut Cnf,
+    var_manager: &mut BasicVarManager,
+) -> Vec<Lit>
+where
+    E: pb::BoundUpper + FromIterator<(Lit, usize)>,
+{
+    let mut encoding: E = inputs.collect();
+    encoding
+        .encode_ub(bound..=bound, cnf, var_manager)
+        .unwrap_or_else(|e| bug!("could not encode pseudo-Boolean constraint: {e}"));
+    encoding
+        .enforce_ub(bound)
+        .unwrap_or_else(|e| bug!("could not enforce pseudo-Boolean bound: {e}"))
+}
+
+/// Encodes `sum(lits) <= bound` with a sequential counter (Sinz, 2005).
+///
+/// After each input, `counter[j]` is true if at least `j + 1` of the inputs so far are true, so
+/// the bound is enforced by `!counter[bound]` after the last input.
+fn sequential_counter(
+    lits: &[Lit],
+    bound: usize,
+    cnf: &mut Cnf,
+    var_manager: &mut BasicVarManager,
+) -> Vec<Lit> {
+    let mut counter: Vec<Lit> = vec![];
+
+    for &lit in lits {
+        let mut next_counter = vec![];
+        for j in 0..=counter.len().min(bound) {
+            let count = var_manager.new_var().pos_lit();
+
+            // lit /\ counter[j - 1] -> count
+            match j.checked_sub(1) {
+                Some(previous) => cnf.add_ternary(!lit, !counter[previous], count),
+                None => cnf.add_binary(!lit, count),
+            }
+
+            // counter[j] -> count
+            if let Some(&same) = counter.get(j) {
+                cnf.add_binary(!same, count);
+            }
+
+            next_counter.push(count);
+        }
+        counter = next_counter;
+    }
+
+    counter
+        .get(bound)
+        .map(|&count| vec![!count])
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use conjure_cp::ast::{DeclarationPtr, Domain, Name, Reference};
+    use conjure_cp::settings::{set_sat_cardinality_encoding, set_sat_pb_encoding};
+    use rustsat::solvers::{Solve, SolveIncremental, SolverResult};
+    use rustsat::types::Clause;
+    use rustsat_cadical::CaDiCaL;
+
+    /// Checks that encoding `sum(weights[i] * x[i]) <= bound` with the current encodings holds for
+    /// exactly the assignments of `x` that satisfy it, for every bound the sum can be compared
+    /// against.
+    fn check_against_brute_force(weights: &[i64]) {
+        let mut symbols = SymbolTable::new();
+        let xs: Vec<Expr> = (0..weights.len())
+            .map(|i| {
+                let decl = DeclarationPtr::new_find(Name::user(&format!("x{i}")), Domain::bool());
+                symbols.insert(decl.clone());
+                Expr::Atomic(Metadata::new(), Atom::Reference(Reference::new(decl)))
+            })
+            .collect();
+        let terms: Vec<(Expr, i64)> = xs.iter().cloned().zip(weights.iter().copied()).collect();
+
+        let min: i64 = weights.iter().filter(|&&weight| weight < 0).sum();
+        let max: i64 = weights.iter().filter(|&&weight| weight > 0).sum();
+        for bound in min - 1..=max + 1 {
+            let mut clauses = vec![];
+            let enforce = AtMost::new(&terms, bound).encode(&mut clauses, &mut symbols);
+
+            let mut vars: HashMap<Name, Var> = HashMap::new();
+            let mut to_lit = |expr: &Expr| {
+                let (inner, negated) = match expr {
+                    Expr::Not(_, inner) => (inner.as_ref(), true),
+                    _ => (expr, false),
+                };
+                let Expr::Atomic(_, Atom::Reference(reference)) = inner else {
+                    panic!("expected a literal, got {expr}");
+                };
+                let next = Var::new(vars.len() as u32);
+                vars.entry(reference.name().clone())
+                    .or_insert(next)
+                    .lit(negated)
+            };
+
+            let mut solver = CaDiCaL::default();
+            let inputs: Vec<Lit> = xs.iter().map(&mut to_lit).collect();
+            for clause in &clauses {
+                solver
+                    .add_clause(clause.iter().map(&mut to_lit).collect::<Clause>())
+                    .unwrap();
+            }
+            let enforce: Option<Vec<Lit>> =
+                enforce.map(|lits| lits.iter().map(&mut to_lit).collect());
+
+            for assignment in 0..1u32 << weights.len() {
+                let value = |i: usize| assignment & (1 << i) != 0;
+                let sum: i64 = (0..weights.len())
+                    .filter(|&i| value(i))
+                    .map(|i| weights[i])
+                    .sum();
+
+                let holds = enforce.as_ref().is_some_and(|enforce| {
+                    let mut assumptions: Vec<Lit> = (0..weights.len())
+                        .map(|i| if value(i) { inputs[i] } else { !inputs[i] })
+                        .collect();
+                    assumptions.extend(enforce);
+                    solver.solve_assumps(&assumptions).unwrap() == SolverResult::Sat
+                });
+
+                assert_eq!(
+                    holds,
+                    sum <= bound,
+                    "weights {weights:?}, bound {bound}, assignment {assignment:04b}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn totalizer_matches_brute_force() {
+        set_sat_cardinality_encoding(SatCardinalityEncoding::Totalizer);
+        check_against_brute_force(&[1, 1, 1, 1]);
+        check_against_brute_force(&[1, 1, -1, -1]);
+    }
+
+    #[test]
+    fn sequential_counter_matches_brute_force() {
+        set_sat_cardinality_encoding(SatCardinalityEncoding::SequentialCounter);
+        check_against_brute_force(&[1, 1, 1, 1]);
+        check_against_brute_force(&[1, 1, -1, -1]);
+    }
+
+    #[test]
+    fn generalized_totalizer_matches_brute_force() {
+        set_sat_pb_encoding(SatPbEncoding::Gte);
+        check_against_brute_force(&[1, 2, 3, 4]);
+        check_against_brute_force(&[3, -2, 2, -1]);
+    }
+
+    #[test]
+    fn dynamic_polynomial_watchdog_matches_brute_force() {
+        set_sat_pb_encoding(SatPbEncoding::Dpw);
+        check_against_brute_force(&[1, 2, 3, 4]);
+        check_against_brute_force(&[3, -2, 2, -1]);
+    }
+
+    #[test]
+    fn binary_adder_matches_brute_force() {
+        set_sat_pb_encoding(SatPbEncoding::Adder);
+        check_against_brute_force(&[1, 2, 3, 4]);
+        check_against_brute_force(&[3, -2, 2, -1]);
+    }
+}
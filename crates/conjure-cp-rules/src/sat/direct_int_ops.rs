@@ -1,3 +1,5 @@
+use std::collections::BTreeSet;
+
 use conjure_cp::ast::{Atom, Expression as Expr, Literal};
 use conjure_cp::ast::{SATIntEncoding, SymbolTable};
 use conjure_cp::rule_engine::ApplicationError;
@@ -9,7 +11,10 @@ use conjure_cp::ast::Metadata;
 use conjure_cp::ast::Moo;
 use conjure_cp::into_matrix_expr;
 
-use super::boolean::{tseytin_and, tseytin_iff, tseytin_not, tseytin_or, tseytin_xor};
+use super::boolean::{
+    create_bool_aux, create_clause, tseytin_and, tseytin_iff, tseytin_not, tseytin_or, tseytin_xor,
+};
+use super::integer_repr::{safe_div, safe_mod, safe_pow};
 
 use conjure_cp::ast::CnfClause;
 /// Converts an integer literal to SATInt form
@@ -291,13 +296,119 @@ fn neg_sat_direct(expr: &Expr, _: &SymbolTable) -> ApplicationResult {
     )))
 }
 
-fn floor_div(a: i32, b: i32) -> i32 {
-    let (q, r) = (a / b, a % b);
-    if (r > 0 && b < 0) || (r < 0 && b > 0) {
-        q - 1
-    } else {
-        q
+/// Encodes `op(lhs, rhs)` for two direct SATInts as a new direct SATInt using the "lookup table"
+/// method: for each pair of values `i` and `j` of the operands, `lhs = i AND rhs = j` implies
+/// that the output is `op(i, j)`.
+///
+/// Only the values the output can take are given a variable. Not applicable if `op` overflows.
+fn sat_direct_lookup_table(
+    lhs: &Expr,
+    rhs: &Expr,
+    op: impl Fn(i32, i32) -> Option<i32>,
+    clauses: &mut Vec<CnfClause>,
+    symbols: &mut SymbolTable,
+) -> Result<Expr, ApplicationError> {
+    let (binding, lhs_min, lhs_max) = validate_direct_int_operands(vec![lhs.clone()])?;
+    let [lhs_bits] = binding.as_slice() else {
+        return Err(RuleNotApplicable);
+    };
+
+    let (binding, rhs_min, rhs_max) = validate_direct_int_operands(vec![rhs.clone()])?;
+    let [rhs_bits] = binding.as_slice() else {
+        return Err(RuleNotApplicable);
+    };
+
+    let mut table = Vec::new();
+    for i in lhs_min..=lhs_max {
+        for j in rhs_min..=rhs_max {
+            let k = op(i, j).ok_or(RuleNotApplicable)?;
+            table.push((
+                &lhs_bits[(i - lhs_min) as usize],
+                &rhs_bits[(j - rhs_min) as usize],
+                k,
+            ));
+        }
     }
+
+    let out_min = table
+        .iter()
+        .map(|(_, _, k)| *k)
+        .min()
+        .ok_or(RuleNotApplicable)?;
+    let out_max = table
+        .iter()
+        .map(|(_, _, k)| *k)
+        .max()
+        .ok_or(RuleNotApplicable)?;
+
+    // generate boolean variables for all possible outputs, in order of value
+    let reachable: BTreeSet<i32> = table.iter().map(|(_, _, k)| *k).collect();
+    let out_bits: Vec<Option<Expr>> = (out_min..=out_max)
+        .map(|k| reachable.contains(&k).then(|| create_bool_aux(symbols)))
+        .collect();
+
+    // generate the lookup table clauses: (lhs_i AND rhs_j) => out_k
+    for (lhs_bit, rhs_bit, k) in table {
+        let out_bit = out_bits[(k - out_min) as usize].clone().unwrap();
+        clauses.extend(create_clause(vec![
+            Expr::Not(Metadata::new(), Moo::new(lhs_bit.clone())),
+            Expr::Not(Metadata::new(), Moo::new(rhs_bit.clone())),
+            out_bit,
+        ]));
+    }
+
+    // the output cannot take more than one value simultaneously.
+    let vars: Vec<&Expr> = out_bits.iter().flatten().collect();
+    for a in 0..vars.len() {
+        for b in (a + 1)..vars.len() {
+            clauses.push(CnfClause::new(vec![
+                Expr::Not(Metadata::new(), Moo::new(vars[a].clone())),
+                Expr::Not(Metadata::new(), Moo::new(vars[b].clone())),
+            ]));
+        }
+    }
+
+    let out_bits = out_bits
+        .into_iter()
+        .map(|bit| {
+            bit.unwrap_or_else(|| {
+                Expr::Atomic(Metadata::new(), Atom::Literal(Literal::Bool(false)))
+            })
+        })
+        .collect();
+
+    Ok(Expr::SATInt(
+        Metadata::new(),
+        SATIntEncoding::Direct,
+        Moo::new(into_matrix_expr!(out_bits)),
+        (out_min, out_max),
+    ))
+}
+
+/// Combines the operands of a direct SATInt n-ary operator, such as `Product` or `Min`, using
+/// [`sat_direct_lookup_table`] on each pair in turn.
+fn sat_direct_fold(
+    operands: &Expr,
+    op: impl Fn(i32, i32) -> Option<i32> + Copy,
+    symbols: &SymbolTable,
+) -> ApplicationResult {
+    let Some(exprs) = operands.clone().unwrap_list() else {
+        return Err(RuleNotApplicable);
+    };
+
+    // Check the operands are direct SATInts before encoding anything
+    validate_direct_int_operands(exprs.clone())?;
+
+    let mut new_symbols = symbols.clone();
+    let mut new_clauses = vec![];
+
+    let mut exprs = exprs.into_iter();
+    let first = exprs.next().ok_or(RuleNotApplicable)?;
+    let result = exprs.try_fold(first, |acc, expr| {
+        sat_direct_lookup_table(&acc, &expr, op, &mut new_clauses, &mut new_symbols)
+    })?;
+
+    Ok(Reduction::cnf(result, new_clauses, new_symbols))
 }
 
 /// Converts a / expression between two direct SATInts to a new direct SATInt
@@ -309,90 +420,125 @@ fn floor_div(a: i32, b: i32) -> i32 {
 /// ```
 #[register_rule("SAT_Direct", 9100, [SafeDiv])]
 fn safediv_sat_direct(expr: &Expr, symbols: &SymbolTable) -> ApplicationResult {
-    let Expr::SafeDiv(_, numer_expr, denom_expr) = expr else {
+    let Expr::SafeDiv(_, numer, denom) = expr else {
         return Err(RuleNotApplicable);
     };
 
-    let Expr::SATInt(_, SATIntEncoding::Direct, numer_inner, (numer_min, numer_max)) =
-        numer_expr.as_ref()
-    else {
-        return Err(RuleNotApplicable);
-    };
-    let Some(numer_bits) = numer_inner.as_ref().clone().unwrap_list() else {
-        return Err(RuleNotApplicable);
-    };
+    let mut new_symbols = symbols.clone();
+    let mut new_clauses = vec![];
 
-    let Expr::SATInt(_, SATIntEncoding::Direct, denom_inner, (denom_min, denom_max)) =
-        denom_expr.as_ref()
-    else {
-        return Err(RuleNotApplicable);
-    };
+    let quot_int =
+        sat_direct_lookup_table(numer, denom, safe_div, &mut new_clauses, &mut new_symbols)?;
 
-    let Some(denom_bits) = denom_inner.as_ref().clone().unwrap_list() else {
+    Ok(Reduction::cnf(quot_int, new_clauses, new_symbols))
+}
+
+/// Converts a % expression between two direct SATInts to a new direct SATInt
+/// using the "lookup table" method.
+///
+/// ```text
+/// SafeMod(SATInt(a), SATInt(b)) ~> SATInt(c)
+///
+/// ```
+#[register_rule("SAT_Direct", 9100, [SafeMod])]
+fn safemod_sat_direct(expr: &Expr, symbols: &SymbolTable) -> ApplicationResult {
+    let Expr::SafeMod(_, numer, denom) = expr else {
         return Err(RuleNotApplicable);
     };
 
-    let mut quot_min = i32::MAX;
-    let mut quot_max = i32::MIN;
+    let mut new_symbols = symbols.clone();
+    let mut new_clauses = vec![];
 
-    for i in *numer_min..=*numer_max {
-        for j in *denom_min..=*denom_max {
-            let k = if j == 0 { 0 } else { i / j };
-            quot_min = quot_min.min(k);
-            quot_max = quot_max.max(k);
-        }
-    }
+    let mod_int =
+        sat_direct_lookup_table(numer, denom, safe_mod, &mut new_clauses, &mut new_symbols)?;
 
-    let mut new_symbols = symbols.clone();
-    let mut quot_bits = Vec::new();
+    Ok(Reduction::cnf(mod_int, new_clauses, new_symbols))
+}
 
-    // generate boolean variables for all possible quotients
-    for _ in quot_min..=quot_max {
-        let decl = new_symbols.gen_find(&conjure_cp::ast::Domain::bool());
-        quot_bits.push(Expr::Atomic(
-            Metadata::new(),
-            Atom::Reference(conjure_cp::ast::Reference::new(decl)),
-        ));
-    }
+/// Converts a ** expression between two direct SATInts to a new direct SATInt
+/// using the "lookup table" method.
+///
+/// ```text
+/// SafePow(SATInt(a), SATInt(b)) ~> SATInt(c)
+///
+/// ```
+#[register_rule("SAT_Direct", 9100, [SafePow])]
+fn safepow_sat_direct(expr: &Expr, symbols: &SymbolTable) -> ApplicationResult {
+    let Expr::SafePow(_, base, exponent) = expr else {
+        return Err(RuleNotApplicable);
+    };
 
+    let mut new_symbols = symbols.clone();
     let mut new_clauses = vec![];
 
-    // generate the lookup table clauses: (n_i AND d_j) => q_k
-    for i in *numer_min..=*numer_max {
-        let numer_bit = &numer_bits[(i - numer_min) as usize];
-        for j in *denom_min..=*denom_max {
-            let denom_bit = &denom_bits[(j - denom_min) as usize];
+    let pow_int =
+        sat_direct_lookup_table(base, exponent, safe_pow, &mut new_clauses, &mut new_symbols)?;
 
-            let k = if j == 0 { 0 } else { floor_div(i, j) };
+    Ok(Reduction::cnf(pow_int, new_clauses, new_symbols))
+}
 
-            let quot_bit = &quot_bits[(k - quot_min) as usize];
+/// Converts a product of direct SATInts to a new direct SATInt, multiplying pairs of operands
+/// using the "lookup table" method.
+///
+/// ```text
+/// Product(SATInt(a), SATInt(b), ...) ~> SATInt(c)
+///
+/// ```
+#[register_rule("SAT_Direct", 9100, [Product])]
+fn product_sat_direct(expr: &Expr, symbols: &SymbolTable) -> ApplicationResult {
+    let Expr::Product(_, exprs) = expr else {
+        return Err(RuleNotApplicable);
+    };
 
-            new_clauses.push(CnfClause::new(vec![
-                Expr::Not(Metadata::new(), Moo::new(numer_bit.clone())),
-                Expr::Not(Metadata::new(), Moo::new(denom_bit.clone())),
-                quot_bit.clone(),
-            ]));
-        }
+    // The empty product is 1
+    if exprs
+        .as_ref()
+        .clone()
+        .unwrap_list()
+        .is_some_and(|v| v.is_empty())
+    {
+        return Ok(Reduction::pure(Expr::SATInt(
+            Metadata::new(),
+            SATIntEncoding::Direct,
+            Moo::new(into_matrix_expr!(vec![Expr::Atomic(
+                Metadata::new(),
+                Atom::Literal(Literal::Bool(true)),
+            )])),
+            (1, 1),
+        )));
     }
 
-    // the quotient cannot take more than one value simultaneously.
-    for a in 0..quot_bits.len() {
-        for b in (a + 1)..quot_bits.len() {
-            new_clauses.push(CnfClause::new(vec![
-                Expr::Not(Metadata::new(), Moo::new(quot_bits[a].clone())),
-                Expr::Not(Metadata::new(), Moo::new(quot_bits[b].clone())),
-            ]));
-        }
-    }
+    sat_direct_fold(exprs, i32::checked_mul, symbols)
+}
 
-    let quot_int = Expr::SATInt(
-        Metadata::new(),
-        SATIntEncoding::Direct,
-        Moo::new(into_matrix_expr!(quot_bits)),
-        (quot_min, quot_max),
-    );
+/// Converts a min of direct SATInts to a new direct SATInt
+///
+/// ```text
+/// Min(SATInt(a), SATInt(b), ...) ~> SATInt(c)
+///
+/// ```
+#[register_rule("SAT_Direct", 9100, [Min])]
+fn min_sat_direct(expr: &Expr, symbols: &SymbolTable) -> ApplicationResult {
+    let Expr::Min(_, exprs) = expr else {
+        return Err(RuleNotApplicable);
+    };
 
-    Ok(Reduction::cnf(quot_int, new_clauses, new_symbols))
+    sat_direct_fold(exprs, |a, b| Some(a.min(b)), symbols)
+}
+
+/// Converts a max of direct SATInts to a new direct SATInt
+///
+/// ```text
+/// Max(SATInt(a), SATInt(b), ...) ~> SATInt(c)
+///
+/// ```
+#[register_rule("SAT_Direct", 9100, [Max])]
+fn max_sat_direct(expr: &Expr, symbols: &SymbolTable) -> ApplicationResult {
+    let Expr::Max(_, exprs) = expr else {
+        return Err(RuleNotApplicable);
+    };
+
+    sat_direct_fold(exprs, |a, b| Some(a.max(b)), symbols)
 }
 
 #[register_rule("SAT_Direct", 9100, [Sum])]
@@ -0,0 +1,488 @@
+//! SAT encodings of the `allDiff`, `table` and `negativeTable` global constraints, and of
+//! indexing a matrix with a decision variable (the element constraint).
+//!
+//! The constraints are written as clauses over the literals that say a SATInt takes a given
+//! value, so the same rules work for every integer encoding. At the top level of the model these
+//! clauses are added to the model directly; elsewhere, the constraint is reified using the Tseytin
+//! transformation.
+
+use conjure_cp::ast::{
+    AbstractLiteral, Atom, CnfClause, Expression as Expr, Literal, Metadata, Moo, SATIntEncoding,
+    SymbolTable,
+};
+use conjure_cp::into_matrix_expr;
+use conjure_cp::rule_engine::{
+    ApplicationError::RuleNotApplicable, ApplicationResult, Reduction, register_rule,
+};
+
+use crate::sat::boolean::{create_clause, negate, tseytin_and, tseytin_or};
+use crate::sat::direct_int_ops::validate_direct_int_operands;
+use crate::sat::integer_repr::validate_log_int_operands;
+use crate::sat::order_int_ops::{order_ge, validate_order_int_operands};
+
+/// Encodes an `allDiff`, `table` or `negativeTable` constraint over SATInts.
+///
+/// ```text
+/// allDiff([SATInt(a), SATInt(b), ...]) ~> __0
+/// table([SATInt(a), SATInt(b), ...], [[1, 2, ...], ...]) ~> __0
+/// negativeTable([SATInt(a), SATInt(b), ...], [[1, 2, ...], ...]) ~> __0
+///
+/// new variables:
+/// find __0: bool
+/// (and any auxiliary variables of the encoding)
+///
+/// new clauses:
+/// the clauses of the constraint, all of which hold iff __0 is true
+/// ```
+///
+/// See [`global_constraint_clauses`] for the clauses of each constraint.
+#[register_rule("SAT", 9100, [AllDiff, Table, NegativeTable])]
+fn cnf_global_constraint(expr: &Expr, symbols: &SymbolTable) -> ApplicationResult {
+    let mut new_clauses = vec![];
+    let mut new_symbols = symbols.clone();
+
+    let constraint = global_constraint_clauses(expr, &mut new_clauses, &mut new_symbols)
+        .ok_or(RuleNotApplicable)?;
+
+    let terms = constraint
+        .into_iter()
+        .map(|clause| tseytin_or(&clause, &mut new_clauses, &mut new_symbols))
+        .collect();
+    let new_expr = tseytin_and(&terms, &mut new_clauses, &mut new_symbols);
+
+    Ok(Reduction::cnf(new_expr, new_clauses, new_symbols))
+}
+
+/// Encodes `allDiff`, `table` and `negativeTable` constraints at the top level of the model.
+///
+/// These constraints must always hold, so their clauses are added to the model directly:
+///
+/// ```text
+/// allDiff([SATInt(a), SATInt(b), ...])
+/// ~~>
+/// true
+///
+/// new clauses:
+/// the clauses of the constraint
+/// ```
+#[register_rule("SAT", 9110, [Root])]
+fn cnf_global_constraint_top_level(expr: &Expr, symbols: &SymbolTable) -> ApplicationResult {
+    let Expr::Root(_, children) = expr else {
+        return Err(RuleNotApplicable);
+    };
+
+    let mut new_clauses = vec![];
+    let mut new_symbols = symbols.clone();
+    let mut changed = false;
+
+    let new_children = children
+        .iter()
+        .map(|child| {
+            let Some(constraint) =
+                global_constraint_clauses(child, &mut new_clauses, &mut new_symbols)
+            else {
+                return child.clone();
+            };
+            changed = true;
+
+            let mut holds = true;
+            for clause in constraint.into_iter().filter_map(create_clause) {
+                // an empty clause can never be satisfied
+                if clause.literals().is_empty() {
+                    holds = false;
+                }
+                new_clauses.push(clause);
+            }
+            Expr::Atomic(Metadata::new(), Atom::Literal(Literal::Bool(holds)))
+        })
+        .collect();
+
+    if !changed {
+        return Err(RuleNotApplicable);
+    }
+
+    Ok(Reduction::cnf(
+        Expr::Root(Metadata::new(), new_children),
+        new_clauses,
+        new_symbols,
+    ))
+}
+
+/// Returns the clauses that hold iff the given `allDiff`, `table` or `negativeTable` constraint
+/// holds, or `None` if the constraint is not over SATInts.
+///
+/// Any auxiliary variables needed are added to `symbols`, with the clauses defining them added to
+/// `clauses`.
+///
+/// - `allDiff`: for each value, at most one of the integers takes it. This is only used for
+///   direct and order integers; an `allDiff` of log integers is split into pairwise `!=`
+///   constraints instead, as their domains may be large.
+/// - `table`: a new variable `r` for each row, which is true iff the integers take the values in
+///   that row. At least one of these must be true. For each value `v` in each column `i`,
+///   `x_i = v` also implies that one of the rows with `v` in column `i` is true; these clauses are
+///   redundant, but help the solver propagate.
+/// - `negativeTable`: for each row, at least one of the integers does not take its value in that
+///   row.
+fn global_constraint_clauses(
+    expr: &Expr,
+    clauses: &mut Vec<CnfClause>,
+    symbols: &mut SymbolTable,
+) -> Option<Vec<Vec<Expr>>> {
+    match expr {
+        Expr::AllDiff(_, ints) => all_diff_clauses(ints),
+        Expr::Table(_, ints, rows) => {
+            let rows = table_value_literals(ints, rows)?;
+            Some(table_clauses(rows, clauses, symbols))
+        }
+        Expr::NegativeTable(_, ints, rows) => {
+            let rows = table_value_literals(ints, rows)?;
+            Some(
+                rows.into_iter()
+                    .map(|row| row.iter().flatten().map(negate).collect())
+                    .collect(),
+            )
+        }
+        _ => None,
+    }
+}
+
+fn all_diff_clauses(ints: &Expr) -> Option<Vec<Vec<Expr>>> {
+    let ints = ints.unwrap_list()?;
+
+    let mut min = i32::MAX;
+    let mut max = i32::MIN;
+    for int in &ints {
+        let Expr::SATInt(
+            _,
+            SATIntEncoding::Direct | SATIntEncoding::Order,
+            _,
+            (local_min, local_max),
+        ) = int
+        else {
+            return None;
+        };
+        min = min.min(*local_min);
+        max = max.max(*local_max);
+    }
+
+    let mut constraint = vec![];
+    for value in min..=max {
+        let takes_value = ints
+            .iter()
+            .map(|int| sat_int_value_literals(int, value))
+            .collect::<Option<Vec<_>>>()?;
+
+        // at most one integer takes this value: NOT (a = value AND b = value) for each pair
+        for (i, a) in takes_value.iter().enumerate() {
+            for b in &takes_value[i + 1..] {
+                constraint.push(a.iter().chain(b).map(negate).collect());
+            }
+        }
+    }
+
+    Some(constraint)
+}
+
+fn table_clauses(
+    rows: Vec<Vec<Vec<Expr>>>,
+    clauses: &mut Vec<CnfClause>,
+    symbols: &mut SymbolTable,
+) -> Vec<Vec<Expr>> {
+    // skip rows that can never match, as one of the integers cannot take its value
+    let rows: Vec<_> = rows
+        .into_iter()
+        .filter(|row| !row.iter().flatten().any(|lit| is_bool_literal(lit, false)))
+        .collect();
+
+    // r <-> x_1 = row_1 AND x_2 = row_2 AND ...
+    let row_lits: Vec<Expr> = rows
+        .iter()
+        .map(|row| tseytin_and(&row.concat(), clauses, symbols))
+        .collect();
+
+    let mut constraint = vec![row_lits.clone()];
+
+    // x_i = v -> OR(rows with v in column i)
+    let columns = rows.first().map_or(0, Vec::len);
+    for column in 0..columns {
+        let mut supports: Vec<(&Vec<Expr>, Vec<Expr>)> = vec![];
+        for (row, row_lit) in rows.iter().zip(&row_lits) {
+            let value = &row[column];
+            match supports.iter_mut().find(|(other, _)| *other == value) {
+                Some((_, support)) => support.push(row_lit.clone()),
+                None => supports.push((value, vec![row_lit.clone()])),
+            }
+        }
+
+        for (value, support) in supports {
+            constraint.push(value.iter().map(negate).chain(support).collect());
+        }
+    }
+
+    constraint
+}
+
+/// For each row of a `table` or `negativeTable` constraint, returns the value literals (see
+/// [`sat_int_value_literals`]) for each integer taking its value in that row.
+fn table_value_literals(ints: &Expr, rows: &Expr) -> Option<Vec<Vec<Vec<Expr>>>> {
+    let ints = ints.unwrap_list()?;
+    if !ints.iter().all(|int| matches!(int, Expr::SATInt(..))) {
+        return None;
+    }
+
+    // the rows may have explicit index domains, but only their order matters here
+    let (rows, _) = resolve_value_letting(rows).unwrap_matrix_unchecked()?;
+    rows.into_iter()
+        .map(|row| {
+            let (row, _) = row.unwrap_matrix_unchecked()?;
+            if row.len() != ints.len() {
+                return None;
+            }
+
+            ints.iter()
+                .zip(&row)
+                .map(|(int, value)| sat_int_value_literals(int, int_constant(value)?))
+                .collect()
+        })
+        .collect()
+}
+
+/// Converts indexing a matrix of SATInts with a SATInt to a new SATInt.
+///
+/// Each bit of the output is true iff the index selects an element with that bit set:
+///
+/// ```text
+/// SafeIndex([SATInt(a), SATInt(b), ...], [SATInt(i)]) ~> SATInt([__0, __1, ...])
+///
+/// new clauses:
+/// __0 <-> ((i = 1 AND a_0) OR (i = 2 AND b_0) OR ...)
+/// __1 <-> ((i = 1 AND a_1) OR (i = 2 AND b_1) OR ...)
+/// ...
+/// ```
+///
+/// The elements are first given a common range (or number of bits for log integers), so that
+/// their bits line up.
+#[register_rule("SAT", 9100, [SafeIndex])]
+fn sat_int_element(expr: &Expr, symbols: &SymbolTable) -> ApplicationResult {
+    let Expr::SafeIndex(_, subject, indices) = expr else {
+        return Err(RuleNotApplicable);
+    };
+
+    let [index @ Expr::SATInt(..)] = indices.as_slice() else {
+        return Err(RuleNotApplicable);
+    };
+
+    let index_values: Vec<i32> = match subject.unwrap_list() {
+        Some(elems) => (1..=elems.len() as i32).collect(),
+        None => {
+            let (_, domain) = subject
+                .as_ref()
+                .clone()
+                .unwrap_matrix_unchecked()
+                .ok_or(RuleNotApplicable)?;
+            domain
+                .resolve()
+                .map_err(|_| RuleNotApplicable)?
+                .values()
+                .map_err(|_| RuleNotApplicable)?
+                .map(|value| match value {
+                    Literal::Int(x) => Ok(x),
+                    _ => Err(RuleNotApplicable),
+                })
+                .collect::<Result<_, _>>()?
+        }
+    };
+
+    let (elems, _) = subject
+        .as_ref()
+        .clone()
+        .unwrap_matrix_unchecked()
+        .ok_or(RuleNotApplicable)?;
+
+    if elems.is_empty() || elems.len() != index_values.len() {
+        return Err(RuleNotApplicable);
+    }
+
+    let Expr::SATInt(_, encoding, _, _) = &elems[0] else {
+        return Err(RuleNotApplicable);
+    };
+    let encoding = encoding.clone();
+
+    let (elem_bits, min, max) = match encoding {
+        SATIntEncoding::Direct => validate_direct_int_operands(elems)?,
+        SATIntEncoding::Order => validate_order_int_operands(elems)?,
+        SATIntEncoding::Log => {
+            let mut min = i32::MAX;
+            let mut max = i32::MIN;
+            for elem in &elems {
+                let Expr::SATInt(_, _, _, (local_min, local_max)) = elem else {
+                    return Err(RuleNotApplicable);
+                };
+                min = min.min(*local_min);
+                max = max.max(*local_max);
+            }
+            (validate_log_int_operands(elems, None)?, min, max)
+        }
+    };
+
+    let selects = index_values
+        .iter()
+        .map(|value| sat_int_value_literals(index, *value))
+        .collect::<Option<Vec<_>>>()
+        .ok_or(RuleNotApplicable)?;
+
+    let mut new_clauses = vec![];
+    let mut new_symbols = symbols.clone();
+
+    // index = i
+    let selects: Vec<Expr> = selects
+        .into_iter()
+        .map(|lits| match lits.as_slice() {
+            [lit] => lit.clone(),
+            _ => tseytin_and(&lits, &mut new_clauses, &mut new_symbols),
+        })
+        .collect();
+
+    let mut out_bits = vec![];
+    for bit in 0..elem_bits[0].len() {
+        // the first bit of an order integer is always true
+        if encoding == SATIntEncoding::Order && bit == 0 {
+            out_bits.push(Expr::Atomic(
+                Metadata::new(),
+                Atom::Literal(Literal::Bool(true)),
+            ));
+            continue;
+        }
+
+        let mut terms = vec![];
+        for (select, bits) in selects.iter().zip(&elem_bits) {
+            if is_bool_literal(select, false) || is_bool_literal(&bits[bit], false) {
+                continue;
+            }
+
+            if is_bool_literal(&bits[bit], true) {
+                terms.push(select.clone());
+            } else {
+                terms.push(tseytin_and(
+                    &vec![select.clone(), bits[bit].clone()],
+                    &mut new_clauses,
+                    &mut new_symbols,
+                ));
+            }
+        }
+
+        out_bits.push(match terms.as_slice() {
+            [] => Expr::Atomic(Metadata::new(), Atom::Literal(Literal::Bool(false))),
+            [term] => term.clone(),
+            _ => tseytin_or(&terms, &mut new_clauses, &mut new_symbols),
+        });
+    }
+
+    Ok(Reduction::cnf(
+        Expr::SATInt(
+            Metadata::new(),
+            encoding,
+            Moo::new(into_matrix_expr!(out_bits)),
+            (min, max),
+        ),
+        new_clauses,
+        new_symbols,
+    ))
+}
+
+/// Inlines a constant matrix indexed by a decision variable, so that its elements can be
+/// converted to SATInts.
+///
+/// ```text
+/// letting M be [3, 1, 2]
+/// M[SATInt(i)] ~> [3, 1, 2][SATInt(i)]
+/// ```
+#[register_rule("SAT", 9000, [SafeIndex])]
+fn inline_constant_matrix_subject_for_sat(expr: &Expr, _: &SymbolTable) -> ApplicationResult {
+    let Expr::SafeIndex(_, subject, indices) = expr else {
+        return Err(RuleNotApplicable);
+    };
+
+    if !matches!(subject.as_ref(), Expr::Atomic(..)) {
+        return Err(RuleNotApplicable);
+    }
+
+    // by now, the letting itself may have been rewritten into a matrix of SATInts
+    let (elems, domain) = resolve_value_letting(subject)
+        .unwrap_matrix_unchecked()
+        .ok_or(RuleNotApplicable)?;
+
+    Ok(Reduction::pure(Expr::SafeIndex(
+        Metadata::new(),
+        Moo::new(Expr::AbstractLiteral(
+            Metadata::new(),
+            AbstractLiteral::Matrix(elems, domain),
+        )),
+        indices.clone(),
+    )))
+}
+
+/// Returns literals whose conjunction holds iff the SATInt `int` takes the value `value`.
+///
+/// - Direct: the bit for `value`.
+/// - Order: `int >= value AND NOT (int >= value + 1)`.
+/// - Log: each bit, or its negation, following the bits of `value`.
+///
+/// If `value` is outside the range of `int`, this is `[false]`.
+fn sat_int_value_literals(int: &Expr, value: i32) -> Option<Vec<Expr>> {
+    let Expr::SATInt(_, encoding, inner, (min, max)) = int else {
+        return None;
+    };
+
+    if value < *min || value > *max {
+        return Some(vec![Expr::Atomic(
+            Metadata::new(),
+            Atom::Literal(Literal::Bool(false)),
+        )]);
+    }
+
+    let bits = inner.as_ref().clone().unwrap_list()?;
+    Some(match encoding {
+        SATIntEncoding::Direct => vec![bits[(value - min) as usize].clone()],
+        SATIntEncoding::Order => vec![
+            order_ge(&bits, *min, value),
+            negate(&order_ge(&bits, *min, value + 1)),
+        ],
+        SATIntEncoding::Log => bits
+            .iter()
+            .enumerate()
+            .map(|(i, bit)| {
+                if (value >> i.min(31)) & 1 == 1 {
+                    bit.clone()
+                } else {
+                    negate(bit)
+                }
+            })
+            .collect(),
+    })
+}
+
+/// Follows references to value lettings, returning the expression they stand for.
+fn resolve_value_letting(expr: &Expr) -> Expr {
+    let mut expr = expr.clone();
+    while let Expr::Atomic(_, Atom::Reference(reference)) = &expr {
+        let Some(value) = reference.resolve_expression() else {
+            break;
+        };
+        expr = value;
+    }
+    expr
+}
+
+/// Returns the value of an integer constant, which may already have been converted to a SATInt.
+fn int_constant(expr: &Expr) -> Option<i32> {
+    match expr {
+        Expr::Atomic(_, Atom::Literal(Literal::Int(x))) => Some(*x),
+        Expr::SATInt(_, _, _, (min, max)) if min == max => Some(*min),
+        _ => None,
+    }
+}
+
+fn is_bool_literal(expr: &Expr, value: bool) -> bool {
+    matches!(expr, Expr::Atomic(_, Atom::Literal(Literal::Bool(x))) if *x == value)
+}
@@ -377,3 +377,44 @@ pub fn match_bits_length(a: Vec<Expr>, b: Vec<Expr>) -> (Vec<Expr>, Vec<Expr>) {
         (a, b)
     }
 }
+
+/// Integer division rounding down, as in Essence.
+///
+/// Dividing by 0 gives 0: this only happens in the undefined cases of a `SafeDiv`, which are
+/// ruled out by the constraints that come with it. Returns `None` on overflow.
+pub fn safe_div(a: i32, b: i32) -> Option<i32> {
+    if b == 0 {
+        return Some(0);
+    }
+    let q = a.checked_div(b)?;
+    if a % b != 0 && (a < 0) != (b < 0) {
+        Some(q - 1)
+    } else {
+        Some(q)
+    }
+}
+
+/// The remainder of [`safe_div`], which takes the sign of the divisor, as in Essence.
+///
+/// Taking the remainder of a division by 0 gives 0. Returns `None` on overflow.
+pub fn safe_mod(a: i32, b: i32) -> Option<i32> {
+    if b == 0 {
+        return Some(0);
+    }
+    let r = a.checked_rem(b)?;
+    if r != 0 && (r < 0) != (b < 0) {
+        Some(r + b)
+    } else {
+        Some(r)
+    }
+}
+
+/// `a` to the power of `b`.
+///
+/// Negative exponents and `0 ** 0` give 0, as they are undefined. Returns `None` on overflow.
+pub fn safe_pow(a: i32, b: i32) -> Option<i32> {
+    if b < 0 || (a == 0 && b == 0) {
+        return Some(0);
+    }
+    a.checked_pow(b as u32)
+}
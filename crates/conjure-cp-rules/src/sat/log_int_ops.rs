@@ -14,7 +14,9 @@ use itertools::Itertools;
 use super::boolean::{
     tseytin_and, tseytin_iff, tseytin_imply, tseytin_mux, tseytin_not, tseytin_or, tseytin_xor,
 };
-use super::integer_repr::{bit_magnitude, match_bits_length, validate_log_int_operands};
+use super::integer_repr::{
+    bit_magnitude, match_bits_length, safe_div, safe_pow, validate_log_int_operands,
+};
 
 use conjure_cp::ast::CnfClause;
 
@@ -139,6 +141,39 @@ fn cnf_int_neq(expr: &Expr, symbols: &SymbolTable) -> ApplicationResult {
     Ok(Reduction::cnf(output, new_clauses, new_symbols))
 }
 
+/// Converts an allDiff of SATInts to a conjunction of != expressions between each pair
+///
+/// ```text
+/// allDiff([SATInt(a), SATInt(b), SATInt(c)])
+/// ~>
+/// and([SATInt(a) != SATInt(b), SATInt(a) != SATInt(c), SATInt(b) != SATInt(c)])
+///
+/// ```
+#[register_rule("SAT_Log", 4100, [AllDiff])]
+fn cnf_int_alldiff(expr: &Expr, _: &SymbolTable) -> ApplicationResult {
+    let Expr::AllDiff(_, exprs) = expr else {
+        return Err(RuleNotApplicable);
+    };
+
+    let Some(exprs) = exprs.as_ref().clone().unwrap_list() else {
+        return Err(RuleNotApplicable);
+    };
+
+    // Check operands are valid log ints
+    validate_log_int_operands(exprs.clone(), None)?;
+
+    let neqs = exprs
+        .iter()
+        .array_combinations()
+        .map(|[a, b]| Expr::Neq(Metadata::new(), Moo::new(a.clone()), Moo::new(b.clone())))
+        .collect_vec();
+
+    Ok(Reduction::pure(Expr::And(
+        Metadata::new(),
+        Moo::new(into_matrix_expr!(neqs)),
+    )))
+}
+
 // Creates a boolean expression for > or >=
 // a > b or a >= b
 // This can also be used for < and <= by reversing the order of the inputs
@@ -354,10 +389,20 @@ fn cnf_shift_add_multiply(
     x.extend(std::iter::repeat_n(x[bits - 1].clone(), bits));
     y.extend(std::iter::repeat_n(y[bits - 1].clone(), bits));
 
+    // the sign bit of x has a negative weight, so y is subtracted rather than added for it
+    let addend =
+        |y: &Vec<Expr>, i: usize, clauses: &mut Vec<CnfClause>, symbols: &mut SymbolTable| {
+            if i == bits - 1 {
+                tseytin_negate(y, bits * 2, clauses, symbols)
+            } else {
+                y.clone()
+            }
+        };
+
     let mut s: Vec<Expr> = vec![];
     let mut x_0andy_i;
 
-    for bit in &y {
+    for bit in &addend(&y, 0, clauses, symbols) {
         x_0andy_i = tseytin_and(&vec![x[0].clone(), bit.clone()], clauses, symbols);
         s.push(x_0andy_i);
     }
@@ -367,7 +412,7 @@ fn cnf_shift_add_multiply(
     let mut not_x_n;
     let mut if_false;
 
-    for item in x.iter().take(bits).skip(1) {
+    for (n, item) in x.iter().enumerate().take(bits).skip(1) {
         // y << 1
         for i in (1..bits * 2).rev() {
             y[i] = y[i - 1].clone();
@@ -375,8 +420,13 @@ fn cnf_shift_add_multiply(
         y[0] = false.into();
 
         // TODO switch to multiplexer
-        // TODO Add negatives support once MUX is added
-        sum = tseytin_int_adder(&s, &y, bits * 2, clauses, symbols);
+        sum = tseytin_int_adder(
+            &s,
+            &addend(&y, n, clauses, symbols),
+            bits * 2,
+            clauses,
+            symbols,
+        );
         not_x_n = tseytin_not(item.clone(), clauses, symbols);
 
         for i in 0..(bits * 2) {
@@ -510,10 +560,14 @@ fn cnf_int_neg(expr: &Expr, symbols: &SymbolTable) -> ApplicationResult {
         return Err(RuleNotApplicable);
     };
 
+    // an extra bit is needed to negate the smallest value, e.g. -(-8) = 8
+    let mut bits = bits.clone();
+    bits.push(bits[bits.len() - 1].clone());
+
     let mut new_clauses = vec![];
     let mut new_symbols = symbols.clone();
 
-    let result = tseytin_negate(bits, bits.len(), &mut new_clauses, &mut new_symbols);
+    let result = tseytin_negate(&bits, bits.len(), &mut new_clauses, &mut new_symbols);
 
     Ok(Reduction::cnf(
         Expr::SATInt(
@@ -757,15 +811,17 @@ fn cnf_int_abs(expr: &Expr, symbols: &SymbolTable) -> ApplicationResult {
         return Err(RuleNotApplicable);
     };
 
+    // an extra bit is needed for the absolute value of the smallest value, e.g. |-8| = 8
+    let mut bits = bits.clone();
+    bits.push(bits[bits.len() - 1].clone());
+
     let mut new_clauses = vec![];
     let mut new_symbols = symbols.clone();
 
     let mut result = vec![];
 
-    // How does this handle negatives edge cases: -(-8) = 8, an extra bit is needed
-
     // invert bits
-    for bit in bits {
+    for bit in &bits {
         result.push(tseytin_not(bit.clone(), &mut new_clauses, &mut new_symbols));
     }
 
@@ -796,6 +852,132 @@ fn cnf_int_abs(expr: &Expr, symbols: &SymbolTable) -> ApplicationResult {
     ))
 }
 
+/// Divides two log integers with the same number of bits, rounding the quotient down as in
+/// Essence, and returns the quotient and remainder. The remainder takes the sign of the
+/// denominator.
+///
+/// Both have one more bit than the operands, as the quotient of the smallest integer by -1 does
+/// not fit in the same number of bits. Both are 0 when the denominator is 0.
+fn tseytin_divmod(
+    numer: &[Expr],
+    denom: &[Expr],
+    clauses: &mut Vec<CnfClause>,
+    symbols: &mut SymbolTable,
+) -> (Vec<Expr>, Vec<Expr>) {
+    // Divides the absolute values using the "Restoring division" algorithm, then adjusts the
+    // signs of the results
+    // https://en.wikipedia.org/wiki/Division_algorithm#Restoring_division
+    let numer_sign = numer[numer.len() - 1].clone();
+    let denom_sign = denom[denom.len() - 1].clone();
+
+    // extend by a sign bit, so the absolute values of the operands fit
+    let mut numer = numer.to_owned();
+    numer.push(numer_sign.clone());
+    let mut denom = denom.to_owned();
+    denom.push(denom_sign.clone());
+
+    let bit_count = numer.len();
+
+    let minus_numer = tseytin_negate(&numer, bit_count, clauses, symbols);
+    let minus_denom = tseytin_negate(&denom, bit_count, clauses, symbols);
+
+    let abs_numer =
+        tseytin_select_array(numer_sign.clone(), &numer, &minus_numer, clauses, symbols);
+    let abs_denom =
+        tseytin_select_array(denom_sign.clone(), &denom, &minus_denom, clauses, symbols);
+
+    let mut quotient = vec![false.into(); bit_count];
+
+    let mut r = abs_numer;
+    r.extend(std::iter::repeat_n(false.into(), bit_count));
+    let mut d = std::iter::repeat_n(false.into(), bit_count).collect_vec();
+    d.extend(abs_denom.clone());
+
+    let minus_d = tseytin_negate(&d, 2 * bit_count, clauses, symbols);
+    let mut rminusd;
+
+    for i in (0..bit_count).rev() {
+        // r << 1
+        for j in (1..bit_count * 2).rev() {
+            r[j] = r[j - 1].clone();
+        }
+        r[0] = false.into();
+
+        rminusd = tseytin_int_adder(&r, &minus_d, 2 * bit_count, clauses, symbols);
+
+        quotient[i] = tseytin_not(
+            // q[i] = inverse of sign bit - 1 if positive, 0 if negative
+            rminusd[2 * bit_count - 1].clone(),
+            clauses,
+            symbols,
+        );
+
+        for j in 0..(2 * bit_count) {
+            r[j] = tseytin_mux(
+                quotient[i].clone(),
+                r[j].clone(),       // use r if negative
+                rminusd[j].clone(), // use r-d if positive
+                clauses,
+                symbols,
+            );
+        }
+    }
+
+    // the remainder of the absolute values is left in the upper half of r
+    let remainder = r.split_off(bit_count);
+
+    let signs_differ = tseytin_xor(numer_sign, denom_sign.clone(), clauses, symbols);
+    let remainder_nonzero = tseytin_or(&remainder, clauses, symbols);
+    let round_down = tseytin_and(
+        &vec![signs_differ.clone(), remainder_nonzero],
+        clauses,
+        symbols,
+    );
+
+    // quotient: q if the signs are the same, otherwise -q, or -q - 1 = ~q if rounded down
+    let minus_quotient = tseytin_negate(&quotient, bit_count, clauses, symbols);
+    let not_quotient = quotient
+        .iter()
+        .map(|bit| tseytin_not(bit.clone(), clauses, symbols))
+        .collect_vec();
+    let quotient = tseytin_select_array(signs_differ, &quotient, &minus_quotient, clauses, symbols);
+    let quotient = tseytin_select_array(
+        round_down.clone(),
+        &quotient,
+        &not_quotient,
+        clauses,
+        symbols,
+    );
+
+    // remainder: r, or |d| - r if rounded down, with the sign of the denominator
+    let minus_remainder = tseytin_negate(&remainder, bit_count, clauses, symbols);
+    let denom_minus_remainder =
+        tseytin_int_adder(&abs_denom, &minus_remainder, bit_count, clauses, symbols);
+    let remainder = tseytin_select_array(
+        round_down,
+        &remainder,
+        &denom_minus_remainder,
+        clauses,
+        symbols,
+    );
+    let minus_remainder = tseytin_negate(&remainder, bit_count, clauses, symbols);
+    let remainder =
+        tseytin_select_array(denom_sign, &remainder, &minus_remainder, clauses, symbols);
+
+    // both are 0 if the denominator is 0
+    let denom_nonzero = tseytin_or(&denom, clauses, symbols);
+    let quotient = quotient
+        .into_iter()
+        .map(|bit| tseytin_and(&vec![denom_nonzero.clone(), bit], clauses, symbols))
+        .collect();
+    let remainder = remainder
+        .into_iter()
+        .map(|bit| tseytin_and(&vec![denom_nonzero.clone(), bit], clauses, symbols))
+        .collect();
+
+    (quotient, remainder)
+}
+
 /// Converts SafeDiv of SATInts to a single SATInt
 ///
 /// ```text
@@ -804,8 +986,6 @@ fn cnf_int_abs(expr: &Expr, symbols: &SymbolTable) -> ApplicationResult {
 /// ```
 #[register_rule("SAT_Log", 4100, [SafeDiv])]
 fn cnf_int_safediv(expr: &Expr, symbols: &SymbolTable) -> ApplicationResult {
-    // Using "Restoring division" algorithm
-    // https://en.wikipedia.org/wiki/Division_algorithm#Restoring_division
     let Expr::SafeDiv(_, numer, denom) = expr else {
         return Err(RuleNotApplicable);
     };
@@ -818,12 +998,17 @@ fn cnf_int_safediv(expr: &Expr, symbols: &SymbolTable) -> ApplicationResult {
         return Err(RuleNotApplicable);
     };
 
-    let candidates = [
-        numer_min / denom_min,
-        numer_min / denom_max,
-        numer_max / denom_min,
-        numer_max / denom_max,
-    ];
+    // The quotient is monotonic in the numerator, and in the denominator on either side of 0, so
+    // its bounds are found at the bounds of the numerator and at the bounds and +-1 for the
+    // denominator. 0 is the value used for division by 0.
+    let mut candidates = vec![0];
+    for n in [*numer_min, *numer_max] {
+        for d in [*denom_min, -1, 1, *denom_max] {
+            if d != 0 && *denom_min <= d && d <= *denom_max {
+                candidates.push(safe_div(n, d).ok_or(RuleNotApplicable)?);
+            }
+        }
+    }
 
     let min = *candidates.iter().min().unwrap();
     let max = *candidates.iter().max().unwrap();
@@ -834,142 +1019,196 @@ fn cnf_int_safediv(expr: &Expr, symbols: &SymbolTable) -> ApplicationResult {
         return Err(RuleNotApplicable);
     };
 
-    let bit_count = numer_bits.len();
+    let mut new_symbols = symbols.clone();
+    let mut new_clauses = vec![];
+
+    let (quotient, _) = tseytin_divmod(numer_bits, denom_bits, &mut new_clauses, &mut new_symbols);
 
-    // TODO: Separate into division/mod function
+    Ok(Reduction::cnf(
+        Expr::SATInt(
+            Metadata::new(),
+            SATIntEncoding::Log,
+            Moo::new(into_matrix_expr!(quotient)),
+            (min, max),
+        ),
+        new_clauses,
+        new_symbols,
+    ))
+}
+
+/// Converts SafeMod of SATInts to a single SATInt
+///
+/// ```text
+/// SafeMod(SATInt(a), SATInt(b)) ~> SATInt(c)
+///
+/// ```
+#[register_rule("SAT_Log", 4100, [SafeMod])]
+fn cnf_int_safemod(expr: &Expr, symbols: &SymbolTable) -> ApplicationResult {
+    let Expr::SafeMod(_, numer, denom) = expr else {
+        return Err(RuleNotApplicable);
+    };
+
+    let Expr::SATInt(_, _, _, (denom_min, denom_max)) = denom.as_ref() else {
+        return Err(RuleNotApplicable);
+    };
+
+    // The remainder is strictly between 0 and the denominator, or 0
+    let min = cmp::min(0, denom_min + 1);
+    let max = cmp::max(0, denom_max - 1);
+
+    let binding =
+        validate_log_int_operands(vec![numer.as_ref().clone(), denom.as_ref().clone()], None)?;
+    let [numer_bits, denom_bits] = binding.as_slice() else {
+        return Err(RuleNotApplicable);
+    };
 
     let mut new_symbols = symbols.clone();
     let mut new_clauses = vec![];
-    let mut quotient = vec![false.into(); bit_count];
 
-    let minus_numer = tseytin_negate(
-        &numer_bits.clone(),
-        bit_count,
-        &mut new_clauses,
-        &mut new_symbols,
-    );
-    let minus_denom = tseytin_negate(
-        &denom_bits.clone(),
-        bit_count,
-        &mut new_clauses,
-        &mut new_symbols,
-    );
+    let (_, remainder) = tseytin_divmod(numer_bits, denom_bits, &mut new_clauses, &mut new_symbols);
 
-    let sign_bit = tseytin_xor(
-        numer_bits[bit_count - 1].clone(),
-        denom_bits[bit_count - 1].clone(),
-        &mut new_clauses,
-        &mut new_symbols,
-    );
+    Ok(Reduction::cnf(
+        Expr::SATInt(
+            Metadata::new(),
+            SATIntEncoding::Log,
+            Moo::new(into_matrix_expr!(remainder)),
+            (min, max),
+        ),
+        new_clauses,
+        new_symbols,
+    ))
+}
 
-    let numer_bits = tseytin_select_array(
-        numer_bits[bit_count - 1].clone(),
-        &numer_bits.clone(),
-        &minus_numer,
-        &mut new_clauses,
-        &mut new_symbols,
-    );
-    let denom_bits = tseytin_select_array(
-        denom_bits[bit_count - 1].clone(),
-        &denom_bits.clone(),
-        &minus_denom,
-        &mut new_clauses,
-        &mut new_symbols,
-    );
+/// Converts SafePow of SATInts to a single SATInt
+///
+/// ```text
+/// SafePow(SATInt(a), SATInt(b)) ~> SATInt(c)
+///
+/// ```
+#[register_rule("SAT_Log", 4100, [SafePow])]
+fn cnf_int_safepow(expr: &Expr, symbols: &SymbolTable) -> ApplicationResult {
+    // Using "Exponentiation by squaring": a ** b is the product of a ** (2 ** i) for each bit i
+    // set in b
+    let Expr::SafePow(_, base, exponent) = expr else {
+        return Err(RuleNotApplicable);
+    };
 
-    let mut r = numer_bits;
-    r.extend(std::iter::repeat_n(r[bit_count - 1].clone(), bit_count));
-    let mut d = std::iter::repeat_n(false.into(), bit_count).collect_vec();
-    d.extend(denom_bits);
+    let Expr::SATInt(_, _, _, (base_min, base_max)) = base.as_ref() else {
+        return Err(RuleNotApplicable);
+    };
 
-    let minus_d = tseytin_negate(
-        &d.clone(),
-        2 * bit_count,
-        &mut new_clauses,
-        &mut new_symbols,
-    );
-    let mut rminusd;
+    let Expr::SATInt(_, _, _, (exponent_min, exponent_max)) = exponent.as_ref() else {
+        return Err(RuleNotApplicable);
+    };
 
-    for i in (0..bit_count).rev() {
-        // r << 1
-        for j in (1..bit_count * 2).rev() {
-            r[j] = r[j - 1].clone();
+    // For a given exponent, the bounds of the power are found at the bounds of the base, or at
+    // the values closest to 0. 0 is the value used for undefined powers.
+    let mut candidates = vec![0];
+    for a in [*base_min, -1, 0, 1, *base_max] {
+        if *base_min <= a && a <= *base_max {
+            for b in cmp::max(0, *exponent_min)..=*exponent_max {
+                candidates.push(safe_pow(a, b).ok_or(RuleNotApplicable)?);
+            }
         }
-        r[0] = false.into();
+    }
+
+    let min = *candidates.iter().min().unwrap();
+    let max = *candidates.iter().max().unwrap();
+    let bit_count = cmp::max(bit_magnitude(min), bit_magnitude(max));
 
-        rminusd = tseytin_int_adder(
-            &r.clone(),
-            &minus_d.clone(),
-            2 * bit_count,
+    let binding = validate_log_int_operands(vec![base.as_ref().clone()], None)?;
+    let [base_bits] = binding.as_slice() else {
+        return Err(RuleNotApplicable);
+    };
+
+    let mut new_symbols = symbols.clone();
+    let mut new_clauses = vec![];
+
+    // The result only needs to be correct modulo 2 ** bit_count, as it fits in bit_count bits,
+    // so the base can be cropped
+    let mut power = validate_log_int_operands(vec![base.as_ref().clone()], Some(bit_count as u32))?
+        .pop()
+        .unwrap();
+
+    let mut one = vec![false.into(); bit_count];
+    one[0] = true.into();
+
+    // only the bits of the exponent up to its maximum can be set when it is defined
+    let exponent_bit_count = if *exponent_max > 0 {
+        32 - exponent_max.leading_zeros() as usize
+    } else {
+        0
+    };
+
+    let exponent_bits = validate_log_int_operands(
+        vec![exponent.as_ref().clone()],
+        Some(cmp::max(bit_magnitude(*exponent_min), exponent_bit_count + 1) as u32),
+    )?
+    .pop()
+    .unwrap();
+
+    let mut result = one.clone();
+    for (i, exponent_bit) in exponent_bits.iter().take(exponent_bit_count).enumerate() {
+        // result *= exponent[i] ? power : 1
+        let factor = tseytin_select_array(
+            exponent_bit.clone(),
+            &one,
+            &power,
             &mut new_clauses,
             &mut new_symbols,
         );
-
-        // TODO: For mod don't calculate on final iter
-        quotient[i] = tseytin_not(
-            // q[i] = inverse of sign bit - 1 if positive, 0 if negative
-            rminusd[2 * bit_count - 1].clone(),
+        result = cnf_shift_add_multiply(
+            &result,
+            &factor,
+            bit_count,
             &mut new_clauses,
             &mut new_symbols,
         );
+        result.truncate(bit_count);
 
-        // TODO: For div don't calculate on final iter
-        for j in 0..(2 * bit_count) {
-            r[j] = tseytin_mux(
-                quotient[i].clone(),
-                r[j].clone(),       // use r if negative
-                rminusd[j].clone(), // use r-d if positive
+        if i + 1 < exponent_bit_count {
+            power = cnf_shift_add_multiply(
+                &power,
+                &power,
+                bit_count,
                 &mut new_clauses,
                 &mut new_symbols,
             );
+            power.truncate(bit_count);
         }
     }
 
-    let minus_quotient = tseytin_negate(
-        &quotient.clone(),
-        bit_count,
+    // the power is 0 if the exponent is negative, or for 0 ** 0
+    let exponent_negative = exponent_bits[exponent_bits.len() - 1].clone();
+    let exponent_nonnegative = tseytin_not(exponent_negative, &mut new_clauses, &mut new_symbols);
+    let mut nonzero = base_bits.clone();
+    nonzero.extend(exponent_bits.iter().cloned());
+    let nonzero = tseytin_or(&nonzero, &mut new_clauses, &mut new_symbols);
+    let defined = tseytin_and(
+        &vec![exponent_nonnegative, nonzero],
         &mut new_clauses,
         &mut new_symbols,
     );
 
-    let out = tseytin_select_array(
-        sign_bit,
-        &quotient,
-        &minus_quotient,
-        &mut new_clauses,
-        &mut new_symbols,
-    );
+    let result = result
+        .into_iter()
+        .map(|bit| {
+            tseytin_and(
+                &vec![defined.clone(), bit],
+                &mut new_clauses,
+                &mut new_symbols,
+            )
+        })
+        .collect_vec();
 
     Ok(Reduction::cnf(
         Expr::SATInt(
             Metadata::new(),
             SATIntEncoding::Log,
-            Moo::new(into_matrix_expr!(out)),
+            Moo::new(into_matrix_expr!(result)),
             (min, max),
         ),
         new_clauses,
         new_symbols,
     ))
 }
-
-/*
-/// Converts SafeMod of SATInts to a single SATInt
-///
-/// ```text
-/// SafeMod(SATInt(a), SATInt(b)) ~> SATInt(c)
-///
-/// ```
-#[register_rule("SAT_Log", 4100, [SafeMod])]
-fn cnf_int_safemod(expr: &Expr, _: &SymbolTable) -> ApplicationResult {}
-
-/// Converts SafePow of SATInts to a single SATInt
-///
-/// ```text
-/// SafePow(SATInt(a), SATInt(b)) ~> SATInt(c)
-///
-/// ```
-#[register_rule("SAT", 4100, [SafePow])]
-fn cnf_int_safepow(expr: &Expr, _: &SymbolTable) -> ApplicationResult {
-    // use 'Exponentiation by squaring'
-}
-*/
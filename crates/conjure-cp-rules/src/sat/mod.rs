@@ -1,6 +1,8 @@
 mod boolean;
+mod cardinality;
 mod direct_int_ops;
 mod encoding_rules;
+mod global_constraints;
 mod integer_repr;
 mod log_int_ops;
 mod order_int_ops;
@@ -5,7 +5,11 @@ use conjure_cp::rule_engine::{
     ApplicationError::RuleNotApplicable, ApplicationResult, Reduction, register_rule,
 };
 
-use crate::sat::boolean::{tseytin_and, tseytin_iff, tseytin_not, tseytin_or};
+use crate::sat::boolean::{
+    create_bool_aux, create_clause, tseytin_and, tseytin_iff, tseytin_not, tseytin_or,
+};
+use crate::sat::integer_repr::{safe_div, safe_mod, safe_pow};
+use conjure_cp::ast::CnfClause;
 use conjure_cp::ast::Metadata;
 use conjure_cp::ast::Moo;
 use conjure_cp::into_matrix_expr;
@@ -282,3 +286,379 @@ fn neg_sat_order(expr: &Expr, symbols: &SymbolTable) -> ApplicationResult {
         new_symbols,
     ))
 }
+
+/// Returns the literal for `x >= value`, where `bits` and `min` are the bits and minimum value of
+/// an order SATInt `x`.
+pub fn order_ge(bits: &[Expr], min: i32, value: i32) -> Expr {
+    if value <= min {
+        Expr::Atomic(Metadata::new(), Atom::Literal(Literal::Bool(true)))
+    } else if (value - min) as usize >= bits.len() {
+        Expr::Atomic(Metadata::new(), Atom::Literal(Literal::Bool(false)))
+    } else {
+        bits[(value - min) as usize].clone()
+    }
+}
+
+/// Returns the negation of a literal, for use in clauses.
+fn not(expr: Expr) -> Expr {
+    Expr::Not(Metadata::new(), Moo::new(expr))
+}
+
+/// Creates the bits of a new order SATInt taking values from `min` to `max`, with the clauses
+/// that keep them ordered: `b_i -> b_{i-1}`.
+fn new_order_int_bits(
+    min: i32,
+    max: i32,
+    clauses: &mut Vec<CnfClause>,
+    symbols: &mut SymbolTable,
+) -> Vec<Expr> {
+    let mut bits = vec![Expr::Atomic(
+        Metadata::new(),
+        Atom::Literal(Literal::Bool(true)),
+    )];
+
+    for _ in min..max {
+        let bit = create_bool_aux(symbols);
+        clauses.extend(create_clause(vec![
+            not(bit.clone()),
+            bits.last().unwrap().clone(),
+        ]));
+        bits.push(bit);
+    }
+
+    bits
+}
+
+/// Returns the bits, minimum and maximum of an order SATInt.
+fn order_int_operand(expr: &Expr) -> Result<(Vec<Expr>, i32, i32), ApplicationError> {
+    let (mut binding, min, max) = validate_order_int_operands(vec![expr.clone()])?;
+    Ok((binding.pop().ok_or(RuleNotApplicable)?, min, max))
+}
+
+/// Adds two order SATInts.
+///
+/// For each pair of values `i` and `j` of the operands:
+/// `x >= i AND y >= j -> z >= i + j` and `x <= i AND y <= j -> z <= i + j`.
+fn sat_order_add(
+    lhs: &Expr,
+    rhs: &Expr,
+    clauses: &mut Vec<CnfClause>,
+    symbols: &mut SymbolTable,
+) -> Result<Expr, ApplicationError> {
+    let (lhs_bits, lhs_min, lhs_max) = order_int_operand(lhs)?;
+    let (rhs_bits, rhs_min, rhs_max) = order_int_operand(rhs)?;
+
+    let (min, max) = (lhs_min + rhs_min, lhs_max + rhs_max);
+    let out_bits = new_order_int_bits(min, max, clauses, symbols);
+
+    for i in lhs_min..=lhs_max {
+        for j in rhs_min..=rhs_max {
+            clauses.extend(create_clause(vec![
+                not(order_ge(&lhs_bits, lhs_min, i)),
+                not(order_ge(&rhs_bits, rhs_min, j)),
+                order_ge(&out_bits, min, i + j),
+            ]));
+            clauses.extend(create_clause(vec![
+                order_ge(&lhs_bits, lhs_min, i + 1),
+                order_ge(&rhs_bits, rhs_min, j + 1),
+                not(order_ge(&out_bits, min, i + j + 1)),
+            ]));
+        }
+    }
+
+    Ok(Expr::SATInt(
+        Metadata::new(),
+        SATIntEncoding::Order,
+        Moo::new(into_matrix_expr!(out_bits)),
+        (min, max),
+    ))
+}
+
+/// Encodes `op(lhs, rhs)` for two order SATInts as a new order SATInt using the "lookup table"
+/// method: for each pair of values `i` and `j` of the operands, `lhs = i AND rhs = j` implies
+/// that the output is `op(i, j)`.
+///
+/// Not applicable if `op` overflows.
+fn sat_order_lookup_table(
+    lhs: &Expr,
+    rhs: &Expr,
+    op: impl Fn(i32, i32) -> Option<i32>,
+    clauses: &mut Vec<CnfClause>,
+    symbols: &mut SymbolTable,
+) -> Result<Expr, ApplicationError> {
+    let (lhs_bits, lhs_min, lhs_max) = order_int_operand(lhs)?;
+    let (rhs_bits, rhs_min, rhs_max) = order_int_operand(rhs)?;
+
+    let mut table = Vec::new();
+    for i in lhs_min..=lhs_max {
+        for j in rhs_min..=rhs_max {
+            table.push((i, j, op(i, j).ok_or(RuleNotApplicable)?));
+        }
+    }
+
+    let min = table
+        .iter()
+        .map(|(_, _, k)| *k)
+        .min()
+        .ok_or(RuleNotApplicable)?;
+    let max = table
+        .iter()
+        .map(|(_, _, k)| *k)
+        .max()
+        .ok_or(RuleNotApplicable)?;
+    let out_bits = new_order_int_bits(min, max, clauses, symbols);
+
+    for (i, j, k) in table {
+        // lhs = i AND rhs = j
+        let condition = vec![
+            not(order_ge(&lhs_bits, lhs_min, i)),
+            order_ge(&lhs_bits, lhs_min, i + 1),
+            not(order_ge(&rhs_bits, rhs_min, j)),
+            order_ge(&rhs_bits, rhs_min, j + 1),
+        ];
+
+        // ... => out >= k
+        let mut lower = condition.clone();
+        lower.push(order_ge(&out_bits, min, k));
+        clauses.extend(create_clause(lower));
+
+        // ... => out <= k
+        let mut upper = condition;
+        upper.push(not(order_ge(&out_bits, min, k + 1)));
+        clauses.extend(create_clause(upper));
+    }
+
+    Ok(Expr::SATInt(
+        Metadata::new(),
+        SATIntEncoding::Order,
+        Moo::new(into_matrix_expr!(out_bits)),
+        (min, max),
+    ))
+}
+
+/// Converts a sum of order SATInts to a new order SATInt, adding pairs of operands in turn.
+///
+/// ```text
+/// Sum(SATInt(a), SATInt(b), ...) ~> SATInt(c)
+///
+/// ```
+#[register_rule("SAT_Order", 9100, [Sum])]
+fn sum_sat_order(expr: &Expr, symbols: &SymbolTable) -> ApplicationResult {
+    let Expr::Sum(_, exprs) = expr else {
+        return Err(RuleNotApplicable);
+    };
+
+    let Some(exprs) = exprs.as_ref().clone().unwrap_list() else {
+        return Err(RuleNotApplicable);
+    };
+
+    validate_order_int_operands(exprs.clone())?;
+
+    let mut new_symbols = symbols.clone();
+    let mut new_clauses = vec![];
+
+    let mut exprs = exprs.into_iter();
+    let Some(first) = exprs.next() else {
+        // The empty sum is 0
+        return Ok(Reduction::pure(Expr::SATInt(
+            Metadata::new(),
+            SATIntEncoding::Order,
+            Moo::new(into_matrix_expr!(vec![Expr::Atomic(
+                Metadata::new(),
+                Atom::Literal(Literal::Bool(true)),
+            )])),
+            (0, 0),
+        )));
+    };
+
+    let result = exprs.try_fold(first, |acc, expr| {
+        sat_order_add(&acc, &expr, &mut new_clauses, &mut new_symbols)
+    })?;
+
+    Ok(Reduction::cnf(result, new_clauses, new_symbols))
+}
+
+/// Converts a product of order SATInts to a new order SATInt, multiplying pairs of operands
+/// using the "lookup table" method.
+///
+/// ```text
+/// Product(SATInt(a), SATInt(b), ...) ~> SATInt(c)
+///
+/// ```
+#[register_rule("SAT_Order", 9100, [Product])]
+fn product_sat_order(expr: &Expr, symbols: &SymbolTable) -> ApplicationResult {
+    let Expr::Product(_, exprs) = expr else {
+        return Err(RuleNotApplicable);
+    };
+
+    let Some(exprs) = exprs.as_ref().clone().unwrap_list() else {
+        return Err(RuleNotApplicable);
+    };
+
+    validate_order_int_operands(exprs.clone())?;
+
+    let mut new_symbols = symbols.clone();
+    let mut new_clauses = vec![];
+
+    let mut exprs = exprs.into_iter();
+    let Some(first) = exprs.next() else {
+        // The empty product is 1
+        return Ok(Reduction::pure(Expr::SATInt(
+            Metadata::new(),
+            SATIntEncoding::Order,
+            Moo::new(into_matrix_expr!(vec![Expr::Atomic(
+                Metadata::new(),
+                Atom::Literal(Literal::Bool(true)),
+            )])),
+            (1, 1),
+        )));
+    };
+
+    let result = exprs.try_fold(first, |acc, expr| {
+        sat_order_lookup_table(
+            &acc,
+            &expr,
+            i32::checked_mul,
+            &mut new_clauses,
+            &mut new_symbols,
+        )
+    })?;
+
+    Ok(Reduction::cnf(result, new_clauses, new_symbols))
+}
+
+/// Converts a min or max of order SATInts to a new order SATInt.
+///
+/// Over a common range of values, `min(a, b, ...) >= i` iff `a >= i AND b >= i AND ...`, and
+/// `max(a, b, ...) >= i` iff `a >= i OR b >= i OR ...`.
+///
+/// ```text
+/// Min(SATInt(a), SATInt(b), ...) ~> SATInt(c)
+/// Max(SATInt(a), SATInt(b), ...) ~> SATInt(c)
+///
+/// ```
+#[register_rule("SAT_Order", 9100, [Min, Max])]
+fn min_max_sat_order(expr: &Expr, symbols: &SymbolTable) -> ApplicationResult {
+    let (exprs, is_min) = match expr {
+        Expr::Min(_, exprs) => (exprs, true),
+        Expr::Max(_, exprs) => (exprs, false),
+        _ => return Err(RuleNotApplicable),
+    };
+
+    let Some(exprs) = exprs.as_ref().clone().unwrap_list() else {
+        return Err(RuleNotApplicable);
+    };
+
+    if exprs.is_empty() {
+        return Err(RuleNotApplicable);
+    }
+
+    let (operands, min, max) = validate_order_int_operands(exprs)?;
+
+    let mut new_symbols = symbols.clone();
+    let mut new_clauses = vec![];
+
+    let out_bits = (0..operands[0].len())
+        .map(|i| {
+            let bits = operands.iter().map(|bits| bits[i].clone()).collect();
+            if is_min {
+                tseytin_and(&bits, &mut new_clauses, &mut new_symbols)
+            } else {
+                tseytin_or(&bits, &mut new_clauses, &mut new_symbols)
+            }
+        })
+        .collect();
+
+    Ok(Reduction::cnf(
+        Expr::SATInt(
+            Metadata::new(),
+            SATIntEncoding::Order,
+            Moo::new(into_matrix_expr!(out_bits)),
+            (min, max),
+        ),
+        new_clauses,
+        new_symbols,
+    ))
+}
+
+/// Converts the absolute value of an order SATInt to a new order SATInt.
+///
+/// `|x| >= i` iff `x >= i OR NOT (x >= 1 - i)`
+///
+/// ```text
+/// |SATInt(a)| ~> SATInt(b)
+///
+/// ```
+#[register_rule("SAT_Order", 9100, [Abs])]
+fn abs_sat_order(expr: &Expr, symbols: &SymbolTable) -> ApplicationResult {
+    let Expr::Abs(_, value) = expr else {
+        return Err(RuleNotApplicable);
+    };
+
+    let (bits, old_min, old_max) = order_int_operand(value)?;
+
+    let new_min = if old_min <= 0 && old_max >= 0 {
+        0
+    } else {
+        old_min.abs().min(old_max.abs())
+    };
+    let new_max = old_min.abs().max(old_max.abs());
+
+    let mut new_symbols = symbols.clone();
+    let mut new_clauses = vec![];
+
+    let mut out_bits = vec![Expr::Atomic(
+        Metadata::new(),
+        Atom::Literal(Literal::Bool(true)),
+    )];
+
+    for i in (new_min + 1)..=new_max {
+        let below = tseytin_not(
+            order_ge(&bits, old_min, 1 - i),
+            &mut new_clauses,
+            &mut new_symbols,
+        );
+        out_bits.push(tseytin_or(
+            &vec![order_ge(&bits, old_min, i), below],
+            &mut new_clauses,
+            &mut new_symbols,
+        ));
+    }
+
+    Ok(Reduction::cnf(
+        Expr::SATInt(
+            Metadata::new(),
+            SATIntEncoding::Order,
+            Moo::new(into_matrix_expr!(out_bits)),
+            (new_min, new_max),
+        ),
+        new_clauses,
+        new_symbols,
+    ))
+}
+
+/// Converts a /, % or ** expression between two order SATInts to a new order SATInt
+/// using the "lookup table" method.
+///
+/// ```text
+/// SafeDiv(SATInt(a), SATInt(b)) ~> SATInt(c)
+/// SafeMod(SATInt(a), SATInt(b)) ~> SATInt(c)
+/// SafePow(SATInt(a), SATInt(b)) ~> SATInt(c)
+///
+/// ```
+#[register_rule("SAT_Order", 9100, [SafeDiv, SafeMod, SafePow])]
+fn safe_binary_op_sat_order(expr: &Expr, symbols: &SymbolTable) -> ApplicationResult {
+    let (lhs, rhs, op): (_, _, fn(i32, i32) -> Option<i32>) = match expr {
+        Expr::SafeDiv(_, x, y) => (x, y, safe_div),
+        Expr::SafeMod(_, x, y) => (x, y, safe_mod),
+        Expr::SafePow(_, x, y) => (x, y, safe_pow),
+        _ => return Err(RuleNotApplicable),
+    };
+
+    let mut new_symbols = symbols.clone();
+    let mut new_clauses = vec![];
+
+    let result = sat_order_lookup_table(lhs, rhs, op, &mut new_clauses, &mut new_symbols)?;
+
+    Ok(Reduction::cnf(result, new_clauses, new_symbols))
+}
@@ -1,5 +1,8 @@
 use conjure_cp::{
-    ast::{Atom, Expression as Expr, GroundDomain, Metadata, Name, SymbolTable, serde::HasId},
+    ast::{
+        Atom, Expression as Expr, GroundDomain, Metadata, Name, Range, SetAttr, SymbolTable,
+        serde::HasId,
+    },
     bug,
     representation::Representation,
     rule_engine::{
@@ -49,7 +52,10 @@ fn select_representation_matrix(expr: &Expr, symbols: &SymbolTable) -> Applicati
         };
 
         // TODO: loosen these requirements once we are able to
-        if !matches!(valdom.as_ref(), GroundDomain::Bool | GroundDomain::Int(_)) {
+        if !matches!(
+            valdom.as_ref(),
+            GroundDomain::Bool | GroundDomain::Int(_) | GroundDomain::Enum(_, _)
+        ) {
             return None;
         }
 
@@ -109,6 +115,82 @@ fn select_representation_matrix(expr: &Expr, symbols: &SymbolTable) -> Applicati
     }
 }
 
+// special case rule to select representations for sets, msets, functions, sequences, relations,
+// and partitions in one go.
+//
+// like matrices, these need representing even when they are not referenced, as the solvers cannot
+// take them directly. this rule adds a representation for all such variables in the model, and
+// adds their structural constraints to the top level.
+#[register_rule("Representations", 8001, [Root])]
+fn select_representation_abstract(expr: &Expr, symbols: &SymbolTable) -> ApplicationResult {
+    let Expr::Root(_, _) = expr else {
+        return Err(RuleNotApplicable);
+    };
+
+    // cannot create representations on non-local variables, so use lookup_local.
+    let abstract_vars = symbols
+        .clone()
+        .into_iter_local()
+        .filter_map(|(n, decl)| {
+            let var = decl.as_find()?.clone();
+            let resolved_domain = var.domain.resolve().ok()?;
+
+            let repr_name = abstract_representation_name(resolved_domain.as_ref())?;
+
+            Some((n, repr_name))
+        })
+        .collect_vec();
+
+    let mut symbols = symbols.clone();
+    let mut new_constraints = vec![];
+    let mut has_new_representation = false;
+    for (name, repr_name) in &abstract_vars {
+        if symbols
+            .representations_for(name)
+            .is_none_or(|reprs| !reprs.is_empty())
+        {
+            continue;
+        }
+
+        // (creates the represented variables as a side effect)
+        let representation = symbols
+            .get_or_add_representation(name, &[repr_name])
+            .ok_or(RuleNotApplicable)?;
+        has_new_representation = true;
+
+        for repr in representation {
+            new_constraints.extend(repr.structural_constraints(&symbols)?);
+        }
+    }
+
+    let mut expr = expr.clone();
+    let has_changed = Arc::new(AtomicBool::new(has_new_representation));
+    for (name, repr_name) in abstract_vars {
+        let new_name = Name::WithRepresentation(Box::new(name.clone()), vec![repr_name.into()]);
+
+        // give all references to this variable this representation
+        let has_changed_ptr = Arc::clone(&has_changed);
+        expr = expr.transform_bi(&move |n: Name| {
+            if n == name {
+                has_changed_ptr.store(true, Ordering::SeqCst);
+                new_name.clone()
+            } else {
+                n
+            }
+        });
+    }
+
+    if let Expr::Root(_, constraints) = &mut expr {
+        constraints.extend(new_constraints);
+    }
+
+    if has_changed.load(Ordering::Relaxed) {
+        Ok(Reduction::with_symbols(expr, symbols))
+    } else {
+        Err(RuleNotApplicable)
+    }
+}
+
 #[register_rule("Representations", 8000, [Atomic])]
 fn select_representation(expr: &Expr, symbols: &SymbolTable) -> ApplicationResult {
     // thing we are representing must be a reference
@@ -129,9 +211,24 @@ fn select_representation(expr: &Expr, symbols: &SymbolTable) -> ApplicationResul
     }
 
     let mut symbols = symbols.clone();
+    let is_new = symbols
+        .representations_for(&name)
+        .is_none_or(|reprs| reprs.is_empty());
+
     let representation =
         get_or_create_representation(&name, &mut symbols).ok_or(RuleNotApplicable)?;
 
+    // constrain the representation variables when the representation is first created
+    let new_top = if is_new {
+        representation
+            .iter()
+            .map(|repr| repr.structural_constraints(&symbols))
+            .flatten_ok()
+            .collect::<Result<Vec<_>, _>>()?
+    } else {
+        vec![]
+    };
+
     let representation_names = representation
         .into_iter()
         .map(|x| x.repr_name().into())
@@ -153,11 +250,12 @@ fn select_representation(expr: &Expr, symbols: &SymbolTable) -> ApplicationResul
     let mut decl_ptr = decl.clone().into_ptr().detach();
     decl_ptr.replace_name(new_name);
 
-    Ok(Reduction::with_symbols(
+    Ok(Reduction::new(
         Expr::Atomic(
             Metadata::new(),
             Atom::Reference(conjure_cp::ast::Reference::new(decl_ptr)),
         ),
+        new_top,
         symbols,
     ))
 }
@@ -190,6 +288,7 @@ fn domain_needs_representation(domain: &GroundDomain) -> bool {
         | GroundDomain::Partition(_, _)
         | GroundDomain::Variant(_) => true,
         GroundDomain::Relation(_, _) => true,
+        GroundDomain::Enum(_, _) => true,
         GroundDomain::Empty(_) => false,
     }
 }
@@ -207,7 +306,14 @@ fn get_or_create_representation(
 
     let dom = symbols.resolve_domain(name)?;
     match dom.as_ref() {
-        GroundDomain::Set(_, _) => None, // has no representations yet!
+        GroundDomain::Set(_, _)
+        | GroundDomain::MSet(_, _)
+        | GroundDomain::Function(_, _, _)
+        | GroundDomain::Sequence(_, _)
+        | GroundDomain::Relation(_, _)
+        | GroundDomain::Partition(_, _) => {
+            symbols.get_or_add_representation(name, &[abstract_representation_name(dom.as_ref())?])
+        }
         GroundDomain::Tuple(elem_domains) => {
             if elem_domains
                 .iter()
@@ -228,6 +334,75 @@ fn get_or_create_representation(
 
             symbols.get_or_add_representation(name, &["record_to_atom"])
         }
+        GroundDomain::Enum(_, _) => symbols.get_or_add_representation(name, &["enum_int"]),
         _ => unreachable!("non abstract domains should never need representations"),
     }
 }
+
+/// Returns the name of the representation to use for a variable of the set, multi-set, function,
+/// sequence, relation, or partition domain `domain`.
+///
+/// Returns None if `domain` is not one of these, or if it has an element domain that needs a
+/// representation itself.
+fn abstract_representation_name(domain: &GroundDomain) -> Option<&'static str> {
+    // TODO: loosen these requirements once we are able to
+    match domain {
+        GroundDomain::Set(attr, elem_domain)
+            if !domain_needs_representation(elem_domain.as_ref()) =>
+        {
+            Some(set_representation(attr, elem_domain))
+        }
+        GroundDomain::MSet(_, elem_domain)
+            if !domain_needs_representation(elem_domain.as_ref()) =>
+        {
+            Some("mset_occurrence")
+        }
+        GroundDomain::Function(_, dom, codom)
+            if !domain_needs_representation(dom.as_ref())
+                && !domain_needs_representation(codom.as_ref()) =>
+        {
+            Some("function_1d")
+        }
+        GroundDomain::Sequence(_, elem_domain)
+            if !domain_needs_representation(elem_domain.as_ref()) =>
+        {
+            Some("sequence_explicit_bounded")
+        }
+        GroundDomain::Relation(_, domains)
+            if !domains
+                .iter()
+                .any(|d| domain_needs_representation(d.as_ref())) =>
+        {
+            Some("relation_as_matrix")
+        }
+        GroundDomain::Partition(_, elem_domain)
+            if !domain_needs_representation(elem_domain.as_ref()) =>
+        {
+            Some("partition_as_set")
+        }
+        _ => None,
+    }
+}
+
+/// The largest element domain for which variable sized sets use the occurrence representation.
+const MAX_OCCURRENCE_ELEMENTS: u64 = 256;
+
+/// Chooses a representation for a set with the given attributes and element domain.
+///
+/// Fixed size sets are represented explicitly, as a sorted matrix of their elements. Other sets
+/// use the occurrence representation, unless their element domain is too large to have a boolean
+/// for each value.
+fn set_representation(attr: &SetAttr<i32>, elem_domain: &GroundDomain) -> &'static str {
+    if !matches!(elem_domain, GroundDomain::Int(_)) {
+        return "set_occurrence";
+    }
+
+    if let Range::Single(_) = attr.size {
+        return "set_explicit";
+    }
+
+    match elem_domain.length() {
+        Ok(n_elements) if n_elements <= MAX_OCCURRENCE_ELEMENTS => "set_occurrence",
+        _ => "set_explicit_var_size_with_marker",
+    }
+}
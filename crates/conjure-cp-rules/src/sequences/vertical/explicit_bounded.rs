@@ -0,0 +1,276 @@
+use conjure_cp::ast::{
+    AbstractLiteral, Atom, Domain, Expression as Expr, Literal, Metadata, Moo, SymbolTable,
+};
+use conjure_cp::into_matrix_expr;
+use conjure_cp::matrix_expr;
+use conjure_cp::rule_engine::{
+    ApplicationError, ApplicationError::RuleNotApplicable, ApplicationResult, Reduction,
+    register_rule,
+};
+use itertools::Itertools;
+
+use crate::representation::sequence_explicit_bounded::SequenceExplicitBounded;
+use crate::utils::represented_as;
+
+const REPR_NAME: &str = "sequence_explicit_bounded";
+
+/// Returns the length and elements of `expr`, if it is either a sequence with the
+/// `sequence_explicit_bounded` representation or a sequence literal.
+///
+/// For represented sequences, only the first `length` elements are in the sequence.
+fn length_and_elements(expr: &Expr, symbols: &SymbolTable) -> Option<(Expr, Vec<Expr>)> {
+    if let Some(repr) = represented_as::<SequenceExplicitBounded>(expr, REPR_NAME, symbols) {
+        return Some((repr.length(symbols).ok()?, repr.elements(symbols).ok()?));
+    }
+
+    let elements = match expr {
+        Expr::AbstractLiteral(_, AbstractLiteral::Sequence(elems)) => elems.clone(),
+        Expr::Atomic(
+            _,
+            Atom::Literal(Literal::AbstractLiteral(AbstractLiteral::Sequence(elems))),
+        ) => elems.iter().cloned().map(Expr::from).collect(),
+        _ => return None,
+    };
+
+    let length = i32::try_from(elements.len()).ok()?;
+    Some((Expr::from(length), elements))
+}
+
+fn eq(a: Expr, b: Expr) -> Expr {
+    Expr::Eq(Metadata::new(), Moo::new(a), Moo::new(b))
+}
+
+fn leq(a: Expr, b: Expr) -> Expr {
+    Expr::Leq(Metadata::new(), Moo::new(a), Moo::new(b))
+}
+
+fn and(conjuncts: Vec<Expr>) -> Expr {
+    Expr::And(Metadata::new(), Moo::new(into_matrix_expr![conjuncts]))
+}
+
+fn or(disjuncts: Vec<Expr>) -> Expr {
+    Expr::Or(Metadata::new(), Moo::new(into_matrix_expr![disjuncts]))
+}
+
+// s(i) ~~> s#sequence_explicit_bounded_values_i
+//
+// s(i) ~~> {y @ 1 <= i /\ i <= s#sequence_explicit_bounded_length} ;
+//          and([ i = k -> y = s#sequence_explicit_bounded_values_k | k in 1..maxSize ]),
+//          or([ i = k | k in 1..maxSize ] ++ [ y = min(elemDomain(s)) ])
+//
+//   where y is a new variable. The top level constraints fix y to the element at index i, or to a
+//   single value if i is out of bounds, so that y has exactly one assignment.
+#[register_rule("Base", 2000, [Image])]
+fn image_sequence_explicit_bounded(expr: &Expr, symbols: &SymbolTable) -> ApplicationResult {
+    let Expr::Image(_, sequence, i) = expr else {
+        return Err(RuleNotApplicable);
+    };
+
+    let repr: SequenceExplicitBounded =
+        represented_as(sequence, REPR_NAME, symbols).ok_or(RuleNotApplicable)?;
+
+    let length = repr.length(symbols)?;
+    let elements = repr.elements(symbols)?;
+
+    if let Some(Literal::Int(k)) = i.as_ref().clone().into_literal() {
+        let Some(element) = usize::try_from(k - 1).ok().and_then(|k| elements.get(k)) else {
+            // s(i) is undefined outside the bounds of the sequence
+            return Ok(Reduction::pure(Expr::Bubble(
+                Metadata::new(),
+                Moo::new(Expr::from(repr.fill_value().clone())),
+                Moo::new(Expr::from(false)),
+            )));
+        };
+
+        // every sequence has at least minSize elements
+        if k <= repr.min_size() {
+            return Ok(Reduction::pure(element.clone()));
+        }
+
+        return Ok(Reduction::pure(Expr::Bubble(
+            Metadata::new(),
+            Moo::new(element.clone()),
+            Moo::new(leq(Expr::from(k), length)),
+        )));
+    }
+
+    let domain = expr.domain_of().ok_or(ApplicationError::DomainError)?;
+    let mut symbols = symbols.clone();
+    let y = Expr::from(Atom::new_ref(symbols.gen_find(&domain)));
+
+    let i_is = |k: usize| eq(i.as_ref().clone(), Expr::from(k as i32 + 1));
+
+    let mut new_top = elements
+        .into_iter()
+        .enumerate()
+        .map(|(k, element)| {
+            Expr::Imply(
+                Metadata::new(),
+                Moo::new(i_is(k)),
+                Moo::new(eq(y.clone(), element)),
+            )
+        })
+        .collect_vec();
+
+    let mut in_bounds = (0..new_top.len()).map(i_is).collect_vec();
+    in_bounds.push(eq(y.clone(), Expr::from(repr.fill_value().clone())));
+    new_top.push(or(in_bounds));
+
+    let is_defined = and(vec![
+        leq(Expr::from(1), i.as_ref().clone()),
+        leq(i.as_ref().clone(), length),
+    ]);
+
+    Ok(Reduction::new(
+        Expr::Bubble(Metadata::new(), Moo::new(y), Moo::new(is_defined)),
+        new_top,
+        symbols,
+    ))
+}
+
+// |s| ~~> s#sequence_explicit_bounded_length
+#[register_rule("Base", 2000, [Card])]
+fn card_sequence_explicit_bounded(expr: &Expr, symbols: &SymbolTable) -> ApplicationResult {
+    let Expr::Card(_, sequence) = expr else {
+        return Err(RuleNotApplicable);
+    };
+
+    let repr: SequenceExplicitBounded =
+        represented_as(sequence, REPR_NAME, symbols).ok_or(RuleNotApplicable)?;
+
+    Ok(Reduction::pure(repr.length(symbols)?))
+}
+
+// s substring t ~~> or([ d_o | o in 0..maxSize(t) ])
+//
+//   where d_o is a new variable that is true iff s occurs in t at offset o, and s and t are each
+//   either a sequence literal or a sequence with this representation. These are fixed by the top
+//   level constraints:
+//
+//   d_o <-> (|s| + o <= |t| /\ and([ i <= |s| -> s(i) = t(o + i) | i in 1..maxSize(s) ]))
+//
+//   Using new variables for each offset avoids an exponential blowup when the disjunction of
+//   conjunctions is converted to CNF.
+#[register_rule("Base", 2000, [Substring])]
+fn substring_sequence_explicit_bounded(expr: &Expr, symbols: &SymbolTable) -> ApplicationResult {
+    let Expr::Substring(_, s, t) = expr else {
+        return Err(RuleNotApplicable);
+    };
+
+    if represented_as::<SequenceExplicitBounded>(s, REPR_NAME, symbols).is_none()
+        && represented_as::<SequenceExplicitBounded>(t, REPR_NAME, symbols).is_none()
+    {
+        return Err(RuleNotApplicable);
+    }
+
+    let (s_length, s_elements) = length_and_elements(s, symbols).ok_or(RuleNotApplicable)?;
+    let (t_length, t_elements) = length_and_elements(t, symbols).ok_or(RuleNotApplicable)?;
+
+    let mut symbols = symbols.clone();
+    let mut new_top = vec![];
+    let mut disjuncts = vec![];
+
+    for offset in 0..=t_elements.len() {
+        let fits = leq(
+            Expr::Sum(
+                Metadata::new(),
+                Moo::new(matrix_expr![s_length.clone(), Expr::from(offset as i32)]),
+            ),
+            t_length.clone(),
+        );
+
+        // as s fits in t at this offset, s has no elements past the end of t
+        let matches = s_elements
+            .iter()
+            .zip(t_elements.iter().skip(offset))
+            .enumerate()
+            .map(|(i, (a, b))| {
+                Expr::Imply(
+                    Metadata::new(),
+                    Moo::new(leq(Expr::from(i as i32 + 1), s_length.clone())),
+                    Moo::new(eq(a.clone(), b.clone())),
+                )
+            });
+
+        let d = Expr::from(Atom::new_ref(symbols.gen_find(&Domain::bool())));
+        new_top.push(Expr::Iff(
+            Metadata::new(),
+            Moo::new(d.clone()),
+            Moo::new(and(std::iter::once(fits).chain(matches).collect())),
+        ));
+        disjuncts.push(d);
+    }
+
+    Ok(Reduction::new(or(disjuncts), new_top, symbols))
+}
+
+// s subsequence t ~~> and([ (|s| = i /\ |t| = j) -> m_i_j | i in 0..maxSize(s), j in 0..maxSize(t) ])
+//
+//   where m_i_j is a new variable that is true iff the first i elements of s are a subsequence of
+//   the first j elements of t, and s and t are each either a sequence literal or a sequence with
+//   this representation. These are fixed by the top level constraints:
+//
+//   m_i_j <-> (m_i_(j-1) \/ (m_(i-1)_(j-1) /\ s(i) = t(j))),
+//
+//   where m_0_j is true and m_i_0 is false for i > 0.
+#[register_rule("Base", 2000, [Subsequence])]
+fn subsequence_sequence_explicit_bounded(expr: &Expr, symbols: &SymbolTable) -> ApplicationResult {
+    let Expr::Subsequence(_, s, t) = expr else {
+        return Err(RuleNotApplicable);
+    };
+
+    if represented_as::<SequenceExplicitBounded>(s, REPR_NAME, symbols).is_none()
+        && represented_as::<SequenceExplicitBounded>(t, REPR_NAME, symbols).is_none()
+    {
+        return Err(RuleNotApplicable);
+    }
+
+    let (s_length, s_elements) = length_and_elements(s, symbols).ok_or(RuleNotApplicable)?;
+    let (t_length, t_elements) = length_and_elements(t, symbols).ok_or(RuleNotApplicable)?;
+
+    let mut symbols = symbols.clone();
+    let mut new_top = vec![];
+
+    // is_subsequence[i][j] is m_i_j
+    let mut is_subsequence: Vec<Vec<Expr>> = vec![vec![Expr::from(true); t_elements.len() + 1]];
+    for a in &s_elements {
+        let mut row = vec![Expr::from(false)];
+        for (j, b) in t_elements.iter().enumerate() {
+            let m = Expr::from(Atom::new_ref(symbols.gen_find(&Domain::bool())));
+            let previous = is_subsequence.last().expect("there is at least one row");
+
+            new_top.push(Expr::Iff(
+                Metadata::new(),
+                Moo::new(m.clone()),
+                Moo::new(or(vec![
+                    row[j].clone(),
+                    and(vec![previous[j].clone(), eq(a.clone(), b.clone())]),
+                ])),
+            ));
+            row.push(m);
+        }
+        is_subsequence.push(row);
+    }
+
+    // exactly one pair of lengths holds, so this is equivalent to a disjunction over the pairs
+    let conjuncts = is_subsequence
+        .into_iter()
+        .enumerate()
+        .flat_map(|(i, row)| {
+            let s_length = s_length.clone();
+            let t_length = t_length.clone();
+            row.into_iter().enumerate().map(move |(j, m)| {
+                Expr::Imply(
+                    Metadata::new(),
+                    Moo::new(and(vec![
+                        eq(s_length.clone(), Expr::from(i as i32)),
+                        eq(t_length.clone(), Expr::from(j as i32)),
+                    ])),
+                    Moo::new(m),
+                )
+            })
+        })
+        .collect();
+
+    Ok(Reduction::new(and(conjuncts), new_top, symbols))
+}
@@ -0,0 +1 @@
+mod explicit_bounded;
@@ -1,7 +1,7 @@
 use conjure_cp::ast::Moo;
-// Equals rule for sets
+// Equals rule for sets and multi-sets
 use conjure_cp::ast::Metadata;
-use conjure_cp::ast::{Expression, ReturnType::Set, SymbolTable, Typeable};
+use conjure_cp::ast::{Expression, ReturnType::MSet, ReturnType::Set, SymbolTable, Typeable};
 use conjure_cp::matrix_expr;
 use conjure_cp::rule_engine::Reduction;
 use conjure_cp::rule_engine::{
@@ -14,8 +14,8 @@ use Expression::{And, Eq, SubsetEq};
 fn eq_to_subset_eq(expr: &Expression, _: &SymbolTable) -> ApplicationResult {
     match expr {
         Eq(_, a, b)
-            if matches!(a.as_ref().return_type(), Set(_))
-                && matches!(b.as_ref().return_type(), Set(_)) =>
+            if matches!(a.as_ref().return_type(), Set(_) | MSet(_))
+                && matches!(b.as_ref().return_type(), Set(_) | MSet(_)) =>
         {
             let expr1 = SubsetEq(Metadata::new(), a.clone(), b.clone());
             let expr2 = SubsetEq(Metadata::new(), b.clone(), a.clone());
@@ -1,4 +1,4 @@
-// Subset rule for sets
+// Subset rule for sets and multi-sets
 use conjure_cp::ast::Metadata;
 use conjure_cp::ast::{Expression as Expr, Moo, ReturnType, SymbolTable, Typeable};
 use conjure_cp::matrix_expr;
@@ -11,8 +11,13 @@ use conjure_cp::rule_engine::{
 fn subset_to_subset_eq_neq(expr: &Expr, _: &SymbolTable) -> ApplicationResult {
     match expr {
         Expr::Subset(_, a, b)
-            if matches!(a.as_ref().return_type(), ReturnType::Set(_))
-                && matches!(b.as_ref().return_type(), ReturnType::Set(_)) =>
+            if matches!(
+                a.as_ref().return_type(),
+                ReturnType::Set(_) | ReturnType::MSet(_)
+            ) && matches!(
+                b.as_ref().return_type(),
+                ReturnType::Set(_) | ReturnType::MSet(_)
+            ) =>
         {
             let expr1 = Expr::SubsetEq(Metadata::new(), a.clone(), b.clone());
             let expr2 = Expr::Neq(Metadata::new(), a.clone(), b.clone());
@@ -1,6 +1,6 @@
 use conjure_cp::{
     ast::{
-        Atom, Expression as Expr, Metadata, Moo, SymbolTable, SymbolTablePtr,
+        Atom, Expression as Expr, Metadata, Moo, ReturnType, SymbolTable, SymbolTablePtr, Typeable,
         ac_operators::ACOperatorKind, comprehension::ComprehensionBuilder,
     },
     bug,
@@ -10,10 +10,15 @@ use conjure_cp::{
 };
 
 // A subsetEq B ~~> and([ i in B | i <- A ])
+//
+// this does not hold for multi-sets, as it ignores how many times each element occurs.
 #[register_rule("Base", 8700, [SubsetEq])]
 fn subseteq_set(expr: &Expr, scope: &SymbolTable) -> ApplicationResult {
     match expr {
-        Expr::SubsetEq(_, a, b) => {
+        Expr::SubsetEq(_, a, b)
+            if !matches!(a.as_ref().return_type(), ReturnType::MSet(_))
+                && !matches!(b.as_ref().return_type(), ReturnType::MSet(_)) =>
+        {
             let scope_ptr = SymbolTablePtr::new();
             *scope_ptr.write() = scope.clone();
             let mut comp_builder = ComprehensionBuilder::new(scope_ptr);
@@ -1,4 +1,4 @@
-// Supset rule for sets
+// Supset rule for sets and multi-sets
 use conjure_cp::ast::Metadata;
 use conjure_cp::ast::{Expression as Expr, ReturnType, SymbolTable, Typeable};
 use conjure_cp::rule_engine::Reduction;
@@ -10,8 +10,13 @@ use conjure_cp::rule_engine::{
 fn supset_to_subset(expr: &Expr, _: &SymbolTable) -> ApplicationResult {
     match expr {
         Expr::Supset(_, a, b)
-            if matches!(a.as_ref().return_type(), ReturnType::Set(_))
-                && matches!(b.as_ref().return_type(), ReturnType::Set(_)) =>
+            if matches!(
+                a.as_ref().return_type(),
+                ReturnType::Set(_) | ReturnType::MSet(_)
+            ) && matches!(
+                b.as_ref().return_type(),
+                ReturnType::Set(_) | ReturnType::MSet(_)
+            ) =>
         {
             Ok(Reduction::pure(Expr::Subset(
                 Metadata::new(),
@@ -0,0 +1,44 @@
+use conjure_cp::ast::{Expression as Expr, Metadata, Moo, SymbolTable};
+use conjure_cp::into_matrix_expr;
+use conjure_cp::rule_engine::{
+    ApplicationError::RuleNotApplicable, ApplicationResult, Reduction, register_rule,
+};
+
+use crate::representation::set_explicit::SetExplicit;
+use crate::utils::represented_as;
+
+// x in S ~~> or([ x = S#set_explicit_i | i in 1..|S| ])
+#[register_rule("Base", 2000, [In])]
+fn in_set_explicit(expr: &Expr, symbols: &SymbolTable) -> ApplicationResult {
+    let Expr::In(_, x, set) = expr else {
+        return Err(RuleNotApplicable);
+    };
+
+    let repr: SetExplicit =
+        represented_as(set, "set_explicit", symbols).ok_or(RuleNotApplicable)?;
+
+    let disjuncts = repr
+        .elements(symbols)?
+        .into_iter()
+        .map(|element| Expr::Eq(Metadata::new(), x.clone(), Moo::new(element)))
+        .collect();
+
+    Ok(Reduction::pure(Expr::Or(
+        Metadata::new(),
+        Moo::new(into_matrix_expr!(disjuncts)),
+    )))
+}
+
+// |S| ~~> n, where S has size n
+#[register_rule("Base", 2000, [Card])]
+fn card_set_explicit(expr: &Expr, symbols: &SymbolTable) -> ApplicationResult {
+    let Expr::Card(_, set) = expr else {
+        return Err(RuleNotApplicable);
+    };
+
+    let repr: SetExplicit =
+        represented_as(set, "set_explicit", symbols).ok_or(RuleNotApplicable)?;
+
+    let size = i32::try_from(repr.size()).map_err(|_| RuleNotApplicable)?;
+    Ok(Reduction::pure(Expr::from(size)))
+}
@@ -0,0 +1,56 @@
+use conjure_cp::ast::{Expression as Expr, Metadata, Moo, SymbolTable};
+use conjure_cp::into_matrix_expr;
+use conjure_cp::matrix_expr;
+use conjure_cp::rule_engine::{
+    ApplicationError::RuleNotApplicable, ApplicationResult, Reduction, register_rule,
+};
+
+use crate::representation::set_explicit_var_size_with_marker::SetExplicitVarSizeWithMarker;
+use crate::utils::represented_as;
+
+const REPR_NAME: &str = "set_explicit_var_size_with_marker";
+
+// x in S ~~> or([ i <= S#marker /\ x = S#i | i in 1..maxSize ])
+#[register_rule("Base", 2000, [In])]
+fn in_set_explicit_var_size_with_marker(expr: &Expr, symbols: &SymbolTable) -> ApplicationResult {
+    let Expr::In(_, x, set) = expr else {
+        return Err(RuleNotApplicable);
+    };
+
+    let repr: SetExplicitVarSizeWithMarker =
+        represented_as(set, REPR_NAME, symbols).ok_or(RuleNotApplicable)?;
+
+    let marker = Moo::new(repr.marker(symbols)?);
+    let disjuncts = repr
+        .elements(symbols)?
+        .into_iter()
+        .zip(1..)
+        .map(|(element, i)| {
+            Expr::And(
+                Metadata::new(),
+                Moo::new(matrix_expr![
+                    Expr::Leq(Metadata::new(), Moo::new(Expr::from(i)), marker.clone()),
+                    Expr::Eq(Metadata::new(), x.clone(), Moo::new(element))
+                ]),
+            )
+        })
+        .collect();
+
+    Ok(Reduction::pure(Expr::Or(
+        Metadata::new(),
+        Moo::new(into_matrix_expr!(disjuncts)),
+    )))
+}
+
+// |S| ~~> S#marker
+#[register_rule("Base", 2000, [Card])]
+fn card_set_explicit_var_size_with_marker(expr: &Expr, symbols: &SymbolTable) -> ApplicationResult {
+    let Expr::Card(_, set) = expr else {
+        return Err(RuleNotApplicable);
+    };
+
+    let repr: SetExplicitVarSizeWithMarker =
+        represented_as(set, REPR_NAME, symbols).ok_or(RuleNotApplicable)?;
+
+    Ok(Reduction::pure(repr.marker(symbols)?))
+}
@@ -1 +1,4 @@
+mod explicit;
+mod explicit_var_size_with_marker;
 mod literal;
+mod occurrence;
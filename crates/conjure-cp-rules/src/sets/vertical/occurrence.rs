@@ -0,0 +1,65 @@
+use conjure_cp::ast::{Expression as Expr, Literal, Metadata, Moo, SymbolTable};
+use conjure_cp::into_matrix_expr;
+use conjure_cp::matrix_expr;
+use conjure_cp::rule_engine::{
+    ApplicationError::RuleNotApplicable, ApplicationResult, Reduction, register_rule,
+};
+
+use crate::representation::set_occurrence::SetOccurrence;
+use crate::utils::represented_as;
+
+// x in S ~~> S#set_occurrence_x
+// x in S ~~> or([ x = v /\ S#set_occurrence_v | v in elements of S ])
+#[register_rule("Base", 2000, [In])]
+fn in_set_occurrence(expr: &Expr, symbols: &SymbolTable) -> ApplicationResult {
+    let Expr::In(_, x, set) = expr else {
+        return Err(RuleNotApplicable);
+    };
+
+    let repr: SetOccurrence =
+        represented_as(set, "set_occurrence", symbols).ok_or(RuleNotApplicable)?;
+
+    if let Some(x) = x.as_ref().clone().into_literal() {
+        return Ok(Reduction::pure(
+            repr.occurrence(&x, symbols)
+                .unwrap_or(Expr::from(Literal::Bool(false))),
+        ));
+    }
+
+    let disjuncts = repr
+        .elements()
+        .iter()
+        .map(|element| {
+            let occurrence = repr.occurrence(element, symbols).ok_or(RuleNotApplicable)?;
+            Ok(Expr::And(
+                Metadata::new(),
+                Moo::new(matrix_expr![
+                    Expr::Eq(
+                        Metadata::new(),
+                        x.clone(),
+                        Moo::new(Expr::from(element.clone()))
+                    ),
+                    occurrence
+                ]),
+            ))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(Reduction::pure(Expr::Or(
+        Metadata::new(),
+        Moo::new(into_matrix_expr!(disjuncts)),
+    )))
+}
+
+// |S| ~~> sum([ toInt(S#set_occurrence_v) | v in elements of S ])
+#[register_rule("Base", 2000, [Card])]
+fn card_set_occurrence(expr: &Expr, symbols: &SymbolTable) -> ApplicationResult {
+    let Expr::Card(_, set) = expr else {
+        return Err(RuleNotApplicable);
+    };
+
+    let repr: SetOccurrence =
+        represented_as(set, "set_occurrence", symbols).ok_or(RuleNotApplicable)?;
+
+    Ok(Reduction::pure(repr.cardinality(symbols)?))
+}
@@ -6,6 +6,7 @@ use conjure_cp::ast::{
     categories::Category,
     comprehension::{Comprehension, ComprehensionQualifier},
 };
+use conjure_cp::representation::Representation;
 
 use tracing::{instrument, trace};
 use uniplate::{Biplate, Uniplate};
@@ -92,6 +93,28 @@ pub fn constant_record_names(expr: &Expr) -> Option<Vec<Name>> {
     }
 }
 
+/// If `expr` is a reference to a variable with the representation `repr_name`, returns that
+/// representation.
+pub fn represented_as<R: Representation>(
+    expr: &Expr,
+    repr_name: &str,
+    symbols: &SymbolTable,
+) -> Option<R> {
+    let Expr::Atomic(_, Atom::Reference(decl)) = expr else {
+        return None;
+    };
+
+    let Name::WithRepresentation(name, reprs) = &decl.name() as &Name else {
+        return None;
+    };
+
+    if reprs.first().is_none_or(|x| x.as_str() != repr_name) {
+        return None;
+    }
+
+    R::init(name, symbols)
+}
+
 /// True if the entire AST is constants.
 pub fn is_all_constant(expression: &Expr) -> bool {
     expression
@@ -0,0 +1,55 @@
+use std::collections::BTreeMap;
+
+use conjure_cp::ast::{GroundDomain, Literal, Name, Range, SATIntEncoding};
+use conjure_cp::solver::adaptors::rustsat::{DimacsVar, DimacsVarMap, RepresentedVar};
+#[allow(unused_imports)]
+#[allow(clippy::single_component_path_imports)] // ensure this is linked so we can lookup rules
+use conjure_cp_rules;
+use rustsat::types::Var;
+
+// x: int(-2..1) in log encoding, as bits 1 and 2 of the DIMACS file
+fn log_encoded_x() -> DimacsVarMap {
+    let bit = |index: u32| DimacsVar {
+        index: index + 1,
+        name: Name::Represented(Box::new((
+            Name::user("x"),
+            "sat_log_int".into(),
+            format!("{index:02}").into(),
+        ))),
+        encoding: Some(SATIntEncoding::Log),
+        position: Some(index as i32),
+    };
+
+    DimacsVarMap {
+        variables: vec![bit(0), bit(1)],
+        represented: vec![RepresentedVar {
+            name: Name::user("x"),
+            domain: GroundDomain::Int(vec![Range::Bounded(-2, 1)]),
+            representation: "sat_log_int".to_owned(),
+        }],
+    }
+}
+
+#[test]
+fn decodes_log_encoded_integers() {
+    let map = log_encoded_x();
+
+    for (bits, value) in [
+        ([false, false], 0),
+        ([true, false], 1),
+        ([false, true], -2),
+        ([true, true], -1),
+    ] {
+        let solution: Vec<_> = bits
+            .iter()
+            .enumerate()
+            .map(|(index, bit)| Var::new(index as u32).lit(!bit))
+            .collect();
+
+        assert_eq!(
+            map.decode(&solution).unwrap(),
+            BTreeMap::from([(Name::user("x"), Literal::Int(value))]),
+            "decoding the bits {bits:?}"
+        );
+    }
+}
@@ -0,0 +1,216 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use conjure_cp::ast::{
+    Atom, DeclarationPtr, Domain, Expression, Literal, Metadata, Model, Moo, Name, Range, Reference,
+};
+use conjure_cp::defaults::DEFAULT_RULE_SETS;
+use conjure_cp::parse::tree_sitter::parse_essence;
+use conjure_cp::representation::Representation;
+use conjure_cp::rule_engine::{resolve_rule_sets, rewrite_naive};
+use conjure_cp::settings::{
+    QuantifiedExpander, SatEncoding, SolverFamily, set_comprehension_expander,
+};
+use conjure_cp::solver::model_modifier::{ModelModifier, ModificationFailure};
+use conjure_cp::solver::{Solver, SolverAdaptor, adaptors};
+#[allow(unused_imports)]
+#[allow(clippy::single_component_path_imports)] // ensure this is linked so we can lookup rules
+use conjure_cp_rules;
+
+// the model `find x : int(1..5)`, rewritten for the given solver family, and the declaration of x.
+// the SAT encodings only represent integers that are used in a constraint, so one is added
+fn x_in_1_to_5(family: SolverFamily) -> (Model, DeclarationPtr) {
+    set_comprehension_expander(QuantifiedExpander::Native);
+    let (model, _) = parse_essence("find x : int(1..5)\nsuch that x >= 1").unwrap();
+    let x = model.symbols().lookup(&Name::user("x")).unwrap();
+
+    let mut rule_sets = DEFAULT_RULE_SETS.to_vec();
+    if let SolverFamily::Sat(encoding) = family {
+        rule_sets.push(encoding.as_rule_set());
+    }
+    let rule_sets = resolve_rule_sets(family, &rule_sets).unwrap();
+
+    // constraints added during search are rewritten using the rule sets in the context
+    model
+        .context
+        .write()
+        .unwrap()
+        .rule_sets
+        .clone_from(&rule_sets);
+    (rewrite_naive(&model, &rule_sets, false).unwrap(), x)
+}
+
+fn reference(decl: &DeclarationPtr) -> Moo<Expression> {
+    Moo::new(Expression::Atomic(
+        Metadata::new(),
+        Atom::Reference(Reference::new(decl.clone())),
+    ))
+}
+
+fn int(value: i32) -> Moo<Expression> {
+    Moo::new(Expression::Atomic(Metadata::new(), value.into()))
+}
+
+// the value of x in a solution, found through its representation if it has one
+fn value_of_x(
+    solution: HashMap<Name, Literal>,
+    representation: Option<&dyn Representation>,
+) -> i32 {
+    let value = match representation {
+        Some(representation) => representation
+            .value_up(&solution.into_iter().collect())
+            .unwrap(),
+        None => solution[&Name::user("x")].clone(),
+    };
+    match value {
+        Literal::Int(value) => value,
+        other => panic!("expected an integer value for x, got {other}"),
+    }
+}
+
+// solves the model with solve_mut, calling `modify` with each solution and recording the values
+// of x found, in order
+fn values_of_x<A, F>(adaptor: A, model: Model, modify: F) -> Vec<i32>
+where
+    A: SolverAdaptor,
+    F: Fn(i32, &dyn ModelModifier) -> Result<(), ModificationFailure> + Send + Sync + 'static,
+{
+    let representation = model
+        .symbols()
+        .representations_for(&Name::user("x"))
+        .and_then(|representations| representations.into_iter().next())
+        .and_then(|representations| representations.into_iter().next());
+    let values = Arc::new(Mutex::new(vec![]));
+    let values_2 = Arc::clone(&values);
+
+    Solver::new(adaptor)
+        .load_model(model)
+        .unwrap()
+        .solve_mut(Box::new(move |solution, modifier| {
+            let x = value_of_x(solution, representation.as_deref());
+            values_2.lock().unwrap().push(x);
+            assert!(
+                modify(x, modifier.as_ref()).is_ok(),
+                "modifying the model after x = {x} failed"
+            );
+            true
+        }))
+        .unwrap();
+
+    values.lock().unwrap().clone()
+}
+
+#[test]
+fn minion_added_constraint_restricts_later_solutions() {
+    let (model, x) = x_in_1_to_5(SolverFamily::Minion);
+
+    // x is assigned in increasing order, so the first solution is x = 1
+    let values = values_of_x(adaptors::Minion::new(), model, move |value, modifier| {
+        if value != 1 {
+            return Ok(());
+        }
+        modifier.add_constraint(Expression::Geq(Metadata::new(), reference(&x), int(4)))
+    });
+
+    assert_eq!(values, vec![1, 4, 5]);
+}
+
+#[test]
+fn minion_constraints_can_be_added_after_every_solution() {
+    let (model, x) = x_in_1_to_5(SolverFamily::Minion);
+
+    // x != value + 1, so every other value is skipped
+    let values = values_of_x(adaptors::Minion::new(), model, move |value, modifier| {
+        modifier.add_constraint(Expression::Neq(
+            Metadata::new(),
+            reference(&x),
+            int(value + 1),
+        ))
+    });
+
+    assert_eq!(values, vec![1, 3, 5]);
+}
+
+#[test]
+fn minion_added_variable_can_be_constrained() {
+    let (model, x) = x_in_1_to_5(SolverFamily::Minion);
+
+    let values = values_of_x(adaptors::Minion::new(), model, move |value, modifier| {
+        if value != 1 {
+            return Ok(());
+        }
+
+        // y in 4..5, x = y
+        let domain = Domain::int(vec![Range::Bounded(4, 5)]);
+        let y = DeclarationPtr::new_find(Name::user("y"), domain.clone());
+        modifier.add_variable(Name::user("y"), Moo::unwrap_or_clone(domain))?;
+        modifier.add_constraint(Expression::Eq(
+            Metadata::new(),
+            reference(&x),
+            reference(&y),
+        ))
+    });
+
+    assert_eq!(values, vec![1, 4, 5]);
+}
+
+#[test]
+fn minion_adding_an_existing_variable_fails() {
+    let (model, _) = x_in_1_to_5(SolverFamily::Minion);
+
+    let rejected = Arc::new(Mutex::new(false));
+    let rejected_2 = Arc::clone(&rejected);
+    Solver::new(adaptors::Minion::new())
+        .load_model(model)
+        .unwrap()
+        .solve_mut(Box::new(move |_, modifier| {
+            let domain = Moo::unwrap_or_clone(Domain::int(vec![Range::Bounded(1, 2)]));
+            let result = modifier.add_variable(Name::user("x"), domain);
+            *rejected_2.lock().unwrap() =
+                matches!(result, Err(ModificationFailure::ArgsInvalid(_)));
+            false
+        }))
+        .unwrap();
+
+    assert!(*rejected.lock().unwrap());
+}
+
+#[test]
+fn sat_added_constraint_restricts_later_solutions() {
+    let (model, x) = x_in_1_to_5(SolverFamily::Sat(SatEncoding::Log));
+
+    let first = Arc::new(Mutex::new(None));
+    let values = values_of_x(adaptors::Sat::default(), model, move |value, modifier| {
+        if first.lock().unwrap().replace(value).is_some() {
+            return Ok(());
+        }
+        modifier.add_constraint(Expression::Geq(Metadata::new(), reference(&x), int(4)))
+    });
+
+    let mut later = values[1..].to_vec();
+    later.sort();
+    let expected: Vec<i32> = (4..=5).filter(|value| *value != values[0]).collect();
+    assert_eq!(later, expected);
+}
+
+#[test]
+fn sat_assumed_constraint_only_applies_to_the_next_solution() {
+    let (model, x) = x_in_1_to_5(SolverFamily::Sat(SatEncoding::Log));
+
+    let first = Arc::new(Mutex::new(None));
+    let values = values_of_x(adaptors::Sat::default(), model, move |value, modifier| {
+        if first.lock().unwrap().replace(value).is_some() {
+            return Ok(());
+        }
+        // the largest value that has not been found yet
+        let next = if value == 5 { 4 } else { 5 };
+        modifier.add_assumed_constraint(Expression::Eq(Metadata::new(), reference(&x), int(next)))
+    });
+
+    let next = if values[0] == 5 { 4 } else { 5 };
+    assert_eq!(values[1], next);
+
+    let mut values = values;
+    values.sort();
+    assert_eq!(values, vec![1, 2, 3, 4, 5]);
+}
@@ -2,7 +2,7 @@
 
 use std::{collections::HashMap, fmt::Display};
 
-use crate::print::{print_const_array, print_constraint_array, print_var_array};
+use crate::print::{print_const_array, print_constraint_array, print_tuple_array, print_var_array};
 
 pub type VarName = String;
 pub type Tuple = Vec<Constant>;
@@ -14,6 +14,13 @@ pub type TwoVars = (Var, Var);
 pub struct Model {
     pub named_variables: SymbolTable,
     pub constraints: Vec<Constraint>,
+    pub objective: Option<Objective>,
+
+    /// The variable ordering heuristic used for the search variables.
+    pub var_order: VarOrder,
+
+    /// The value ordering heuristic used for the search variables, if not Minion's default.
+    pub value_order: Option<ValueOrder>,
 }
 
 impl Model {
@@ -22,6 +29,9 @@ impl Model {
         Model {
             named_variables: SymbolTable::new(),
             constraints: Vec::new(),
+            objective: None,
+            var_order: VarOrder::Static,
+            value_order: None,
         }
     }
 }
@@ -32,6 +42,84 @@ impl Default for Model {
     }
 }
 
+/// An optimisation objective over a single named variable.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Objective {
+    Minimising(VarName),
+    Maximising(VarName),
+}
+
+impl Objective {
+    /// The variable being optimised.
+    pub fn var_name(&self) -> &VarName {
+        match self {
+            Objective::Minimising(name) | Objective::Maximising(name) => name,
+        }
+    }
+}
+
+impl Display for Objective {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Objective::Minimising(name) => write!(f, "MINIMISING {name}"),
+            Objective::Maximising(name) => write!(f, "MAXIMISING {name}"),
+        }
+    }
+}
+
+/// Variable ordering heuristic for Minion branching.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum VarOrder {
+    /// Branch on the search variables in order.
+    #[default]
+    Static,
+    /// Smallest domain first.
+    Sdf,
+    /// Smallest ratio of current to initial domain size first.
+    Srf,
+    /// Largest domain first.
+    Ldf,
+    /// Weighted degree.
+    Wdeg,
+    /// Smallest ratio of domain size to weighted degree first.
+    DomOverWdeg,
+    /// Conflict ordering.
+    Conflict,
+}
+
+impl Display for VarOrder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VarOrder::Static => write!(f, "STATIC"),
+            VarOrder::Sdf => write!(f, "SDF"),
+            VarOrder::Srf => write!(f, "SRF"),
+            VarOrder::Ldf => write!(f, "LDF"),
+            VarOrder::Wdeg => write!(f, "WDEG"),
+            VarOrder::DomOverWdeg => write!(f, "DOMOVERWDEG"),
+            VarOrder::Conflict => write!(f, "CONFLICT"),
+        }
+    }
+}
+
+/// Value-order strategy for Minion branching.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueOrder {
+    Ascend,
+    Descend,
+    Random,
+}
+
+impl Display for ValueOrder {
+    /// Prints the value order of a single variable, as used in a Minion `VALORDER` list.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ValueOrder::Ascend => write!(f, "a"),
+            ValueOrder::Descend => write!(f, "d"),
+            ValueOrder::Random => write!(f, "r"),
+        }
+    }
+}
+
 /// All supported Minion constraints.
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -84,7 +172,7 @@ pub enum Constraint {
     LightTable(Vec<Var>, Vec<Tuple>),
     Mddc(Vec<Var>, Vec<Tuple>),
     NegativeMddc(Vec<Var>, Vec<Tuple>),
-    Str2Plus(Vec<Var>, Var),
+    Str2Plus(Vec<Var>, Vec<Tuple>),
     Max(Vec<Var>, Var),
     Min(Vec<Var>, Var),
     NvalueGeq(Vec<Var>, Var),
@@ -117,7 +205,7 @@ pub enum Constraint {
     True,
 }
 
-#[allow(clippy::todo, unused_variables)]
+#[allow(unused_variables)]
 impl Display for Constraint {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -144,15 +232,9 @@ impl Display for Constraint {
                     print_var_array(vars)
                 )
             }
-            Constraint::CheckAssign(constraint) => {
-                todo!("don't know how to print checkassign constriant...")
-            }
-            Constraint::CheckGsa(constraint) => {
-                todo!("don't know how to print checkgsa constraint...")
-            }
-            Constraint::ForwardChecking(constraint) => {
-                todo!("don't know how to print forwardchecking constraint...")
-            }
+            Constraint::CheckAssign(constraint) => write!(f, "check[assign]({constraint})"),
+            Constraint::CheckGsa(constraint) => write!(f, "check[gsa]({constraint})"),
+            Constraint::ForwardChecking(constraint) => write!(f, "forwardchecking({constraint})"),
             Constraint::Reify(constraint, var) => write!(f, "reify({constraint},{var})"),
             Constraint::ReifyImply(constraint, var) => write!(f, "reifyimply({constraint},{var})"),
             Constraint::ReifyImplyQuick(constraint, var) => {
@@ -244,30 +326,74 @@ impl Display for Constraint {
                 print_var_array(vars),
                 print_var_array(vars1)
             ),
-            Constraint::WatchVecExistsLess(vars, vars1) => {
-                todo!("don't know how to print watchvecexistsless...")
-            }
+            Constraint::WatchVecExistsLess(vars, vars1) => write!(
+                f,
+                "watchvecexists_less({},{})",
+                print_var_array(vars),
+                print_var_array(vars1)
+            ),
             Constraint::Hamming(vars, vars1, constant) => write!(
                 f,
                 "hamming({},{},{constant})",
                 print_var_array(vars),
                 print_var_array(vars1)
             ),
-            Constraint::NotHamming(vars, vars1, constant) => {
-                todo!("don't know how to print nothamming...")
-            }
-            Constraint::FrameUpdate(vars, vars1, vars2, vars3, constant) => {
-                todo!("don't know how to print frame update...")
-            }
-            Constraint::NegativeTable(_, _)
-            | Constraint::Table(_, _)
-            | Constraint::GacSchema(_, _)
-            | Constraint::LightTable(_, _)
-            | Constraint::Mddc(_, _)
-            | Constraint::Str2Plus(_, _)
-            | Constraint::NegativeMddc(_, _) => {
-                todo!("tuples not properly implemented yet, so can't print them...")
-            }
+            Constraint::NotHamming(vars, vars1, constant) => write!(
+                f,
+                "not-hamming({},{},{constant})",
+                print_var_array(vars),
+                print_var_array(vars1)
+            ),
+            Constraint::FrameUpdate(vars, vars1, vars2, vars3, constant) => write!(
+                f,
+                "frameupdate({},{},{},{},{constant})",
+                print_var_array(vars),
+                print_var_array(vars1),
+                print_var_array(vars2),
+                print_var_array(vars3)
+            ),
+            Constraint::NegativeTable(vars, tuples) => write!(
+                f,
+                "negativetable({},{})",
+                print_var_array(vars),
+                print_tuple_array(tuples)
+            ),
+            Constraint::Table(vars, tuples) => write!(
+                f,
+                "table({},{})",
+                print_var_array(vars),
+                print_tuple_array(tuples)
+            ),
+            Constraint::GacSchema(vars, tuples) => write!(
+                f,
+                "gacschema({},{})",
+                print_var_array(vars),
+                print_tuple_array(tuples)
+            ),
+            Constraint::LightTable(vars, tuples) => write!(
+                f,
+                "lighttable({},{})",
+                print_var_array(vars),
+                print_tuple_array(tuples)
+            ),
+            Constraint::Mddc(vars, tuples) => write!(
+                f,
+                "mddc({},{})",
+                print_var_array(vars),
+                print_tuple_array(tuples)
+            ),
+            Constraint::NegativeMddc(vars, tuples) => write!(
+                f,
+                "negativemddc({},{})",
+                print_var_array(vars),
+                print_tuple_array(tuples)
+            ),
+            Constraint::Str2Plus(vars, tuples) => write!(
+                f,
+                "str2plus({},{})",
+                print_var_array(vars),
+                print_tuple_array(tuples)
+            ),
             Constraint::Max(vars, var) => write!(f, "max({},{var})", print_var_array(vars)),
             Constraint::Min(vars, var) => write!(f, "min({},{var})", print_var_array(vars)),
             Constraint::NvalueGeq(vars, var) => {
@@ -328,7 +454,7 @@ impl Display for Constraint {
             Constraint::DisEq(var, var1) => write!(f, "diseq({var},{var1})"),
             Constraint::Eq(var, var1) => write!(f, "eq({var},{var1})"),
             Constraint::MinusEq(var, var1) => write!(f, "minuseq({var},{var1})"),
-            Constraint::GacEq(var, var1) => todo!("don't know how to print gaceq..."),
+            Constraint::GacEq(var, var1) => write!(f, "gaceq({var},{var1})"),
             Constraint::WatchLess(var, var1) => write!(f, "watchless({var},{var1})"),
             Constraint::WatchNeq(var, var1) => write!(f, "watchneq({var},{var1})"),
             Constraint::Ineq(var, var1, constant) => write!(f, "ineq({var},{var1},{constant})"),
@@ -52,6 +52,11 @@ pub enum RuntimeError {
     #[error("invalid argument: {0}")]
     InvalidArgument(String),
 
+    /// A constraint added during search failed propagation at the current search node, so there
+    /// are no solutions below it.
+    #[error("propagation failure when adding constraint midsearch")]
+    PropagationFailure,
+
     /// An unknown error has occurred.
     #[error("an unknown error has occurred while running minion: {0}")]
     UnknownError(String),
@@ -97,6 +97,9 @@
 //! These bindings have no replacement for Minion's `PRINT` and `VARORDER` statements - any
 //! variable given to the model that does not have a constant value is considered a search
 //! variable. Solutions are returned through the [callback function](Callback) as a `HashMap`.
+//!
+//! The variable and value ordering heuristics used for the search variables can be set through
+//! [`Model::var_order`](ast::Model::var_order) and [`Model::value_order`](ast::Model::value_order).
 
 pub use run::*;
 
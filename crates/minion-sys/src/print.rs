@@ -3,7 +3,7 @@
 
 use std::io::Write;
 
-use crate::ast::{Constant, Constraint, Model, Var, VarName};
+use crate::ast::{Constant, Constraint, Model, Tuple, Var, VarName};
 
 /// Writes a complete Minion file for this model to `writer`.
 pub fn write_minion_file(writer: &mut impl Write, model: &Model) -> Result<(), std::io::Error> {
@@ -34,16 +34,30 @@ pub fn write_variables_section(
 
 /// Writes the `SEARCH` section of the Minion file to `writer`.
 pub fn write_search_section(writer: &mut impl Write, model: &Model) -> Result<(), std::io::Error> {
-    // TODO: print maximising and minimising once we get it
-
     let symtab = &model.named_variables;
 
     writeln!(writer, "**SEARCH**")?;
 
+    if let Some(objective) = &model.objective {
+        writeln!(writer, "{objective}")?;
+    }
+
     // no aux vars
     let varorder = symtab.get_search_variable_order();
 
-    writeln!(writer, "VARORDER STATIC [{}]", varorder.join(","))
+    writeln!(
+        writer,
+        "VARORDER {} [{}]",
+        model.var_order,
+        varorder.join(",")
+    )?;
+
+    if let Some(value_order) = model.value_order {
+        let valorder = vec![value_order.to_string(); varorder.len()];
+        writeln!(writer, "VALORDER [{}]", valorder.join(","))?;
+    }
+
+    Ok(())
 }
 
 /// Writes the `CONSTRAINTS` section of the Minion file to `writer`.
@@ -95,6 +109,19 @@ pub(crate) fn print_var_array(array: &[Var]) -> String {
     format!("[{string}]")
 }
 
+/// Prints a list of tuples inline, as used by the table constraints, e.g. `{<1,2>,<2,1>}`.
+pub(crate) fn print_tuple_array(array: &[Tuple]) -> String {
+    let string_array: Vec<String> = array
+        .iter()
+        .map(|tuple| {
+            let values: Vec<String> = tuple.iter().map(|x| format!("{x}")).collect();
+            format!("<{}>", values.join(","))
+        })
+        .collect();
+    let string = string_array.join(",");
+    format!("{{{string}}}")
+}
+
 pub(crate) fn print_constraint_array(array: &[Constraint]) -> String {
     let string_array: Vec<String> = array.iter().map(|x| format!("{x}")).collect();
     let string = string_array.join(",");
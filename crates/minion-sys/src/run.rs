@@ -19,8 +19,8 @@ use crate::{
     ffi::{self},
 };
 use crate::{
-    ast::{Constant, Constraint, Model, Var, VarDomain, VarName},
-    error::{MinionError, check_minion_result},
+    ast::{Constant, Constraint, Model, Objective, Var, VarDomain, VarName, VarOrder},
+    error::{MinionError, RuntimeError, check_minion_result},
     scoped_ptr::Scoped,
 };
 
@@ -31,6 +31,10 @@ use crate::{
 ///
 /// Return `true` to continue searching, `false` to stop.
 ///
+/// If the model has an [`Objective`], Minion performs branch-and-bound search: after each
+/// solution, the remaining search is restricted to strictly better solutions. The last solution
+/// passed to the callback is therefore optimal, unless the search was stopped early.
+///
 /// Since this is a boxed closure, it can capture state from its environment,
 /// eliminating the need for global or thread-local state in callers.
 ///
@@ -104,13 +108,7 @@ use crate::{
 /// ```
 pub type Callback<'a> = Box<dyn FnMut(HashMap<VarName, Constant>) -> bool + 'a>;
 
-/// Value-order strategy for Minion branching.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum ValueOrder {
-    Ascend,
-    Descend,
-    Random,
-}
+pub use crate::ast::ValueOrder;
 
 /// Optional runtime controls for [`run_minion_with_options`].
 #[derive(Debug, Clone, Copy, Default)]
@@ -129,6 +127,8 @@ pub struct RunOptions {
 struct CallbackState<'a> {
     callback: Callback<'a>,
     print_vars: Vec<VarName>,
+    objective: Option<Objective>,
+    error: Option<MinionError>,
 }
 
 static CURRENT_INSTANCE: AtomicPtr<ffi::ProbSpec_CSPInstance> = AtomicPtr::new(ptr::null_mut());
@@ -201,12 +201,46 @@ unsafe extern "C" fn run_callback(ctx: *mut ffi::MinionContext, userdata: *mut c
         solutions.insert(var.to_string(), solution);
     }
 
+    let objective_value = state
+        .objective
+        .as_ref()
+        .and_then(|objective| solutions.get(objective.var_name()).copied());
+
     INSIDE_MINION_CALLBACK.with(|flag| flag.set(true));
-    let continue_search = (state.callback)(solutions);
+    let mut continue_search = (state.callback)(solutions);
+    if continue_search
+        && let Some(objective) = &state.objective
+        && let Some(Constant::Integer(value)) = objective_value
+        && let Err(err) = add_objective_bound(objective, value)
+    {
+        state.error = Some(err);
+        continue_search = false;
+    }
     INSIDE_MINION_CALLBACK.with(|flag| flag.set(false));
     continue_search
 }
 
+/// Restricts the rest of the search to solutions strictly better than `value`.
+fn add_objective_bound(objective: &Objective, value: i32) -> Result<(), MinionError> {
+    let var = Var::NameRef(objective.var_name().clone());
+    let bound = match objective {
+        // var <= value - 1
+        Objective::Minimising(_) => {
+            Constraint::Ineq(var, Var::ConstantAsVar(value), Constant::Integer(-1))
+        }
+        // value <= var - 1
+        Objective::Maximising(_) => {
+            Constraint::Ineq(Var::ConstantAsVar(value), var, Constant::Integer(-1))
+        }
+    };
+
+    match add_constraint_during_search(bound) {
+        // No better solution exists below this node; Minion backtracks as usual.
+        Err(MinionError::RuntimeError(RuntimeError::PropagationFailure)) => Ok(()),
+        x => x,
+    }
+}
+
 /// Run Minion on the given [Model].
 ///
 /// The given [callback](Callback) is ran whenever a new solution set is found.
@@ -229,6 +263,8 @@ pub fn run_minion_with_options(
     let mut state = CallbackState {
         callback,
         print_vars: vec![],
+        objective: model.objective.clone(),
+        error: None,
     };
 
     unsafe {
@@ -244,7 +280,7 @@ pub fn run_minion_with_options(
         // themselves instead of going through this wrapper.
         (*search_opts).silent = true;
         (*search_opts).print_solution = false;
-        if let Some(value_order) = options.value_order {
+        if let Some(value_order) = options.value_order.or(model.value_order) {
             let value_order = match value_order {
                 ValueOrder::Ascend => ffi::ValOrderEnum_VALORDER_ASCEND,
                 ValueOrder::Descend => ffi::ValOrderEnum_VALORDER_DESCEND,
@@ -274,12 +310,16 @@ pub fn run_minion_with_options(
         CURRENT_CTX.store(ptr::null_mut(), Ordering::SeqCst);
         ffi::instance_free(search_instance);
 
-        match check_minion_result(res) {
-            Ok(()) => Ok(SolverContext { ctx }),
-            Err(e) => {
+        match (check_minion_result(res), state.error) {
+            (Ok(()), None) => Ok(SolverContext { ctx }),
+            (Err(e), _) => {
                 ffi::minion_freeContext(ctx);
                 Err(MinionError::from(e))
             }
+            (Ok(()), Some(e)) => {
+                ffi::minion_freeContext(ctx);
+                Err(e)
+            }
         }
     }
 }
@@ -321,9 +361,16 @@ pub fn add_aux_var_during_search(name: VarName, domain: VarDomain) -> Result<(),
     Ok(())
 }
 
+/// The start of the error message Minion gives when a constraint added during search fails
+/// propagation.
+const MIDSEARCH_PROPAGATION_FAILURE: &str = "propagation failure when adding constraint midsearch";
+
 /// Adds a constraint to the currently-running Minion instance.
 ///
 /// This is intended for use from a solver callback while `run_minion` is active.
+///
+/// Returns [`RuntimeError::PropagationFailure`] if the constraint fails propagation at the
+/// current search node.
 pub fn add_constraint_during_search(constraint: Constraint) -> Result<(), MinionError> {
     let instance = CURRENT_INSTANCE.load(Ordering::SeqCst);
     let ctx = CURRENT_CTX.load(Ordering::SeqCst);
@@ -341,11 +388,19 @@ pub fn add_constraint_during_search(constraint: Constraint) -> Result<(), Minion
         });
 
         constraint_add_args(instance, raw_constraint.ptr, &constraint)?;
-        check_minion_result(ffi::minion_addConstraintMidsearch(
+        match check_minion_result(ffi::minion_addConstraintMidsearch(
             ctx,
             instance,
             raw_constraint.ptr,
-        ))?;
+        )) {
+            // Minion reports propagation failures as invalid instances
+            Err(RuntimeError::InvalidInstance(msg))
+                if msg.starts_with(MIDSEARCH_PROPAGATION_FAILURE) =>
+            {
+                return Err(RuntimeError::PropagationFailure.into());
+            }
+            x => x?,
+        }
     }
 
     Ok(())
@@ -423,8 +478,18 @@ unsafe fn convert_model_to_raw(
         ffi::vec_var_push_back(search_vars.ptr, var_result.var);
     }
 
+    let var_order = match model.var_order {
+        VarOrder::Static => ffi::VarOrderEnum_ORDER_STATIC,
+        VarOrder::Sdf => ffi::VarOrderEnum_ORDER_SDF,
+        VarOrder::Srf => ffi::VarOrderEnum_ORDER_SRF,
+        VarOrder::Ldf => ffi::VarOrderEnum_ORDER_LDF,
+        VarOrder::Wdeg => ffi::VarOrderEnum_ORDER_WDEG,
+        VarOrder::DomOverWdeg => ffi::VarOrderEnum_ORDER_DOMOVERWDEG,
+        VarOrder::Conflict => ffi::VarOrderEnum_ORDER_CONFLICT,
+    };
+
     let search_order = Scoped::new(
-        ffi::searchOrder_new(search_vars.ptr, ffi::VarOrderEnum_ORDER_STATIC, false),
+        ffi::searchOrder_new(search_vars.ptr, var_order, false),
         |x| ffi::searchOrder_free(x as _),
     );
 
@@ -489,7 +554,7 @@ unsafe fn get_constraint_type(constraint: &Constraint) -> Result<u32, MinionErro
         Constraint::LexLeq(_, _) => Ok(ffi::ConstraintType_CT_LEXLEQ),
         Constraint::LexLess(_, _) => Ok(ffi::ConstraintType_CT_LEXLESS),
         Constraint::LexLeqQuick(_, _) => Ok(ffi::ConstraintType_CT_QUICK_LEXLEQ),
-        Constraint::LexLessQuick(_, _) => Ok(ffi::ConstraintType_CT_QUICK_LEXLEQ),
+        Constraint::LexLessQuick(_, _) => Ok(ffi::ConstraintType_CT_QUICK_LEXLESS),
         Constraint::WatchVecNeq(_, _) => Ok(ffi::ConstraintType_CT_WATCHED_VECNEQ),
         Constraint::WatchVecExistsLess(_, _) => Ok(ffi::ConstraintType_CT_WATCHED_VEC_OR_LESS),
         Constraint::Hamming(_, _, _) => Ok(ffi::ConstraintType_CT_WATCHED_HAMMING),
@@ -705,61 +770,88 @@ unsafe fn constraint_add_args(
             read_list(i, r_constr, b)?;
             Ok(())
         }
-        //Constraint::LitSumGeq(_, _, _) => todo!(),
-        //Constraint::Gcc(_, _, _) => todo!(),
-        //Constraint::GccWeak(_, _, _) => todo!(),
-        //Constraint::LexLeqRv(_, _) => todo!(),
-        //Constraint::LexLeqQuick(_, _) => todo!(),
-        //Constraint::LexLessQuick(_, _) => todo!(),
-        //Constraint::WatchVecExistsLess(_, _) => todo!(),
-        //Constraint::Hamming(_, _, _) => todo!(),
-        //Constraint::NotHamming(_, _, _) => todo!(),
-        //Constraint::FrameUpdate(_, _, _, _, _) => todo!(),
-        Constraint::NegativeTable(vars, tuple_list) | Constraint::Table(vars, tuple_list) => {
+        Constraint::LitSumGeq(vars, constants, c) => {
+            read_list(i, r_constr, vars)?;
+            read_constant_list(r_constr, constants)?;
+            read_constant(r_constr, c)?;
+            Ok(())
+        }
+        Constraint::Gcc(vars, values, counts) | Constraint::GccWeak(vars, values, counts) => {
+            read_list(i, r_constr, vars)?;
+            read_constant_list(r_constr, values)?;
+            read_list(i, r_constr, counts)?;
+            Ok(())
+        }
+        Constraint::LexLeqRv(a, b)
+        | Constraint::LexLeqQuick(a, b)
+        | Constraint::LexLessQuick(a, b)
+        | Constraint::WatchVecExistsLess(a, b) => {
+            read_list(i, r_constr, a)?;
+            read_list(i, r_constr, b)?;
+            Ok(())
+        }
+        Constraint::Hamming(a, b, c) | Constraint::NotHamming(a, b, c) => {
+            read_list(i, r_constr, a)?;
+            read_list(i, r_constr, b)?;
+            read_constant(r_constr, c)?;
+            Ok(())
+        }
+        Constraint::FrameUpdate(a, b, c, d, e) => {
+            read_list(i, r_constr, a)?;
+            read_list(i, r_constr, b)?;
+            read_list(i, r_constr, c)?;
+            read_list(i, r_constr, d)?;
+            read_constant(r_constr, e)?;
+            Ok(())
+        }
+        Constraint::NegativeTable(vars, tuple_list)
+        | Constraint::Table(vars, tuple_list)
+        | Constraint::GacSchema(vars, tuple_list)
+        | Constraint::LightTable(vars, tuple_list)
+        | Constraint::Mddc(vars, tuple_list)
+        | Constraint::NegativeMddc(vars, tuple_list)
+        | Constraint::Str2Plus(vars, tuple_list) => {
             read_list(i, r_constr, vars)?;
             read_tuple_list(r_constr, tuple_list)?;
             Ok(())
         }
-        //Constraint::GacSchema(_, _) => todo!(),
-        //Constraint::LightTable(_, _) => todo!(),
-        //Constraint::Mddc(_, _) => todo!(),
-        //Constraint::NegativeMddc(_, _) => todo!(),
-        //Constraint::Str2Plus(_, _) => todo!(),
-        //Constraint::Max(_, _) => todo!(),
-        //Constraint::Min(_, _) => todo!(),
-        //Constraint::NvalueGeq(_, _) => todo!(),
-        //Constraint::NvalueLeq(_, _) => todo!(),
-        //Constraint::Element(_, _, _) => todo!(),
-        //Constraint::ElementUndefZero(_, _, _) => todo!(),
-        //Constraint::WatchElement(_, _, _) => todo!(),
-        //Constraint::WatchElementOne(_, _, _) => todo!(),
-        Constraint::ElementOne(vec, j, e) => {
+        Constraint::Max(vars, var)
+        | Constraint::Min(vars, var)
+        | Constraint::NvalueGeq(vars, var)
+        | Constraint::NvalueLeq(vars, var) => {
+            read_list(i, r_constr, vars)?;
+            read_var(i, r_constr, var)?;
+            Ok(())
+        }
+        Constraint::Element(vec, j, e)
+        | Constraint::ElementOne(vec, j, e)
+        | Constraint::ElementUndefZero(vec, j, e)
+        | Constraint::WatchElement(vec, j, e)
+        | Constraint::WatchElementOne(vec, j, e)
+        | Constraint::WatchElementOneUndefZero(vec, j, e)
+        | Constraint::WatchElementUndefZero(vec, j, e) => {
             read_list(i, r_constr, vec)?;
             read_var(i, r_constr, j)?;
             read_var(i, r_constr, e)?;
             Ok(())
         }
-        //Constraint::WatchElementOneUndefZero(_, _, _) => todo!(),
-        //Constraint::WatchElementUndefZero(_, _, _) => todo!(),
-        Constraint::WLiteral(a, b) => {
+        Constraint::WLiteral(a, b) | Constraint::WNotLiteral(a, b) => {
             read_var(i, r_constr, a)?;
             read_constant(r_constr, b)?;
             Ok(())
         }
-        //Constraint::WNotLiteral(_, _) => todo!(),
-        Constraint::WInIntervalSet(var, consts) => {
+        Constraint::WInIntervalSet(var, consts)
+        | Constraint::WInRange(var, consts)
+        | Constraint::WNotInRange(var, consts) => {
             read_var(i, r_constr, var)?;
             read_constant_list(r_constr, consts)?;
             Ok(())
         }
-        //Constraint::WInRange(_, _) => todo!(),
-        Constraint::WInset(a, b) => {
+        Constraint::WInset(a, b) | Constraint::WNotInset(a, b) => {
             read_var(i, r_constr, a)?;
             read_constant_list(r_constr, b)?;
             Ok(())
         }
-        //Constraint::WNotInRange(_, _) => todo!(),
-        //Constraint::WNotInset(_, _) => todo!(),
         Constraint::Abs(a, b) => {
             read_var(i, r_constr, a)?;
             read_var(i, r_constr, b)?;
@@ -775,8 +867,11 @@ unsafe fn constraint_add_args(
             read_var(i, r_constr, b)?;
             Ok(())
         }
-        //Constraint::GacEq(_, _) => todo!(),
-        //Constraint::WatchLess(_, _) => todo!(),
+        Constraint::GacEq(a, b) | Constraint::WatchLess(a, b) => {
+            read_var(i, r_constr, a)?;
+            read_var(i, r_constr, b)?;
+            Ok(())
+        }
         // TODO: ensure that this is a bool?
         Constraint::WatchNeq(a, b) => {
             read_var(i, r_constr, a)?;
@@ -0,0 +1,293 @@
+// Solution counts for constraints passed to Minion through the FFI, each over a small set of
+// variables so the expected counts can be worked out by hand.
+
+use minion_sys::ast::{Constant, Constraint, Model, Tuple, Var, VarDomain};
+use minion_sys::error::MinionError;
+
+fn var(name: &str) -> Var {
+    Var::NameRef(String::from(name))
+}
+
+fn vars(names: &[&str]) -> Vec<Var> {
+    names.iter().map(|name| var(name)).collect()
+}
+
+fn ints(values: &[i32]) -> Vec<Constant> {
+    values.iter().copied().map(Constant::Integer).collect()
+}
+
+// The tuples (x, y, z) in 1..3 where x + y = z
+fn sum_tuples() -> Vec<Tuple> {
+    vec![ints(&[1, 1, 2]), ints(&[1, 2, 3]), ints(&[2, 1, 3])]
+}
+
+fn count_solutions(
+    variables: &[(&str, VarDomain)],
+    constraint: Constraint,
+) -> Result<u32, MinionError> {
+    let mut model = Model::new();
+    for (name, domain) in variables {
+        model.named_variables.add_var(String::from(*name), *domain);
+    }
+    model.constraints.push(constraint);
+
+    let mut sols_counter = 0u32;
+    minion_sys::run_minion(
+        model,
+        Box::new(|_| {
+            sols_counter += 1;
+            true
+        }),
+    )?;
+    Ok(sols_counter)
+}
+
+fn bools(names: &[&'static str]) -> Vec<(&'static str, VarDomain)> {
+    names.iter().map(|name| (*name, VarDomain::Bool)).collect()
+}
+
+#[test]
+#[allow(clippy::panic_in_result_fn)]
+fn test_litsumgeq() -> Result<(), MinionError> {
+    // at least one of x, y is 1
+    let constraint = Constraint::LitSumGeq(vars(&["x", "y"]), ints(&[1, 1]), Constant::Integer(1));
+    assert_eq!(count_solutions(&bools(&["x", "y"]), constraint)?, 3);
+    Ok(())
+}
+
+#[test]
+#[allow(clippy::panic_in_result_fn)]
+fn test_gcc() -> Result<(), MinionError> {
+    // c1 and c2 are determined by x, y, and z
+    let variables = [
+        ("x", VarDomain::Discrete(1, 2)),
+        ("y", VarDomain::Discrete(1, 2)),
+        ("z", VarDomain::Discrete(1, 2)),
+        ("c1", VarDomain::Discrete(0, 3)),
+        ("c2", VarDomain::Discrete(0, 3)),
+    ];
+
+    let gcc = Constraint::Gcc(vars(&["x", "y", "z"]), ints(&[1, 2]), vars(&["c1", "c2"]));
+    assert_eq!(count_solutions(&variables, gcc)?, 8);
+
+    let gcc_weak = Constraint::GccWeak(vars(&["x", "y", "z"]), ints(&[1, 2]), vars(&["c1", "c2"]));
+    assert_eq!(count_solutions(&variables, gcc_weak)?, 8);
+    Ok(())
+}
+
+#[test]
+#[allow(clippy::panic_in_result_fn)]
+fn test_lex_constraints() -> Result<(), MinionError> {
+    let variables = bools(&["a1", "a2", "b1", "b2"]);
+    let a = || vars(&["a1", "a2"]);
+    let b = || vars(&["b1", "b2"]);
+
+    // 16 pairs of vectors: 4 are equal, and half of the other 12 are ordered
+    assert_eq!(
+        count_solutions(&variables, Constraint::LexLeqRv(a(), b()))?,
+        10
+    );
+    assert_eq!(
+        count_solutions(&variables, Constraint::LexLeqQuick(a(), b()))?,
+        10
+    );
+    assert_eq!(
+        count_solutions(&variables, Constraint::LexLessQuick(a(), b()))?,
+        6
+    );
+
+    // some a[i] < b[i]: 16 - 3 * 3 pairs
+    assert_eq!(
+        count_solutions(&variables, Constraint::WatchVecExistsLess(a(), b()))?,
+        7
+    );
+    Ok(())
+}
+
+#[test]
+#[allow(clippy::panic_in_result_fn)]
+fn test_hamming() -> Result<(), MinionError> {
+    let variables = bools(&["a1", "a2", "b1", "b2"]);
+    let a = || vars(&["a1", "a2"]);
+    let b = || vars(&["b1", "b2"]);
+
+    // a and b differ in at least one position
+    assert_eq!(
+        count_solutions(
+            &variables,
+            Constraint::Hamming(a(), b(), Constant::Integer(1))
+        )?,
+        12
+    );
+
+    // a and b differ in fewer than one position
+    assert_eq!(
+        count_solutions(
+            &variables,
+            Constraint::NotHamming(a(), b(), Constant::Integer(1))
+        )?,
+        4
+    );
+    Ok(())
+}
+
+#[test]
+#[allow(clippy::panic_in_result_fn)]
+fn test_tuple_constraints() -> Result<(), MinionError> {
+    let variables = [
+        ("x", VarDomain::Discrete(1, 3)),
+        ("y", VarDomain::Discrete(1, 3)),
+        ("z", VarDomain::Discrete(1, 3)),
+    ];
+    let xyz = || vars(&["x", "y", "z"]);
+
+    for constraint in [
+        Constraint::GacSchema(xyz(), sum_tuples()),
+        Constraint::LightTable(xyz(), sum_tuples()),
+        Constraint::Mddc(xyz(), sum_tuples()),
+        Constraint::Str2Plus(xyz(), sum_tuples()),
+    ] {
+        assert_eq!(count_solutions(&variables, constraint)?, 3);
+    }
+
+    assert_eq!(
+        count_solutions(&variables, Constraint::NegativeMddc(xyz(), sum_tuples()))?,
+        24
+    );
+    Ok(())
+}
+
+#[test]
+#[allow(clippy::panic_in_result_fn)]
+fn test_max_min() -> Result<(), MinionError> {
+    // m is determined by x and y
+    let variables = [
+        ("x", VarDomain::Discrete(1, 3)),
+        ("y", VarDomain::Discrete(1, 3)),
+        ("m", VarDomain::Discrete(1, 3)),
+    ];
+
+    assert_eq!(
+        count_solutions(&variables, Constraint::Max(vars(&["x", "y"]), var("m")))?,
+        9
+    );
+    assert_eq!(
+        count_solutions(&variables, Constraint::Min(vars(&["x", "y"]), var("m")))?,
+        9
+    );
+    Ok(())
+}
+
+#[test]
+#[allow(clippy::panic_in_result_fn)]
+fn test_nvalue() -> Result<(), MinionError> {
+    // 2 assignments of x, y, z use one value, and 6 use two values
+    let variables = [
+        ("x", VarDomain::Discrete(1, 2)),
+        ("y", VarDomain::Discrete(1, 2)),
+        ("z", VarDomain::Discrete(1, 2)),
+        ("n", VarDomain::Discrete(1, 3)),
+    ];
+
+    // n = 1: 2, n = 2: 8, n = 3: 8
+    assert_eq!(
+        count_solutions(
+            &variables,
+            Constraint::NvalueLeq(vars(&["x", "y", "z"]), var("n"))
+        )?,
+        18
+    );
+
+    // n = 1: 8, n = 2: 6, n = 3: 0
+    assert_eq!(
+        count_solutions(
+            &variables,
+            Constraint::NvalueGeq(vars(&["x", "y", "z"]), var("n"))
+        )?,
+        14
+    );
+    Ok(())
+}
+
+#[test]
+#[allow(clippy::panic_in_result_fn)]
+fn test_element() -> Result<(), MinionError> {
+    // e is determined by x, y, and i
+    let zero_indexed = [
+        ("x", VarDomain::Discrete(1, 2)),
+        ("y", VarDomain::Discrete(1, 2)),
+        ("i", VarDomain::Discrete(0, 1)),
+        ("e", VarDomain::Discrete(1, 2)),
+    ];
+    let one_indexed = [
+        ("x", VarDomain::Discrete(1, 2)),
+        ("y", VarDomain::Discrete(1, 2)),
+        ("i", VarDomain::Discrete(1, 2)),
+        ("e", VarDomain::Discrete(1, 2)),
+    ];
+    let xy = || vars(&["x", "y"]);
+
+    for constraint in [
+        Constraint::Element(xy(), var("i"), var("e")),
+        Constraint::ElementUndefZero(xy(), var("i"), var("e")),
+        Constraint::WatchElement(xy(), var("i"), var("e")),
+        Constraint::WatchElementUndefZero(xy(), var("i"), var("e")),
+    ] {
+        assert_eq!(count_solutions(&zero_indexed, constraint)?, 8);
+    }
+
+    for constraint in [
+        Constraint::ElementOne(xy(), var("i"), var("e")),
+        Constraint::WatchElementOne(xy(), var("i"), var("e")),
+        Constraint::WatchElementOneUndefZero(xy(), var("i"), var("e")),
+    ] {
+        assert_eq!(count_solutions(&one_indexed, constraint)?, 8);
+    }
+    Ok(())
+}
+
+#[test]
+#[allow(clippy::panic_in_result_fn)]
+fn test_unary_constraints() -> Result<(), MinionError> {
+    let variables = [("x", VarDomain::Discrete(1, 5))];
+
+    assert_eq!(
+        count_solutions(
+            &variables,
+            Constraint::WNotLiteral(var("x"), Constant::Integer(1))
+        )?,
+        4
+    );
+    assert_eq!(
+        count_solutions(&variables, Constraint::WInRange(var("x"), ints(&[2, 3])))?,
+        2
+    );
+    assert_eq!(
+        count_solutions(&variables, Constraint::WNotInRange(var("x"), ints(&[2, 3])))?,
+        3
+    );
+    assert_eq!(
+        count_solutions(&variables, Constraint::WNotInset(var("x"), ints(&[1, 3])))?,
+        3
+    );
+    Ok(())
+}
+
+#[test]
+#[allow(clippy::panic_in_result_fn)]
+fn test_binary_constraints() -> Result<(), MinionError> {
+    let variables = [
+        ("x", VarDomain::Discrete(1, 3)),
+        ("y", VarDomain::Discrete(1, 3)),
+    ];
+
+    assert_eq!(
+        count_solutions(&variables, Constraint::GacEq(var("x"), var("y")))?,
+        3
+    );
+    assert_eq!(
+        count_solutions(&variables, Constraint::WatchLess(var("x"), var("y")))?,
+        3
+    );
+    Ok(())
+}
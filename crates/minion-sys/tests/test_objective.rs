@@ -0,0 +1,43 @@
+// There are two integers x and y between 1 and 5 inclusive, where x + y <= 7. Maximise x.
+
+use minion_sys::ast::{Constant, Constraint, Model, Objective, Var, VarDomain};
+use minion_sys::error::MinionError;
+
+#[test]
+#[allow(clippy::panic_in_result_fn)]
+fn test_maximising_objective() -> Result<(), MinionError> {
+    let mut model = Model::new();
+
+    model
+        .named_variables
+        .add_var(String::from("x"), VarDomain::Bound(1, 5));
+    model
+        .named_variables
+        .add_var(String::from("y"), VarDomain::Bound(1, 5));
+
+    model.constraints.push(Constraint::SumLeq(
+        vec![
+            Var::NameRef(String::from("x")),
+            Var::NameRef(String::from("y")),
+        ],
+        Var::ConstantAsVar(7),
+    ));
+
+    model.objective = Some(Objective::Maximising(String::from("x")));
+
+    // Every solution found must strictly improve on the previous one
+    let mut values: Vec<i32> = vec![];
+    minion_sys::run_minion(
+        model,
+        Box::new(|solution| {
+            if let Some(Constant::Integer(x)) = solution.get("x") {
+                values.push(*x);
+            }
+            true
+        }),
+    )?;
+
+    assert!(values.windows(2).all(|pair| pair[0] < pair[1]));
+    assert_eq!(values.last(), Some(&5));
+    Ok(())
+}
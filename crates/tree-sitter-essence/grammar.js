@@ -28,7 +28,11 @@ module.exports = grammar ({
         commaSep1(choice(field("bool_expr", $.bool_expr), field("atom", $.atom), field("comparison_expr", $.comparison_expr))), 
       ),
       field("letting_statement", $.letting_statement),
+      field("where_statement", $.where_statement),
+      field("branching_statement", $.branching_statement),
+      field("heuristic_statement", $.heuristic_statement),
       field("dominance_relation", $.dominance_relation),
+      field("objective_statement", $.objective_statement),
     )),
 
     single_line_comment: $ => token(seq('$', /.*/)),
@@ -63,7 +67,18 @@ module.exports = grammar ({
     //given statements
     given_statement: $ => seq(
       field("given_keyword", "given"),
-      field("variable_declaration", commaSep1($.variable_declaration))
+      choice(
+        field("variable_declaration", commaSep1($.variable_declaration)),
+        field("enum_declaration", $.given_enum_declaration)
+      )
+    ),
+
+    // given E new type enum
+    given_enum_declaration: $ => seq(
+      field("variables", $.variable_list),
+      "new",
+      "type",
+      "enum"
     ),
 
     COLON: $ => ":",
@@ -84,7 +99,12 @@ module.exports = grammar ({
       field("matrix_domain", $.matrix_domain),
       field("record_domain", $.record_domain),
       field("set_domain", $.set_domain),
-      field("set_domain", $.set_domain),
+      field("mset_domain", $.mset_domain),
+      field("sequence_domain", $.sequence_domain),
+      field("function_domain", $.function_domain),
+      field("relation_domain", $.relation_domain),
+      field("partition_domain", $.partition_domain),
+      field("variant_domain", $.variant_domain),
     ),
     bool_domain: $ => "bool",
 
@@ -172,12 +192,129 @@ module.exports = grammar ({
       )
     ),
 
+    mset_domain: $ => seq(
+      "mset",
+      optional($.domain_attributes),
+      "of",
+      field("value_domain", $.domain)
+    ),
+
+    sequence_domain: $ => seq(
+      "sequence",
+      optional($.domain_attributes),
+      "of",
+      field("value_domain", $.domain)
+    ),
+
+    function_domain: $ => prec.right(seq(
+      "function",
+      optional($.domain_attributes),
+      field("domain", $.domain),
+      "-->",
+      field("codomain", $.domain)
+    )),
+
+    relation_domain: $ => seq(
+      "relation",
+      optional($.domain_attributes),
+      "of",
+      "(",
+      field("domain", $.domain),
+      repeat(seq("*", field("domain", $.domain))),
+      ")"
+    ),
+
+    partition_domain: $ => seq(
+      "partition",
+      optional($.domain_attributes),
+      "from",
+      field("value_domain", $.domain)
+    ),
+
+    variant_domain: $ => seq(
+      "variant",
+      "{",
+      commaSep1(field("name_domain_pair", $.name_domain_pair)),
+      "}"
+    ),
+
+    // attributes of mset, sequence, function, relation and partition domains.
+    // which attributes are allowed for which domain is checked by the parser.
+    domain_attributes: $ => seq("(", commaSep1(field("attribute", $.domain_attribute)), ")"),
+
+    domain_attribute: $ => choice(
+      seq(
+        field("name", choice(
+          "size", "minSize", "maxSize",
+          "minOccur", "maxOccur",
+          "numParts", "minNumParts", "maxNumParts",
+          "partSize", "minPartSize", "maxPartSize"
+        )),
+        field("value", $.integer)
+      ),
+      field("name", choice(
+        "total", "partial",
+        "injective", "surjective", "bijective",
+        "regular",
+        "reflexive", "irreflexive", "coreflexive", "symmetric", "antiSymmetric", "aSymmetric",
+        "transitive", "connex", "Euclidean", "serial", "equivalence", "partialOrder"
+      ))
+    ),
+
     set_literal: $ => seq(
       "{",
       field("element", commaSep1(choice($.bool_expr, $.arithmetic_expr, $.comparison_expr, $.atom))),
       "}"
     ),
 
+    mset_literal: $ => seq(
+      "mset",
+      "(",
+      optional(field("element", commaSep1(choice($.bool_expr, $.arithmetic_expr, $.comparison_expr, $.atom)))),
+      ")"
+    ),
+
+    sequence_literal: $ => seq(
+      "sequence",
+      "(",
+      optional(field("element", commaSep1(choice($.bool_expr, $.arithmetic_expr, $.comparison_expr, $.atom)))),
+      ")"
+    ),
+
+    function_literal: $ => seq(
+      "function",
+      "(",
+      optional(commaSep1(field("mapping", $.function_mapping))),
+      ")"
+    ),
+
+    function_mapping: $ => seq(
+      field("from", choice($.arithmetic_expr, $.atom)),
+      "-->",
+      field("to", choice($.arithmetic_expr, $.atom))
+    ),
+
+    relation_literal: $ => seq(
+      "relation",
+      "(",
+      optional(commaSep1(field("element", $.tuple))),
+      ")"
+    ),
+
+    partition_literal: $ => seq(
+      "partition",
+      "(",
+      optional(commaSep1(field("part", $.set_literal))),
+      ")"
+    ),
+
+    variant_literal: $ => seq(
+      "variant",
+      "{",
+      field("name_value_pair", $.name_value_pair),
+      "}"
+    ),
+
     name_domain_pair: $ => seq(
       field("name", $.identifier),
       ":",
@@ -200,10 +337,38 @@ module.exports = grammar ({
         seq(
           field("domain", "domain"),
           field("expr_or_domain", $.domain)
-        )
+        ),
+        field("new_type", $.new_type)
       )
     ),
 
+    // new type enum {a, b, c} or new type of size n
+    new_type: $ => seq(
+      "new",
+      "type",
+      choice(
+        seq("enum", "{", optional(commaSep1(field("value", $.identifier))), "}"),
+        seq("of", "size", field("size", choice($.arithmetic_expr, $.atom)))
+      )
+    ),
+
+    //where statements
+    where_statement: $ => seq(
+      field("where_keyword", "where"),
+      commaSep1(field("expression", choice($.bool_expr, $.comparison_expr, $.atom)))
+    ),
+
+    //search annotations
+    branching_statement: $ => seq(
+      field("branching_keyword", "branching on"),
+      field("variables", $.matrix)
+    ),
+
+    heuristic_statement: $ => seq(
+      field("heuristic_keyword", "heuristic"),
+      field("heuristic", $.identifier)
+    ),
+
     // Constraints 
     // boolean expressions require the operands to be boolean and return a boolean
     bool_expr: $ => prec(2, choice(
@@ -287,6 +452,7 @@ module.exports = grammar ({
       $.lex_comparison,
       $.equality_comparison,
       $.set_comparison,
+      $.sequence_comparison,
       $.all_diff_comparison
     )),
 
@@ -318,6 +484,13 @@ module.exports = grammar ({
       field("right", $.atom)
     ),
 
+    // Sequence comparisons: require sequence operands, return boolean
+    sequence_comparison: $ => seq(
+      field("left", $.atom),
+      field("operator", choice("subsequence", "substring")),
+      field("right", $.atom)
+    ),
+
     all_diff_comparison: $ => prec(-10, seq(
       field("operator", "allDiff"),
       "(",
@@ -337,6 +510,7 @@ module.exports = grammar ({
       field("sum_expression", $.sum_expr),
       field("list_combining_expression_arith", $.list_combining_expr_arith),
       field("aggregate_expression", $.aggregate_expr),
+      field("freq_expression", $.freq_expr),
       field("sub_arith_expression", $.sub_arith_expr)
     )),
 
@@ -352,7 +526,17 @@ module.exports = grammar ({
       field("from_solution", $.from_solution),
       field("index_or_slice", $.index_or_slice),
       field("set_literal", $.set_literal),
+      field("mset_literal", $.mset_literal),
+      field("sequence_literal", $.sequence_literal),
+      field("function_literal", $.function_literal),
+      field("relation_literal", $.relation_literal),
+      field("partition_literal", $.partition_literal),
+      field("variant_literal", $.variant_literal),
       field("set_operation", $.set_operation),
+      field("unary_operator", $.unary_operator),
+      field("binary_operator", $.binary_operator),
+      field("active", $.active),
+      field("application", $.application),
       field("flatten", $.flatten),
       field("table", $.table),
       field("negative_table", $.negative_table),
@@ -456,6 +640,50 @@ module.exports = grammar ({
     //   field("argument", $.atom)
     // )),
 
+    // built-in operators written as function calls, e.g. defined(f)
+    unary_operator: $ => seq(
+      field("operator", choice(
+        "defined", "range", "toSet", "toMSet", "toRelation",
+        "participants", "parts", "dontCare"
+      )),
+      "(",
+      field("argument", choice($.bool_expr, $.arithmetic_expr, $.comparison_expr, $.atom)),
+      ")"
+    ),
+
+    // built-in operators written as function calls, e.g. image(f, x)
+    binary_operator: $ => seq(
+      field("operator", choice(
+        "image", "imageSet", "preImage", "inverse", "restrict",
+        "party", "together", "apart", "catchUndef"
+      )),
+      "(",
+      field("left", choice($.bool_expr, $.arithmetic_expr, $.comparison_expr, $.atom)),
+      ",",
+      field("right", choice($.bool_expr, $.arithmetic_expr, $.comparison_expr, $.atom)),
+      ")"
+    ),
+
+    // active(v, name): whether the variant v currently holds the field name
+    active: $ => seq(
+      "active",
+      "(",
+      field("variant", $.atom),
+      ",",
+      field("name", $.identifier),
+      ")"
+    ),
+
+    // f(x) for functions and sequences, R(x, _) for relations
+    application: $ => seq(
+      field("function", $.identifier),
+      "(",
+      commaSep1(field("argument", choice($.arithmetic_expr, $.atom, $.wildcard))),
+      ")"
+    ),
+
+    wildcard: $ => "_",
+
     flatten: $ => seq(
       "flatten",
       "(",
@@ -519,6 +747,15 @@ module.exports = grammar ({
 
     additive_op: $ => choice("+", "-"),
 
+    freq_expr: $ => seq(
+      "freq",
+      "(",
+      field("mset", $.atom),
+      ",",
+      field("element", choice($.arithmetic_expr, $.atom)),
+      ")"
+    ),
+
     list_combining_expr_arith: $ => prec(-10, seq(
       field("operator", choice("min", "max", "sum")),
       "(",
@@ -543,6 +780,12 @@ module.exports = grammar ({
     dominance_relation: $ => seq(
       "dominance relation",
       field("expression", choice($.bool_expr, $.comparison_expr, $.arithmetic_expr, $.atom)),
+    ),
+
+    //objective statements
+    objective_statement: $ => seq(
+      field("direction", choice("minimising", "maximising")),
+      field("expression", choice($.arithmetic_expr, $.atom))
     )
   }
 });
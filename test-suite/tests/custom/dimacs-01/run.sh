@@ -0,0 +1,4 @@
+conjure-oxide --solver sat --save-solver-input-file model.cnf solve --parser=tree-sitter --no-run-solver model.essence > /dev/null
+cat model.cnf
+cat model.cnf.map.json
+rm model.cnf model.cnf.map.json
@@ -0,0 +1 @@
+conjure-oxide --solver minion --parser=tree-sitter solve model.essence model.param
@@ -0,0 +1 @@
+conjure-oxide --solver smt --parser=tree-sitter solve model.essence model.param
@@ -0,0 +1,10 @@
+# A stand-in for a SAT solver, which only solves `model.essence`: its one solution, a = true, is
+# found unless the clause blocking it (`-1 0`) has been added.
+if grep -qx -- "-1 0" "$1"; then
+    echo "s UNSATISFIABLE"
+    exit 20
+fi
+echo "c mock solver"
+echo "s SATISFIABLE"
+echo "v 1 0"
+exit 10
@@ -0,0 +1 @@
+conjure-oxide --solver sat --sat-solver-command "sh mock-sat.sh" solve --parser=tree-sitter -n all model.essence
@@ -0,0 +1,4 @@
+conjure-oxide --solver sat solve --parser=tree-sitter -n all model.essence
+conjure-oxide --solver sat --sat-cardinality-encoding sequential-counter --sat-pb-encoding dpw solve --parser=tree-sitter -n all model.essence
+conjure-oxide --solver sat-order --sat-pb-encoding adder solve --parser=tree-sitter -n all model.essence
+conjure-oxide --solver sat solve --parser=tree-sitter -n all unsat.essence
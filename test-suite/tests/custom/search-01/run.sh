@@ -0,0 +1,3 @@
+conjure-oxide --solver minion --save-solver-input-file model.minion solve --parser=tree-sitter --no-run-solver model.essence > /dev/null
+cat model.minion
+rm model.minion
@@ -0,0 +1 @@
+conjure-oxide --solver sat --parser=tree-sitter solve --number-of-solutions=all model.essence
@@ -0,0 +1,2 @@
+conjure-oxide --solver sat-order solve --parser=tree-sitter -n 1 ascend.essence
+conjure-oxide --solver sat-order solve --parser=tree-sitter -n 1 descend.essence
@@ -270,11 +270,15 @@ fn integration_test_inner(
 
     let rule_sets = resolve_rule_sets(solver_fam, &rules_to_load)?;
 
+    // Solvers rewrite constraints added during search (objective bounds, dominance blocking)
+    // using the rule sets in the context, as the CLI does.
+    context.write().unwrap().rule_sets.clone_from(&rule_sets);
+
     let model = parsed_model;
 
     let rewritten_model = match rewriter {
         Rewriter::Naive => rewrite_naive(&model, &rule_sets, false)?,
-        Rewriter::Morph(config) => rewrite_morph(model, &rule_sets, false, config),
+        Rewriter::Morph(config) => rewrite_morph(model, &rule_sets, false, config)?,
     };
     let solver_input_file = None;
     let solver = match solver_fam {